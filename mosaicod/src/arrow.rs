@@ -1,8 +1,8 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
 use arrow::array::{ArrayRef, AsArray, RecordBatch, StructArray};
-use arrow::datatypes::{DataType, Field, FieldRef, SchemaRef};
+use arrow::datatypes::{DataType, Field, FieldRef, Fields, Schema, SchemaRef};
 use arrow::error::ArrowError;
 
 use crate::{params, traits::SquashedIterator, types};
@@ -12,27 +12,59 @@ pub enum SchemaError {
     /// Returned when the required `timestamp` field is missing in the provided schema.
     #[error("missing timestamp field in schema")]
     MissingTimestampInSchema,
-    #[error("wrong timestamp field type, expected int64")]
-    WrongTimestampType,
+    /// Returned when the `timestamp` field exists but its type isn't one of
+    /// the encodings that normalize to an i64 epoch (`Int64`, any
+    /// `Timestamp`, or `Date64`).
+    #[error("unsupported timestamp encoding: {0:?}")]
+    UnsupportedTimestampEncoding(DataType),
+}
+
+/// Checks if the given Arrow [`DataType`] can serve as the platform's
+/// `timestamp` column: the original `Int64` epoch convention, or a genuine
+/// Arrow temporal type that [`cast_array_to_numeric`] can normalize to an
+/// i64 epoch.
+fn is_valid_timestamp_encoding(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int64 | DataType::Timestamp(_, _) | DataType::Date64
+    )
 }
 
 /// Validates that the provided Arrow schema meets certain structural requirements.
 ///
 /// This function performs a series of validation checks on an [`arrow::datatypes::SchemaRef`]
-/// to ensure it conforms to the platform conventions.  
+/// to ensure it conforms to the platform conventions.
 pub fn check_schema(schema: &SchemaRef) -> Result<(), SchemaError> {
-    let field = schema.field_with_name(params::ARROW_SCHEMA_COLUMN_NAME_TIMESTAMP);
-    if let Ok(field) = field {
-        if DataType::Int64 != *field.data_type() {
-            return Err(SchemaError::WrongTimestampType);
-        }
-    } else {
-        return Err(SchemaError::MissingTimestampInSchema);
+    let field = schema
+        .field_with_name(params::ARROW_SCHEMA_COLUMN_NAME_TIMESTAMP)
+        .map_err(|_| SchemaError::MissingTimestampInSchema)?;
+
+    if !is_valid_timestamp_encoding(field.data_type()) {
+        return Err(SchemaError::UnsupportedTimestampEncoding(
+            field.data_type().clone(),
+        ));
     }
+
     Ok(())
 }
 
-/// Checks if the given Arrow [`DataType`] is considered numeric
+/// Checks if the given Arrow [`DataType`] is a temporal type that
+/// [`cast_array_to_numeric`] normalizes to an i64 epoch before casting to
+/// `f64`, rather than casting directly.
+fn is_temporal(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Timestamp(_, _)
+            | DataType::Date32
+            | DataType::Date64
+            | DataType::Time32(_)
+            | DataType::Time64(_)
+    )
+}
+
+/// Checks if the given Arrow [`DataType`] is considered numeric -- including
+/// temporal types, which [`NumericStats`](types::NumericStats) summarizes as
+/// an i64 epoch (see [`is_temporal`]).
 pub fn is_numeric(data_type: &DataType) -> bool {
     matches!(
         data_type,
@@ -48,7 +80,7 @@ pub fn is_numeric(data_type: &DataType) -> bool {
             | DataType::UInt32
             | DataType::UInt64
             | DataType::Boolean,
-    )
+    ) || is_temporal(data_type)
 }
 
 /// Checks if the given Arrow [`DataType`] is considered literal
@@ -79,8 +111,16 @@ pub fn cast_array_to_literal(array: &ArrayRef) -> Result<ArrayRef, ArrowError> {
     }
 }
 
-/// Converts an arrow [`Array`] to a numberic type array (f64)
+/// Converts an arrow [`Array`] to a numberic type array (f64). Temporal
+/// types (`Timestamp`, `Date32/64`, `Time32/64`) are first normalized to an
+/// i64 epoch, since arrow's cast kernels don't support casting them to
+/// `f64` directly.
 pub fn cast_array_to_numeric(array: &ArrayRef) -> Result<ArrayRef, ArrowError> {
+    if is_temporal(array.data_type()) {
+        let epoch = arrow_cast::cast(array.as_ref(), &arrow_schema::DataType::Int64)?;
+        return Ok(arrow_cast::cast(&epoch, &arrow_schema::DataType::Float64)?);
+    }
+
     if is_numeric(array.data_type()) {
         Ok(arrow_cast::cast(
             array.as_ref(),
@@ -93,11 +133,40 @@ pub fn cast_array_to_numeric(array: &ArrayRef) -> Result<ArrayRef, ArrowError> {
     }
 }
 
+/// Splits a single flattened-path component off its `[]` collection marker,
+/// e.g. `"scores[]"` -> `("scores", true)`, `"street"` -> `("street", false)`.
+fn split_collection_suffix(component: &str) -> (&str, bool) {
+    match component.strip_suffix("[]") {
+        Some(stripped) => (stripped, true),
+        None => (component, false),
+    }
+}
+
+/// Crosses a `[]` boundary in a flattened field path: returns a
+/// `List`/`LargeList`/`FixedSizeList`'s element array (ignoring offsets --
+/// column-wide stats don't care about per-row grouping), or a `Map`'s
+/// `entries` struct array so its `key`/`value` children can be looked up as
+/// ordinary struct subfields.
+fn collection_values(array: &ArrayRef, field_name: &str) -> Result<ArrayRef, ArrowError> {
+    match array.data_type() {
+        DataType::List(_) => Ok(Arc::clone(array.as_list::<i32>().values())),
+        DataType::LargeList(_) => Ok(Arc::clone(array.as_list::<i64>().values())),
+        DataType::FixedSizeList(_, _) => Ok(Arc::clone(array.as_fixed_size_list().values())),
+        DataType::Map(_, _) => Ok(Arc::new(array.as_map().entries().clone())),
+        _ => Err(ArrowError::SchemaError(format!(
+            "field `{0}` has a `[]` suffix but isn't a list or map",
+            field_name
+        ))),
+    }
+}
+
 /// Retrieves a nested array from a RecordBatch based on a flattened field name.
 ///
 /// For example, given a flattened field name like "user.address.street",
 /// this function will traverse the nested structure in the RecordBatch to
-/// retrieve the corresponding ArrayRef.
+/// retrieve the corresponding ArrayRef. A `[]` suffix on a path component
+/// (e.g. "scores[]", "tags[].key") crosses into a list's element array or a
+/// map's entries, as produced by [`SchemaFlattenerIter`].
 pub fn array_from_flat_field_name(
     flattened_field_name: &str,
     batch: &RecordBatch,
@@ -109,39 +178,390 @@ pub fn array_from_flat_field_name(
         ));
     }
 
-    let top_level_name = subfields[0];
-    let mut current_array = batch.column_by_name(top_level_name).ok_or_else(|| {
+    let (top_level_name, top_is_collection) = split_collection_suffix(subfields[0]);
+    let mut current_array = Arc::clone(batch.column_by_name(top_level_name).ok_or_else(|| {
         ArrowError::SchemaError(format!("can't find top level field `{0}`", top_level_name))
-    })?;
+    })?);
+    if top_is_collection {
+        current_array = collection_values(&current_array, top_level_name)?;
+    }
 
     // Iterate and traverse the remaining nested path components
     //
-    // *Note*: only structs fields are supported
+    // *Note*: only struct fields (and `[]` collection boundaries) are
+    // supported
     for subfield in &subfields[1..] {
-        let struct_array = current_array
-            .as_any()
-            .downcast_ref::<StructArray>()
-            .ok_or_else(|| {
-                ArrowError::SchemaError(format!(
-                    "can't downcast to struct subfield `{0}` for top level field `{1}`",
-                    subfield, top_level_name
-                ))
-            })?;
+        let (subfield, is_collection) = split_collection_suffix(subfield);
 
-        current_array = struct_array.column_by_name(subfield).ok_or_else(|| {
+        let struct_array = current_array.as_struct_opt().ok_or_else(|| {
+            ArrowError::SchemaError(format!(
+                "can't downcast to struct subfield `{0}` for top level field `{1}`",
+                subfield, top_level_name
+            ))
+        })?;
+
+        current_array = Arc::clone(struct_array.column_by_name(subfield).ok_or_else(|| {
             ArrowError::SchemaError(format!(
                 "can't find subfield `{0}` for top level field `{1}`",
                 subfield, top_level_name
             ))
+        })?);
+
+        if is_collection {
+            current_array = collection_values(&current_array, subfield)?;
+        }
+    }
+
+    Ok(current_array)
+}
+
+/// Builds a schema containing only the leaf fields `predicate` selects,
+/// preserving the surrounding struct nesting and dropping any struct that
+/// ends up with no retained children.
+///
+/// `predicate` is invoked once per leaf (non-struct) field, in depth-first
+/// order, with a running leaf index -- the same index space a caller would
+/// get by enumerating [`SchemaFlattenerIter`], except list/map children
+/// aren't descended into, so a `List`/`Map` field is itself one leaf here.
+pub fn project_schema(
+    schema: &SchemaRef,
+    mut predicate: impl FnMut(usize, &FieldRef) -> bool,
+) -> SchemaRef {
+    let mut leaf_index = 0;
+    let fields = project_fields(schema.fields(), &mut predicate, &mut leaf_index);
+    Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+fn project_fields(
+    fields: &Fields,
+    predicate: &mut impl FnMut(usize, &FieldRef) -> bool,
+    leaf_index: &mut usize,
+) -> Fields {
+    let mut retained: Vec<FieldRef> = Vec::new();
+
+    for field in fields.iter() {
+        match field.data_type() {
+            DataType::Struct(children) => {
+                let projected_children = project_fields(children, predicate, leaf_index);
+                if !projected_children.is_empty() {
+                    retained.push(Arc::new(Field::new(
+                        field.name(),
+                        DataType::Struct(projected_children),
+                        field.is_nullable(),
+                    )));
+                }
+            }
+            _ => {
+                let index = *leaf_index;
+                *leaf_index += 1;
+                if predicate(index, field) {
+                    retained.push(field.clone());
+                }
+            }
+        }
+    }
+
+    retained.into()
+}
+
+/// Assembles a [`RecordBatch`] matching a schema produced by
+/// [`project_schema`], pulling each retained column out of `batch` via the
+/// same nested traversal [`array_from_flat_field_name`] uses.
+pub fn project_record_batch(
+    batch: &RecordBatch,
+    projected_schema: &SchemaRef,
+) -> Result<RecordBatch, ArrowError> {
+    let mut columns = Vec::with_capacity(projected_schema.fields().len());
+
+    for field in projected_schema.fields() {
+        let source = batch.column_by_name(field.name()).ok_or_else(|| {
+            ArrowError::SchemaError(format!("can't find top level field `{0}`", field.name()))
         })?;
+        columns.push(project_array(source, field)?);
     }
 
-    Ok(Arc::clone(current_array))
+    RecordBatch::try_new(Arc::clone(projected_schema), columns)
+}
+
+fn project_array(source: &ArrayRef, field: &FieldRef) -> Result<ArrayRef, ArrowError> {
+    match field.data_type() {
+        DataType::Struct(children) => {
+            let struct_array = source.as_struct_opt().ok_or_else(|| {
+                ArrowError::SchemaError(format!(
+                    "can't downcast to struct field `{0}`",
+                    field.name()
+                ))
+            })?;
+
+            let mut columns = Vec::with_capacity(children.len());
+            for child in children.iter() {
+                let child_source = struct_array.column_by_name(child.name()).ok_or_else(|| {
+                    ArrowError::SchemaError(format!(
+                        "can't find subfield `{0}` for field `{1}`",
+                        child.name(),
+                        field.name()
+                    ))
+                })?;
+                columns.push(project_array(child_source, child)?);
+            }
+
+            Ok(Arc::new(StructArray::new(
+                children.clone(),
+                columns,
+                struct_array.nulls().cloned(),
+            )))
+        }
+        _ => Ok(Arc::clone(source)),
+    }
+}
+
+/// Metadata key a field's stable id (see [`assign_field_ids`]) is stored
+/// under, mirroring Iceberg's field-id approach: an id survives renames and
+/// struct-child reordering, unlike the dotted names [`SchemaFlattenerIter`]
+/// produces.
+pub const FIELD_ID_METADATA_KEY: &str = "mosaico.field_id";
+
+/// Reads the stable id [`assign_field_ids`]/[`reassign_field_ids`] wrote
+/// into `field`'s metadata, if any.
+pub fn field_id(field: &Field) -> Option<i64> {
+    field.metadata().get(FIELD_ID_METADATA_KEY)?.parse().ok()
+}
+
+fn with_field_id(field: &Field, id: i64) -> Field {
+    let mut metadata = field.metadata().clone();
+    metadata.insert(FIELD_ID_METADATA_KEY.to_string(), id.to_string());
+    field.clone().with_metadata(metadata)
+}
+
+/// Assigns a unique, monotonically increasing id (starting at 1, Iceberg
+/// style) to every field of `schema` in depth-first order -- a struct,
+/// list or map field gets its id before its children do -- and writes it
+/// into each field's metadata under [`FIELD_ID_METADATA_KEY`].
+///
+/// Every field gets an id, not just leaves: a `List`'s element field and a
+/// `Map`'s `entries`/`key`/`value` fields are each assigned one too, so a
+/// later [`reassign_field_ids`] call can match them individually as the
+/// schema evolves.
+pub fn assign_field_ids(schema: &SchemaRef) -> SchemaRef {
+    let mut next_id = 1i64;
+    let fields = assign_field_ids_to_fields(schema.fields(), &mut next_id);
+    Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+fn assign_field_ids_to_fields(fields: &Fields, next_id: &mut i64) -> Fields {
+    fields
+        .iter()
+        .map(|field| Arc::new(assign_field_id(field, next_id)))
+        .collect::<Vec<FieldRef>>()
+        .into()
+}
+
+fn assign_field_id(field: &Field, next_id: &mut i64) -> Field {
+    let id = *next_id;
+    *next_id += 1;
+    let field = with_field_id(field, id);
+
+    match field.data_type().clone() {
+        DataType::Struct(children) => {
+            let children = assign_field_ids_to_fields(&children, next_id);
+            field.with_data_type(DataType::Struct(children))
+        }
+        DataType::List(child) => {
+            let child = Arc::new(assign_field_id(&child, next_id));
+            field.with_data_type(DataType::List(child))
+        }
+        DataType::LargeList(child) => {
+            let child = Arc::new(assign_field_id(&child, next_id));
+            field.with_data_type(DataType::LargeList(child))
+        }
+        DataType::FixedSizeList(child, size) => {
+            let child = Arc::new(assign_field_id(&child, next_id));
+            field.with_data_type(DataType::FixedSizeList(child, size))
+        }
+        DataType::Map(entries, sorted) => {
+            let entries = Arc::new(assign_field_id(&entries, next_id));
+            field.with_data_type(DataType::Map(entries, sorted))
+        }
+        _ => field,
+    }
+}
+
+/// Walks `field` (named `name` in its parent, or its own name at the top
+/// level), recording every id already present in its metadata under its
+/// dotted path -- the same `[]`-suffixed convention [`SchemaFlattenerIter`]
+/// uses for list/map children -- and tracking the highest id seen.
+fn collect_field_ids(name: String, field: &Field, out: &mut HashMap<String, i64>, max_id: &mut i64) {
+    if let Some(id) = field_id(field) {
+        out.insert(name.clone(), id);
+        if id > *max_id {
+            *max_id = id;
+        }
+    }
+
+    match field.data_type() {
+        DataType::Struct(children) => {
+            for child in children.iter() {
+                collect_field_ids(format!("{}.{}", name, child.name()), child, out, max_id);
+            }
+        }
+        DataType::List(child) | DataType::LargeList(child) | DataType::FixedSizeList(child, _) => {
+            collect_field_ids(format!("{}[]", name), child, out, max_id);
+        }
+        DataType::Map(entries, _) => {
+            collect_field_ids(format!("{}[]", name), entries, out, max_id);
+        }
+        _ => {}
+    }
+}
+
+fn field_id_map(schema: &SchemaRef) -> (HashMap<String, i64>, i64) {
+    let mut out = HashMap::new();
+    let mut max_id = 0i64;
+    for field in schema.fields().iter() {
+        collect_field_ids(field.name().clone(), field, &mut out, &mut max_id);
+    }
+    (out, max_id)
+}
+
+/// Assigns ids to `evolved_schema`, reusing `previous_schema`'s id for any
+/// field whose dotted path still exists there, and minting a fresh one
+/// (`max(previous ids) + 1`, incrementing) for every field that doesn't --
+/// new columns added since `previous_schema` was last assigned.
+///
+/// This is how a long-running stats accumulator follows columns across
+/// schema evolution: it keeps re-deriving the current schema's field ids
+/// from the schema it last saw, rather than assuming ids never change, so
+/// [`types::ColumnsStatsById`] entries survive as long as a field's dotted
+/// path does.
+pub fn reassign_field_ids(evolved_schema: &SchemaRef, previous_schema: &SchemaRef) -> SchemaRef {
+    let (previous_ids, max_id) = field_id_map(previous_schema);
+    let mut next_id = max_id + 1;
+    let fields = reassign_fields(evolved_schema.fields(), "", &previous_ids, &mut next_id);
+    Arc::new(Schema::new_with_metadata(
+        fields,
+        evolved_schema.metadata().clone(),
+    ))
+}
+
+fn reassign_fields(
+    fields: &Fields,
+    prefix: &str,
+    previous_ids: &HashMap<String, i64>,
+    next_id: &mut i64,
+) -> Fields {
+    fields
+        .iter()
+        .map(|field| {
+            let name = if prefix.is_empty() {
+                field.name().clone()
+            } else {
+                format!("{}.{}", prefix, field.name())
+            };
+            Arc::new(reassign_field(field, &name, previous_ids, next_id))
+        })
+        .collect::<Vec<FieldRef>>()
+        .into()
+}
+
+fn reassign_field(
+    field: &Field,
+    name: &str,
+    previous_ids: &HashMap<String, i64>,
+    next_id: &mut i64,
+) -> Field {
+    let id = previous_ids.get(name).copied().unwrap_or_else(|| {
+        let id = *next_id;
+        *next_id += 1;
+        id
+    });
+    let field = with_field_id(field, id);
+
+    match field.data_type().clone() {
+        DataType::Struct(children) => {
+            let children = reassign_fields(&children, name, previous_ids, next_id);
+            field.with_data_type(DataType::Struct(children))
+        }
+        DataType::List(child) => {
+            let child = Arc::new(reassign_field(&child, &format!("{}[]", name), previous_ids, next_id));
+            field.with_data_type(DataType::List(child))
+        }
+        DataType::LargeList(child) => {
+            let child = Arc::new(reassign_field(&child, &format!("{}[]", name), previous_ids, next_id));
+            field.with_data_type(DataType::LargeList(child))
+        }
+        DataType::FixedSizeList(child, size) => {
+            let child = Arc::new(reassign_field(&child, &format!("{}[]", name), previous_ids, next_id));
+            field.with_data_type(DataType::FixedSizeList(child, size))
+        }
+        DataType::Map(entries, sorted) => {
+            let entries = Arc::new(reassign_field(
+                &entries,
+                &format!("{}[]", name),
+                previous_ids,
+                next_id,
+            ));
+            field.with_data_type(DataType::Map(entries, sorted))
+        }
+        _ => field,
+    }
+}
+
+/// Like [`column_stats_from_schema`], but keyed by each leaf's stable
+/// [`field_id`] rather than its dotted name -- `schema` must already carry
+/// ids from [`assign_field_ids`]/[`reassign_field_ids`]. Leaves with no id
+/// (a schema that was never assigned one) are skipped.
+pub fn column_stats_from_schema_by_id(schema: &SchemaRef) -> types::ColumnsStatsById {
+    let mut cs = types::ColumnsStatsById::empty();
+    for (_, field) in schema.squashed_iter() {
+        if let Some(id) = field_id(field.as_ref()) {
+            cs.stats.insert(id, stats_from_arrow_field(field.as_ref()));
+        }
+    }
+    cs
+}
+
+/// Resolves a stable field id back to its current array in `batch`, given
+/// `schema` (which must carry the same ids `field_id_value` was assigned
+/// under). Walks the same nested path [`array_from_flat_field_name`] does,
+/// just found by id instead of supplied directly.
+pub fn array_from_field_id(
+    field_id_value: i64,
+    schema: &SchemaRef,
+    batch: &RecordBatch,
+) -> Result<ArrayRef, ArrowError> {
+    for (name, field) in schema.squashed_iter() {
+        if field_id(field.as_ref()) == Some(field_id_value) {
+            return array_from_flat_field_name(&name, batch);
+        }
+    }
+
+    Err(ArrowError::SchemaError(format!(
+        "no field with id `{0}` in schema",
+        field_id_value
+    )))
+}
+
+/// Like [`column_stats_inspect_record_batch`], but for a
+/// [`types::ColumnsStatsById`] -- each entry's array is found by resolving
+/// its field id against `schema` rather than by dotted name.
+pub fn column_stats_inspect_record_batch_by_id(
+    cstats: &mut types::ColumnsStatsById,
+    schema: &SchemaRef,
+    batch: &RecordBatch,
+) -> Result<(), ArrowError> {
+    for (field_id_value, stats) in cstats.stats.iter_mut() {
+        let array = array_from_field_id(*field_id_value, schema, batch)?;
+        stats_inspect_array(stats, &array)?;
+    }
+    Ok(())
 }
 
 pub struct SchemaFlattenerIter {
-    // A queue to hold fields and their current name prefix.
-    field_queue: VecDeque<(String, FieldRef)>,
+    // A queue to hold fields and their current name prefix. `prefix_is_name`
+    // marks entries whose prefix is already the field's full flattened name
+    // (pushed by a List/LargeList/FixedSizeList descent) rather than a
+    // parent name the field's own name still needs appending to.
+    field_queue: VecDeque<(String, FieldRef, bool)>,
 }
 
 impl SchemaFlattenerIter {
@@ -150,7 +570,7 @@ impl SchemaFlattenerIter {
 
         // Traverse the schema to build a queue of fields
         for field in schema.fields().iter() {
-            queue.push_back(("".to_string(), field.clone()));
+            queue.push_back(("".to_string(), field.clone(), false));
         }
 
         SchemaFlattenerIter { field_queue: queue }
@@ -162,8 +582,10 @@ impl Iterator for SchemaFlattenerIter {
 
     fn next(&mut self) -> Option<Self::Item> {
         // Continue looping until the queue is empty OR we find a simple field
-        while let Some((prefix, field)) = self.field_queue.pop_front() {
-            let current_name = if prefix.is_empty() {
+        while let Some((prefix, field, prefix_is_name)) = self.field_queue.pop_front() {
+            let current_name = if prefix_is_name {
+                prefix
+            } else if prefix.is_empty() {
                 field.name().clone()
             } else {
                 format!("{}.{}", prefix, field.name())
@@ -176,12 +598,38 @@ impl Iterator for SchemaFlattenerIter {
                     // We reverse the children before pushing to maintain order when popping from the front.
                     for child_field in children.iter().rev() {
                         self.field_queue
-                            .push_front((current_name.clone(), child_field.clone()));
+                            .push_front((current_name.clone(), child_field.clone(), false));
                     }
                     // Skip the struct itself and continue to process its children.
                 }
 
-                // 2) Primitive/Leaf Type - This is a field we want to yield.
+                // 2) List/LargeList/FixedSizeList - descend into the element
+                // field, naming it after the collection itself (e.g.
+                // "scores[]") rather than the element field's own name,
+                // which is usually a meaningless placeholder like "item".
+                DataType::List(child) | DataType::LargeList(child) => {
+                    self.field_queue
+                        .push_front((format!("{}[]", current_name), child.clone(), true));
+                }
+                DataType::FixedSizeList(child, _) => {
+                    self.field_queue
+                        .push_front((format!("{}[]", current_name), child.clone(), true));
+                }
+
+                // 3) Map - descend into the `key`/`value` fields of its
+                // (struct) entries field, yielding e.g. "tags[].key" /
+                // "tags[].value".
+                DataType::Map(entries, _) => {
+                    if let DataType::Struct(children) = entries.data_type() {
+                        let map_name = format!("{}[]", current_name);
+                        for child_field in children.iter().rev() {
+                            self.field_queue
+                                .push_front((map_name.clone(), child_field.clone(), false));
+                        }
+                    }
+                }
+
+                // 4) Primitive/Leaf Type - This is a field we want to yield.
                 _ => {
                     return Some((current_name, field));
                 }
@@ -321,6 +769,62 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn valid_schema_with_arrow_timestamp_type() {
+        let fields = vec![
+            Field::new(
+                "timestamp",
+                DataType::Timestamp(arrow::datatypes::TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("value", DataType::Float64, true),
+        ];
+        let schema = create_schema(fields);
+
+        assert!(check_schema(&schema).is_ok());
+    }
+
+    #[test]
+    fn valid_schema_with_date64_timestamp() {
+        let fields = vec![
+            Field::new("timestamp", DataType::Date64, false),
+            Field::new("value", DataType::Float64, true),
+        ];
+        let schema = create_schema(fields);
+
+        assert!(check_schema(&schema).is_ok());
+    }
+
+    #[test]
+    fn invalid_schema_unsupported_timestamp_encoding() {
+        let fields = vec![
+            Field::new("timestamp", DataType::Utf8, false),
+            Field::new("value", DataType::Float64, true),
+        ];
+        let schema = create_schema(fields);
+
+        let result = check_schema(&schema);
+        assert!(matches!(
+            result,
+            Err(SchemaError::UnsupportedTimestampEncoding(DataType::Utf8))
+        ));
+    }
+
+    #[test]
+    fn numeric_stats_cast_normalizes_timestamp_to_epoch() {
+        use arrow::array::TimestampNanosecondArray;
+
+        let array: ArrayRef = Arc::new(TimestampNanosecondArray::from(vec![1_000_000_000]));
+        let numeric = cast_array_to_numeric(&array).unwrap();
+
+        assert_eq!(
+            numeric
+                .as_primitive::<arrow::datatypes::Float64Type>()
+                .value(0),
+            1_000_000_000.0
+        );
+    }
+
     // Helper function to create a simplified schema reference
     fn create_schema_ref(fields: Vec<Field>) -> Arc<Schema> {
         Arc::new(Schema::new(fields))
@@ -402,15 +906,7 @@ mod tests {
     }
 
     #[test]
-    fn non_struct_nested_types_are_leafs() {
-        // // Arrow List types are complex but are treated as leaf nodes in flattening
-        // // unless you specifically wanted to flatten list elements, which is rare.
-        // let list_field = Field::new(
-        //     "items",
-        //     DataType::List(Arc::new(Field::new("element", DataType::Int32, false))),
-        //     true,
-        // );
-
+    fn list_and_map_elements_are_flattened() {
         let fields = vec![
             Field::new(
                 "list_of_ints",
@@ -442,10 +938,214 @@ mod tests {
         let flattened_names: Vec<String> =
             schema_ref.squashed_iter().map(|(name, _)| name).collect();
 
-        // Since the code only recurses on DataType::Struct, List and Map remain as leaf nodes.
+        // List elements are named after the collection itself ("[]"); map
+        // entries descend into their key/value struct fields.
         assert_eq!(
             flattened_names,
-            vec!["list_of_ints".to_string(), "map_data".to_string(),]
+            vec![
+                "list_of_ints[]".to_string(),
+                "map_data[].key".to_string(),
+                "map_data[].value".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_list_of_structs_is_flattened() {
+        let point_fields = vec![
+            Field::new("x", DataType::Float64, false),
+            Field::new("y", DataType::Float64, false),
+        ];
+
+        let fields = vec![Field::new(
+            "path",
+            DataType::List(Arc::new(Field::new(
+                "element",
+                DataType::Struct(point_fields.into()),
+                false,
+            ))),
+            false,
+        )];
+
+        let schema_ref = create_schema_ref(fields);
+
+        let flattened_names: Vec<String> =
+            schema_ref.squashed_iter().map(|(name, _)| name).collect();
+
+        assert_eq!(
+            flattened_names,
+            vec!["path[].x".to_string(), "path[].y".to_string()]
+        );
+    }
+
+    #[test]
+    fn project_schema_keeps_nesting_and_drops_empty_structs() {
+        let address_fields = vec![
+            Field::new("street", DataType::Utf8, true),
+            Field::new("zip", DataType::Int32, false),
+        ];
+
+        let fields = vec![
+            Field::new("user_id", DataType::Int64, false),
+            Field::new("address", DataType::Struct(address_fields.into()), false),
+            Field::new("is_active", DataType::Boolean, false),
+        ];
+        let schema = create_schema_ref(fields);
+
+        // Leaf order matches `squashed_iter`: user_id=0, address.street=1,
+        // address.zip=2, is_active=3. Drop everything but address.zip.
+        let projected = project_schema(&schema, |index, _| index == 2);
+
+        let flattened_names: Vec<String> = projected
+            .squashed_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(flattened_names, vec!["address.zip".to_string()]);
+    }
+
+    #[test]
+    fn project_schema_drops_struct_left_fully_empty() {
+        let address_fields = vec![Field::new("street", DataType::Utf8, true)];
+
+        let fields = vec![
+            Field::new("user_id", DataType::Int64, false),
+            Field::new("address", DataType::Struct(address_fields.into()), false),
+        ];
+        let schema = create_schema_ref(fields);
+
+        // Only user_id (leaf index 0) survives; `address` has no retained
+        // children left, so it's dropped entirely.
+        let projected = project_schema(&schema, |index, _| index == 0);
+
+        assert_eq!(projected.fields().len(), 1);
+        assert_eq!(projected.field(0).name(), "user_id");
+    }
+
+    #[test]
+    fn project_record_batch_matches_projected_schema() {
+        use arrow::array::{Int32Array, Int64Array, StringArray};
+
+        let address_fields = vec![
+            Field::new("street", DataType::Utf8, true),
+            Field::new("zip", DataType::Int32, false),
+        ];
+
+        let fields = vec![
+            Field::new("user_id", DataType::Int64, false),
+            Field::new("address", DataType::Struct(address_fields.clone().into()), false),
+        ];
+        let schema = create_schema_ref(fields);
+
+        let address_array = StructArray::new(
+            address_fields.clone().into(),
+            vec![
+                Arc::new(StringArray::from(vec!["Main St"])) as ArrayRef,
+                Arc::new(Int32Array::from(vec![10001])) as ArrayRef,
+            ],
+            None,
+        );
+
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int64Array::from(vec![42])),
+                Arc::new(address_array),
+            ],
+        )
+        .unwrap();
+
+        // Keep only address.zip (leaf index 2: user_id=0, address.street=1,
+        // address.zip=2).
+        let projected_schema = project_schema(&schema, |index, _| index == 2);
+        let projected_batch = project_record_batch(&batch, &projected_schema).unwrap();
+
+        assert_eq!(projected_batch.schema(), projected_schema);
+        let zip_array = array_from_flat_field_name("address.zip", &projected_batch).unwrap();
+        assert_eq!(
+            zip_array.as_primitive::<arrow::datatypes::Int32Type>().value(0),
+            10001
+        );
+    }
+
+    #[test]
+    fn assign_field_ids_gives_unique_ids_parent_before_children() {
+        let address_fields = vec![
+            Field::new("street", DataType::Utf8, true),
+            Field::new("zip", DataType::Int32, false),
+        ];
+
+        let fields = vec![
+            Field::new("user_id", DataType::Int64, false),
+            Field::new("address", DataType::Struct(address_fields.into()), false),
+        ];
+        let schema = create_schema_ref(fields);
+
+        let assigned = assign_field_ids(&schema);
+
+        let user_id_id = field_id(assigned.field(0)).unwrap();
+        let DataType::Struct(address_children) = assigned.field(1).data_type() else {
+            panic!("expected address to stay a struct");
+        };
+        let address_id = field_id(assigned.field(1)).unwrap();
+        let street_id = field_id(&address_children[0]).unwrap();
+        let zip_id = field_id(&address_children[1]).unwrap();
+
+        // All ids are unique, and a struct's own id precedes its children's.
+        let mut ids = vec![user_id_id, address_id, street_id, zip_id];
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), 4);
+        assert!(address_id < street_id);
+        assert!(street_id < zip_id);
+    }
+
+    #[test]
+    fn reassign_field_ids_preserves_existing_and_mints_new_for_added_field() {
+        let fields = vec![
+            Field::new("user_id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ];
+        let previous = assign_field_ids(&create_schema_ref(fields));
+        let previous_name_id = field_id(previous.field(1)).unwrap();
+
+        // Evolved schema: same two fields (reordered) plus a brand new one.
+        let evolved_fields = vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("user_id", DataType::Int64, false),
+            Field::new("email", DataType::Utf8, true),
+        ];
+        let evolved = reassign_field_ids(&create_schema_ref(evolved_fields), &previous);
+
+        let evolved_name_id = field_id(evolved.field_with_name("name").unwrap());
+        let evolved_user_id_id = field_id(evolved.field_with_name("user_id").unwrap());
+        let evolved_email_id = field_id(evolved.field_with_name("email").unwrap());
+
+        assert_eq!(evolved_name_id, Some(previous_name_id));
+        assert_eq!(
+            evolved_user_id_id,
+            Some(field_id(previous.field(0)).unwrap())
         );
+        // A fresh id, distinct from everything carried over.
+        assert!(evolved_email_id.is_some());
+        assert_ne!(evolved_email_id, evolved_name_id);
+        assert_ne!(evolved_email_id, evolved_user_id_id);
+    }
+
+    #[test]
+    fn column_stats_by_id_resolves_through_field_id() {
+        use arrow::array::Int64Array;
+
+        let fields = vec![Field::new("user_id", DataType::Int64, false)];
+        let schema = assign_field_ids(&create_schema_ref(fields));
+        let user_id_id = field_id(schema.field(0)).unwrap();
+
+        let cstats = column_stats_from_schema_by_id(&schema);
+        assert!(cstats.stats.contains_key(&user_id_id));
+
+        let batch =
+            RecordBatch::try_new(Arc::clone(&schema), vec![Arc::new(Int64Array::from(vec![7]))])
+                .unwrap();
+        let array = array_from_field_id(user_id_id, &schema, &batch).unwrap();
+        assert_eq!(array.as_primitive::<arrow::datatypes::Int64Type>().value(0), 7);
     }
 }
@@ -17,23 +17,181 @@ struct Cli {
 
 #[derive(Args, Debug)]
 struct CommandRun {
-    /// Listen on all addresses, including LAN and public addresses
-    #[arg(long, default_value_t = false)]
-    host: bool,
+    /// Listen on all addresses, including LAN and public addresses.
+    ///
+    /// Overrides `host` from `--config-file` when given. Defaults to `false`
+    /// if neither is given.
+    #[arg(long)]
+    host: Option<bool>,
+
+    /// Port.
+    ///
+    /// Overrides `port` from `--config-file` when given. Defaults to `6726`
+    /// if neither is given.
+    #[arg(long)]
+    port: Option<u16>,
 
-    /// Port
-    #[arg(long, default_value_t = 6726)]
-    port: u16,
+    /// Port to serve Prometheus metrics (`GET /metrics`) on. Set to `0` to
+    /// disable the metrics endpoint entirely.
+    #[arg(long, default_value_t = 9090)]
+    metrics_port: u16,
 
     /// Enable to store objects on the local filesystem at the specified directory path
     #[arg(long)]
     local_store: Option<std::path::PathBuf>,
+
+    /// Path to a TOML (`.toml`) or YAML (`.yaml`/`.yml`) file providing the
+    /// full config tree: `repository_db_url`, the `store` block
+    /// (`endpoint`/`bucket`/`access_key`/`secret_key`/`region`), and
+    /// `host`/`port`.
+    ///
+    /// Acts as the lowest-priority layer: environment variables override
+    /// the file, and the CLI flags above override both. Lets deployment
+    /// config live in a checked-in file instead of shell-exported secrets.
+    #[arg(long)]
+    config_file: Option<std::path::PathBuf>,
+
+    /// Number of background workers polling the durable job queue (reindex
+    /// jobs, ...). Set to 0 to disable the job queue.
+    #[arg(long, default_value_t = 2)]
+    job_workers: usize,
+
+    /// How often an idle job worker polls for new work, in seconds.
+    #[arg(long, default_value_t = 5)]
+    job_heartbeat_interval_secs: u64,
+
+    /// How long a claimed job may go without a heartbeat before the sweeper
+    /// assumes its worker crashed and reclaims it, in seconds.
+    #[arg(long, default_value_t = 60)]
+    job_heartbeat_timeout_secs: u64,
+
+    /// Number of times a job may be claimed and fail before it's left alone
+    /// as a dead letter instead of being rescheduled.
+    #[arg(long, default_value_t = 5)]
+    job_max_retries: u32,
+
+    /// How long a single poll of a running job's future may take before a
+    /// warning is logged, in seconds.
+    #[arg(long, default_value_t = 10)]
+    job_poll_warn_threshold_secs: u64,
+
+    /// Maximum number of pooled connections held open to the database.
+    #[arg(long, default_value_t = 10)]
+    db_pool_max_connections: u32,
+
+    /// Minimum number of idle connections the database pool keeps warm.
+    #[arg(long, default_value_t = 0)]
+    db_pool_min_connections: u32,
+
+    /// How long to wait for a pooled database connection to become
+    /// available before giving up, in seconds.
+    #[arg(long, default_value_t = 30)]
+    db_pool_acquire_timeout_secs: u64,
+
+    /// How long a pooled database connection may sit idle before it's
+    /// closed, in seconds. Unset keeps idle connections open indefinitely.
+    #[arg(long)]
+    db_pool_idle_timeout_secs: Option<u64>,
+
+    /// Maximum lifetime of a pooled database connection, in seconds, closed
+    /// and replaced once exceeded even if still healthy. Unset never
+    /// recycles a connection on age alone.
+    #[arg(long)]
+    db_pool_max_lifetime_secs: Option<u64>,
+
+    /// Ping a pooled connection with a lightweight health check before
+    /// handing it out, catching one the server already dropped.
+    #[arg(long, default_value_t = false)]
+    db_pool_test_before_acquire: bool,
+
+    /// Comma-separated read-replica database URLs. When set,
+    /// `Repository::connection` round-robins reads across them instead of
+    /// the primary; writes always target the primary.
+    #[arg(long, value_delimiter = ',')]
+    db_replica_urls: Vec<url::Url>,
+
+    /// Base delay before the first database connection retry attempt, in
+    /// milliseconds. Doubled after each subsequent transient failure, up to
+    /// `db_connect_retry_max_backoff_secs`. Each actual wait is jittered to
+    /// between half and all of the current delay.
+    #[arg(long, default_value_t = 100)]
+    db_connect_retry_initial_backoff_ms: u64,
+
+    /// Ceiling the database connection retry delay is capped at, in
+    /// seconds, no matter how many transient failures precede it.
+    #[arg(long, default_value_t = 30)]
+    db_connect_retry_max_backoff_secs: u64,
+
+    /// Total time, in seconds, the initial database connection is allowed
+    /// to keep retrying transient failures (connection refused/reset/
+    /// aborted) before giving up. Lets `mosaicod` come up even if Postgres
+    /// is still booting.
+    #[arg(long, default_value_t = 60)]
+    db_connect_retry_max_elapsed_secs: u64,
+
+    /// Path to a PEM certificate chain to terminate TLS with. Requires
+    /// `--tls-key`; mutually exclusive with `--acme-domain`.
+    #[arg(long)]
+    tls_cert: Option<std::path::PathBuf>,
+
+    /// Path to the PEM private key matching `--tls-cert`.
+    #[arg(long)]
+    tls_key: Option<std::path::PathBuf>,
+
+    /// Domain to automatically provision (and keep renewed) a TLS
+    /// certificate for via ACME. Requires `--acme-contact`; mutually
+    /// exclusive with `--tls-cert`/`--tls-key`.
+    #[arg(long)]
+    acme_domain: Option<String>,
+
+    /// Contact email registered with the ACME account used for
+    /// `--acme-domain`.
+    #[arg(long)]
+    acme_contact: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct CommandMigrate {
+    #[command(subcommand)]
+    action: MigrateAction,
+
+    /// Path to a TOML (`.toml`) or YAML (`.yaml`/`.yml`) file providing
+    /// `repository_db_url` (see `CommandRun::config_file`).
+    #[arg(long)]
+    config_file: Option<std::path::PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum MigrateAction {
+    /// Applies every pending migration up to and including `--to`, or all
+    /// pending migrations if `--to` is unset.
+    ///
+    /// Note: `mosaicod run` already applies every migration unconditionally
+    /// on every connect, so this is mainly useful to apply migrations ahead
+    /// of a deploy, or to stop partway with `--to` during a staged rollout.
+    Up {
+        #[arg(long)]
+        to: Option<i64>,
+    },
+    /// Always fails: this server's migration registry applies forward-only,
+    /// idempotent migrations with no rollback step. Kept as an explicit
+    /// subcommand so running it reports that clearly instead of the command
+    /// just not existing.
+    Down {
+        #[arg(long)]
+        to: i64,
+    },
+    /// Lists every migration and whether it's currently applied.
+    Status,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Start the mosaico server
     Run(CommandRun),
+    /// Inspect or drive the database schema migrations independently of
+    /// starting the server.
+    Migrate(CommandMigrate),
 }
 
 #[derive(Debug)]
@@ -45,14 +203,21 @@ fn init_logger() {
     env_logger::builder().format_target(true).init();
 }
 
-/// Load the defined env variables from the system.
-fn load_env_variables() -> Result<Variables, Box<dyn std::error::Error>> {
+/// Load the defined env variables from the system, falling back to
+/// `config_file` for anything the environment doesn't set.
+fn load_env_variables(
+    config_file: Option<&params::ConfigFile>,
+) -> Result<Variables, Box<dyn std::error::Error>> {
     info!("Loading .env file");
     dotenv().ok();
 
     params::load_configurables_from_env();
 
-    let repository_db_url: String = params::require_env_var("MOSAICO_REPOSITORY_DB_URL")?;
+    let repository_db_url: String = params::layered(
+        None,
+        "MOSAICO_REPOSITORY_DB_URL",
+        config_file.and_then(|c| c.repository_db_url.clone()),
+    )?;
     let repository_db_url: url::Url = repository_db_url.parse()?;
 
     let vars = Variables { repository_db_url };
@@ -63,18 +228,96 @@ fn load_env_variables() -> Result<Variables, Box<dyn std::error::Error>> {
     Ok(vars)
 }
 
-fn load_remote_store_vars() -> Result<store::S3Config, Box<dyn std::error::Error>> {
-    let store_endpoint: String = params::require_env_var("MOSAICO_STORE_ENDPOINT")?;
-    let store_bucket: String = params::require_env_var("MOSAICO_STORE_BUCKET")?;
-    let secret_key: String = params::require_env_var("MOSAICO_STORE_SECRET_KEY")?;
-    let store_secret_key = params::Hidden::from(secret_key);
-    let store_access_key: String = params::require_env_var("MOSAICO_STORE_ACCESS_KEY")?;
+/// Loads the optional `handshake` credentials from the environment.
+///
+/// Authentication is opt-in: if either variable is unset the server starts
+/// unauthenticated, as it always has.
+fn load_auth_vars() -> Option<server::Credentials> {
+    let username = env::var("MOSAICO_AUTH_USERNAME").ok()?;
+    let password = env::var("MOSAICO_AUTH_PASSWORD").ok()?;
+
+    Some(server::Credentials { username, password })
+}
+
+/// Builds the [`server::TlsSource`] requested on the command line, if any.
+///
+/// `--tls-cert`/`--tls-key` and `--acme-domain`/`--acme-contact` are each
+/// required together and mutually exclusive with the other pair.
+fn load_tls_source(
+    args: &CommandRun,
+) -> Result<Option<server::TlsSource>, Box<dyn std::error::Error>> {
+    match (
+        &args.tls_cert,
+        &args.tls_key,
+        &args.acme_domain,
+        &args.acme_contact,
+    ) {
+        (None, None, None, None) => Ok(None),
+        (Some(cert_path), Some(key_path), None, None) => Ok(Some(server::TlsSource::Static {
+            cert_path: cert_path.clone(),
+            key_path: key_path.clone(),
+        })),
+        (None, None, Some(domain), Some(contact)) => Ok(Some(server::TlsSource::Acme {
+            domain: domain.clone(),
+            contact: contact.clone(),
+        })),
+        (Some(_), None, ..) | (None, Some(_), ..) => {
+            Err("`--tls-cert` and `--tls-key` must be given together".into())
+        }
+        (.., Some(_), None) | (.., None, Some(_)) => {
+            Err("`--acme-domain` and `--acme-contact` must be given together".into())
+        }
+        _ => Err(
+            "`--tls-cert`/`--tls-key` and `--acme-domain`/`--acme-contact` are mutually exclusive"
+                .into(),
+        ),
+    }
+}
+
+fn load_remote_store_vars(
+    config_file: Option<&params::ConfigFile>,
+) -> Result<store::S3Config, Box<dyn std::error::Error>> {
+    let store_section = config_file.and_then(|c| c.store.as_ref());
+
+    let store_endpoint: String = params::layered(
+        None,
+        "MOSAICO_STORE_ENDPOINT",
+        store_section.and_then(|s| s.endpoint.clone()),
+    )?;
+    let store_bucket: String = params::layered(
+        None,
+        "MOSAICO_STORE_BUCKET",
+        store_section.and_then(|s| s.bucket.clone()),
+    )?;
+    // Static keys are optional: when unset, `Store::try_from_s3_store` falls
+    // through to the rest of the AWS credential chain (env vars, IMDS,
+    // web-identity/STS).
+    let store_secret_key = params::layered_opt_hidden(
+        None,
+        "MOSAICO_STORE_SECRET_KEY",
+        store_section.and_then(|s| s.secret_key.clone()),
+    );
+    let store_access_key: Option<String> = params::layered_opt(
+        None,
+        "MOSAICO_STORE_ACCESS_KEY",
+        store_section.and_then(|s| s.access_key.clone()),
+    );
+    let store_region: Option<String> = params::layered_opt(
+        None,
+        "MOSAICO_STORE_REGION",
+        store_section.and_then(|s| s.region.clone()),
+    );
 
     let vars = store::S3Config {
         endpoint: store_endpoint,
         bucket: store_bucket,
         secret_key: store_secret_key,
         access_key: store_access_key,
+        region: store_region,
+        // Not yet exposed as CLI/env/config-file settings; callers that
+        // want retrying/throttling construct `S3Config` directly for now.
+        retry: None,
+        max_concurrent_requests: None,
     };
 
     debug!("{:#?}", vars);
@@ -87,20 +330,72 @@ fn run(startup_time: &Instant) -> Result<(), Box<dyn std::error::Error>> {
 
     init_logger();
 
-    let vars = load_env_variables()?;
-
     match args.cmd {
         Commands::Run(args) => {
-            let store = get_store(&args)?;
+            let config_file = args
+                .config_file
+                .as_ref()
+                .map(params::load_config_file)
+                .transpose()?;
+
+            let vars = load_env_variables(config_file.as_ref())?;
+            let store = get_store(&args, config_file.as_ref())?;
             let store_display_name = get_store_display_name(&store);
+            let tls = load_tls_source(&args)?;
+            let is_tls = tls.is_some();
+
+            let host = args
+                .host
+                .or(config_file.as_ref().and_then(|c| c.host))
+                .unwrap_or(false);
+            let port = args
+                .port
+                .or(config_file.as_ref().and_then(|c| c.port))
+                .unwrap_or(6726);
 
             let server = server::Server::new(
-                args.host,
-                args.port,
+                host,
+                port,
+                args.metrics_port,
                 store,
                 repo::Config {
                     db_url: vars.repository_db_url,
+                    job_workers: args.job_workers,
+                    job_heartbeat_interval: std::time::Duration::from_secs(
+                        args.job_heartbeat_interval_secs,
+                    ),
+                    job_heartbeat_timeout: std::time::Duration::from_secs(
+                        args.job_heartbeat_timeout_secs,
+                    ),
+                    job_max_retries: args.job_max_retries,
+                    job_poll_warn_threshold: std::time::Duration::from_secs(
+                        args.job_poll_warn_threshold_secs,
+                    ),
+                    pool_max_connections: args.db_pool_max_connections,
+                    pool_min_connections: args.db_pool_min_connections,
+                    pool_acquire_timeout: std::time::Duration::from_secs(
+                        args.db_pool_acquire_timeout_secs,
+                    ),
+                    pool_idle_timeout: args
+                        .db_pool_idle_timeout_secs
+                        .map(std::time::Duration::from_secs),
+                    pool_max_lifetime: args
+                        .db_pool_max_lifetime_secs
+                        .map(std::time::Duration::from_secs),
+                    pool_test_before_acquire: args.db_pool_test_before_acquire,
+                    replica_urls: args.db_replica_urls.clone(),
+                    connect_retry_initial_backoff: std::time::Duration::from_millis(
+                        args.db_connect_retry_initial_backoff_ms,
+                    ),
+                    connect_retry_max_backoff: std::time::Duration::from_secs(
+                        args.db_connect_retry_max_backoff_secs,
+                    ),
+                    connect_retry_max_elapsed: std::time::Duration::from_secs(
+                        args.db_connect_retry_max_elapsed_secs,
+                    ),
                 },
+                load_auth_vars(),
+                tls,
             );
 
             let mut signals = Signals::new([SIGINT]).map_err(|e| e.to_string())?;
@@ -114,27 +409,72 @@ fn run(startup_time: &Instant) -> Result<(), Box<dyn std::error::Error>> {
 
             server.start_and_wait(|| {
                 print::print_startup_info(
-                    args.host,
-                    args.port,
+                    host,
+                    port,
+                    is_tls,
                     &store_display_name,
                     &get_version(),
                     startup_time,
                 );
             })?;
         }
+        Commands::Migrate(args) => {
+            let config_file = args
+                .config_file
+                .as_ref()
+                .map(params::load_config_file)
+                .transpose()?;
+            let vars = load_env_variables(config_file.as_ref())?;
+
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()?;
+
+            rt.block_on(async {
+                let pool = sqlx::postgres::PgPoolOptions::new()
+                    .connect(vars.repository_db_url.as_str())
+                    .await?;
+
+                match args.action {
+                    MigrateAction::Up { to } => {
+                        repo::migrate_up(&pool, to).await?;
+                        info!("migrations applied");
+                    }
+                    MigrateAction::Down { to } => {
+                        repo::migrate_down(&pool, to).await?;
+                        info!("reverted migrations down to version {to}");
+                    }
+                    MigrateAction::Status => {
+                        for status in repo::migration_status(&pool).await? {
+                            println!(
+                                "{:>14} {:<7} {}",
+                                status.id,
+                                if status.applied { "applied" } else { "pending" },
+                                status.name,
+                            );
+                        }
+                    }
+                }
+
+                Ok::<(), Box<dyn std::error::Error>>(())
+            })?;
+        }
     }
 
     Ok(())
 }
 
-fn get_store(cmds: &CommandRun) -> Result<store::StoreRef, Box<dyn std::error::Error>> {
+fn get_store(
+    cmds: &CommandRun,
+    config_file: Option<&params::ConfigFile>,
+) -> Result<store::StoreRef, Box<dyn std::error::Error>> {
     if let Some(path) = &cmds.local_store {
         info!("initializing filesystem store");
         Ok(Arc::new(store::Store::try_from_filesystem(path)?))
     } else {
         info!("initializing s3-compatible store");
 
-        let s3_config = load_remote_store_vars()?;
+        let s3_config = load_remote_store_vars(config_file)?;
 
         let store = Arc::new(store::Store::try_from_s3_store(s3_config)?);
 
@@ -164,6 +504,26 @@ fn get_store_display_name(store: &store::StoreRef) -> String {
                 "]".dimmed(),
             )
         }
+        store::StoreTarget::Azure(container) => {
+            format!(
+                "{}{} {}{}{}",
+                "az://".yellow(),
+                container.yellow(),
+                "[".dimmed(),
+                "remote".cyan(),
+                "]".dimmed(),
+            )
+        }
+        store::StoreTarget::Gcs(bucket) => {
+            format!(
+                "{}{} {}{}{}",
+                "gs://".yellow(),
+                bucket.yellow(),
+                "[".dimmed(),
+                "remote".cyan(),
+                "]".dimmed(),
+            )
+        }
     }
 }
 
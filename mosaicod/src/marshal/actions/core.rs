@@ -18,6 +18,10 @@ pub enum ActionError {
     /// Failed to serialize the response.
     #[error("response serialization error: {0}")]
     ResponseSerializationError(String),
+
+    /// A [`requests::Batch`] contained a sub-action of type `"batch"`.
+    #[error("batch actions cannot be nested")]
+    NestedBatch,
 }
 
 /// Represents the list of actions allowed in the system.
@@ -88,6 +92,27 @@ pub enum ActionRequest {
     /// Ask for system informations about the topic
     TopicSystemInfo(requests::ResourceLocator),
 
+    /// Poll the checkpoint and progress of a topic's upload job.
+    TopicUploadStatus(requests::ResourceLocator),
+
+    /// Recomputes every chunk's digest from the bytes actually on disk and
+    /// reports mismatches, missing files, and drift against the topic's
+    /// recorded upload size.
+    TopicVerify(requests::ResourceLocator),
+
+    /// Merges a locked topic's small data files into fewer, larger ones,
+    /// deferred to the job queue since rewriting every chunk can take a
+    /// while on a topic with a long upload history.
+    TopicCompact(requests::ResourceLocator),
+
+    /// Long-polls for topic lifecycle/metadata changes, as an alternative
+    /// to busy-polling [`Self::Query`]/[`Self::TopicSystemInfo`].
+    Watch(requests::Watch),
+
+    /// Polls the status of a job previously queued by an action that
+    /// returned [`responses::JobAccepted`].
+    JobStatus(requests::JobStatus),
+
     Query(requests::Query),
 
     /// Creates a new layer in the repository
@@ -101,6 +126,23 @@ pub enum ActionRequest {
 
     /// Ask for the list of existing layers in the system
     LayerList(requests::Empty),
+
+    /// Runs several actions from a single call, either failing the whole
+    /// batch on the first error (`requests::BatchMode::FailFast`) or running
+    /// every action independently and reporting each outcome
+    /// (`requests::BatchMode::BestEffort`).
+    ///
+    /// `FailFast` stops the batch early but does **not** undo the side
+    /// effects of sub-actions that already completed -- see the
+    /// `BatchMode::FailFast` doc comment for why.
+    ///
+    /// A batch itself cannot appear among its own actions;
+    /// [`ActionRequest::try_new`] rejects that with [`ActionError::NestedBatch`].
+    Batch(requests::BatchMode, Vec<ActionRequest>),
+
+    /// Runs an online consistency scan reconciling the chunk catalog
+    /// against the store, and the sequence/topic catalog against itself.
+    Repair(requests::Repair),
 }
 
 /// Internal macro used to parse action requests
@@ -125,6 +167,9 @@ impl ActionRequest {
             "topic_create" => parse_action_req!(TopicCreate, body),
             "topic_delete" => parse_action_req!(TopicDelete, body),
             "topic_system_info" => parse_action_req!(TopicSystemInfo, body),
+            "topic_upload_status" => parse_action_req!(TopicUploadStatus, body),
+            "topic_verify" => parse_action_req!(TopicVerify, body),
+            "topic_compact" => parse_action_req!(TopicCompact, body),
             "topic_notify_create" => parse_action_req!(TopicNotifyCreate, body),
             "topic_notify_list" => parse_action_req!(TopicNotifyList, body),
             "topic_notify_purge" => parse_action_req!(TopicNotifyPurge, body),
@@ -134,11 +179,67 @@ impl ActionRequest {
             "layer_update" => parse_action_req!(LayerUpdate, body),
             "layer_list" => parse_action_req!(LayerList, body),
 
+            "watch" => parse_action_req!(Watch, body),
+            "job_status" => parse_action_req!(JobStatus, body),
+
             "query" => parse_action_req!(Query, body),
 
+            "repair" => parse_action_req!(Repair, body),
+
+            "batch" => {
+                let batch: requests::Batch = serde_json::from_slice(body)?;
+                let actions = batch
+                    .actions
+                    .into_iter()
+                    .map(|item| {
+                        if item.r#type == "batch" {
+                            return Err(ActionError::NestedBatch);
+                        }
+                        let item_body = serde_json::to_vec(&item.body)?;
+                        Self::try_new(&item.r#type, &item_body)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(ActionRequest::Batch(batch.mode, actions))
+            }
+
             _ => Err(ActionError::MissingAction(value.to_string())),
         }
     }
+
+    /// The action-type string this request was parsed from (or, for
+    /// [`ActionRequest::Batch`], `"batch"` itself — its sub-actions are
+    /// labeled individually as they're dispatched). Used to label metrics
+    /// per action, independent of resource kind.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SequenceCreate(_) => "sequence_create",
+            Self::SequenceDelete(_) => "sequence_delete",
+            Self::SequenceAbort(_) => "sequence_abort",
+            Self::SequenceSystemInfo(_) => "sequence_system_info",
+            Self::SequenceFinalize(_) => "sequence_finalize",
+            Self::SequenceNotifyCreate(_) => "sequence_notify_create",
+            Self::SequenceNotifyList(_) => "sequence_notify_list",
+            Self::SequenceNotifyPurge(_) => "sequence_notify_purge",
+            Self::TopicCreate(_) => "topic_create",
+            Self::TopicDelete(_) => "topic_delete",
+            Self::TopicNotifyCreate(_) => "topic_notify_create",
+            Self::TopicNotifyList(_) => "topic_notify_list",
+            Self::TopicNotifyPurge(_) => "topic_notify_purge",
+            Self::TopicSystemInfo(_) => "topic_system_info",
+            Self::TopicUploadStatus(_) => "topic_upload_status",
+            Self::TopicVerify(_) => "topic_verify",
+            Self::TopicCompact(_) => "topic_compact",
+            Self::Watch(_) => "watch",
+            Self::JobStatus(_) => "job_status",
+            Self::Query(_) => "query",
+            Self::LayerCreate(_) => "layer_create",
+            Self::LayerDelete(_) => "layer_delete",
+            Self::LayerUpdate(_) => "layer_update",
+            Self::LayerList(_) => "layer_list",
+            Self::Batch(..) => "batch",
+            Self::Repair(_) => "repair",
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -150,12 +251,34 @@ pub enum ActionResponse {
 
     TopicCreate(responses::ResourceKey),
     TopicSystemInfo(responses::TopicSystemInfo),
+    TopicUploadStatus(responses::TopicUploadStatus),
+    TopicVerify(responses::ChunkVerifySummary),
     TopicNotifyList(responses::NotifyList),
+    Watch(responses::WatchResult),
 
     LayerList(responses::LayerList),
 
     Query(responses::Query),
 
+    /// The action's heavy work was queued on the background job queue
+    /// rather than run inline; carries the id of the queued job.
+    JobAccepted(responses::JobAccepted),
+
+    /// The current state of a job looked up by [`super::ActionRequest::JobStatus`].
+    JobStatus(responses::JobStatus),
+
+    /// Per-action outcome of a [`super::ActionRequest::Batch`], in the same
+    /// order as the request's actions. In `fail_fast` mode every entry is
+    /// `Ok` (the response returns early with an error as soon as one
+    /// sub-action fails, and earlier sub-actions' side effects are **not**
+    /// rolled back -- see `requests::BatchMode::FailFast`); in `best_effort`
+    /// mode a failing sub-action is reported in place instead of aborting
+    /// the rest.
+    Batch(Vec<Result<ActionResponse, responses::BatchItemError>>),
+
+    /// The outcome of a [`super::ActionRequest::Repair`] scan.
+    Repair(responses::RepairSummary),
+
     // Empty response, no data to send
     Empty,
 }
@@ -27,6 +27,10 @@ pub struct TopicCreate {
     pub sequence_key: String,
     pub serialization_format: rw::Format,
     pub ontology_tag: String,
+    /// When `true`, the topic's chunks are encrypted at rest with a key
+    /// derived from the server's `MOSAICO_MASTER_KEY`.
+    #[serde(default)]
+    pub encrypted: bool,
 
     user_metadata: serde_json::Value,
 }
@@ -86,3 +90,84 @@ pub struct Query {
     #[serde(flatten)]
     pub query: serde_json::Value,
 }
+
+/// Runs an online consistency scan reconciling the chunk catalog against
+/// the store, and the sequence/topic catalog against itself.
+#[derive(Deserialize, Debug)]
+pub struct Repair {
+    /// When `true`, anomalies are reported but nothing is deleted. Defaults
+    /// to `true` when omitted, so a scan is safe to run without first
+    /// reading the code that handles it.
+    #[serde(default = "Repair::default_dry_run")]
+    pub dry_run: bool,
+}
+
+impl Repair {
+    fn default_dry_run() -> bool {
+        true
+    }
+}
+
+/// Polls the status of a job previously queued by an action that returned
+/// [`super::responses::JobAccepted`].
+#[derive(Deserialize, Debug)]
+pub struct JobStatus {
+    pub job_id: String,
+}
+
+/// Long-polls for topic lifecycle/metadata changes matching `query` (the
+/// same `sequence`/`topic`/`ontology` shape accepted by
+/// [`Query`]/`do_exchange`'s subscribe command), resuming from
+/// `since_token` (`0` to watch starting now).
+#[derive(Deserialize, Debug)]
+pub struct Watch {
+    #[serde(flatten)]
+    pub query: serde_json::Value,
+    #[serde(default)]
+    pub since_token: u64,
+    /// Maximum time, in milliseconds, to block waiting for a matching
+    /// change before returning the unchanged token so the caller can
+    /// immediately re-issue the call.
+    #[serde(default = "Watch::default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Watch {
+    fn default_timeout_ms() -> u64 {
+        30_000
+    }
+}
+
+/// A single sub-action inside a [`Batch`], carrying its own action type
+/// string and JSON body the same way the top-level `do_action` request does.
+#[derive(Deserialize, Debug)]
+pub struct BatchItem {
+    pub r#type: String,
+    pub body: serde_json::Value,
+}
+
+/// How a [`Batch`] handles a sub-action failing partway through.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// Fail the whole batch as soon as one sub-action fails, instead of
+    /// returning partial results.
+    ///
+    /// This is fail-fast only, **not** a transaction: each sub-action that
+    /// already ran before the failing one keeps whatever it committed. There
+    /// is no rollback of completed side effects, because each sub-action's
+    /// `Facade*` call opens and commits its own repository transaction
+    /// independently. Callers that need an all-or-nothing guarantee cannot
+    /// get one from this mode today.
+    FailFast,
+    /// Run every sub-action independently and report each one's outcome,
+    /// without letting one failure stop the rest.
+    BestEffort,
+}
+
+/// Runs several actions from a single `do_action` call.
+#[derive(Deserialize, Debug)]
+pub struct Batch {
+    pub mode: BatchMode,
+    pub actions: Vec<BatchItem>,
+}
@@ -1,5 +1,6 @@
 use serde::Serialize;
 
+use crate::repo;
 use crate::types::{self, Resource};
 
 /// Generic response message used to provide to clients the key
@@ -28,6 +29,9 @@ pub struct TopicSystemInfo {
     pub is_locked: bool,
     /// Datetime of the topic creation
     pub created_datetime: String,
+    /// Root of the Merkle tree over the topic's chunk digests, in hex.
+    /// `None` if the topic has no chunks yet.
+    pub merkle_root: Option<String>,
 }
 
 impl From<types::TopicSystemInfo> for TopicSystemInfo {
@@ -37,6 +41,29 @@ impl From<types::TopicSystemInfo> for TopicSystemInfo {
             total_size_bytes: value.total_size_bytes,
             is_locked: value.is_locked,
             created_datetime: value.created_datetime.to_string(),
+            merkle_root: value.merkle_root,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct TopicUploadStatus {
+    pub state: String,
+    /// Number of chunks successfully finalized and durably persisted so far.
+    pub chunks_written: usize,
+    /// Cumulative bytes written across those chunks.
+    pub bytes_written: usize,
+    /// Path of the most recently written chunk, if any.
+    pub current_file: Option<String>,
+}
+
+impl From<types::TopicUploadStatus> for TopicUploadStatus {
+    fn from(value: types::TopicUploadStatus) -> Self {
+        Self {
+            state: value.state.to_string(),
+            chunks_written: value.chunks_written,
+            bytes_written: value.bytes_written,
+            current_file: value.current_file,
         }
     }
 }
@@ -122,6 +149,120 @@ impl From<Vec<types::Layer>> for LayerList {
     }
 }
 
+/// Response returned for actions whose heavy work is deferred to the
+/// background job queue instead of running inline (e.g. deleting a
+/// sequence with thousands of chunks).
+///
+/// The client can poll the job's status out-of-band; the action itself
+/// only confirms that the work was durably queued.
+#[derive(Serialize, Debug)]
+pub struct JobAccepted {
+    pub job_id: String,
+}
+
+impl From<uuid::Uuid> for JobAccepted {
+    fn from(value: uuid::Uuid) -> Self {
+        Self {
+            job_id: value.to_string(),
+        }
+    }
+}
+
+/// Response to a [`super::ActionRequest::JobStatus`] poll.
+///
+/// `retries` is `None` once the job is `"done_or_unknown"` -- the queue
+/// table has no row to report a retry count from once a job is gone, and a
+/// never-existed id looks identical to a completed one.
+#[derive(Serialize, Debug)]
+pub struct JobStatus {
+    pub state: String,
+    pub retries: Option<i32>,
+}
+
+impl From<repo::JobState> for JobStatus {
+    fn from(value: repo::JobState) -> Self {
+        match value {
+            repo::JobState::Pending { retries } => Self {
+                state: "pending".to_string(),
+                retries: Some(retries),
+            },
+            repo::JobState::Running { retries } => Self {
+                state: "running".to_string(),
+                retries: Some(retries),
+            },
+            repo::JobState::DoneOrUnknown => Self {
+                state: "done_or_unknown".to_string(),
+                retries: None,
+            },
+        }
+    }
+}
+
+/// The failure half of one sub-action's outcome inside a
+/// [`super::ActionResponse::Batch`].
+///
+/// `code` mirrors the stable identity a top-level failure would carry in
+/// its `tonic::Status` (see `server::errors::ErrCode`), without `marshal`
+/// depending on the server layer's error types directly.
+#[derive(Serialize, Debug)]
+pub struct BatchItemError {
+    pub code: String,
+    pub message: String,
+}
+
+/// Anomalies found (and, unless the scan ran with `dry_run = true`,
+/// already repaired) by a [`super::ActionRequest::Repair`] scan.
+#[derive(Serialize, Debug)]
+pub struct RepairSummary {
+    pub orphaned_data_files: Vec<String>,
+    pub missing_data_files: Vec<String>,
+    pub misplaced_topics: Vec<String>,
+    pub empty_sequences: Vec<String>,
+    pub reclaimed_chunk_refs: Vec<String>,
+}
+
+impl From<types::RepairReport> for RepairSummary {
+    fn from(value: types::RepairReport) -> Self {
+        Self {
+            orphaned_data_files: value.orphaned_data_files,
+            missing_data_files: value.missing_data_files,
+            misplaced_topics: value.misplaced_topics,
+            empty_sequences: value.empty_sequences,
+            reclaimed_chunk_refs: value.reclaimed_chunk_refs,
+        }
+    }
+}
+
+/// Anomalies found by a [`super::ActionRequest::TopicVerify`] scan, with
+/// offending chunks named by uuid so an operator can cross-reference them
+/// with the failure notify the scan also recorded.
+#[derive(Serialize, Debug)]
+pub struct ChunkVerifySummary {
+    pub missing_chunks: Vec<String>,
+    pub digest_mismatches: Vec<String>,
+    pub unsupported_digest_algo: Vec<String>,
+    pub size_drift: Option<(usize, usize)>,
+}
+
+impl From<types::ChunkVerifyReport> for ChunkVerifySummary {
+    fn from(value: types::ChunkVerifyReport) -> Self {
+        Self {
+            missing_chunks: value.missing_chunks.iter().map(ToString::to_string).collect(),
+            digest_mismatches: value
+                .digest_mismatches
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            unsupported_digest_algo: value
+                .unsupported_digest_algo
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            size_drift: value.size_drift,
+        }
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct ResponseQueryItem {
     pub sequence: String,
@@ -149,3 +290,37 @@ impl From<Vec<types::SequenceTopicGroup>> for Query {
         }
     }
 }
+
+/// One change observed by a [`super::ActionRequest::Watch`] call.
+#[derive(Serialize, Debug)]
+pub struct ResponseWatchEvent {
+    pub topic_name: String,
+    pub kind: String,
+}
+
+impl From<repo::watch::WatchEvent> for ResponseWatchEvent {
+    fn from(value: repo::watch::WatchEvent) -> Self {
+        Self {
+            topic_name: value.topic_name,
+            kind: value.kind.to_string(),
+        }
+    }
+}
+
+/// The outcome of a [`super::ActionRequest::Watch`] long-poll: the token to
+/// resume from on the next call, and whatever changes (if any) were
+/// observed before it returned.
+#[derive(Serialize, Debug)]
+pub struct WatchResult {
+    pub token: u64,
+    pub changes: Vec<ResponseWatchEvent>,
+}
+
+impl From<(u64, Vec<repo::watch::WatchEvent>)> for WatchResult {
+    fn from((token, changes): (u64, Vec<repo::watch::WatchEvent>)) -> Self {
+        Self {
+            token,
+            changes: changes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
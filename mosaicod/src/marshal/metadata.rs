@@ -1,5 +1,6 @@
 use crate::rw;
 use crate::types::{self, MetadataBlob, MetadataError};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -143,6 +144,8 @@ impl TryInto<Vec<u8>> for JsonTopicMetadata {
 pub struct JsonTopicProperties {
     pub serialization_format: rw::Format,
     pub ontology_tag: String,
+    #[serde(default)]
+    pub encryption: Option<JsonEncryptionInfo>,
 }
 
 impl From<JsonTopicProperties> for types::TopicProperties {
@@ -150,6 +153,7 @@ impl From<JsonTopicProperties> for types::TopicProperties {
         Self {
             serialization_format: value.serialization_format,
             ontology_tag: value.ontology_tag,
+            encryption: value.encryption.map(Into::into),
         }
     }
 }
@@ -159,6 +163,227 @@ impl From<types::TopicProperties> for JsonTopicProperties {
         Self {
             serialization_format: value.serialization_format,
             ontology_tag: value.ontology_tag,
+            encryption: value.encryption.map(Into::into),
         }
     }
 }
+
+/// Mirror of [`types::EncryptionInfo`] for the JSON metadata sidecar.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JsonEncryptionInfo {
+    pub key_id: String,
+    pub algorithm: String,
+}
+
+impl From<JsonEncryptionInfo> for types::EncryptionInfo {
+    fn from(value: JsonEncryptionInfo) -> Self {
+        Self {
+            key_id: value.key_id,
+            algorithm: value.algorithm,
+        }
+    }
+}
+
+impl From<types::EncryptionInfo> for JsonEncryptionInfo {
+    fn from(value: types::EncryptionInfo) -> Self {
+        Self {
+            key_id: value.key_id,
+            algorithm: value.algorithm,
+        }
+    }
+}
+
+/// Compact mirror of [`JsonMetadataBlob`], serializing the same
+/// self-describing value to MessagePack instead of JSON, for topics whose
+/// [`JsonTopicProperties::serialization_format`] selects a binary format
+/// and would rather not pay JSON's text overhead on every object-metadata
+/// header and sidecar.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MsgPackMetadataBlob(serde_json::Value);
+
+impl MetadataBlob for MsgPackMetadataBlob {
+    /// MessagePack is binary, so the string form used for object-metadata
+    /// headers is base64-encoded rather than the raw bytes.
+    fn try_to_string(&self) -> Result<String, Error> {
+        let bytes = self.to_bytes()?;
+        Ok(BASE64.encode(bytes))
+    }
+
+    #[allow(refining_impl_trait)]
+    fn try_from_str(v: &str) -> Result<MsgPackMetadataBlob, Error> {
+        let bytes = BASE64
+            .decode(v)
+            .map_err(|e| Error::DeserializationError(e.to_string()))?;
+        Ok(MsgPackMetadataBlob(
+            rmp_serde::from_slice(&bytes).map_err(|e| Error::DeserializationError(e.to_string()))?,
+        ))
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        rmp_serde::to_vec(&self.0).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+}
+
+impl From<MsgPackMetadataBlob> for serde_json::Value {
+    fn from(value: MsgPackMetadataBlob) -> Self {
+        value.0
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MsgPackSequenceMetadata {
+    pub user_metadata: MsgPackMetadataBlob,
+}
+
+impl From<MsgPackSequenceMetadata> for types::SequenceMetadata<MsgPackMetadataBlob> {
+    fn from(value: MsgPackSequenceMetadata) -> Self {
+        Self {
+            user_metadata: value.user_metadata,
+        }
+    }
+}
+
+impl From<types::SequenceMetadata<MsgPackMetadataBlob>> for MsgPackSequenceMetadata {
+    fn from(value: types::SequenceMetadata<MsgPackMetadataBlob>) -> Self {
+        Self {
+            user_metadata: value.user_metadata,
+        }
+    }
+}
+
+impl TryFrom<Vec<u8>> for MsgPackSequenceMetadata {
+    type Error = Error;
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        rmp_serde::from_slice(&bytes).map_err(|e| Error::DeserializationError(e.to_string()))
+    }
+}
+
+impl TryInto<Vec<u8>> for MsgPackSequenceMetadata {
+    type Error = Error;
+    fn try_into(self) -> Result<Vec<u8>, Self::Error> {
+        rmp_serde::to_vec(&self).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+}
+
+impl MsgPackSequenceMetadata {
+    /// Converts the metadata into a flattened [`HashMap`] representation.
+    pub fn to_flat_hashmap(self) -> Result<HashMap<String, String>, MetadataError> {
+        Ok(HashMap::from([
+            (
+                "mosaico:context".to_string(), //
+                "sequence".into(),
+            ),
+            (
+                "mosaico:user_metadata".to_string(),
+                self.user_metadata.try_to_string()?,
+            ),
+        ]))
+    }
+
+    /// Converts to the JSON mirror, for callers that decode a sidecar
+    /// without knowing ahead of time which codec wrote it -- see
+    /// [`decode_sequence_metadata`].
+    fn into_json(self) -> JsonSequenceMetadata {
+        JsonSequenceMetadata {
+            user_metadata: JsonMetadataBlob(self.user_metadata.into()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MsgPackTopicMetadata {
+    pub properties: JsonTopicProperties,
+    pub user_metadata: MsgPackMetadataBlob,
+}
+
+impl MsgPackTopicMetadata {
+    pub fn to_flat_hashmap(self) -> Result<HashMap<String, String>, MetadataError> {
+        Ok(HashMap::from([
+            (
+                "mosaico:context".into(), //
+                "topic".into(),
+            ),
+            (
+                "mosaico:properties".to_string(),
+                serde_json::to_string(&self.properties)
+                    .map_err(|e| Error::SerializationError(e.to_string()))?,
+            ),
+            (
+                "mosaico:user_metadata".to_string(),
+                self.user_metadata.try_to_string()?,
+            ),
+        ]))
+    }
+
+    /// Converts to the JSON mirror, for callers that decode a sidecar
+    /// without knowing ahead of time which codec wrote it -- see
+    /// [`decode_topic_metadata`].
+    fn into_json(self) -> JsonTopicMetadata {
+        JsonTopicMetadata {
+            properties: self.properties,
+            user_metadata: JsonMetadataBlob(self.user_metadata.into()),
+        }
+    }
+}
+
+impl From<MsgPackTopicMetadata> for types::TopicMetadata<MsgPackMetadataBlob> {
+    fn from(v: MsgPackTopicMetadata) -> Self {
+        Self {
+            user_metadata: v.user_metadata,
+            properties: v.properties.into(),
+        }
+    }
+}
+
+impl From<types::TopicMetadata<MsgPackMetadataBlob>> for MsgPackTopicMetadata {
+    fn from(value: types::TopicMetadata<MsgPackMetadataBlob>) -> Self {
+        Self {
+            user_metadata: value.user_metadata,
+            properties: JsonTopicProperties::from(value.properties),
+        }
+    }
+}
+
+impl TryFrom<Vec<u8>> for MsgPackTopicMetadata {
+    type Error = Error;
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        rmp_serde::from_slice(&bytes).map_err(|e| Error::DeserializationError(e.to_string()))
+    }
+}
+
+impl TryInto<Vec<u8>> for MsgPackTopicMetadata {
+    type Error = Error;
+    fn try_into(self) -> Result<Vec<u8>, Self::Error> {
+        rmp_serde::to_vec(&self).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+}
+
+/// Decodes a sequence-metadata sidecar written by either codec:
+/// [`JsonSequenceMetadata`] (the long-standing default) is tried first,
+/// falling back to [`MsgPackSequenceMetadata`], so readers don't need to
+/// know in advance which one a given sidecar was written with.
+pub fn decode_sequence_metadata(
+    bytes: &[u8],
+) -> Result<types::SequenceMetadata<JsonMetadataBlob>, MetadataError> {
+    if let Ok(json) = serde_json::from_slice::<JsonSequenceMetadata>(bytes) {
+        return Ok(json.into());
+    }
+
+    let msgpack: MsgPackSequenceMetadata =
+        rmp_serde::from_slice(bytes).map_err(|e| Error::DeserializationError(e.to_string()))?;
+    Ok(msgpack.into_json().into())
+}
+
+/// Decodes a topic-metadata sidecar written by either codec: see
+/// [`decode_sequence_metadata`].
+pub fn decode_topic_metadata(
+    bytes: &[u8],
+) -> Result<types::TopicMetadata<JsonMetadataBlob>, MetadataError> {
+    if let Ok(json) = serde_json::from_slice::<JsonTopicMetadata>(bytes) {
+        return Ok(json.into());
+    }
+
+    let msgpack: MsgPackTopicMetadata =
+        rmp_serde::from_slice(bytes).map_err(|e| Error::DeserializationError(e.to_string()))?;
+    Ok(msgpack.into_json().into())
+}
@@ -37,6 +37,11 @@ impl TryInto<query::Float> for Value {
     fn try_into(self) -> Result<query::Float, Self::Error> {
         match self {
             Value::Float(v) => Ok(v),
+            // A JSON integer literal is also a valid float literal.
+            Value::Integer(v) => Ok(v as f64),
+            // Allow numeric values quoted as strings (e.g. produced by clients
+            // that serialize everything as text).
+            Value::Text(v) => v.parse().map_err(|_| Self::Error::WrongType),
             _ => Err(Self::Error::WrongType),
         }
     }
@@ -47,6 +52,7 @@ impl TryInto<query::Integer> for Value {
     fn try_into(self) -> Result<query::Integer, Self::Error> {
         match self {
             Value::Integer(v) => Ok(v),
+            Value::Text(v) => v.parse().map_err(|_| Self::Error::WrongType),
             _ => Err(Self::Error::WrongType),
         }
     }
@@ -56,12 +62,37 @@ impl TryInto<query::Timestamp> for Value {
     type Error = query::OpError;
     fn try_into(self) -> Result<query::Timestamp, Self::Error> {
         match self {
+            // A bare integer is interpreted as milliseconds since the Unix epoch.
             Value::Integer(v) => Ok(v.into()),
+            Value::Text(v) => parse_timestamp_text(&v),
             _ => Err(Self::Error::WrongType),
         }
     }
 }
 
+/// Coerces a textual timestamp literal into a [`query::Timestamp`] (milliseconds
+/// since the Unix epoch), accepting the formats clients commonly send:
+/// - RFC 3339 (e.g. `2024-05-01T12:00:00Z`)
+/// - A bare date (`2024-05-01`, interpreted as midnight UTC)
+/// - A decimal string of milliseconds since the epoch
+fn parse_timestamp_text(v: &str) -> Result<query::Timestamp, query::OpError> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(v) {
+        return Ok(query::Timestamp::from(dt.timestamp_millis()));
+    }
+
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d") {
+        let dt = date
+            .and_hms_opt(0, 0, 0)
+            .ok_or(query::OpError::WrongType)?
+            .and_utc();
+        return Ok(query::Timestamp::from(dt.timestamp_millis()));
+    }
+
+    v.parse::<i64>()
+        .map(query::Timestamp::from)
+        .map_err(|_| query::OpError::WrongType)
+}
+
 #[derive(Debug, Deserialize)]
 enum Op {
     #[serde(rename = "$eq")]
@@ -86,6 +117,14 @@ enum Op {
     In(Vec<Value>),
     #[serde(rename = "$match")]
     Match(Value),
+    #[serde(rename = "$nin")]
+    Nin(Vec<Value>),
+    #[serde(rename = "$like")]
+    Like(Value),
+    #[serde(rename = "$ilike")]
+    Ilike(Value),
+    #[serde(rename = "$regex")]
+    Regex(Value),
 }
 
 impl TryInto<query::Op<query::Text>> for Op {
@@ -95,11 +134,15 @@ impl TryInto<query::Op<query::Text>> for Op {
         Ok(match self {
             Op::Eq(v) => query::Op::Eq(v.try_into()?),
             Op::Neq(v) => query::Op::Neq(v.try_into()?),
-            Op::Leq(_) => return Err(query::OpError::UnsupportedOperation),
-            Op::Geq(_) => return Err(query::OpError::UnsupportedOperation),
-            Op::Lt(_) => return Err(query::OpError::UnsupportedOperation),
-            Op::Gt(_) => return Err(query::OpError::UnsupportedOperation),
-            Op::Between(_) => return Err(query::OpError::UnsupportedOperation),
+            // Compared lexicographically, enabling prefix/key-range scans
+            // over names and ontology fields (e.g. everything under `image.`).
+            Op::Leq(v) => query::Op::Leq(v.try_into()?),
+            Op::Geq(v) => query::Op::Geq(v.try_into()?),
+            Op::Lt(v) => query::Op::Lt(v.try_into()?),
+            Op::Gt(v) => query::Op::Gt(v.try_into()?),
+            Op::Between([min, max]) => {
+                query::Op::Between(query::Range::try_new(min.try_into()?, max.try_into()?)?)
+            }
             Op::Ex => query::Op::Ex,
             Op::Nex => query::Op::Nex,
             Op::In(vec) => query::Op::In(
@@ -108,6 +151,14 @@ impl TryInto<query::Op<query::Text>> for Op {
                     .collect::<Result<_, _>>()?,
             ),
             Op::Match(v) => query::Op::Match(v.try_into()?),
+            Op::Nin(vec) => query::Op::Nin(
+                vec.into_iter()
+                    .map(|v| v.try_into())
+                    .collect::<Result<_, _>>()?,
+            ),
+            Op::Like(v) => query::Op::Like(v.try_into()?),
+            Op::Ilike(v) => query::Op::Ilike(v.try_into()?),
+            Op::Regex(v) => query::Op::Regex(v.try_into()?),
         })
     }
 }
@@ -133,6 +184,14 @@ impl TryInto<query::Op<query::Timestamp>> for Op {
                     .collect::<Result<_, _>>()?,
             ),
             Op::Match(_) => return Err(Self::Error::UnsupportedOperation),
+            Op::Nin(vec) => query::Op::Nin(
+                vec.into_iter()
+                    .map(|v| v.try_into())
+                    .collect::<Result<_, _>>()?,
+            ),
+            Op::Like(_) | Op::Ilike(_) | Op::Regex(_) => {
+                return Err(Self::Error::UnsupportedOperation)
+            }
         })
     }
 }
@@ -154,6 +213,10 @@ impl TryInto<query::Op<query::Value>> for Op {
             }
             Op::In(vec) => query::Op::In(vec.into_iter().map(Into::into).collect()),
             Op::Match(v) => query::Op::Match(v.into()),
+            Op::Nin(vec) => query::Op::Nin(vec.into_iter().map(Into::into).collect()),
+            Op::Like(v) => query::Op::Like(v.into()),
+            Op::Ilike(v) => query::Op::Ilike(v.into()),
+            Op::Regex(v) => query::Op::Regex(v.into()),
         })
     }
 }
@@ -202,6 +265,7 @@ struct Sequence {
     name: Option<Op>,
     created_timestamp: Option<Op>,
     user_metadata: Option<HashMap<String, Op>>,
+    since: Option<query::SyncToken>,
 }
 
 impl TryInto<query::SequenceFilter> for Sequence {
@@ -223,6 +287,7 @@ impl TryInto<query::SequenceFilter> for Sequence {
                     err: e,
                 })?,
             user_metadata: self.user_metadata.map(|v| v.try_into()).transpose()?,
+            since: self.since,
         })
     }
 }
@@ -234,6 +299,7 @@ pub struct Topic {
     ontology_tag: Option<Op>,
     serialization_format: Option<Op>,
     user_metadata: Option<HashMap<String, Op>>,
+    since: Option<query::SyncToken>,
 }
 
 impl TryInto<query::TopicFilter> for Topic {
@@ -276,24 +342,107 @@ impl TryInto<query::TopicFilter> for Topic {
                 })?,
 
             user_metadata: self.user_metadata.map(|v| v.try_into()).transpose()?,
+            since: self.since,
         })
     }
 }
 
-pub fn query_filter_from_string(s: &str) -> Result<query::Filter, super::Error> {
-    let query: Query =
+/// A recursive query expression: either a bare [`Query`] leaf, or one of the
+/// `$and`/`$or`/`$not` logical combinators wrapping further expressions.
+///
+/// Variants are tried in order, so a plain JSON object with none of the
+/// combinator keys falls through to `Leaf`, keeping today's flat object
+/// shape working unchanged.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Expr {
+    And {
+        #[serde(rename = "$and", deserialize_with = "deserialize_nonempty")]
+        and: Vec<Expr>,
+    },
+    Or {
+        #[serde(rename = "$or", deserialize_with = "deserialize_nonempty")]
+        or: Vec<Expr>,
+    },
+    Not {
+        #[serde(rename = "$not")]
+        not: Box<Expr>,
+    },
+    Leaf(Query),
+}
+
+/// Rejects an empty `$and`/`$or` array, which would otherwise lower into a
+/// vacuous (always-true or always-false) clause.
+fn deserialize_nonempty<'de, D>(deserializer: D) -> Result<Vec<Expr>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let exprs = Vec::<Expr>::deserialize(deserializer)?;
+    if exprs.is_empty() {
+        return Err(serde::de::Error::custom("expected a non-empty array"));
+    }
+    Ok(exprs)
+}
+
+impl TryInto<query::Expr> for Expr {
+    type Error = query::Error;
+
+    fn try_into(self) -> Result<query::Expr, Self::Error> {
+        Ok(match self {
+            Self::And { and } => query::Expr::And(
+                and.into_iter()
+                    .map(|e| e.try_into())
+                    .collect::<Result<_, _>>()?,
+            ),
+            Self::Or { or } => query::Expr::Or(
+                or.into_iter()
+                    .map(|e| e.try_into())
+                    .collect::<Result<_, _>>()?,
+            ),
+            Self::Not { not } => query::Expr::Not(Box::new((*not).try_into()?)),
+            Self::Leaf(query) => query::Expr::Leaf(query.try_into()?),
+        })
+    }
+}
+
+/// Parses a query expression that may wrap one or more [`query::Filter`]
+/// leaves in `$and`/`$or`/`$not` combinators, e.g.
+/// `{"$or": [{"topic": {...}}, {"$not": {"ontology": {...}}}]}`.
+pub fn query_expr_from_string(s: &str) -> Result<query::Expr, super::Error> {
+    let expr: Expr =
         serde_json::from_str(s).map_err(|e| super::Error::DeserializationError(e.to_string()))?;
-    let query: query::Filter = query
-        .try_into()
-        .map_err(|e: query::Error| super::Error::DeserializationError(e.to_string()))?;
-    Ok(query)
+    expr.try_into()
+        .map_err(|e: query::Error| super::Error::DeserializationError(e.to_string()))
 }
 
-pub fn query_filter_from_serde_value(v: serde_json::Value) -> Result<query::Filter, super::Error> {
-    let query: Query =
+/// Same as [`query_expr_from_string`], starting from an already-parsed
+/// [`serde_json::Value`].
+pub fn query_expr_from_serde_value(v: serde_json::Value) -> Result<query::Expr, super::Error> {
+    let expr: Expr =
         serde_json::from_value(v).map_err(|e| super::Error::DeserializationError(e.to_string()))?;
-    let query: query::Filter = query
-        .try_into()
-        .map_err(|e: query::Error| super::Error::DeserializationError(e.to_string()))?;
-    Ok(query)
+    expr.try_into()
+        .map_err(|e: query::Error| super::Error::DeserializationError(e.to_string()))
+}
+
+/// Parses a single, flat [`query::Filter`] (no `$and`/`$or`/`$not`
+/// combinators). Returns a [`super::Error::DeserializationError`] if the
+/// query is a compound expression.
+pub fn query_filter_from_string(s: &str) -> Result<query::Filter, super::Error> {
+    query_expr_from_string(s)?
+        .into_leaf()
+        .ok_or_else(compound_query_error)
+}
+
+/// Same as [`query_filter_from_string`], starting from an already-parsed
+/// [`serde_json::Value`].
+pub fn query_filter_from_serde_value(v: serde_json::Value) -> Result<query::Filter, super::Error> {
+    query_expr_from_serde_value(v)?
+        .into_leaf()
+        .ok_or_else(compound_query_error)
+}
+
+fn compound_query_error() -> super::Error {
+    super::Error::DeserializationError(
+        "compound ($and/$or/$not) queries are not supported here".to_string(),
+    )
 }
@@ -0,0 +1,296 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use prometheus::{Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, Registry};
+
+/// Every collector the process registers, plus the [`Registry`] they live
+/// in. Access via [`collectors()`]; never constructed directly outside this
+/// module.
+pub struct Metrics {
+    pub registry: Registry,
+
+    /// Count of `do_action` dispatches, labeled by action name (see
+    /// `marshal::ActionRequest::name`).
+    pub action_requests_total: IntCounterVec,
+    /// Latency of `do_action` dispatches, labeled by action name.
+    pub action_latency_seconds: HistogramVec,
+
+    /// Notifications created or purged, labeled by resource type
+    /// (`"sequence"`/`"topic"`) and operation (`"create"`/`"purge"`).
+    pub notify_total: IntCounterVec,
+    /// Lock transitions, labeled by resource type (`"sequence"`/`"topic"`).
+    /// There is no corresponding unlock counter: neither resource can be
+    /// unlocked once locked (see `repo::FacadeSequence::lock`).
+    pub lock_total: IntCounterVec,
+
+    /// `FacadeSequence` lifecycle events (`"create"`/`"lock"`/`"delete"`),
+    /// labeled by outcome (`"success"`/`"error"`).
+    pub sequence_lifecycle_total: IntCounterVec,
+
+    /// Latency of a `TimeseriesGw::read` call (table registration and
+    /// `optimal_batch_size` on a repartitioned read; excludes whatever the
+    /// caller does with the returned `TimeseriesGwResult` afterwards, since
+    /// filtering/streaming/counting are separate, lazily-executed steps).
+    pub query_read_latency_seconds: Histogram,
+    /// Rows returned by `TimeseriesGwResult::count`. Only the `count` path
+    /// is instrumented: the streaming path (`TimeseriesGwResult::stream`)
+    /// would need to tally every batch just for this metric, which isn't
+    /// worth the overhead on the hot Flight `do_get`/`do_exchange` path.
+    pub query_rows_returned_total: IntCounter,
+    /// Bytes `TimeseriesGw::optimal_batch_size` summed from cached Parquet
+    /// footer sizes, labeled by the store's backend scheme (`"file"`,
+    /// `"s3"`, `"az"`, `"gs"`) so S3 vs GCS traffic can be distinguished.
+    pub query_bytes_scanned_total: IntCounterVec,
+    /// Most recently computed `TimeseriesGw::optimal_batch_size`.
+    pub query_optimal_batch_size: IntGauge,
+
+    /// Topics remaining after the repo-side filter, for the most recently
+    /// completed query.
+    pub query_candidate_topics: IntGauge,
+    /// Chunks returned by `repo::chunks_from_filters`, for the most
+    /// recently completed query.
+    pub query_chunks_enumerated: IntGauge,
+    /// Chunks actually opened through `ts_engine.read`, for the most
+    /// recently completed query.
+    pub query_chunks_opened: IntGauge,
+    /// Chunks discarded for matching zero rows, for the most recently
+    /// completed query.
+    pub query_chunks_discarded: IntGauge,
+    /// Total records matched across every opened chunk, for the most
+    /// recently completed query. Compares against
+    /// `query_chunks_opened`/`query_chunks_enumerated` to spot queries that
+    /// open far more chunks than they match (scan amplification).
+    pub query_records_matched: IntGauge,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let action_requests_total = IntCounterVec::new(
+            prometheus::Opts::new(
+                "mosaico_action_requests_total",
+                "Number of do_action dispatches, by action name",
+            ),
+            &["action"],
+        )
+        .unwrap();
+
+        let action_latency_seconds = HistogramVec::new(
+            prometheus::HistogramOpts::new(
+                "mosaico_action_latency_seconds",
+                "do_action dispatch latency in seconds, by action name",
+            ),
+            &["action"],
+        )
+        .unwrap();
+
+        let notify_total = IntCounterVec::new(
+            prometheus::Opts::new(
+                "mosaico_notify_total",
+                "Notifications created or purged, by resource type and operation",
+            ),
+            &["resource", "op"],
+        )
+        .unwrap();
+
+        let lock_total = IntCounterVec::new(
+            prometheus::Opts::new(
+                "mosaico_lock_total",
+                "Lock transitions, by resource type",
+            ),
+            &["resource"],
+        )
+        .unwrap();
+
+        let sequence_lifecycle_total = IntCounterVec::new(
+            prometheus::Opts::new(
+                "mosaico_sequence_lifecycle_total",
+                "FacadeSequence lifecycle events, by operation and outcome",
+            ),
+            &["op", "outcome"],
+        )
+        .unwrap();
+
+        let query_read_latency_seconds = Histogram::with_opts(prometheus::HistogramOpts::new(
+            "mosaico_query_read_latency_seconds",
+            "TimeseriesGw::read latency in seconds",
+        ))
+        .unwrap();
+        let query_rows_returned_total = IntCounter::new(
+            "mosaico_query_rows_returned_total",
+            "Rows returned across completed TimeseriesGwResult::count calls",
+        )
+        .unwrap();
+        let query_bytes_scanned_total = IntCounterVec::new(
+            prometheus::Opts::new(
+                "mosaico_query_bytes_scanned_total",
+                "Bytes summed from cached Parquet footer sizes, by store backend",
+            ),
+            &["backend"],
+        )
+        .unwrap();
+        let query_optimal_batch_size = IntGauge::new(
+            "mosaico_query_optimal_batch_size",
+            "Most recently computed TimeseriesGw::optimal_batch_size",
+        )
+        .unwrap();
+
+        let query_candidate_topics = IntGauge::new(
+            "mosaico_query_candidate_topics",
+            "Topics remaining after the repo-side filter, for the last completed query",
+        )
+        .unwrap();
+        let query_chunks_enumerated = IntGauge::new(
+            "mosaico_query_chunks_enumerated",
+            "Chunks returned by chunks_from_filters, for the last completed query",
+        )
+        .unwrap();
+        let query_chunks_opened = IntGauge::new(
+            "mosaico_query_chunks_opened",
+            "Chunks actually opened through ts_engine.read, for the last completed query",
+        )
+        .unwrap();
+        let query_chunks_discarded = IntGauge::new(
+            "mosaico_query_chunks_discarded",
+            "Chunks discarded for matching zero rows, for the last completed query",
+        )
+        .unwrap();
+        let query_records_matched = IntGauge::new(
+            "mosaico_query_records_matched",
+            "Total records matched across every opened chunk, for the last completed query",
+        )
+        .unwrap();
+
+        for collector in [
+            Box::new(action_requests_total.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(action_latency_seconds.clone()),
+            Box::new(notify_total.clone()),
+            Box::new(lock_total.clone()),
+            Box::new(sequence_lifecycle_total.clone()),
+            Box::new(query_read_latency_seconds.clone()),
+            Box::new(query_rows_returned_total.clone()),
+            Box::new(query_bytes_scanned_total.clone()),
+            Box::new(query_optimal_batch_size.clone()),
+            Box::new(query_candidate_topics.clone()),
+            Box::new(query_chunks_enumerated.clone()),
+            Box::new(query_chunks_opened.clone()),
+            Box::new(query_chunks_discarded.clone()),
+            Box::new(query_records_matched.clone()),
+        ] {
+            registry.register(collector).unwrap();
+        }
+
+        Self {
+            registry,
+            action_requests_total,
+            action_latency_seconds,
+            notify_total,
+            lock_total,
+            sequence_lifecycle_total,
+            query_read_latency_seconds,
+            query_rows_returned_total,
+            query_bytes_scanned_total,
+            query_optimal_batch_size,
+            query_candidate_topics,
+            query_chunks_enumerated,
+            query_chunks_opened,
+            query_chunks_discarded,
+            query_records_matched,
+        }
+    }
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+/// Returns the process-wide [`Metrics`], creating and registering its
+/// collectors on first call.
+pub fn collectors() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::new)
+}
+
+/// Increments `mosaico_action_requests_total{action}` and returns a guard
+/// that observes `mosaico_action_latency_seconds{action}` with the elapsed
+/// time once dropped, however the dispatch returns (success, error, or an
+/// early `?`).
+pub fn start_action_timer(action: &'static str) -> ActionTimer {
+    collectors()
+        .action_requests_total
+        .with_label_values(&[action])
+        .inc();
+
+    ActionTimer {
+        action,
+        start: Instant::now(),
+    }
+}
+
+pub struct ActionTimer {
+    action: &'static str,
+    start: Instant,
+}
+
+impl Drop for ActionTimer {
+    fn drop(&mut self) {
+        collectors()
+            .action_latency_seconds
+            .with_label_values(&[self.action])
+            .observe(self.start.elapsed().as_secs_f64());
+    }
+}
+
+/// Increments `mosaico_notify_total{resource, op}`.
+pub fn record_notify(resource: &'static str, op: &'static str) {
+    collectors()
+        .notify_total
+        .with_label_values(&[resource, op])
+        .inc();
+}
+
+/// Increments `mosaico_lock_total{resource}`.
+pub fn record_lock(resource: &'static str) {
+    collectors().lock_total.with_label_values(&[resource]).inc();
+}
+
+/// Increments `mosaico_sequence_lifecycle_total{op, outcome}`, `outcome`
+/// derived from `success`.
+pub fn record_sequence_lifecycle(op: &'static str, success: bool) {
+    let outcome = if success { "success" } else { "error" };
+    collectors()
+        .sequence_lifecycle_total
+        .with_label_values(&[op, outcome])
+        .inc();
+}
+
+/// Returns a guard that observes `mosaico_query_read_latency_seconds` with
+/// the elapsed time once dropped, however `TimeseriesGw::read` returns.
+pub fn start_query_read_timer() -> QueryReadTimer {
+    QueryReadTimer {
+        start: Instant::now(),
+    }
+}
+
+pub struct QueryReadTimer {
+    start: Instant,
+}
+
+impl Drop for QueryReadTimer {
+    fn drop(&mut self) {
+        collectors()
+            .query_read_latency_seconds
+            .observe(self.start.elapsed().as_secs_f64());
+    }
+}
+
+/// Increments `mosaico_query_rows_returned_total` by `rows`.
+pub fn record_query_rows(rows: usize) {
+    collectors().query_rows_returned_total.inc_by(rows as u64);
+}
+
+/// Increments `mosaico_query_bytes_scanned_total{backend}` by `bytes`.
+pub fn record_bytes_scanned(backend: &'static str, bytes: usize) {
+    collectors()
+        .query_bytes_scanned_total
+        .with_label_values(&[backend])
+        .inc_by(bytes as u64);
+}
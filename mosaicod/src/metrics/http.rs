@@ -0,0 +1,80 @@
+//! A minimal `GET /metrics` endpoint for Prometheus to scrape.
+//!
+//! Deliberately hand-rolled instead of pulling in a web framework: this is
+//! the only HTTP surface mosaicod exposes (the rest is Arrow Flight/gRPC via
+//! `server::flight`), so a plain `TcpListener` loop that understands exactly
+//! one request line is enough.
+use log::{error, trace, warn};
+use prometheus::{Encoder, TextEncoder};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use super::collectors;
+
+/// Serves the process-wide metrics registry as `GET /metrics` on
+/// `host:port` until `shutdown` is notified.
+///
+/// Runs until cancelled; intended to be spawned alongside the Flight
+/// listener in `server::core::Server::start_and_wait`.
+pub async fn serve(
+    host: &str,
+    port: u16,
+    shutdown: std::sync::Arc<tokio::sync::Notify>,
+) -> Result<(), std::io::Error> {
+    let listener = TcpListener::bind((host, port)).await?;
+    trace!("metrics endpoint listening on {}:{}", host, port);
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (socket, _) = accepted?;
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(socket).await {
+                        warn!("metrics connection error: {}", e);
+                    }
+                });
+            }
+            _ = shutdown.notified() => {
+                trace!("metrics endpoint received shutdown notification");
+                return Ok(());
+            }
+        }
+    }
+}
+
+async fn handle_connection(mut socket: tokio::net::TcpStream) -> Result<(), std::io::Error> {
+    // A scrape request has no body worth reading, so a single read of the
+    // request line (plus whatever headers fit the buffer) is enough.
+    let mut buf = [0u8; 1024];
+    let n = socket.read(&mut buf).await?;
+    let request_line = String::from_utf8_lossy(&buf[..n]);
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+
+    let response = if path == "/metrics" {
+        match encode_metrics() {
+            Ok(body) => format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            ),
+            Err(e) => {
+                error!("failed to encode metrics: {}", e);
+                "HTTP/1.1 500 Internal Server Error\r\nConnection: close\r\n\r\n".to_string()
+            }
+        }
+    } else {
+        "HTTP/1.1 404 Not Found\r\nConnection: close\r\n\r\n".to_string()
+    };
+
+    socket.write_all(response.as_bytes()).await?;
+    socket.shutdown().await?;
+    Ok(())
+}
+
+fn encode_metrics() -> Result<String, prometheus::Error> {
+    let encoder = TextEncoder::new();
+    let families = collectors().registry.gather();
+    let mut buf = Vec::new();
+    encoder.encode(&families, &mut buf)?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
@@ -0,0 +1,16 @@
+//! Process-wide Prometheus metrics.
+//!
+//! [`collectors()`] returns the single process-wide [`Metrics`] instance,
+//! created (and registered into its [`prometheus::Registry`]) on first use.
+//! [`start_action_timer`] instruments a `do_action` dispatch; the per-query
+//! `query_candidate_topics`/`query_chunks_*`/`query_records_matched` gauges
+//! on [`Metrics`] are set directly from the `Query` match arm in
+//! `server::endpoints::do_action`, and the remaining `query_*` fields from
+//! `query::TimeseriesGw`. [`http::serve`] exposes the
+//! registry's current snapshot as `GET /metrics` for Prometheus to scrape.
+
+mod core;
+pub use core::*;
+
+mod http;
+pub use http::serve;
@@ -0,0 +1,109 @@
+//! Layered configuration: an optional TOML/YAML file merged with
+//! environment variables (env wins) and, on top of both, CLI flags (CLI
+//! wins over everything).
+//!
+//! The file is entirely optional; deployments that only set environment
+//! variables keep working unchanged. When given, it expresses the same
+//! config tree that used to require one environment variable per setting:
+//! `repository_db_url`, the object store block, and `host`/`port`.
+
+use std::{fmt, path::Path, str::FromStr};
+
+use serde::Deserialize;
+
+use super::{Error, Hidden};
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub repository_db_url: Option<String>,
+    pub host: Option<bool>,
+    pub port: Option<u16>,
+    pub store: Option<StoreSection>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct StoreSection {
+    pub endpoint: Option<String>,
+    pub bucket: Option<String>,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub region: Option<String>,
+}
+
+/// Reads and parses `path` as a [`ConfigFile`], dispatching on its
+/// extension (`.toml`, or `.yaml`/`.yml`).
+pub fn load_config_file(path: impl AsRef<Path>) -> Result<ConfigFile, Error> {
+    let path = path.as_ref();
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| Error::ConfigFileIo(path.to_path_buf(), e))?;
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&raw)
+            .map_err(|e| Error::ConfigFileParse(path.to_path_buf(), e.to_string())),
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&raw)
+            .map_err(|e| Error::ConfigFileParse(path.to_path_buf(), e.to_string())),
+        _ => Err(Error::UnsupportedConfigFileFormat(path.to_path_buf())),
+    }
+}
+
+/// Resolves a setting from, in priority order, a CLI flag, an environment
+/// variable, then the config file — the first `Some` wins.
+///
+/// This is [`super::require_env_var`] with an extra, lowest-priority layer:
+/// the config file can supply a default for whatever the environment
+/// doesn't set.
+pub fn layered<T: FromStr>(cli: Option<T>, env_var: &str, from_file: Option<T>) -> Result<T, Error>
+where
+    T::Err: fmt::Display,
+{
+    if let Some(v) = cli {
+        return Ok(v);
+    }
+
+    if let Ok(raw) = std::env::var(env_var) {
+        return raw
+            .parse()
+            .map_err(|e: T::Err| Error::BadEnvVar(env_var.to_string(), e.to_string()));
+    }
+
+    from_file.ok_or_else(|| Error::MissingEnvVar(env_var.to_string()))
+}
+
+/// Same as [`layered`], but wraps the resolved value in [`Hidden`] so it is
+/// redacted by a `debug!` dump — for secrets that may be sourced from the
+/// config file as plain text.
+pub fn layered_hidden(
+    cli: Option<String>,
+    env_var: &str,
+    from_file: Option<String>,
+) -> Result<Hidden, Error> {
+    layered(cli, env_var, from_file).map(Hidden::from)
+}
+
+/// Same as [`layered`], but for a setting that's genuinely optional: returns
+/// `None` instead of erroring when none of the three layers supply a value.
+pub fn layered_opt<T: FromStr>(cli: Option<T>, env_var: &str, from_file: Option<T>) -> Option<T>
+where
+    T::Err: fmt::Display,
+{
+    if let Some(v) = cli {
+        return Some(v);
+    }
+
+    if let Ok(raw) = std::env::var(env_var) {
+        return raw.parse().ok();
+    }
+
+    from_file
+}
+
+/// Same as [`layered_opt`], but wraps the resolved value in [`Hidden`].
+pub fn layered_opt_hidden(
+    cli: Option<String>,
+    env_var: &str,
+    from_file: Option<String>,
+) -> Option<Hidden> {
+    layered_opt(cli, env_var, from_file).map(Hidden::from)
+}
@@ -0,0 +1,163 @@
+use std::{fmt, str::FromStr, sync::OnceLock};
+
+use thiserror::Error;
+
+/// File extensions used when naming resource files on the [`crate::store::Store`].
+pub mod ext {
+    pub const JSON: &str = "json";
+    pub const PARQUET: &str = "parquet";
+    pub const ARROW: &str = "arrow";
+    pub const CSV: &str = "csv";
+}
+
+/// Name of the Arrow schema column mosaico treats as the ingestion timestamp.
+pub const ARROW_SCHEMA_COLUMN_NAME_TIMESTAMP: &str = "timestamp";
+
+/// Name and description of the layer every topic belongs to until it is
+/// explicitly reassigned, created on bootstrap by [`crate::repo::layer_bootstrap`].
+pub const DEFAULT_LAYER_NAME: &str = "default";
+pub const DEFAULT_LAYER_DESCRIPTION: &str = "default layer, automatically created on bootstrap";
+
+/// Tolerance used when comparing floating point column statistics.
+pub const EPSILON: f64 = 1e-9;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("missing required environment variable `{0}`")]
+    MissingEnvVar(String),
+    #[error("environment variable `{0}` could not be parsed: {1}")]
+    BadEnvVar(String, String),
+    #[error("config file `{0}` could not be read: {1}")]
+    ConfigFileIo(std::path::PathBuf, std::io::Error),
+    #[error("config file `{0}` could not be parsed: {1}")]
+    ConfigFileParse(std::path::PathBuf, String),
+    #[error("config file `{0}` has an unsupported extension, expected `.toml`, `.yaml` or `.yml`")]
+    UnsupportedConfigFileFormat(std::path::PathBuf),
+}
+
+/// Wraps a secret value so it is never printed in full by `{:?}`/`{:#?}`.
+///
+/// Used for credentials threaded through [`crate::store::S3Config`] and
+/// similar config structs that end up in a `debug!` dump at startup.
+#[derive(Clone)]
+pub struct Hidden(String);
+
+impl Hidden {
+    /// Consumes the wrapper, returning the secret value.
+    pub fn take(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for Hidden {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Debug for Hidden {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Hidden(\"***\")")
+    }
+}
+
+/// Runtime-tunable parameters, loaded once at startup by
+/// [`load_configurables_from_env`].
+#[derive(Debug, Clone, Copy)]
+pub struct Configurables {
+    /// Target size, in bytes, of a single Arrow Flight message when slicing
+    /// a query result into pages.
+    pub target_message_size_in_bytes: usize,
+    /// Maximum size, in bytes, of a single gRPC message tonic will
+    /// encode/decode, in either direction.
+    pub max_message_size_in_bytes: usize,
+    /// Maximum number of Parquet footers [`crate::query::ParquetFooterCache`]
+    /// keeps in memory at once, LRU-evicted beyond this bound.
+    pub parquet_footer_cache_capacity: usize,
+}
+
+impl Default for Configurables {
+    fn default() -> Self {
+        Self {
+            target_message_size_in_bytes: 2 * 1024 * 1024,
+            max_message_size_in_bytes: 16 * 1024 * 1024,
+            parquet_footer_cache_capacity: 256,
+        }
+    }
+}
+
+static CONFIGURABLES: OnceLock<Configurables> = OnceLock::new();
+
+/// Loads [`Configurables`] from the environment, falling back to their
+/// defaults for anything unset. Must be called once at startup, before the
+/// first call to [`configurables`]; later calls are no-ops.
+pub fn load_configurables_from_env() {
+    let mut vars = Configurables::default();
+
+    if let Ok(v) = env_var_opt("MOSAICO_TARGET_MESSAGE_SIZE_BYTES") {
+        vars.target_message_size_in_bytes = v;
+    }
+    if let Ok(v) = env_var_opt("MOSAICO_MAX_MESSAGE_SIZE_BYTES") {
+        vars.max_message_size_in_bytes = v;
+    }
+    if let Ok(v) = env_var_opt("MOSAICO_PARQUET_FOOTER_CACHE_CAPACITY") {
+        vars.parquet_footer_cache_capacity = v;
+    }
+
+    // Only the first caller's value wins; later calls just read it back.
+    let _ = CONFIGURABLES.set(vars);
+}
+
+/// Returns the process-wide [`Configurables`], loading defaults if
+/// [`load_configurables_from_env`] was never called (e.g. in tests).
+pub fn configurables() -> Configurables {
+    *CONFIGURABLES.get_or_init(Configurables::default)
+}
+
+fn env_var_opt<T: FromStr>(name: &str) -> Result<T, ()> {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .ok_or(())
+}
+
+static MASTER_KEY: OnceLock<Option<[u8; 32]>> = OnceLock::new();
+
+/// Reads the chunk-encryption master key from `MOSAICO_MASTER_KEY`, a 64
+/// character hex string (32 bytes), caching the result. Returns `None` if
+/// the variable is unset, meaning no topic may opt into encryption.
+///
+/// Like [`configurables`], this only reads the environment once; later
+/// calls (including from a different value in the environment) return the
+/// first-seen result.
+pub fn master_key() -> Option<[u8; 32]> {
+    *MASTER_KEY.get_or_init(|| {
+        let raw = std::env::var("MOSAICO_MASTER_KEY").ok()?;
+        decode_hex_32(raw.trim())
+    })
+}
+
+/// Decodes a 64-character hex string into 32 raw bytes, returning `None` on
+/// any malformed input (wrong length, non-hex digit).
+fn decode_hex_32(raw: &str) -> Option<[u8; 32]> {
+    if raw.len() != 64 {
+        return None;
+    }
+
+    let mut key = [0u8; 32];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&raw[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(key)
+}
+
+/// Reads and parses the environment variable `name`, failing if it is unset
+/// or cannot be parsed as `T`.
+pub fn require_env_var<T: FromStr>(name: &str) -> Result<T, Error>
+where
+    T::Err: fmt::Display,
+{
+    let raw = std::env::var(name).map_err(|_| Error::MissingEnvVar(name.to_string()))?;
+    raw.parse()
+        .map_err(|e: T::Err| Error::BadEnvVar(name.to_string(), e.to_string()))
+}
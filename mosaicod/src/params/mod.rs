@@ -0,0 +1,9 @@
+//! Process-wide runtime parameters: compile-time constants shared across
+//! modules, and the small set of knobs an operator tunes per deployment
+//! (message size limits, the repository URL, object store credentials, ...).
+
+mod core;
+pub use core::*;
+
+mod config_file;
+pub use config_file::*;
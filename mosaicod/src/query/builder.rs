@@ -1,7 +1,18 @@
-use super::{Error, IsSupportedOp, OntologyField, Op, Value};
+use super::{Error, IsSupportedOp, OntologyField, OntologyFilter, Op, Value};
 
 const EMPTY_CLAUSE: &str = "()";
 
+/// Tests whether `value` might be present in a serialized [`crate::types::BloomFilter`].
+///
+/// Returns `true` (may-contain) when `bloom_bytes` can't be decoded, so callers
+/// degrade gracefully to scanning the chunk rather than pruning it incorrectly.
+pub fn bloom_maybe_contains(bloom_bytes: &[u8], value: &str) -> bool {
+    match crate::types::BloomFilter::from_bytes(bloom_bytes) {
+        Some(bloom) => bloom.maybe_contains(value),
+        None => true,
+    }
+}
+
 pub struct CompiledClause {
     pub clause: String,
     pub values: Vec<Value>,
@@ -127,3 +138,407 @@ impl ClausesCompiler {
         Ok(self.result)
     }
 }
+
+/// A boolean combination of [`OntologyFilter`] leaves for compilers whose
+/// clauses are themselves `chunk_id`-returning subqueries, such as
+/// `repo::sql_models::pg_queries::builders::ChunkQueryBuilder`'s bloom-filter
+/// and zone-map pruning index.
+///
+/// Unlike [`super::Expr`], whose leaves compile to ordinary `WHERE` clause
+/// fragments joined with textual `AND`/`OR`/`NOT`, a pruning leaf compiles to
+/// a `SELECT ...` subquery -- so combining them has to use SQL set
+/// operations instead: `AND` -> `INTERSECT`, `OR` -> `UNION`, `NOT` ->
+/// `EXCEPT` against a caller-supplied `universe` subquery.
+#[derive(Debug, Clone)]
+pub enum OntologyExpr {
+    And(Vec<OntologyExpr>),
+    Or(Vec<OntologyExpr>),
+    Not(Box<OntologyExpr>),
+    Leaf(OntologyFilter),
+}
+
+impl From<OntologyFilter> for OntologyExpr {
+    fn from(filter: OntologyFilter) -> Self {
+        Self::Leaf(filter)
+    }
+}
+
+impl OntologyExpr {
+    /// Recursively compiles this expression into a single subquery, calling
+    /// `compile_leaf` for every [`OntologyFilter`] leaf and combining the
+    /// results with set operations against `universe` -- a subquery selecting
+    /// every key in scope (e.g. `SELECT chunk_id FROM chunk_t`), used as the
+    /// left-hand side of `NOT`'s `EXCEPT`.
+    ///
+    /// As with [`super::Expr::compile`], a leaf or sub-group that compiles to
+    /// an unfiltered [`CompilerResult`] is dropped from its parent group, and
+    /// `NOT` over an unfiltered expression is itself unfiltered.
+    pub fn compile<F>(&self, universe: &str, compile_leaf: &mut F) -> Result<CompilerResult, Error>
+    where
+        F: FnMut(&OntologyFilter) -> Result<CompilerResult, Error>,
+    {
+        match self {
+            Self::Leaf(filter) => compile_leaf(filter),
+            Self::Not(inner) => {
+                let inner = inner.compile(universe, compile_leaf)?;
+                if inner.is_unfiltered() {
+                    return Ok(inner);
+                }
+
+                Ok(CompilerResult {
+                    clauses: vec![format!(
+                        "({universe}) EXCEPT ({})",
+                        inner.clauses.join(" INTERSECT ")
+                    )],
+                    values: inner.values,
+                })
+            }
+            Self::And(items) => Self::compile_group(items, "INTERSECT", universe, compile_leaf),
+            Self::Or(items) => Self::compile_group(items, "UNION", universe, compile_leaf),
+        }
+    }
+
+    fn compile_group<F>(
+        items: &[OntologyExpr],
+        joiner: &str,
+        universe: &str,
+        compile_leaf: &mut F,
+    ) -> Result<CompilerResult, Error>
+    where
+        F: FnMut(&OntologyFilter) -> Result<CompilerResult, Error>,
+    {
+        let mut clauses = Vec::with_capacity(items.len());
+        let mut values = Vec::new();
+
+        for item in items {
+            let compiled = item.compile(universe, compile_leaf)?;
+            if compiled.is_unfiltered() {
+                continue;
+            }
+
+            clauses.push(format!("({})", compiled.clauses.join(" INTERSECT ")));
+            values.extend(compiled.values);
+        }
+
+        if clauses.is_empty() {
+            return Ok(CompilerResult {
+                clauses: Vec::new(),
+                values: Vec::new(),
+            });
+        }
+
+        Ok(CompilerResult {
+            clauses: vec![clauses.join(&format!(" {joiner} "))],
+            values,
+        })
+    }
+}
+
+/// A boolean combination of `field op value` leaves for any [`CompileClause`]
+/// compiler (`SqlQueryCompiler`, `JsonQueryCompiler`), letting a caller
+/// express e.g. `(a = 1 AND b > 2) OR NOT c IS NULL` instead of the flat,
+/// implicitly-ANDed clause list [`ClausesCompiler`] builds.
+///
+/// Unlike [`OntologyExpr`], whose leaves compile to `chunk_id`-returning
+/// subqueries combined with set operations, a `QueryNode`'s leaves compile to
+/// ordinary `WHERE`-clause text via [`CompileClause::compile_clause`], so
+/// groups are combined with textual `AND`/`OR`/`NOT` instead.
+#[derive(Debug, Clone)]
+pub enum QueryNode {
+    Clause { field: String, op: Op<Value> },
+    And(Vec<QueryNode>),
+    Or(Vec<QueryNode>),
+    Not(Box<QueryNode>),
+}
+
+impl QueryNode {
+    /// Recursively compiles this tree into a single [`CompiledClause`] by
+    /// threading `compiler` through every [`Self::Clause`] leaf left-to-right
+    /// -- the same instance for the whole walk, so the `$N` placeholder
+    /// indices it hands out (e.g. `SqlQueryCompiler::consume_placeholder`)
+    /// stay monotonic and aligned with the returned `values`, no matter how
+    /// leaves are nested under `And`/`Or`/`Not`.
+    ///
+    /// A leaf or sub-group that compiles to the empty clause (see
+    /// `CompiledClause::empty`, e.g. `Op::In(vec![])`) is dropped from its
+    /// parent rather than emitted as `() AND ...`; `Not` over an empty inner
+    /// expression is itself empty.
+    pub fn compile<F: CompileClause>(self, compiler: &mut F) -> Result<CompiledClause, Error> {
+        match self {
+            Self::Clause { field, op } => compiler.compile_clause(&field, op),
+            Self::Not(inner) => {
+                let inner = inner.compile(compiler)?;
+                if inner.is_empty() {
+                    return Ok(inner);
+                }
+
+                Ok(CompiledClause::new(
+                    format!("NOT ({})", inner.clause),
+                    inner.values,
+                ))
+            }
+            Self::And(items) => Self::compile_group(items, "AND", compiler),
+            Self::Or(items) => Self::compile_group(items, "OR", compiler),
+        }
+    }
+
+    fn compile_group<F: CompileClause>(
+        items: Vec<QueryNode>,
+        joiner: &str,
+        compiler: &mut F,
+    ) -> Result<CompiledClause, Error> {
+        let mut clauses = Vec::with_capacity(items.len());
+        let mut values = Vec::new();
+
+        for item in items {
+            let compiled = item.compile(compiler)?;
+            if compiled.is_empty() {
+                continue;
+            }
+
+            clauses.push(format!("({})", compiled.clause));
+            values.extend(compiled.values);
+        }
+
+        if clauses.is_empty() {
+            return Ok(CompiledClause::empty());
+        }
+
+        Ok(CompiledClause::new(
+            clauses.join(&format!(" {joiner} ")),
+            values,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// A stand-in leaf compiler: lowers a leaf's single `name = '...'` field
+    /// into a fake `SELECT chunk_id ...` subquery, mirroring the shape
+    /// `ChunkQueryBuilder` produces for a real ontology field.
+    fn compile_leaf(filter: &OntologyFilter) -> Result<CompilerResult, Error> {
+        let mut clauses = Vec::new();
+        let mut values = Vec::new();
+
+        for (field, op) in filter.iter() {
+            if let Op::Eq(Value::Text(name)) = op {
+                clauses.push(format!(
+                    "SELECT chunk_id FROM chunk_t WHERE {} = '{name}'",
+                    field.value()
+                ));
+                values.push(name.clone().into());
+            }
+        }
+
+        Ok(CompilerResult { clauses, values })
+    }
+
+    fn leaf(field: &str, value: &str) -> OntologyExpr {
+        let mut map = HashMap::new();
+        map.insert(
+            OntologyField::from(field),
+            Op::Eq(Value::Text(value.to_string())),
+        );
+        OntologyExpr::Leaf(OntologyFilter::new(map))
+    }
+
+    const UNIVERSE: &str = "SELECT chunk_id FROM chunk_t";
+
+    #[test]
+    fn and_intersects_each_leaf() {
+        let expr = OntologyExpr::And(vec![leaf("sensor", "lidar"), leaf("topic", "camera")]);
+
+        let qr = expr
+            .compile(UNIVERSE, &mut compile_leaf)
+            .expect("should compile");
+
+        assert_eq!(
+            qr.clauses,
+            vec![
+                "(SELECT chunk_id FROM chunk_t WHERE sensor = 'lidar') INTERSECT (SELECT chunk_id FROM chunk_t WHERE topic = 'camera')"
+            ]
+        );
+    }
+
+    #[test]
+    fn or_unions_each_leaf() {
+        let expr = OntologyExpr::Or(vec![leaf("sensor", "lidar"), leaf("topic", "camera")]);
+
+        let qr = expr
+            .compile(UNIVERSE, &mut compile_leaf)
+            .expect("should compile");
+
+        assert_eq!(
+            qr.clauses,
+            vec![
+                "(SELECT chunk_id FROM chunk_t WHERE sensor = 'lidar') UNION (SELECT chunk_id FROM chunk_t WHERE topic = 'camera')"
+            ]
+        );
+    }
+
+    #[test]
+    fn not_excepts_the_universe() {
+        let expr = OntologyExpr::Not(Box::new(leaf("sensor", "lidar")));
+
+        let qr = expr
+            .compile(UNIVERSE, &mut compile_leaf)
+            .expect("should compile");
+
+        assert_eq!(
+            qr.clauses,
+            vec![
+                "(SELECT chunk_id FROM chunk_t) EXCEPT (SELECT chunk_id FROM chunk_t WHERE sensor = 'lidar')"
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_leaf_is_dropped_from_its_group() {
+        let expr = OntologyExpr::And(vec![
+            OntologyExpr::Leaf(OntologyFilter::new(HashMap::new())),
+            leaf("topic", "camera"),
+        ]);
+
+        let qr = expr
+            .compile(UNIVERSE, &mut compile_leaf)
+            .expect("should compile");
+
+        assert_eq!(
+            qr.clauses,
+            vec!["(SELECT chunk_id FROM chunk_t WHERE topic = 'camera')"]
+        );
+    }
+
+    /// A stand-in [`CompileClause`] mirroring `SqlQueryCompiler`'s shape:
+    /// tracks its own `placeholder_counter` across every `compile_clause`
+    /// call so the tests below can assert placeholders stay monotonic
+    /// across nested `QueryNode` groups.
+    struct FakeSqlCompiler {
+        placeholder_counter: usize,
+    }
+
+    impl FakeSqlCompiler {
+        fn new() -> Self {
+            Self {
+                placeholder_counter: 0,
+            }
+        }
+
+        fn consume_placeholder(&mut self) -> String {
+            self.placeholder_counter += 1;
+            format!("${}", self.placeholder_counter)
+        }
+    }
+
+    impl CompileClause for FakeSqlCompiler {
+        fn compile_clause<V>(&mut self, field: &str, op: Op<V>) -> Result<CompiledClause, Error>
+        where
+            V: Into<Value> + IsSupportedOp,
+        {
+            match op {
+                Op::Eq(value) => {
+                    let placeholder = self.consume_placeholder();
+                    Ok(CompiledClause::new(
+                        format!("{field} = {placeholder}"),
+                        vec![value.into()],
+                    ))
+                }
+                Op::Gt(value) => {
+                    let placeholder = self.consume_placeholder();
+                    Ok(CompiledClause::new(
+                        format!("{field} > {placeholder}"),
+                        vec![value.into()],
+                    ))
+                }
+                Op::In(values) if values.is_empty() => Ok(CompiledClause::empty()),
+                _ => panic!("unsupported op in test fixture"),
+            }
+        }
+    }
+
+    fn node_clause(field: &str, op: Op<Value>) -> QueryNode {
+        QueryNode::Clause {
+            field: field.to_string(),
+            op,
+        }
+    }
+
+    #[test]
+    fn and_parenthesizes_each_clause() {
+        let node = QueryNode::And(vec![
+            node_clause("a", Op::Eq(Value::Integer(1))),
+            node_clause("b", Op::Gt(Value::Integer(2))),
+        ]);
+
+        let mut compiler = FakeSqlCompiler::new();
+        let compiled = node.compile(&mut compiler).expect("should compile");
+
+        assert_eq!(compiled.clause, "(a = $1) AND (b > $2)");
+        assert_eq!(compiled.values, vec![Value::Integer(1), Value::Integer(2)]);
+    }
+
+    #[test]
+    fn or_parenthesizes_each_clause() {
+        let node = QueryNode::Or(vec![
+            node_clause("a", Op::Eq(Value::Integer(1))),
+            node_clause("b", Op::Gt(Value::Integer(2))),
+        ]);
+
+        let mut compiler = FakeSqlCompiler::new();
+        let compiled = node.compile(&mut compiler).expect("should compile");
+
+        assert_eq!(compiled.clause, "(a = $1) OR (b > $2)");
+    }
+
+    #[test]
+    fn not_negates_the_inner_group() {
+        let node = QueryNode::Not(Box::new(node_clause("a", Op::Eq(Value::Integer(1)))));
+
+        let mut compiler = FakeSqlCompiler::new();
+        let compiled = node.compile(&mut compiler).expect("should compile");
+
+        assert_eq!(compiled.clause, "NOT (a = $1)");
+    }
+
+    #[test]
+    fn nested_and_or_not_keeps_placeholders_monotonic() {
+        let node = QueryNode::Or(vec![
+            node_clause("c", Op::Eq(Value::Integer(3))),
+            QueryNode::And(vec![
+                node_clause("a", Op::Eq(Value::Integer(1))),
+                QueryNode::Not(Box::new(node_clause("b", Op::Gt(Value::Integer(2))))),
+            ]),
+        ]);
+
+        let mut compiler = FakeSqlCompiler::new();
+        let compiled = node.compile(&mut compiler).expect("should compile");
+
+        assert_eq!(
+            compiled.clause,
+            "(c = $1) OR ((a = $2) AND (NOT (b > $3)))"
+        );
+        assert_eq!(
+            compiled.values,
+            vec![Value::Integer(3), Value::Integer(1), Value::Integer(2)]
+        );
+    }
+
+    #[test]
+    fn empty_in_clause_is_dropped_from_its_group() {
+        let node = QueryNode::And(vec![
+            QueryNode::Clause {
+                field: "a".to_string(),
+                op: Op::In(Vec::new()),
+            },
+            node_clause("b", Op::Eq(Value::Integer(1))),
+        ]);
+
+        let mut compiler = FakeSqlCompiler::new();
+        let compiled = node.compile(&mut compiler).expect("should compile");
+
+        assert_eq!(compiled.clause, "(b = $1)");
+    }
+}
@@ -0,0 +1,213 @@
+//! Graphviz DOT export of a [`super::Filter`], useful when debugging why a
+//! particular query matched (or failed to match) the expected rows.
+
+use super::{Filter, Op, OntologyFilter, Range, SequenceFilter, TopicFilter, Value};
+
+/// Renders a [`Filter`] as a Graphviz DOT digraph.
+///
+/// The root node fans out into one node per populated domain
+/// (`sequence`/`topic`/`ontology`), each of which fans out into one leaf node
+/// per field it constrains. The output is meant to be piped into `dot -Tsvg`
+/// or similar, not parsed back.
+pub fn filter_to_dot(filter: &Filter) -> String {
+    let mut w = DotWriter::new();
+    let root = w.node("filter");
+
+    if let Some(sequence) = &filter.sequence {
+        let id = w.node("sequence");
+        w.edge(&root, &id);
+        sequence_to_dot(&mut w, &id, sequence);
+    }
+
+    if let Some(topic) = &filter.topic {
+        let id = w.node("topic");
+        w.edge(&root, &id);
+        topic_to_dot(&mut w, &id, topic);
+    }
+
+    if let Some(ontology) = &filter.ontology {
+        let id = w.node("ontology");
+        w.edge(&root, &id);
+        ontology_to_dot(&mut w, &id, ontology);
+    }
+
+    w.finish()
+}
+
+fn sequence_to_dot(w: &mut DotWriter, parent: &str, filter: &SequenceFilter) {
+    if let Some(op) = &filter.name {
+        field_to_dot(w, parent, "name", op);
+    }
+    if let Some(op) = &filter.creation {
+        field_to_dot(w, parent, "creation", op);
+    }
+    if let Some(metadata) = &filter.user_metadata {
+        let id = w.node("user_metadata");
+        w.edge(parent, &id);
+        ontology_to_dot(w, &id, metadata);
+    }
+    if let Some(since) = &filter.since {
+        since_to_dot(w, parent, *since);
+    }
+}
+
+fn topic_to_dot(w: &mut DotWriter, parent: &str, filter: &TopicFilter) {
+    if let Some(op) = &filter.name {
+        field_to_dot(w, parent, "name", op);
+    }
+    if let Some(op) = &filter.creation {
+        field_to_dot(w, parent, "creation", op);
+    }
+    if let Some(op) = &filter.ontology_tag {
+        field_to_dot(w, parent, "ontology_tag", op);
+    }
+    if let Some(op) = &filter.serialization_format {
+        field_to_dot(w, parent, "serialization_format", op);
+    }
+    if let Some(metadata) = &filter.user_metadata {
+        let id = w.node("user_metadata");
+        w.edge(parent, &id);
+        ontology_to_dot(w, &id, metadata);
+    }
+    if let Some(since) = &filter.since {
+        since_to_dot(w, parent, *since);
+    }
+}
+
+fn since_to_dot(w: &mut DotWriter, parent: &str, since: super::SyncToken) {
+    let id = w.node(&format!("since\\n> {since}"));
+    w.edge(parent, &id);
+}
+
+fn ontology_to_dot(w: &mut DotWriter, parent: &str, filter: &OntologyFilter) {
+    for (field, op) in filter.iter() {
+        field_to_dot(w, parent, field.value(), op);
+    }
+}
+
+fn field_to_dot<T>(w: &mut DotWriter, parent: &str, field: &str, op: &Op<T>)
+where
+    T: Into<Value> + Clone,
+{
+    let id = w.node(&format!("{field}\\n{}", op_label(op)));
+    w.edge(parent, &id);
+}
+
+fn op_label<T>(op: &Op<T>) -> String
+where
+    T: Into<Value> + Clone,
+{
+    match op {
+        Op::Eq(v) => format!("= {}", value_label(v.clone().into())),
+        Op::Neq(v) => format!("!= {}", value_label(v.clone().into())),
+        Op::Leq(v) => format!("<= {}", value_label(v.clone().into())),
+        Op::Geq(v) => format!(">= {}", value_label(v.clone().into())),
+        Op::Lt(v) => format!("< {}", value_label(v.clone().into())),
+        Op::Gt(v) => format!("> {}", value_label(v.clone().into())),
+        Op::Ex => "exists".to_string(),
+        Op::Nex => "not exists".to_string(),
+        Op::Between(Range { min, max }) => format!(
+            "between {} and {}",
+            value_label(min.clone().into()),
+            value_label(max.clone().into())
+        ),
+        Op::In(values) => {
+            let values = values
+                .iter()
+                .map(|v| value_label(v.clone().into()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("in [{values}]")
+        }
+        Op::Match(v) => format!("~ {}", value_label(v.clone().into())),
+        Op::Nin(values) => {
+            let values = values
+                .iter()
+                .map(|v| value_label(v.clone().into()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("not in [{values}]")
+        }
+        Op::Like(v) => format!("like {}", value_label(v.clone().into())),
+        Op::Ilike(v) => format!("ilike {}", value_label(v.clone().into())),
+        Op::Regex(v) => format!("regex {}", value_label(v.clone().into())),
+    }
+}
+
+fn value_label(value: Value) -> String {
+    match value {
+        Value::Integer(v) => v.to_string(),
+        Value::Float(v) => v.to_string(),
+        Value::Text(v) => format!("\"{v}\""),
+        Value::Boolean(v) => v.to_string(),
+    }
+}
+
+/// Minimal incremental DOT digraph builder: hands out unique node ids,
+/// quoting/escaping labels along the way.
+struct DotWriter {
+    lines: Vec<String>,
+    next_id: usize,
+}
+
+impl DotWriter {
+    fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    fn node(&mut self, label: &str) -> String {
+        let id = format!("n{}", self.next_id);
+        self.next_id += 1;
+        self.lines
+            .push(format!("  {id} [label=\"{}\"];", escape(label)));
+        id
+    }
+
+    fn edge(&mut self, from: &str, to: &str) {
+        self.lines.push(format!("  {from} -> {to};"));
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::from("digraph filter {\n");
+        for line in self.lines {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out.push('}');
+        out
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_filter_is_just_the_root_node() {
+        let dot = filter_to_dot(&Filter::default());
+        assert_eq!(dot, "digraph filter {\n  n0 [label=\"filter\"];\n}");
+    }
+
+    #[test]
+    fn topic_filter_renders_field_nodes() {
+        let filter = Filter {
+            topic: Some(TopicFilter {
+                name: Some(Op::Eq("robot-1".to_string())),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let dot = filter_to_dot(&filter);
+        assert!(dot.contains("topic"));
+        assert!(dot.contains("name"));
+        assert!(dot.contains("robot-1"));
+    }
+}
@@ -13,6 +13,13 @@ pub enum Error {
     #[error("bad field `{field}`")]
     BadField { field: String },
 
+    /// A `query::lang` parse failure, with the source span it occurred at.
+    #[error("parse error at {span} :: {message}")]
+    Parse {
+        span: super::lang::Span,
+        message: String,
+    },
+
     #[error("datafusion backend error :: {0}")]
     DataFusion(#[from] datafusion::error::DataFusionError),
 
@@ -21,6 +28,9 @@ pub enum Error {
 
     #[error("store error :: {0}")]
     StoreError(#[from] store::Error),
+
+    #[error("parquet footer error :: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
 }
 
 impl Error {
@@ -30,4 +40,8 @@ impl Error {
             err: super::OpError::UnsupportedOperation,
         }
     }
+
+    pub fn parse(span: super::lang::Span, message: String) -> Self {
+        Self::Parse { span, message }
+    }
 }
@@ -0,0 +1,216 @@
+//! Boolean combinators (`$and`/`$or`/`$not`) over [`Filter`] leaves.
+//!
+//! This is the compiled counterpart to the marshaling layer's own `Expr`,
+//! produced once a query has been parsed and its leaves lowered into
+//! [`Filter`]s. [`Expr::compile`] turns the tree into a single parenthesized
+//! clause, delegating the per-leaf clause building (SQL column names, JSON
+//! paths, ...) to the caller via a leaf-compiling closure.
+
+use super::{CompilerResult, Error, Filter};
+
+/// A boolean combination of [`Filter`] leaves, built by lowering the
+/// marshaling layer's recursive `Expr`.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Leaf(Filter),
+}
+
+impl Expr {
+    /// Returns the wrapped [`Filter`] if this expression is a bare `Leaf`,
+    /// and `None` for any compound (`$and`/`$or`/`$not`) expression.
+    ///
+    /// Existing callers that don't yet combine filters can use this to keep
+    /// working unchanged for the common flat case.
+    pub fn into_leaf(self) -> Option<Filter> {
+        match self {
+            Self::Leaf(filter) => Some(filter),
+            _ => None,
+        }
+    }
+
+    /// Recursively compiles this expression into a single clause, calling
+    /// `compile_leaf` for every [`Filter`] leaf and combining the results
+    /// into parenthesized `AND`/`OR`/`NOT` groups.
+    ///
+    /// A leaf (or sub-group) that compiles to an unfiltered [`CompilerResult`]
+    /// is dropped from its parent group rather than contributing a vacuous
+    /// `()` clause; `NOT` over an unfiltered expression is itself unfiltered,
+    /// rather than negating nothing.
+    pub fn compile<F>(&self, compile_leaf: &mut F) -> Result<CompilerResult, Error>
+    where
+        F: FnMut(&Filter) -> Result<CompilerResult, Error>,
+    {
+        match self {
+            Self::Leaf(filter) => compile_leaf(filter),
+            Self::Not(inner) => {
+                let inner = inner.compile(compile_leaf)?;
+                if inner.is_unfiltered() {
+                    return Ok(inner);
+                }
+
+                Ok(CompilerResult {
+                    clauses: vec![format!("NOT ({})", inner.clauses.join(" AND "))],
+                    values: inner.values,
+                })
+            }
+            Self::And(items) => Self::compile_group(items, "AND", compile_leaf),
+            Self::Or(items) => Self::compile_group(items, "OR", compile_leaf),
+        }
+    }
+
+    fn compile_group<F>(
+        items: &[Expr],
+        joiner: &str,
+        compile_leaf: &mut F,
+    ) -> Result<CompilerResult, Error>
+    where
+        F: FnMut(&Filter) -> Result<CompilerResult, Error>,
+    {
+        let mut clauses = Vec::with_capacity(items.len());
+        let mut values = Vec::new();
+
+        for item in items {
+            let compiled = item.compile(compile_leaf)?;
+            if compiled.is_unfiltered() {
+                continue;
+            }
+
+            clauses.push(format!("({})", compiled.clauses.join(" AND ")));
+            values.extend(compiled.values);
+        }
+
+        if clauses.is_empty() {
+            return Ok(CompilerResult {
+                clauses: Vec::new(),
+                values: Vec::new(),
+            });
+        }
+
+        Ok(CompilerResult {
+            clauses: vec![clauses.join(&format!(" {joiner} "))],
+            values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::{Op, SequenceFilter, TopicFilter};
+
+    /// A stand-in leaf compiler: treats `sequence.name`/`topic.name` as the
+    /// only supported fields, mirroring the shape real SQL compilers use.
+    fn compile_leaf(filter: &Filter) -> Result<CompilerResult, Error> {
+        let mut clauses = Vec::new();
+        let mut values = Vec::new();
+
+        if let Some(seq) = &filter.sequence {
+            if let Some(Op::Eq(name)) = &seq.name {
+                clauses.push(format!("sequence.name = '{name}'"));
+                values.push(name.clone().into());
+            }
+        }
+
+        if let Some(top) = &filter.topic {
+            if let Some(Op::Eq(name)) = &top.name {
+                clauses.push(format!("topic.name = '{name}'"));
+                values.push(name.clone().into());
+            }
+        }
+
+        Ok(CompilerResult { clauses, values })
+    }
+
+    fn leaf_with_sequence_name(name: &str) -> Expr {
+        Expr::Leaf(Filter {
+            sequence: Some(SequenceFilter {
+                name: Some(Op::Eq(name.to_string())),
+                creation: None,
+                user_metadata: None,
+                since: None,
+            }),
+            ..Default::default()
+        })
+    }
+
+    fn leaf_with_topic_name(name: &str) -> Expr {
+        Expr::Leaf(Filter {
+            topic: Some(TopicFilter {
+                name: Some(Op::Eq(name.to_string())),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn and_parenthesizes_each_leaf() {
+        let expr = Expr::And(vec![
+            leaf_with_sequence_name("robot-1"),
+            leaf_with_topic_name("camera"),
+        ]);
+
+        let qr = expr.compile(&mut compile_leaf).expect("should compile");
+
+        assert_eq!(
+            qr.clauses,
+            vec!["(sequence.name = 'robot-1') AND (topic.name = 'camera')"]
+        );
+    }
+
+    #[test]
+    fn or_parenthesizes_each_leaf() {
+        let expr = Expr::Or(vec![
+            leaf_with_sequence_name("robot-1"),
+            leaf_with_topic_name("camera"),
+        ]);
+
+        let qr = expr.compile(&mut compile_leaf).expect("should compile");
+
+        assert_eq!(
+            qr.clauses,
+            vec!["(sequence.name = 'robot-1') OR (topic.name = 'camera')"]
+        );
+    }
+
+    #[test]
+    fn not_negates_the_inner_group() {
+        let expr = Expr::Not(Box::new(leaf_with_sequence_name("robot-1")));
+
+        let qr = expr.compile(&mut compile_leaf).expect("should compile");
+
+        assert_eq!(qr.clauses, vec!["NOT (sequence.name = 'robot-1')"]);
+    }
+
+    #[test]
+    fn nested_and_or_not() {
+        let expr = Expr::Or(vec![
+            leaf_with_topic_name("camera"),
+            Expr::And(vec![
+                leaf_with_sequence_name("robot-1"),
+                Expr::Not(Box::new(leaf_with_topic_name("lidar"))),
+            ]),
+        ]);
+
+        let qr = expr.compile(&mut compile_leaf).expect("should compile");
+
+        assert_eq!(
+            qr.clauses,
+            vec![
+                "(topic.name = 'camera') OR ((sequence.name = 'robot-1') AND (NOT (topic.name = 'lidar')))"
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_leaf_is_dropped_from_its_group() {
+        let expr = Expr::And(vec![Expr::Leaf(Filter::default()), leaf_with_topic_name("camera")]);
+
+        let qr = expr.compile(&mut compile_leaf).expect("should compile");
+
+        assert_eq!(qr.clauses, vec!["(topic.name = 'camera')"]);
+    }
+}
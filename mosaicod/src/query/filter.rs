@@ -9,6 +9,10 @@ pub type Integer = i64;
 pub type Timestamp = types::Timestamp;
 /// Literal type alias
 pub type Text = String;
+/// A monotonically increasing cursor (backed by a resource's serial id) used
+/// to request only the records created after a previously observed point,
+/// for incremental sync.
+pub type SyncToken = Integer;
 
 #[derive(Debug, thiserror::Error)]
 pub enum OpError {
@@ -96,7 +100,9 @@ impl IsSupportedOp for Value {
 
     fn support_ordering(&self) -> bool {
         match self {
-            Self::Text(_) => false,
+            // Gates `Between`/`Lt`/`Gt`/`Leq`/`Geq` only; equality (`Eq`/`Neq`)
+            // is handled separately by `support_eq` and is always available.
+            Self::Text(_) => true,
             Self::Boolean(_) => false,
             Self::Integer(_) => true,
             Self::Float(_) => true,
@@ -147,6 +153,13 @@ impl IsSupportedOp for Text {
         true
     }
 
+    // Gates `Between`/`Lt`/`Gt`/`Leq`/`Geq`, compared lexicographically, so
+    // clients can do prefix/key-range scans over names and ontology fields
+    // (e.g. everything under `image.`).
+    fn support_ordering(&self) -> bool {
+        true
+    }
+
     fn support_in(&self) -> bool {
         true
     }
@@ -289,8 +302,22 @@ pub enum Op<T> {
     Between(Range<T>),
     /// Found in a set
     In(Vec<T>),
-    /// Matches a certain expression
+    /// Matches a glob or anchored regex pattern, as parsed by
+    /// [`MatchPattern::parse`].
     Match(T),
+    /// Not found in a set; the logical complement of [`Op::In`].
+    Nin(Vec<T>),
+    /// A case-sensitive SQL `LIKE` pattern (`%` matches a run of zero or
+    /// more characters, `_` matches exactly one), supplied as-is by the
+    /// caller -- unlike [`Op::Match`]'s glob, no translation is applied.
+    Like(T),
+    /// Case-insensitive variant of [`Op::Like`].
+    Ilike(T),
+    /// An unanchored regular expression.
+    ///
+    /// Unlike [`Op::Match`], which infers glob vs. regex from `^...$`
+    /// anchors, this always compiles `T` as a regex.
+    Regex(T),
 }
 
 impl<T> Op<T>
@@ -310,10 +337,124 @@ where
             Op::Between(range) => range.min.support_ordering(),
             Op::In(items) => items[0].support_in(),
             Op::Match(v) => v.support_match(),
+            Op::Nin(items) => items[0].support_in(),
+            Op::Like(v) => v.support_match(),
+            Op::Ilike(v) => v.support_match(),
+            Op::Regex(v) => v.support_match(),
+        }
+    }
+}
+
+impl Op<Text> {
+    /// Evaluates this operation against `candidate`, for operations that
+    /// support in-process matching.
+    ///
+    /// Currently only [`Op::Match`] is supported; every other variant
+    /// returns [`OpError::UnsupportedOperation`].
+    pub fn matches(&self, candidate: &str) -> Result<bool, OpError> {
+        match self {
+            Self::Match(pattern) => MatchPattern::parse(pattern).matches(candidate),
+            _ => Err(OpError::UnsupportedOperation),
         }
     }
 }
 
+/// The pattern kind carried by an [`Op::Match`] on [`Text`], as determined by
+/// [`MatchPattern::parse`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchPattern {
+    /// A shell-style glob: `*` matches a run of zero or more characters,
+    /// `?` matches exactly one.
+    Glob(String),
+    /// An anchored regular expression, written as `^...$`.
+    Regex(String),
+}
+
+impl MatchPattern {
+    /// Parses `raw` into a [`MatchPattern`]. A pattern both starting with
+    /// `^` and ending with `$` is treated as an anchored regex; anything
+    /// else is treated as a glob.
+    pub fn parse(raw: &str) -> Self {
+        if raw.len() >= 2 && raw.starts_with('^') && raw.ends_with('$') {
+            Self::Regex(raw.to_string())
+        } else {
+            Self::Glob(raw.to_string())
+        }
+    }
+
+    /// Compiles and applies the pattern against `candidate`.
+    ///
+    /// Returns [`OpError::UnsupportedOperation`] if the pattern (a glob is
+    /// translated to a regex first) fails to compile.
+    pub fn matches(&self, candidate: &str) -> Result<bool, OpError> {
+        let pattern = match self {
+            Self::Glob(glob) => glob_to_regex(glob),
+            Self::Regex(pattern) => pattern.clone(),
+        };
+
+        let re = regex::Regex::new(&pattern).map_err(|_| OpError::UnsupportedOperation)?;
+        Ok(re.is_match(candidate))
+    }
+}
+
+/// Translates a shell-style glob into an anchored regex, escaping every
+/// other regex metacharacter literally.
+fn glob_to_regex(glob: &str) -> String {
+    let mut out = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => out.push_str(".*"),
+            '?' => out.push('.'),
+            '\\' | '.' | '+' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '^' | '$' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out.push('$');
+    out
+}
+
+/// Translates a shell-style glob into a SQL `LIKE` pattern (`*` -> `%`,
+/// `?` -> `_`), escaping literal `%`, `_` and `\` with a backslash. Callers
+/// should pair the result with `ESCAPE '\'` in the generated clause.
+pub fn glob_to_sql_like(glob: &str) -> String {
+    let mut out = String::new();
+    for c in glob.chars() {
+        match c {
+            '*' => out.push('%'),
+            '?' => out.push('_'),
+            '%' | '_' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escapes literal `%`, `_` and `\` with a backslash, leaving every other
+/// character (including `*` and `?`) untouched. Unlike [`glob_to_sql_like`],
+/// this does not treat the input as a glob, so it's the right building block
+/// for plain substring-containment `LIKE` patterns where `*`/`?` are just
+/// ordinary characters the caller wants to match literally. Callers should
+/// pair the result with `ESCAPE '\'` in the generated clause.
+pub fn escape_like_literal(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '%' | '_' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 /// The root object representing a complete search query.
 ///
 /// A query allows filtering across three distinct domains:
@@ -351,11 +492,17 @@ pub struct SequenceFilter {
     pub name: Option<Op<Text>>,
     pub creation: Option<Op<Timestamp>>,
     pub user_metadata: Option<OntologyFilter>,
+    /// Restricts results to sequences created after this [`SyncToken`], for
+    /// incremental sync.
+    pub since: Option<SyncToken>,
 }
 
 impl SequenceFilter {
     pub fn is_empty(&self) -> bool {
-        self.name.is_none() && self.creation.is_none() && self.user_metadata.is_none()
+        self.name.is_none()
+            && self.creation.is_none()
+            && self.user_metadata.is_none()
+            && self.since.is_none()
     }
 }
 
@@ -366,6 +513,9 @@ pub struct TopicFilter {
     pub ontology_tag: Option<Op<Text>>,
     pub serialization_format: Option<Op<Text>>,
     pub user_metadata: Option<OntologyFilter>,
+    /// Restricts results to topics created after this [`SyncToken`], for
+    /// incremental sync.
+    pub since: Option<SyncToken>,
 }
 
 impl TopicFilter {
@@ -375,6 +525,7 @@ impl TopicFilter {
             && self.user_metadata.is_none()
             && self.ontology_tag.is_none()
             && self.serialization_format.is_none()
+            && self.since.is_none()
     }
 }
 
@@ -390,4 +541,73 @@ mod tests {
         assert_eq!(oc.ontology_tag(), "image");
         assert_eq!(oc.value(), "image.info.height");
     }
+
+    #[test]
+    fn glob_pattern_matches() {
+        let op = Op::Match("image.*".to_string());
+
+        assert!(op.matches("image.info.height").expect("should evaluate"));
+        assert!(!op.matches("lidar.info.height").expect("should evaluate"));
+    }
+
+    #[test]
+    fn glob_question_mark_matches_single_char() {
+        let op = Op::Match("cam-?".to_string());
+
+        assert!(op.matches("cam-1").expect("should evaluate"));
+        assert!(!op.matches("cam-12").expect("should evaluate"));
+    }
+
+    #[test]
+    fn regex_pattern_matches() {
+        let op = Op::Match("^image\\.\\d+$".to_string());
+
+        assert!(op.matches("image.42").expect("should evaluate"));
+        assert!(!op.matches("image.foo").expect("should evaluate"));
+    }
+
+    #[test]
+    fn malformed_regex_is_unsupported() {
+        let op = Op::Match("^(unclosed$".to_string());
+
+        assert!(matches!(
+            op.matches("anything"),
+            Err(OpError::UnsupportedOperation)
+        ));
+    }
+
+    #[test]
+    fn text_range_is_lexicographic() {
+        let range = Range::try_new("image.a".to_string(), "image.z".to_string())
+            .expect("valid range");
+
+        assert!(range.min <= "image.info.height".to_string());
+        assert!("image.info.height".to_string() <= range.max);
+    }
+
+    #[test]
+    fn support_ordering_gates_range_ops_not_equality() {
+        let value = Value::Text("image.info.height".to_string());
+
+        assert!(value.support_eq());
+        assert!(value.support_ordering());
+    }
+
+    #[test]
+    fn nin_is_supported_wherever_in_is() {
+        let text = vec![Value::Text("lidar".to_string())];
+        assert_eq!(Op::Nin(text.clone()).is_supported_op(), Op::In(text).is_supported_op());
+
+        let boolean = vec![Value::Boolean(true)];
+        assert_eq!(Op::Nin(boolean.clone()).is_supported_op(), Op::In(boolean).is_supported_op());
+    }
+
+    #[test]
+    fn like_ilike_regex_are_gated_like_match() {
+        assert!(Op::Like(Value::Text("image.%".to_string())).is_supported_op());
+        assert!(Op::Ilike(Value::Text("IMAGE.%".to_string())).is_supported_op());
+        assert!(Op::Regex(Value::Text(r"image\.\d+".to_string())).is_supported_op());
+
+        assert!(!Op::Like(Value::Integer(1)).is_supported_op());
+    }
 }
@@ -0,0 +1,310 @@
+use crate::query::Error;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// A byte-offset range into the source string a [`Token`] or parse error was
+/// produced from, so [`Error::Parse`] can point at the offending text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token {
+    Ident(String),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Bool(bool),
+
+    Eq,
+    Neq,
+    Lt,
+    Leq,
+    Gt,
+    Geq,
+
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+
+    And,
+    Or,
+    Not,
+    In,
+    Between,
+    Like,
+    Ilike,
+    Is,
+    Null,
+
+    Eof,
+}
+
+/// Turns a query string into a flat [`Token`] stream for [`super::parser`].
+///
+/// Identifiers may contain dots (`topic.speed`), matching a dotted
+/// [`super::super::OntologyField`] path. Keywords (`AND`, `OR`, `NOT`, `IN`,
+/// `BETWEEN`, `LIKE`, `ILIKE`, `IS`, `NULL`, `TRUE`, `FALSE`) are recognized
+/// case-insensitively and take priority over the identifier rule.
+pub(super) struct Lexer<'a> {
+    src: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    pub(super) fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            chars: src.char_indices().peekable(),
+        }
+    }
+
+    pub(super) fn tokenize(mut self) -> Result<Vec<(Token, Span)>, Error> {
+        let mut tokens = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+
+            let Some(&(start, c)) = self.chars.peek() else {
+                tokens.push((
+                    Token::Eof,
+                    Span {
+                        start: self.src.len(),
+                        end: self.src.len(),
+                    },
+                ));
+                break;
+            };
+
+            let (token, end) = match c {
+                '(' => {
+                    self.chars.next();
+                    (Token::LParen, start + 1)
+                }
+                ')' => {
+                    self.chars.next();
+                    (Token::RParen, start + 1)
+                }
+                '[' => {
+                    self.chars.next();
+                    (Token::LBracket, start + 1)
+                }
+                ']' => {
+                    self.chars.next();
+                    (Token::RBracket, start + 1)
+                }
+                ',' => {
+                    self.chars.next();
+                    (Token::Comma, start + 1)
+                }
+                '=' => {
+                    self.chars.next();
+                    (Token::Eq, start + 1)
+                }
+                '!' => self.lex_bang(start)?,
+                '<' => self.lex_two_char(start, Token::Lt, Token::Leq),
+                '>' => self.lex_two_char(start, Token::Gt, Token::Geq),
+                '"' => self.lex_string(start)?,
+                '-' | '0'..='9' => self.lex_number(start)?,
+                c if c == '_' || c.is_alphabetic() => self.lex_ident_or_keyword(start),
+                _ => {
+                    let end = start + c.len_utf8();
+                    return Err(Error::parse(
+                        Span { start, end },
+                        format!("unexpected character `{c}`"),
+                    ));
+                }
+            };
+
+            tokens.push((token, Span { start, end }));
+        }
+
+        Ok(tokens)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(&(_, c)) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Lexes `!=`; a bare `!` isn't a valid token on its own.
+    fn lex_bang(&mut self, start: usize) -> Result<(Token, usize), Error> {
+        self.chars.next();
+        match self.chars.peek() {
+            Some(&(_, '=')) => {
+                self.chars.next();
+                Ok((Token::Neq, start + 2))
+            }
+            _ => Err(Error::parse(
+                Span {
+                    start,
+                    end: start + 1,
+                },
+                "expected `!=`".to_string(),
+            )),
+        }
+    }
+
+    /// Lexes a one- or two-character operator like `<`/`<=`, given the
+    /// one-char and two-char variants.
+    fn lex_two_char(&mut self, start: usize, one: Token, two: Token) -> (Token, usize) {
+        self.chars.next();
+        if let Some(&(_, '=')) = self.chars.peek() {
+            self.chars.next();
+            (two, start + 2)
+        } else {
+            (one, start + 1)
+        }
+    }
+
+    /// Lexes a `"..."` string literal, unescaping `\"`, `\\`, `\n`, and `\t`.
+    fn lex_string(&mut self, start: usize) -> Result<(Token, usize), Error> {
+        self.chars.next(); // opening quote
+
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => break,
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, '"')) => value.push('"'),
+                    Some((_, '\\')) => value.push('\\'),
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((pos, other)) => {
+                        return Err(Error::parse(
+                            Span {
+                                start,
+                                end: pos + other.len_utf8(),
+                            },
+                            format!("unsupported escape `\\{other}`"),
+                        ));
+                    }
+                    None => {
+                        return Err(Error::parse(
+                            Span {
+                                start,
+                                end: self.src.len(),
+                            },
+                            "unterminated string literal".to_string(),
+                        ));
+                    }
+                },
+                Some((_, c)) => value.push(c),
+                None => {
+                    return Err(Error::parse(
+                        Span {
+                            start,
+                            end: self.src.len(),
+                        },
+                        "unterminated string literal".to_string(),
+                    ));
+                }
+            }
+        }
+
+        let end = self
+            .chars
+            .peek()
+            .map(|&(pos, _)| pos)
+            .unwrap_or(self.src.len());
+        Ok((Token::String(value), end))
+    }
+
+    /// Lexes an integer or float literal, with an optional leading `-`.
+    fn lex_number(&mut self, start: usize) -> Result<(Token, usize), Error> {
+        let mut raw = String::new();
+        let mut is_float = false;
+
+        if let Some(&(_, '-')) = self.chars.peek() {
+            raw.push('-');
+            self.chars.next();
+        }
+
+        while let Some(&(_, c)) = self.chars.peek() {
+            match c {
+                '0'..='9' => {
+                    raw.push(c);
+                    self.chars.next();
+                }
+                '.' if !is_float => {
+                    is_float = true;
+                    raw.push(c);
+                    self.chars.next();
+                }
+                _ => break,
+            }
+        }
+
+        let end = self
+            .chars
+            .peek()
+            .map(|&(pos, _)| pos)
+            .unwrap_or(self.src.len());
+        let span = Span { start, end };
+
+        if is_float {
+            let v = raw
+                .parse::<f64>()
+                .map_err(|_| Error::parse(span, format!("invalid float literal `{raw}`")))?;
+            Ok((Token::Float(v), end))
+        } else {
+            let v = raw
+                .parse::<i64>()
+                .map_err(|_| Error::parse(span, format!("invalid integer literal `{raw}`")))?;
+            Ok((Token::Integer(v), end))
+        }
+    }
+
+    /// Lexes an identifier (which may contain `.` for a dotted ontology
+    /// path), recognizing the language's keywords along the way.
+    fn lex_ident_or_keyword(&mut self, start: usize) -> (Token, usize) {
+        let mut raw = String::new();
+
+        while let Some(&(_, c)) = self.chars.peek() {
+            if c == '_' || c == '.' || c.is_alphanumeric() {
+                raw.push(c);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let end = self
+            .chars
+            .peek()
+            .map(|&(pos, _)| pos)
+            .unwrap_or(self.src.len());
+
+        let token = match raw.to_ascii_uppercase().as_str() {
+            "AND" => Token::And,
+            "OR" => Token::Or,
+            "NOT" => Token::Not,
+            "IN" => Token::In,
+            "BETWEEN" => Token::Between,
+            "LIKE" => Token::Like,
+            "ILIKE" => Token::Ilike,
+            "IS" => Token::Is,
+            "NULL" => Token::Null,
+            "TRUE" => Token::Bool(true),
+            "FALSE" => Token::Bool(false),
+            _ => Token::Ident(raw),
+        };
+
+        (token, end)
+    }
+}
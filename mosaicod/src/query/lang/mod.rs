@@ -0,0 +1,137 @@
+//! A small text query language that parses into the [`super::OntologyExpr`]
+//! tree consumed by `ClausesCompiler`/`ChunkQueryBuilder`, so callers don't
+//! have to build an [`super::OntologyFilter`] by hand:
+//!
+//! ```text
+//! topic.speed >= 10 AND (sensor.name = "lidar" OR sensor.name IN ["imu", "gps"])
+//! ```
+//!
+//! This mirrors how embedded databases expose a tokenizer-backed query
+//! grammar instead of only a builder API. See [`parser::parse`] for the
+//! grammar.
+
+mod lexer;
+mod parser;
+
+pub use lexer::Span;
+pub use parser::parse;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::{OntologyExpr, Op, Range, Value};
+
+    /// Unwraps a bare `Leaf` down to its single `(field, op)` pair, panicking
+    /// if the expression isn't a single-field leaf -- every test here parses
+    /// one comparison at a time.
+    fn only_field_op(expr: &OntologyExpr) -> (String, Op<Value>) {
+        let OntologyExpr::Leaf(filter) = expr else {
+            panic!("expected a leaf, got {expr:?}");
+        };
+        let mut it = filter.iter();
+        let (field, op) = it.next().expect("one field");
+        assert!(it.next().is_none(), "expected exactly one field");
+        (field.value().to_string(), op.clone())
+    }
+
+    #[test]
+    fn parses_a_flat_comparison() {
+        let expr = parse(r#"topic.speed >= 10"#).expect("should parse");
+
+        assert_eq!(
+            only_field_op(&expr),
+            ("topic.speed".to_string(), Op::Geq(Value::Integer(10)))
+        );
+    }
+
+    #[test]
+    fn parses_string_equality() {
+        let expr = parse(r#"sensor.name = "lidar""#).expect("should parse");
+
+        assert_eq!(
+            only_field_op(&expr),
+            (
+                "sensor.name".to_string(),
+                Op::Eq(Value::Text("lidar".to_string()))
+            )
+        );
+    }
+
+    #[test]
+    fn parses_in_list() {
+        let expr = parse(r#"sensor.name IN ["imu", "gps"]"#).expect("should parse");
+
+        assert_eq!(
+            only_field_op(&expr),
+            (
+                "sensor.name".to_string(),
+                Op::In(vec![
+                    Value::Text("imu".to_string()),
+                    Value::Text("gps".to_string())
+                ])
+            )
+        );
+    }
+
+    #[test]
+    fn parses_between() {
+        let expr = parse(r#"topic.speed BETWEEN 1 AND 10"#).expect("should parse");
+
+        assert_eq!(
+            only_field_op(&expr),
+            (
+                "topic.speed".to_string(),
+                Op::Between(Range::try_new(Value::Integer(1), Value::Integer(10)).unwrap())
+            )
+        );
+    }
+
+    #[test]
+    fn parses_is_null_and_is_not_null() {
+        let is_null = parse(r#"sensor.name IS NULL"#).expect("should parse");
+        assert_eq!(only_field_op(&is_null), ("sensor.name".to_string(), Op::Nex));
+
+        let is_not_null = parse(r#"sensor.name IS NOT NULL"#).expect("should parse");
+        assert_eq!(
+            only_field_op(&is_not_null),
+            ("sensor.name".to_string(), Op::Ex)
+        );
+    }
+
+    #[test]
+    fn parses_like() {
+        let expr = parse(r#"sensor.name LIKE "lid%""#).expect("should parse");
+
+        assert_eq!(
+            only_field_op(&expr),
+            (
+                "sensor.name".to_string(),
+                Op::Like(Value::Text("lid%".to_string()))
+            )
+        );
+    }
+
+    #[test]
+    fn and_or_not_combine_into_the_expected_tree() {
+        let expr = parse(
+            r#"topic.speed >= 10 AND (sensor.name = "lidar" OR sensor.name IN ["imu", "gps"])"#,
+        )
+        .expect("should parse");
+
+        let OntologyExpr::And(items) = expr else {
+            panic!("expected a top-level AND");
+        };
+        assert_eq!(items.len(), 2);
+        assert!(matches!(items[1], OntologyExpr::Or(_)));
+
+        let not_expr = parse(r#"NOT sensor.name = "lidar""#).expect("should parse");
+        assert!(matches!(not_expr, OntologyExpr::Not(_)));
+    }
+
+    #[test]
+    fn unknown_operator_is_a_span_annotated_parse_error() {
+        let err = parse(r#"topic.speed ~= 10"#).expect_err("should not parse");
+
+        assert!(matches!(err, crate::query::Error::Parse { .. }));
+    }
+}
@@ -0,0 +1,208 @@
+use super::lexer::{Lexer, Token};
+use super::Span;
+use crate::query::{Error, OntologyExpr, OntologyField, OntologyFilter, Op, Range, Value};
+use std::collections::HashMap;
+
+/// Parses `input` into the [`OntologyExpr`] tree consumed by
+/// `ClausesCompiler`/`ChunkQueryBuilder`.
+///
+/// Grammar, lowest to highest precedence (`AND` binds tighter than `OR`,
+/// `NOT` binds tighter than both):
+///
+/// ```text
+/// expr       := or
+/// or         := and ("OR" and)*
+/// and        := unary ("AND" unary)*
+/// unary      := "NOT" unary | primary
+/// primary    := "(" expr ")" | comparison
+/// comparison := IDENT ( "=" | "!=" | "<" | "<=" | ">" | ">=" | "LIKE" | "ILIKE" ) literal
+///             | IDENT "IN" "[" literal ("," literal)* "]"
+///             | IDENT "BETWEEN" literal "AND" literal
+///             | IDENT "IS" "NULL"
+///             | IDENT "IS" "NOT" "NULL"
+/// literal    := INTEGER | FLOAT | STRING | "TRUE" | "FALSE"
+/// ```
+pub fn parse(input: &str) -> Result<OntologyExpr, Error> {
+    let tokens = Lexer::new(input).tokenize()?;
+    let mut parser = Parser { tokens, pos: 0 };
+
+    let expr = parser.parse_or()?;
+    parser.expect_eof()?;
+    Ok(expr)
+}
+
+struct Parser {
+    tokens: Vec<(Token, Span)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn span(&self) -> Span {
+        self.tokens[self.pos].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), Error> {
+        if self.peek() == expected {
+            self.advance();
+            Ok(())
+        } else {
+            Err(Error::parse(
+                self.span(),
+                format!("expected `{expected:?}`, found `{:?}`", self.peek()),
+            ))
+        }
+    }
+
+    fn expect_eof(&mut self) -> Result<(), Error> {
+        if matches!(self.peek(), Token::Eof) {
+            Ok(())
+        } else {
+            Err(Error::parse(
+                self.span(),
+                format!("unexpected trailing token `{:?}`", self.peek()),
+            ))
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<OntologyExpr, Error> {
+        let mut items = vec![self.parse_and()?];
+
+        while matches!(self.peek(), Token::Or) {
+            self.advance();
+            items.push(self.parse_and()?);
+        }
+
+        Ok(if items.len() == 1 {
+            items.pop().expect("just checked len == 1")
+        } else {
+            OntologyExpr::Or(items)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<OntologyExpr, Error> {
+        let mut items = vec![self.parse_unary()?];
+
+        while matches!(self.peek(), Token::And) {
+            self.advance();
+            items.push(self.parse_unary()?);
+        }
+
+        Ok(if items.len() == 1 {
+            items.pop().expect("just checked len == 1")
+        } else {
+            OntologyExpr::And(items)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<OntologyExpr, Error> {
+        if matches!(self.peek(), Token::Not) {
+            self.advance();
+            return Ok(OntologyExpr::Not(Box::new(self.parse_unary()?)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<OntologyExpr, Error> {
+        if matches!(self.peek(), Token::LParen) {
+            self.advance();
+            let expr = self.parse_or()?;
+            self.expect(&Token::RParen)?;
+            return Ok(expr);
+        }
+
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<OntologyExpr, Error> {
+        let span = self.span();
+        let Token::Ident(name) = self.advance() else {
+            return Err(Error::parse(span, "expected a field name".to_string()));
+        };
+        let field = OntologyField::try_new(name)?;
+
+        let op_span = self.span();
+        let op = match self.advance() {
+            Token::Eq => Op::Eq(self.parse_value()?),
+            Token::Neq => Op::Neq(self.parse_value()?),
+            Token::Lt => Op::Lt(self.parse_value()?),
+            Token::Leq => Op::Leq(self.parse_value()?),
+            Token::Gt => Op::Gt(self.parse_value()?),
+            Token::Geq => Op::Geq(self.parse_value()?),
+            Token::Like => Op::Like(self.parse_value()?),
+            Token::Ilike => Op::Ilike(self.parse_value()?),
+            Token::In => Op::In(self.parse_list()?),
+            Token::Between => {
+                let min = self.parse_value()?;
+                self.expect(&Token::And)?;
+                let max = self.parse_value()?;
+                Op::Between(
+                    Range::try_new(min, max)
+                        .map_err(|_| Error::parse(op_span, "BETWEEN requires min <= max".to_string()))?,
+                )
+            }
+            Token::Is => {
+                if matches!(self.peek(), Token::Not) {
+                    self.advance();
+                    self.expect(&Token::Null)?;
+                    Op::Ex
+                } else {
+                    self.expect(&Token::Null)?;
+                    Op::Nex
+                }
+            }
+            other => {
+                return Err(Error::parse(
+                    op_span,
+                    format!("expected a comparison operator, found `{other:?}`"),
+                ));
+            }
+        };
+
+        let mut fields = HashMap::new();
+        fields.insert(field, op);
+        Ok(OntologyExpr::Leaf(OntologyFilter::new(fields)))
+    }
+
+    fn parse_value(&mut self) -> Result<Value, Error> {
+        let span = self.span();
+        match self.advance() {
+            Token::Integer(v) => Ok(Value::Integer(v)),
+            Token::Float(v) => Ok(Value::Float(v)),
+            Token::String(v) => Ok(Value::Text(v)),
+            Token::Bool(v) => Ok(Value::Boolean(v)),
+            other => Err(Error::parse(
+                span,
+                format!("expected a literal, found `{other:?}`"),
+            )),
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<Value>, Error> {
+        self.expect(&Token::LBracket)?;
+
+        let mut values = Vec::new();
+        if !matches!(self.peek(), Token::RBracket) {
+            values.push(self.parse_value()?);
+            while matches!(self.peek(), Token::Comma) {
+                self.advance();
+                values.push(self.parse_value()?);
+            }
+        }
+
+        self.expect(&Token::RBracket)?;
+        Ok(values)
+    }
+}
@@ -3,11 +3,22 @@
 mod filter;
 pub use filter::*;
 
+mod expr;
+pub use expr::*;
+
 mod builder;
 pub use builder::*;
 
 mod timeseries_gw;
 pub use timeseries_gw::*;
 
+mod parquet_footer_cache;
+pub use parquet_footer_cache::*;
+
 mod error;
 pub use error::*;
+
+mod dot;
+pub use dot::*;
+
+pub mod lang;
@@ -0,0 +1,250 @@
+//! Caches parsed Parquet footer metadata for files read through
+//! [`super::TimeseriesGw`], so a repartitioned read doesn't parse the same
+//! footer twice -- once in `optimal_batch_size` to size the batch, again in
+//! `read` to actually scan the data -- and so a repeated read of an
+//! immutable (locked) sequence doesn't re-fetch it at all.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use parquet::file::footer;
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::page_index::index::Index;
+use parquet::file::page_index::index_reader;
+use parquet::file::page_index::offset_index::OffsetIndexMetaData;
+use parquet::file::statistics::Statistics;
+use tokio::sync::Mutex;
+
+use crate::store;
+
+use super::Error;
+
+/// Trailing bytes of a Parquet file: a 4-byte little-endian footer metadata
+/// length, followed by the 4-byte `PAR1` magic.
+const FOOTER_SIZE: usize = 8;
+
+/// Min/max/null-count for one column of one row group, lifted straight from
+/// the footer's row-group statistics without decoding it into an Arrow
+/// scalar -- a caller that needs a typed bound decodes it against the
+/// column's Arrow type; the cache only needs to carry it.
+#[derive(Debug, Clone)]
+pub struct ColumnChunkStats {
+    pub column_path: String,
+    pub statistics: Option<Statistics>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RowGroupStats {
+    pub row_count: i64,
+    pub columns: Vec<ColumnChunkStats>,
+}
+
+/// Page-level column index (per-page min/max/null-count) and offset index
+/// (per-page byte ranges), the finer-grained sibling of [`RowGroupStats`].
+/// Either half is `None` when the writer didn't emit it for that column.
+#[derive(Debug, Clone)]
+pub struct ColumnPageIndex {
+    pub column_path: String,
+    pub column_index: Option<Index>,
+    pub offset_index: Option<OffsetIndexMetaData>,
+}
+
+/// One file's cached footer: everything [`super::TimeseriesGw`] needs to
+/// size a repartitioned read and prune row groups/pages, without re-parsing
+/// the file.
+#[derive(Debug, Clone)]
+pub struct CachedFooter {
+    pub row_count: usize,
+    pub byte_size: usize,
+    pub row_groups: Vec<RowGroupStats>,
+    /// Page index per row group, in the same order as `row_groups`; each
+    /// inner `Vec` is per-column, in schema column order.
+    pub page_index: Vec<Vec<ColumnPageIndex>>,
+    e_tag: Option<String>,
+}
+
+/// Bounded, LRU-evicted cache of [`CachedFooter`]s keyed by object path,
+/// invalidated whenever the store reports a changed ETag.
+///
+/// Guarded by a single [`tokio::sync::Mutex`], following the same
+/// check-freshness-then-refetch-under-lock shape as
+/// [`crate::store::RefreshingCredentials`], with an LRU-ordered path list
+/// alongside the map so the cache stays bounded under many topics.
+pub struct ParquetFooterCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, Arc<CachedFooter>>,
+    /// Least- to most-recently-used order; the front is the next eviction
+    /// candidate.
+    recency: VecDeque<String>,
+}
+
+impl ParquetFooterCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Returns the cached footer for `path`, fetching and parsing it from
+    /// `store` on a miss, or when the store reports a different ETag than
+    /// what the cached entry was parsed from.
+    pub async fn get_or_fetch(
+        &self,
+        store: &store::Store,
+        path: &str,
+    ) -> Result<Arc<CachedFooter>, Error> {
+        let info = store.head(path).await?;
+
+        {
+            let mut state = self.state.lock().await;
+            if let Some(footer) = state.entries.get(path) {
+                if footer.e_tag == info.e_tag {
+                    touch(&mut state.recency, path);
+                    return Ok(footer.clone());
+                }
+            }
+        }
+
+        let footer = Arc::new(fetch_footer(store, path, info).await?);
+
+        let mut state = self.state.lock().await;
+        insert_with_eviction(&mut state, self.capacity, path.to_string(), footer.clone());
+
+        Ok(footer)
+    }
+}
+
+fn touch(recency: &mut VecDeque<String>, path: &str) {
+    if let Some(pos) = recency.iter().position(|p| p == path) {
+        recency.remove(pos);
+    }
+    recency.push_back(path.to_string());
+}
+
+fn insert_with_eviction(
+    state: &mut CacheState,
+    capacity: usize,
+    path: String,
+    footer: Arc<CachedFooter>,
+) {
+    if state.entries.contains_key(&path) {
+        touch(&mut state.recency, &path);
+        state.entries.insert(path, footer);
+        return;
+    }
+
+    while state.entries.len() >= capacity {
+        let Some(oldest) = state.recency.pop_front() else {
+            break;
+        };
+        state.entries.remove(&oldest);
+    }
+
+    state.recency.push_back(path.clone());
+    state.entries.insert(path, footer);
+}
+
+/// Fetches and parses `path`'s Parquet footer (and page index, if present)
+/// from `store`, doing only the two range reads the footer itself requires
+/// -- the 8-byte trailer to learn the metadata's length, then the metadata
+/// itself -- plus one range read per column chunk's page index, rather than
+/// reading the whole object.
+async fn fetch_footer(
+    store: &store::Store,
+    path: &str,
+    info: store::ObjectInfo,
+) -> Result<CachedFooter, Error> {
+    let size = info.size;
+
+    let trailer = store
+        .read_range(path, size.saturating_sub(FOOTER_SIZE)..size)
+        .await?;
+    let metadata_len = u32::from_le_bytes(trailer[0..4].try_into().unwrap()) as usize;
+
+    let metadata_end = size.saturating_sub(FOOTER_SIZE);
+    let metadata_start = metadata_end.saturating_sub(metadata_len);
+    let metadata_bytes = store.read_range(path, metadata_start..metadata_end).await?;
+    let metadata: ParquetMetaData = footer::decode_metadata(&metadata_bytes)?;
+
+    let row_groups: Vec<RowGroupStats> = metadata
+        .row_groups()
+        .iter()
+        .map(|row_group| RowGroupStats {
+            row_count: row_group.num_rows(),
+            columns: row_group
+                .columns()
+                .iter()
+                .map(|column| ColumnChunkStats {
+                    column_path: column.column_path().string(),
+                    statistics: column.statistics().cloned(),
+                })
+                .collect(),
+        })
+        .collect();
+    let row_count = row_groups.iter().map(|rg| rg.row_count.max(0) as usize).sum();
+
+    let page_index = fetch_page_index(store, path, &metadata).await?;
+
+    Ok(CachedFooter {
+        row_count,
+        byte_size: size,
+        row_groups,
+        page_index,
+        e_tag: info.e_tag,
+    })
+}
+
+/// Fetches and decodes the column index and offset index for every column
+/// chunk in every row group of `metadata`, one range read per index half a
+/// writer actually emitted for that column.
+async fn fetch_page_index(
+    store: &store::Store,
+    path: &str,
+    metadata: &ParquetMetaData,
+) -> Result<Vec<Vec<ColumnPageIndex>>, Error> {
+    let mut per_row_group = Vec::with_capacity(metadata.row_groups().len());
+
+    for row_group in metadata.row_groups() {
+        let mut columns = Vec::with_capacity(row_group.columns().len());
+
+        for column in row_group.columns() {
+            let column_index = match (column.column_index_offset(), column.column_index_length())
+            {
+                (Some(offset), Some(len)) => {
+                    let offset = offset as usize;
+                    let len = len as usize;
+                    let bytes = store.read_range(path, offset..offset + len).await?;
+                    index_reader::decode_column_index(&bytes, column.column_type()).ok()
+                }
+                _ => None,
+            };
+
+            let offset_index = match (column.offset_index_offset(), column.offset_index_length())
+            {
+                (Some(offset), Some(len)) => {
+                    let offset = offset as usize;
+                    let len = len as usize;
+                    let bytes = store.read_range(path, offset..offset + len).await?;
+                    index_reader::decode_offset_index(&bytes).ok()
+                }
+                _ => None,
+            };
+
+            columns.push(ColumnPageIndex {
+                column_path: column.column_path().string(),
+                column_index,
+                offset_index,
+            });
+        }
+
+        per_row_group.push(columns);
+    }
+
+    Ok(per_row_group)
+}
@@ -5,13 +5,14 @@
 //! The engine integrates directly with the configured [`store::Store`] to resolve
 //! paths and access data sources like Parquet files efficiently.
 use crate::traits::AsExtension;
-use crate::{params, query, rw, store};
+use crate::{metrics, params, query, rw, store};
 use arrow::datatypes::{Schema, SchemaRef};
 use datafusion::datasource::file_format::parquet::ParquetFormat;
 use datafusion::datasource::listing::ListingOptions;
 use datafusion::execution::SendableRecordBatchStream;
 use datafusion::execution::runtime_env::{RuntimeEnv, RuntimeEnvBuilder};
 use datafusion::functions::core::expr_ext::FieldAccessor;
+use datafusion::logical_expr::{BinaryExpr, Operator};
 use datafusion::prelude::*;
 use std::collections::HashMap;
 use std::path::Path;
@@ -24,6 +25,7 @@ pub type TimeseriesGwRef = Arc<TimeseriesGw>;
 pub struct TimeseriesGw {
     runtime: Arc<RuntimeEnv>,
     store: Arc<store::Store>,
+    footer_cache: query::ParquetFooterCache,
 }
 
 impl TimeseriesGw {
@@ -33,10 +35,13 @@ impl TimeseriesGw {
                 .with_object_store_registry(store.registry())
                 .build()?,
         );
+        let footer_cache =
+            query::ParquetFooterCache::new(params::configurables().parquet_footer_cache_capacity);
 
         Ok(TimeseriesGw {
             runtime,
             store: store.clone(),
+            footer_cache,
         })
     }
 
@@ -52,9 +57,21 @@ impl TimeseriesGw {
         format: rw::Format,
         repartition: bool,
     ) -> Result<TimeseriesGwResult, Error> {
+        let _timer = metrics::start_query_read_timer();
+
         let listing_options = get_listing_options(format);
 
-        let mut conf = SessionConfig::new();
+        // `OntologyFilter`s are applied to the returned `TimeseriesGwResult`
+        // via `filter`/`apply_filter` before it's executed (`stream`/
+        // `count`), so DataFusion's filter-pushdown optimizer still sees the
+        // predicate as part of the plan it optimizes at execution time --
+        // these two flags are what let that pushed-down predicate prune
+        // whole row groups and pages of the underlying Parquet file via
+        // their statistics/page index, instead of just filtering rows after
+        // they're already read off disk.
+        let mut conf = SessionConfig::new()
+            .set_bool("datafusion.execution.parquet.pruning", true)
+            .set_bool("datafusion.execution.parquet.enable_page_index", true);
         if repartition {
             let optimal_batch_size = self.optimal_batch_size(&path, format).await?;
             conf = conf.with_batch_size(optimal_batch_size);
@@ -82,34 +99,37 @@ impl TimeseriesGw {
         Ok(TimeseriesGwResult { data_frame: df })
     }
 
+    /// Computes the batch size to pass to [`SessionConfig::with_batch_size`]
+    /// from each data file's cached Parquet footer (row count, byte size),
+    /// fetching a footer only on a cache miss or ETag change -- this used to
+    /// register the listing table a second time and run a full
+    /// `SELECT * FROM data` just to get `df.count()`, on top of listing the
+    /// directory and `head`-ing every file for its size.
     async fn optimal_batch_size(
         &self,
         path: impl AsRef<Path>,
         format: rw::Format,
     ) -> Result<usize, Error> {
         let datafiles = self.store.list(&path, Some(&format.as_extension())).await?;
+
         let mut total_size = 0;
+        let mut total_rows = 0;
         for file in &datafiles {
-            total_size += self.store.size(file).await?;
+            let footer = self.footer_cache.get_or_fetch(&self.store, file).await?;
+            total_size += footer.byte_size;
+            total_rows += footer.row_count;
         }
 
-        // Compute the number of rows in the datafile
-        let listing_options = get_listing_options(format);
-        let ctx = SessionContext::new_with_config_rt(SessionConfig::new(), self.runtime.clone());
-        ctx.register_listing_table(
-            "data",
-            self.datafile_url(path)?,
-            listing_options,
-            None,
-            None,
-        )
-        .await?;
-        let df = ctx.sql("SELECT * FROM data").await?;
-        let count = df.count().await?;
+        metrics::record_bytes_scanned(self.store.target().backend_label(), total_size);
 
         let target_size = params::configurables().target_message_size_in_bytes;
 
-        Ok((target_size * count) / total_size)
+        let batch_size = (target_size * total_rows) / total_size;
+        metrics::collectors()
+            .query_optimal_batch_size
+            .set(batch_size as i64);
+
+        Ok(batch_size)
     }
 
     fn datafile_url(&self, path: impl AsRef<Path>) -> Result<url::Url, Error> {
@@ -146,12 +166,28 @@ impl TimeseriesGwResult {
         Ok(TimeseriesGwResult { data_frame })
     }
 
+    /// Applies a full [`query::Filter`] to the underlying data frame.
+    ///
+    /// Only the `ontology` clause describes per-row data and can be pushed
+    /// down into the DataFusion logical plan built over the chunk's data
+    /// file; `sequence`/`topic` clauses constrain which chunks are read in
+    /// the first place and are expected to have already been resolved
+    /// against the repository before the chunk ever reaches this gateway.
+    pub fn apply_filter(self, filter: query::Filter) -> Result<Self, Error> {
+        match filter.ontology {
+            Some(ontology) => self.filter(ontology),
+            None => Ok(self),
+        }
+    }
+
     pub async fn stream(self) -> Result<SendableRecordBatchStream, Error> {
         self.data_frame.execute_stream().await.map_err(|e| e.into())
     }
 
     pub async fn count(self) -> Result<usize, Error> {
-        Ok(self.data_frame.count().await?)
+        let rows = self.data_frame.count().await?;
+        metrics::record_query_rows(rows);
+        Ok(rows)
     }
 }
 
@@ -193,7 +229,18 @@ fn ontology_filter_to_df_expr(filter: query::OntologyFilter) -> Option<Expr> {
                 let list = items.into_iter().map(value_to_df_expr).collect();
                 Some(unfold_field(&field).in_list(list, false))
             }
-            query::Op::Match(v) => Some(unfold_field(&field).like(value_to_df_expr(v))),
+            query::Op::Match(v) => Some(match_to_df_expr(&field, v)),
+            query::Op::Nin(items) => {
+                let list = items.into_iter().map(value_to_df_expr).collect();
+                Some(unfold_field(&field).in_list(list, true))
+            }
+            query::Op::Like(v) => Some(unfold_field(&field).like(value_to_df_expr(v))),
+            query::Op::Ilike(v) => Some(unfold_field(&field).ilike(value_to_df_expr(v))),
+            query::Op::Regex(v) => Some(Expr::BinaryExpr(BinaryExpr::new(
+                Box::new(unfold_field(&field)),
+                Operator::RegexMatch,
+                Box::new(value_to_df_expr(v)),
+            ))),
         };
 
         if let Some(expr) = expr {
@@ -208,6 +255,28 @@ fn ontology_filter_to_df_expr(filter: query::OntologyFilter) -> Option<Expr> {
     ret
 }
 
+/// Translates an [`query::Op::Match`] value into the corresponding DataFusion
+/// expression: a glob is lowered to a `LIKE` pattern, an anchored regex to a
+/// `~` (regex match) comparison.
+fn match_to_df_expr(field: &query::OntologyField, v: query::Value) -> Expr {
+    let query::Value::Text(text) = v else {
+        // Only `Value::Text` claims `support_match()`; fall back to an
+        // always-false comparison for anything else that slips through.
+        return lit(false);
+    };
+
+    match query::MatchPattern::parse(&text) {
+        query::MatchPattern::Glob(glob) => {
+            unfold_field(field).like(lit(query::glob_to_sql_like(&glob)))
+        }
+        query::MatchPattern::Regex(pattern) => Expr::BinaryExpr(BinaryExpr::new(
+            Box::new(unfold_field(field)),
+            Operator::RegexMatch,
+            Box::new(lit(pattern)),
+        )),
+    }
+}
+
 fn value_to_df_expr(v: query::Value) -> Expr {
     match v {
         query::Value::Integer(v) => lit(v),
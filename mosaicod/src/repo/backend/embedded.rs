@@ -0,0 +1,474 @@
+//! A `sled`-backed [`RepoBackend`](super::RepoBackend)/[`TopicBackend`](super::TopicBackend)
+//! implementation.
+//!
+//! Aimed at single-node or test deployments where running a full Postgres
+//! instance is unwarranted overhead. Every record [`RepoBackend`] touches is
+//! stored as a JSON blob in its own `sled` tree, keyed by resource name (or
+//! layer name); there is no SQL, no migrations, and no separate connection
+//! pool to manage — `sled::open` on a directory is enough to get going.
+//!
+//! Topic-specific fields ([`TopicBackend`]) live in their own `topics` tree,
+//! keyed by name just like `resources`, rather than folded into
+//! [`ResourceRecord`]: identity/lock state is shared by every resource kind
+//! and stays put in `resources`, while `sequence_id`/`ontology_tag`/
+//! `serialization_format`/`user_metadata` only make sense for topics. A
+//! `topics_by_sequence` tree, keyed by `sequence_id`, indexes topic names by
+//! their parent sequence the same way `resources` is indexed by name.
+use std::str::FromStr;
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    marshal, rw,
+    types::{self, Resource},
+};
+
+use super::{NewTopic, RepoBackend, TopicBackend, TopicRecord};
+use crate::repo::Error;
+
+/// A single resource's (sequence's or topic's) repository record, as stored
+/// in the `resources` tree.
+#[derive(Serialize, Deserialize)]
+struct ResourceRecord {
+    id: i32,
+    /// Stored as text rather than `uuid::Uuid` directly, to avoid depending
+    /// on the `uuid` crate's `serde` feature just for this one field.
+    uuid: String,
+    locked: bool,
+    notifies: Vec<NotifyRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct NotifyRecord {
+    id: i32,
+    notify_type: String,
+    msg: Option<String>,
+    created_at_unix_millis: i64,
+}
+
+/// A single layer's repository record, as stored in the `layers` tree.
+#[derive(Serialize, Deserialize)]
+struct LayerRecord {
+    id: i32,
+    description: String,
+}
+
+/// A single topic's metadata, as stored in the `topics` tree. Identity and
+/// lock state live in the `resources` tree instead (see the module doc).
+#[derive(Serialize, Deserialize, Clone)]
+struct TopicMetaRecord {
+    sequence_id: i32,
+    ontology_tag: Option<String>,
+    serialization_format: Option<String>,
+    user_metadata: Option<serde_json::Value>,
+    created_at_unix_millis: i64,
+}
+
+/// An embedded, `sled`-backed [`RepoBackend`].
+///
+/// Unlike [`super::super::Repository`], this type has no notion of
+/// transactions or connection pooling — `sled` trees are internally
+/// consistent per-operation, which is sufficient for the single-process
+/// deployments this backend targets.
+pub struct EmbeddedRepository {
+    resources: sled::Tree,
+    layers: sled::Tree,
+    topics: sled::Tree,
+    topics_by_sequence: sled::Tree,
+    next_layer_id: AtomicI32,
+    next_notify_id: AtomicI32,
+    next_resource_id: AtomicI32,
+}
+
+impl EmbeddedRepository {
+    /// Opens (creating if needed) a `sled` database at `path`.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        let db = sled::open(path)?;
+        Self::from_db(db)
+    }
+
+    /// Builds an in-memory instance, useful for tests and short-lived tools.
+    pub fn temporary() -> Result<Self, Error> {
+        let db = sled::Config::new().temporary(true).open()?;
+        Self::from_db(db)
+    }
+
+    fn from_db(db: sled::Db) -> Result<Self, Error> {
+        let resources = db.open_tree("resources")?;
+        let layers = db.open_tree("layers")?;
+        let topics = db.open_tree("topics")?;
+        let topics_by_sequence = db.open_tree("topics_by_sequence")?;
+
+        let next_layer_id = layers
+            .iter()
+            .filter_map(Result::ok)
+            .filter_map(|(_, v)| serde_json::from_slice::<LayerRecord>(&v).ok())
+            .map(|l| l.id)
+            .max()
+            .unwrap_or(0);
+
+        let next_resource_id = resources
+            .iter()
+            .filter_map(Result::ok)
+            .filter_map(|(_, v)| serde_json::from_slice::<ResourceRecord>(&v).ok())
+            .map(|r| r.id)
+            .max()
+            .unwrap_or(0);
+
+        Ok(Self {
+            resources,
+            layers,
+            topics,
+            topics_by_sequence,
+            next_layer_id: AtomicI32::new(next_layer_id + 1),
+            next_notify_id: AtomicI32::new(1),
+            next_resource_id: AtomicI32::new(next_resource_id + 1),
+        })
+    }
+
+    fn get_resource(&self, name: &str) -> Result<ResourceRecord, Error> {
+        let bytes = self.resources.get(name)?.ok_or(Error::NotFound)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn put_resource(&self, name: &str, record: &ResourceRecord) -> Result<(), Error> {
+        let bytes = serde_json::to_vec(record)?;
+        self.resources.insert(name, bytes)?;
+        Ok(())
+    }
+
+    fn get_topic_meta(&self, name: &str) -> Result<TopicMetaRecord, Error> {
+        let bytes = self.topics.get(name)?.ok_or(Error::NotFound)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn put_topic_meta(&self, name: &str, record: &TopicMetaRecord) -> Result<(), Error> {
+        let bytes = serde_json::to_vec(record)?;
+        self.topics.insert(name, bytes)?;
+        Ok(())
+    }
+
+    /// Appends `name` to the index of topic names belonging to
+    /// `sequence_id`, used by [`TopicBackend::find_by_sequence_id`].
+    fn index_topic_by_sequence(&self, sequence_id: i32, name: &str) -> Result<(), Error> {
+        let key = sequence_id.to_be_bytes();
+        let mut names: Vec<String> = self
+            .topics_by_sequence
+            .get(key)?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?
+            .unwrap_or_default();
+        names.push(name.to_string());
+        self.topics_by_sequence.insert(key, serde_json::to_vec(&names)?)?;
+        Ok(())
+    }
+
+    fn topic_names_by_sequence(&self, sequence_id: i32) -> Result<Vec<String>, Error> {
+        let key = sequence_id.to_be_bytes();
+        Ok(self
+            .topics_by_sequence
+            .get(key)?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    /// Assembles a [`TopicRecord`] from `name`'s entries in `resources` and
+    /// `topics`, the same split [`Self::get_resource`]/[`Self::get_topic_meta`]
+    /// store them under.
+    fn topic_record(&self, name: &str) -> Result<TopicRecord, Error> {
+        let resource = self.get_resource(name)?;
+        let meta = self.get_topic_meta(name)?;
+        Ok(TopicRecord {
+            id: types::ResourceId {
+                id: resource.id,
+                uuid: uuid::Uuid::parse_str(&resource.uuid).expect("stored uuid is always valid"),
+            },
+            name: name.to_string(),
+            sequence_id: meta.sequence_id,
+            ontology_tag: meta.ontology_tag,
+            serialization_format: meta
+                .serialization_format
+                .map(|f| f.parse().expect("stored format is always valid")),
+            user_metadata: meta.user_metadata,
+            locked: resource.locked,
+            created_at: types::Timestamp::from(meta.created_at_unix_millis),
+        })
+    }
+
+    /// Seeds (or overwrites) `resource`'s record with an id/lock state
+    /// carried over from another backend. Not part of [`RepoBackend`]
+    /// itself, since — unlike `delete`/`lock`/`notify*` — resource
+    /// *creation* genuinely differs between sequences and topics (see the
+    /// module doc on [`super`]); this is the narrower operation
+    /// [`super::migrate`] needs instead.
+    pub fn seed_resource(
+        &self,
+        resource: &dyn types::Resource,
+        id: types::ResourceId,
+        locked: bool,
+    ) -> Result<(), Error> {
+        let notifies = self
+            .get_resource(resource.name())
+            .map(|r| r.notifies)
+            .unwrap_or_default();
+        self.put_resource(
+            resource.name(),
+            &ResourceRecord {
+                id: id.id,
+                uuid: id.uuid.to_string(),
+                locked,
+                notifies,
+            },
+        )
+    }
+
+    /// Appends a notify carried over from another backend to `resource`'s
+    /// record. See [`Self::seed_resource`] for why this isn't on
+    /// [`RepoBackend`].
+    pub fn seed_notify(
+        &self,
+        resource: &dyn types::Resource,
+        notify: types::Notify,
+    ) -> Result<(), Error> {
+        let mut record = self.get_resource(resource.name())?;
+        record.notifies.push(NotifyRecord {
+            id: notify.id,
+            notify_type: notify.notify_type.to_string(),
+            msg: notify.msg,
+            created_at_unix_millis: notify.created_at.unix_millis(),
+        });
+        self.put_resource(resource.name(), &record)
+    }
+}
+
+#[async_trait]
+impl RepoBackend for EmbeddedRepository {
+    async fn resource_id(
+        &self,
+        resource: &dyn types::Resource,
+    ) -> Result<types::ResourceId, Error> {
+        let record = self.get_resource(resource.name())?;
+        Ok(types::ResourceId {
+            id: record.id,
+            // `record.uuid` is only ever written by `seed_resource` and
+            // `TopicBackend::create`, always from a valid `uuid::Uuid`.
+            uuid: uuid::Uuid::parse_str(&record.uuid).expect("stored uuid is always valid"),
+        })
+    }
+
+    async fn is_locked(&self, resource: &dyn types::Resource) -> Result<bool, Error> {
+        Ok(self.get_resource(resource.name())?.locked)
+    }
+
+    async fn lock(&self, resource: &dyn types::Resource) -> Result<(), Error> {
+        let mut record = self.get_resource(resource.name())?;
+        record.locked = true;
+        self.put_resource(resource.name(), &record)
+    }
+
+    async fn delete(&self, resource: &dyn types::Resource) -> Result<(), Error> {
+        self.resources.remove(resource.name())?;
+        Ok(())
+    }
+
+    async fn notify_create(
+        &self,
+        resource: &dyn types::Resource,
+        notify_type: types::NotifyType,
+        msg: String,
+    ) -> Result<types::Notify, Error> {
+        let mut record = self.get_resource(resource.name())?;
+
+        let id = self.next_notify_id.fetch_add(1, Ordering::Relaxed);
+        let created_at = types::Timestamp::now();
+        record.notifies.push(NotifyRecord {
+            id,
+            notify_type: notify_type.to_string(),
+            msg: Some(msg.clone()),
+            created_at_unix_millis: created_at.into(),
+        });
+        self.put_resource(resource.name(), &record)?;
+
+        Ok(types::Notify {
+            id,
+            target: resource_box(resource),
+            notify_type,
+            msg: Some(msg),
+            created_at: created_at.into(),
+        })
+    }
+
+    async fn notify_list(
+        &self,
+        resource: &dyn types::Resource,
+    ) -> Result<Vec<types::Notify>, Error> {
+        let record = self.get_resource(resource.name())?;
+        Ok(record
+            .notifies
+            .into_iter()
+            .map(|n| to_types_notify(resource, n))
+            .collect())
+    }
+
+    async fn notify_purge(&self, resource: &dyn types::Resource) -> Result<(), Error> {
+        let mut record = self.get_resource(resource.name())?;
+        record.notifies.clear();
+        self.put_resource(resource.name(), &record)
+    }
+
+    async fn layer_list(&self) -> Result<Vec<types::Layer>, Error> {
+        self.layers
+            .iter()
+            .map(|entry| {
+                let (name, bytes) = entry?;
+                let record: LayerRecord = serde_json::from_slice(&bytes)?;
+                let name = String::from_utf8_lossy(&name).into_owned();
+                Ok(types::Layer::new(
+                    types::LayerLocator::from(name.as_str()),
+                    record.description,
+                ))
+            })
+            .collect()
+    }
+
+    async fn layer_create(&self, layer: types::Layer) -> Result<i32, Error> {
+        let id = self.next_layer_id.fetch_add(1, Ordering::Relaxed);
+        let record = LayerRecord {
+            id,
+            description: layer.description,
+        };
+        let bytes = serde_json::to_vec(&record)?;
+        self.layers.insert(layer.locator.name(), bytes)?;
+        Ok(id)
+    }
+
+    async fn layer_delete(&self, locator: &types::LayerLocator) -> Result<(), Error> {
+        self.layers.remove(locator.name())?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TopicBackend for EmbeddedRepository {
+    async fn find_by_locator(&self, loc: &types::TopicResourceLocator) -> Result<TopicRecord, Error> {
+        self.topic_record(loc.name())
+    }
+
+    async fn find_all(&self) -> Result<Vec<TopicRecord>, Error> {
+        self.topics
+            .iter()
+            .map(|entry| {
+                let (name, _) = entry?;
+                self.topic_record(&String::from_utf8_lossy(&name))
+            })
+            .collect()
+    }
+
+    async fn find_by_ids(&self, ids: &[i32]) -> Result<Vec<TopicRecord>, Error> {
+        self.find_all()
+            .await
+            .map(|topics| topics.into_iter().filter(|t| ids.contains(&t.id.id)).collect())
+    }
+
+    async fn find_by_sequence_id(&self, sequence_id: i32) -> Result<Vec<TopicRecord>, Error> {
+        self.topic_names_by_sequence(sequence_id)?
+            .iter()
+            .map(|name| self.topic_record(name))
+            .collect()
+    }
+
+    async fn create(&self, new: &NewTopic) -> Result<TopicRecord, Error> {
+        let id = self.next_resource_id.fetch_add(1, Ordering::Relaxed);
+        let uuid = uuid::Uuid::new_v4();
+        let created_at = types::Timestamp::now();
+
+        self.put_resource(
+            &new.name,
+            &ResourceRecord {
+                id,
+                uuid: uuid.to_string(),
+                locked: false,
+                notifies: Vec::new(),
+            },
+        )?;
+        self.put_topic_meta(
+            &new.name,
+            &TopicMetaRecord {
+                sequence_id: new.sequence_id,
+                ontology_tag: new.ontology_tag.clone(),
+                serialization_format: new.serialization_format.map(|f| f.to_string()),
+                user_metadata: new.user_metadata.clone().map(Into::into),
+                created_at_unix_millis: created_at.into(),
+            },
+        )?;
+        self.index_topic_by_sequence(new.sequence_id, &new.name)?;
+
+        self.topic_record(&new.name)
+    }
+
+    async fn lock(&self, loc: &types::TopicResourceLocator) -> Result<(), Error> {
+        let mut record = self.get_resource(loc.name())?;
+        record.locked = true;
+        self.put_resource(loc.name(), &record)
+    }
+
+    async fn update_serialization_format(
+        &self,
+        loc: &types::TopicResourceLocator,
+        serialization_format: rw::Format,
+    ) -> Result<TopicRecord, Error> {
+        let mut meta = self.get_topic_meta(loc.name())?;
+        meta.serialization_format = Some(serialization_format.to_string());
+        self.put_topic_meta(loc.name(), &meta)?;
+        self.topic_record(loc.name())
+    }
+
+    async fn update_ontology_tag(
+        &self,
+        loc: &types::TopicResourceLocator,
+        ontology_tag: &str,
+    ) -> Result<TopicRecord, Error> {
+        let mut meta = self.get_topic_meta(loc.name())?;
+        meta.ontology_tag = Some(ontology_tag.to_string());
+        self.put_topic_meta(loc.name(), &meta)?;
+        self.topic_record(loc.name())
+    }
+
+    async fn update_user_metadata(
+        &self,
+        loc: &types::TopicResourceLocator,
+        user_metadata: marshal::JsonMetadataBlob,
+    ) -> Result<TopicRecord, Error> {
+        let mut meta = self.get_topic_meta(loc.name())?;
+        meta.user_metadata = Some(user_metadata.into());
+        self.put_topic_meta(loc.name(), &meta)?;
+        self.topic_record(loc.name())
+    }
+}
+
+/// Clones `resource`'s identity into an owned, boxed [`types::Resource`],
+/// so it can live on in a returned [`types::Notify`] after the borrow ends.
+fn resource_box(resource: &dyn types::Resource) -> Box<dyn types::Resource> {
+    match resource.resource_type() {
+        types::ResourceType::Sequence => {
+            Box::new(types::SequenceResourceLocator::from(resource.name()))
+        }
+        types::ResourceType::Topic => Box::new(types::TopicResourceLocator::from(resource.name())),
+    }
+}
+
+fn to_types_notify(resource: &dyn types::Resource, record: NotifyRecord) -> types::Notify {
+    types::Notify {
+        id: record.id,
+        target: resource_box(resource),
+        notify_type: record
+            .notify_type
+            .parse()
+            .unwrap_or(types::NotifyType::Error),
+        msg: record.msg,
+        created_at: types::Timestamp::from(record.created_at_unix_millis).into(),
+    }
+}
@@ -0,0 +1,490 @@
+//! A storage-engine-agnostic facade over the handful of operations the
+//! `Facade*` types actually need: resource identity/locking, notifications,
+//! and layer CRUD.
+//!
+//! [`Repository`] is Postgres-specific end to end: every function under
+//! `sql_models` is built on `sqlx::query_as!`/`sqlx::query!`, checked at
+//! compile time against a concrete Postgres schema. [`RepoBackend`] pulls
+//! the operations the facades call out into an object-safe trait so a
+//! lighter-weight engine (see [`embedded`]) can stand in for it on
+//! single-node or test deployments, without the facades needing to know
+//! which engine they're talking to.
+//!
+//! The facades are not migrated onto this trait yet — they still hold a
+//! concrete [`Repository`] and call `sql_models` directly, the same as
+//! before this module existed. [`RepoBackend`] is the extension point that
+//! migration will land on; until then, both paths live side by side the
+//! same way [`super::core::Backend::Sqlite`] is recognized by [`super::core::Backend`]
+//! well before [`Repository::try_new`] accepts it.
+//!
+//! `chunks_from_filters` and `sequences_group_from_topics` are deliberately
+//! **not** on any trait here: they take `query::OntologyExpr`/
+//! `query::SequenceFilter` and return `sql_models::Chunk` rather than
+//! `types::*`, so exposing them here would mean stabilizing those internal
+//! shapes first. They stay Postgres-only (called directly off `Repository`)
+//! until that groundwork is done.
+//!
+//! Resource *creation* is similarly left off [`RepoBackend`] itself, for
+//! sequences and topics: `FacadeSequence::create` only needs user metadata,
+//! but `FacadeTopic::create` also needs a parent sequence id, a
+//! serialization format and an ontology tag, so the two don't share a
+//! signature generic over `&dyn types::Resource` the way
+//! `delete`/`lock`/`notify*` do. Layer creation has no such divergence, so
+//! [`RepoBackend::layer_create`] is included. [`TopicBackend`] is the
+//! topic-specific counterpart: its `create`/`update_*`/`find_*` methods
+//! cover the topic persistence `RepoBackend` leaves out, the same way a
+//! hypothetical `SequenceBackend` would for sequences (not added yet —
+//! nothing outside tests needs it today).
+//!
+//! `TopicBackend::find_by_locator`/`find_all`/`find_by_ids` still don't
+//! cover filtered lookup: `topic_from_query_filter` compiles
+//! `query::ClausesCompiler` output straight to a SQL `WHERE` clause, and
+//! giving [`embedded::EmbeddedRepository`] an equivalent means evaluating
+//! that same `Expr`/`Filter`/`Op` tree against in-memory records instead of
+//! emitting SQL — a second compiler backend, not a small addition. It
+//! stays Postgres-only (called directly off `Repository`) until that's
+//! built.
+use async_trait::async_trait;
+
+use crate::{marshal, rw, types};
+
+use super::{Error, Repository};
+
+pub mod embedded;
+
+/// Operations shared by every facade, independent of the storage engine
+/// backing them.
+///
+/// Methods that depend on resource kind (sequence vs. topic) take
+/// `&dyn types::Resource` and dispatch on [`types::Resource::resource_type`]
+/// internally, the same way [`super::get_resource_locator_from_name`] probes
+/// both tables to resolve a bare name.
+#[async_trait]
+pub trait RepoBackend: Send + Sync {
+    /// Looks up the stable identity of `resource`.
+    async fn resource_id(&self, resource: &dyn types::Resource)
+        -> Result<types::ResourceId, Error>;
+
+    /// Returns whether `resource` is currently locked.
+    async fn is_locked(&self, resource: &dyn types::Resource) -> Result<bool, Error>;
+
+    /// Marks `resource` as locked.
+    async fn lock(&self, resource: &dyn types::Resource) -> Result<(), Error>;
+
+    /// Deletes `resource`'s record. No-op-safe variants that refuse to
+    /// delete a locked resource are the caller's responsibility, the same
+    /// as the existing `sequence_delete_unlocked`/`topic_delete_unlocked`.
+    async fn delete(&self, resource: &dyn types::Resource) -> Result<(), Error>;
+
+    /// Records a new notification against `resource`.
+    async fn notify_create(
+        &self,
+        resource: &dyn types::Resource,
+        notify_type: types::NotifyType,
+        msg: String,
+    ) -> Result<types::Notify, Error>;
+
+    /// Lists every notification recorded against `resource`.
+    async fn notify_list(
+        &self,
+        resource: &dyn types::Resource,
+    ) -> Result<Vec<types::Notify>, Error>;
+
+    /// Deletes every notification recorded against `resource`.
+    async fn notify_purge(&self, resource: &dyn types::Resource) -> Result<(), Error>;
+
+    /// Lists every registered [`types::Layer`].
+    async fn layer_list(&self) -> Result<Vec<types::Layer>, Error>;
+
+    /// Creates a new layer, returning its assigned id.
+    async fn layer_create(&self, layer: types::Layer) -> Result<i32, Error>;
+
+    /// Deletes the layer at `locator`.
+    async fn layer_delete(&self, locator: &types::LayerLocator) -> Result<(), Error>;
+}
+
+#[async_trait]
+impl RepoBackend for Repository {
+    async fn resource_id(
+        &self,
+        resource: &dyn types::Resource,
+    ) -> Result<types::ResourceId, Error> {
+        let mut cx = self.connection();
+        match resource.resource_type() {
+            types::ResourceType::Sequence => {
+                let loc = types::SequenceResourceLocator::from(resource.name());
+                Ok(super::sequence_find_by_locator(&mut cx, &loc).await?.into())
+            }
+            types::ResourceType::Topic => {
+                let loc = types::TopicResourceLocator::from(resource.name());
+                Ok(super::topic_find_by_locator(&mut cx, &loc).await?.into())
+            }
+        }
+    }
+
+    async fn is_locked(&self, resource: &dyn types::Resource) -> Result<bool, Error> {
+        let mut cx = self.connection();
+        match resource.resource_type() {
+            types::ResourceType::Sequence => {
+                let loc = types::SequenceResourceLocator::from(resource.name());
+                Ok(super::sequence_find_by_locator(&mut cx, &loc)
+                    .await?
+                    .is_locked())
+            }
+            types::ResourceType::Topic => {
+                let loc = types::TopicResourceLocator::from(resource.name());
+                Ok(super::topic_find_by_locator(&mut cx, &loc)
+                    .await?
+                    .is_locked())
+            }
+        }
+    }
+
+    async fn lock(&self, resource: &dyn types::Resource) -> Result<(), Error> {
+        let mut tx = self.transaction().await?;
+        match resource.resource_type() {
+            types::ResourceType::Sequence => {
+                let loc = types::SequenceResourceLocator::from(resource.name());
+                super::sequence_lock(&mut tx, &loc).await?;
+            }
+            types::ResourceType::Topic => {
+                let loc = types::TopicResourceLocator::from(resource.name());
+                super::topic_lock(&mut tx, &loc).await?;
+            }
+        }
+        tx.commit().await
+    }
+
+    async fn delete(&self, resource: &dyn types::Resource) -> Result<(), Error> {
+        let mut tx = self.transaction().await?;
+        match resource.resource_type() {
+            types::ResourceType::Sequence => {
+                let loc = types::SequenceResourceLocator::from(resource.name());
+                super::sequence_delete_unlocked(&mut tx, &loc).await?;
+            }
+            types::ResourceType::Topic => {
+                let loc = types::TopicResourceLocator::from(resource.name());
+                super::topic_delete_unlocked(&mut tx, &loc).await?;
+            }
+        }
+        tx.commit().await
+    }
+
+    async fn notify_create(
+        &self,
+        resource: &dyn types::Resource,
+        notify_type: types::NotifyType,
+        msg: String,
+    ) -> Result<types::Notify, Error> {
+        let mut tx = self.transaction().await?;
+        match resource.resource_type() {
+            types::ResourceType::Sequence => {
+                let loc = types::SequenceResourceLocator::from(resource.name());
+                let record = super::sequence_find_by_locator(&mut tx, &loc).await?;
+                let notify = super::SequenceNotify::new(record.sequence_id, notify_type, Some(msg));
+                let notify = super::sequence_notify_create(&mut tx, &notify).await?;
+                tx.commit().await?;
+                Ok(notify.into_types(loc))
+            }
+            types::ResourceType::Topic => {
+                let loc = types::TopicResourceLocator::from(resource.name());
+                let record = super::topic_find_by_locator(&mut tx, &loc).await?;
+                let notify = super::TopicNotify::new(record.topic_id, notify_type, Some(msg));
+                let notify = super::topic_notify_create(&mut tx, &notify).await?;
+                tx.commit().await?;
+                Ok(notify.into_types(loc))
+            }
+        }
+    }
+
+    async fn notify_list(
+        &self,
+        resource: &dyn types::Resource,
+    ) -> Result<Vec<types::Notify>, Error> {
+        let mut cx = self.connection();
+        match resource.resource_type() {
+            types::ResourceType::Sequence => {
+                let loc = types::SequenceResourceLocator::from(resource.name());
+                let notifies = super::sequence_notifies_find_by_name(&mut cx, &loc).await?;
+                Ok(notifies
+                    .into_iter()
+                    .map(|n| n.into_types(loc.clone()))
+                    .collect())
+            }
+            types::ResourceType::Topic => {
+                let loc = types::TopicResourceLocator::from(resource.name());
+                let notifies = super::topic_notifies_find_by_locator(&mut cx, &loc).await?;
+                Ok(notifies
+                    .into_iter()
+                    .map(|n| n.into_types(loc.clone()))
+                    .collect())
+            }
+        }
+    }
+
+    async fn notify_purge(&self, resource: &dyn types::Resource) -> Result<(), Error> {
+        let mut tx = self.transaction().await?;
+        match resource.resource_type() {
+            types::ResourceType::Sequence => {
+                let loc = types::SequenceResourceLocator::from(resource.name());
+                let notifies = super::sequence_notifies_find_by_name(&mut tx, &loc).await?;
+                for notify in notifies {
+                    super::sequence_notify_delete(&mut tx, notify.id().unwrap()).await?;
+                }
+            }
+            types::ResourceType::Topic => {
+                let loc = types::TopicResourceLocator::from(resource.name());
+                let notifies = super::topic_notifies_find_by_locator(&mut tx, &loc).await?;
+                for notify in notifies {
+                    super::topic_notify_delete(&mut tx, notify.id().unwrap()).await?;
+                }
+            }
+        }
+        tx.commit().await
+    }
+
+    async fn layer_list(&self) -> Result<Vec<types::Layer>, Error> {
+        let mut cx = self.connection();
+        Ok(super::layer_find_all(&mut cx)
+            .await?
+            .into_iter()
+            .map(|l| {
+                types::Layer::new(
+                    types::LayerLocator::from(l.layer_name.as_str()),
+                    l.layer_description,
+                )
+            })
+            .collect())
+    }
+
+    async fn layer_create(&self, layer: types::Layer) -> Result<i32, Error> {
+        let mut tx = self.transaction().await?;
+        let layer = super::layer_create(&mut tx, layer).await?;
+        tx.commit().await?;
+        Ok(layer.layer_id)
+    }
+
+    async fn layer_delete(&self, locator: &types::LayerLocator) -> Result<(), Error> {
+        let mut tx = self.transaction().await?;
+        let layer = super::layer_find_by_locator(&mut tx, locator).await?;
+        super::layer_delete(&mut tx, layer.layer_id).await?;
+        tx.commit().await
+    }
+}
+
+/// A topic record, independent of which backend produced it.
+///
+/// Mirrors [`super::TopicRecord`]'s shape, but as plain, publicly
+/// constructible fields rather than a Postgres-row type with a builder —
+/// [`embedded::EmbeddedRepository`] assembles one from its own storage with
+/// no `sql_models`/`sqlx` involved at all.
+#[derive(Debug, Clone)]
+pub struct TopicRecord {
+    pub id: types::ResourceId,
+    pub name: String,
+    pub sequence_id: i32,
+    pub ontology_tag: Option<String>,
+    pub serialization_format: Option<rw::Format>,
+    pub user_metadata: Option<serde_json::Value>,
+    pub locked: bool,
+    pub created_at: types::Timestamp,
+}
+
+impl From<super::TopicRecord> for TopicRecord {
+    fn from(value: super::TopicRecord) -> Self {
+        Self {
+            id: types::ResourceId {
+                id: value.topic_id,
+                uuid: value.topic_uuid,
+            },
+            name: value.topic_name.clone(),
+            sequence_id: value.sequence_id,
+            ontology_tag: value.ontology_tag.clone(),
+            serialization_format: value.serialization_format(),
+            user_metadata: value.user_metadata().cloned(),
+            locked: value.is_locked(),
+            created_at: value.creation_timestamp(),
+        }
+    }
+}
+
+/// The fields needed to create a new topic, independent of backend.
+pub struct NewTopic {
+    pub name: String,
+    pub sequence_id: i32,
+    pub ontology_tag: Option<String>,
+    pub serialization_format: Option<rw::Format>,
+    pub user_metadata: Option<marshal::JsonMetadataBlob>,
+}
+
+/// Topic persistence, pulled out of [`RepoBackend`] because — unlike
+/// `delete`/`lock`/`notify*` — none of this has a signature generic over
+/// `&dyn types::Resource` (see the module doc).
+#[async_trait]
+pub trait TopicBackend: Send + Sync {
+    /// Finds a topic by name.
+    async fn find_by_locator(&self, loc: &types::TopicResourceLocator) -> Result<TopicRecord, Error>;
+
+    /// Lists every topic.
+    async fn find_all(&self) -> Result<Vec<TopicRecord>, Error>;
+
+    /// Finds every topic whose id is in `ids`.
+    async fn find_by_ids(&self, ids: &[i32]) -> Result<Vec<TopicRecord>, Error>;
+
+    /// Finds every topic belonging to the sequence with id `sequence_id`.
+    async fn find_by_sequence_id(&self, sequence_id: i32) -> Result<Vec<TopicRecord>, Error>;
+
+    /// Creates a new, unlocked topic.
+    async fn create(&self, new: &NewTopic) -> Result<TopicRecord, Error>;
+
+    /// Permanently locks the topic at `loc`.
+    ///
+    /// Topic locking is also reachable through [`RepoBackend::lock`] (topics
+    /// implement [`types::Resource`]); this one exists so callers that
+    /// already have a [`types::TopicResourceLocator`] in hand don't need to
+    /// box it as `&dyn types::Resource` first.
+    async fn lock(&self, loc: &types::TopicResourceLocator) -> Result<(), Error>;
+
+    async fn update_serialization_format(
+        &self,
+        loc: &types::TopicResourceLocator,
+        serialization_format: rw::Format,
+    ) -> Result<TopicRecord, Error>;
+
+    async fn update_ontology_tag(
+        &self,
+        loc: &types::TopicResourceLocator,
+        ontology_tag: &str,
+    ) -> Result<TopicRecord, Error>;
+
+    async fn update_user_metadata(
+        &self,
+        loc: &types::TopicResourceLocator,
+        user_metadata: marshal::JsonMetadataBlob,
+    ) -> Result<TopicRecord, Error>;
+}
+
+#[async_trait]
+impl TopicBackend for Repository {
+    async fn find_by_locator(&self, loc: &types::TopicResourceLocator) -> Result<TopicRecord, Error> {
+        let mut cx = self.connection();
+        Ok(super::topic_find_by_locator(&mut cx, loc).await?.into())
+    }
+
+    async fn find_all(&self) -> Result<Vec<TopicRecord>, Error> {
+        let mut cx = self.connection();
+        Ok(super::topic_find_all(&mut cx)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn find_by_ids(&self, ids: &[i32]) -> Result<Vec<TopicRecord>, Error> {
+        let mut cx = self.connection();
+        Ok(super::topic_find_by_ids(&mut cx, ids)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn find_by_sequence_id(&self, sequence_id: i32) -> Result<Vec<TopicRecord>, Error> {
+        let mut cx = self.connection();
+        Ok(super::topic_find_by_sequence_id(&mut cx, sequence_id)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn create(&self, new: &NewTopic) -> Result<TopicRecord, Error> {
+        let mut record = super::TopicRecord::new(new.name.clone(), new.sequence_id);
+        if let Some(tag) = &new.ontology_tag {
+            record = record.with_ontology_tag(tag.clone());
+        }
+        if let Some(format) = new.serialization_format {
+            record = record.with_serialization_format(format.to_string());
+        }
+        if let Some(mdata) = new.user_metadata.clone() {
+            record = record.with_user_metadata(mdata);
+        }
+
+        let mut tx = self.transaction().await?;
+        let record = super::topic_create(&mut tx, &record).await?;
+        tx.commit().await?;
+        Ok(record.into())
+    }
+
+    async fn lock(&self, loc: &types::TopicResourceLocator) -> Result<(), Error> {
+        let mut tx = self.transaction().await?;
+        super::topic_lock(&mut tx, loc).await?;
+        tx.commit().await
+    }
+
+    async fn update_serialization_format(
+        &self,
+        loc: &types::TopicResourceLocator,
+        serialization_format: rw::Format,
+    ) -> Result<TopicRecord, Error> {
+        let mut tx = self.transaction().await?;
+        let record =
+            super::topic_update_serialization_format(&mut tx, loc, &serialization_format.to_string())
+                .await?;
+        tx.commit().await?;
+        Ok(record.into())
+    }
+
+    async fn update_ontology_tag(
+        &self,
+        loc: &types::TopicResourceLocator,
+        ontology_tag: &str,
+    ) -> Result<TopicRecord, Error> {
+        let mut tx = self.transaction().await?;
+        let record = super::topic_update_ontology_tag(&mut tx, loc, ontology_tag).await?;
+        tx.commit().await?;
+        Ok(record.into())
+    }
+
+    async fn update_user_metadata(
+        &self,
+        loc: &types::TopicResourceLocator,
+        user_metadata: marshal::JsonMetadataBlob,
+    ) -> Result<TopicRecord, Error> {
+        let mut tx = self.transaction().await?;
+        let record = super::topic_update_user_metadata(&mut tx, loc, user_metadata).await?;
+        tx.commit().await?;
+        Ok(record.into())
+    }
+}
+
+/// Copies every layer, plus the id/lock state and notifies of each resource
+/// in `resources`, from `source` into `target`.
+///
+/// Intended to seed an [`embedded::EmbeddedRepository`] from the existing
+/// [`Repository`] ahead of cutting a single-node deployment over to it.
+/// `target` is a concrete [`embedded::EmbeddedRepository`] rather than
+/// `&dyn RepoBackend` because seeding a resource's id/lock state uses
+/// [`embedded::EmbeddedRepository::seed_resource`], which — like resource
+/// creation — isn't part of [`RepoBackend`] (see the module doc).
+pub async fn migrate(
+    source: &dyn RepoBackend,
+    target: &embedded::EmbeddedRepository,
+    resources: &[&dyn types::Resource],
+) -> Result<(), Error> {
+    for layer in source.layer_list().await? {
+        target.layer_create(layer).await?;
+    }
+
+    for resource in resources {
+        let id = source.resource_id(*resource).await?;
+        let locked = source.is_locked(*resource).await?;
+        target.seed_resource(*resource, id, locked)?;
+
+        for notify in source.notify_list(*resource).await? {
+            target.seed_notify(*resource, notify)?;
+        }
+    }
+
+    Ok(())
+}
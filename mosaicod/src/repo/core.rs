@@ -5,7 +5,10 @@
 //! methods for interacting with the database. Error handling is unified through the
 //! [`RepositoryError`] enum.
 
-use log::debug;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::{debug, trace, warn};
 use sqlx::Pool;
 use url::Url;
 
@@ -17,10 +20,63 @@ pub type Database = sqlx::Postgres;
 /// If the layer has this id is not registered in the repository
 pub const UNREGISTERED: i32 = -1;
 
+/// Elapsed time above which [`instrument`] logs a query as a warning
+/// instead of a trace, surfacing accidentally slow queries (e.g. a
+/// dynamically built filter matching far more rows than expected) without
+/// needing ad-hoc `dbg!`/timing code at the call site.
+const SLOW_QUERY_WARN_THRESHOLD: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Runs `fut` (a single query against the repository), recording how long
+/// it took and, on failure, wrapping the resulting [`sqlx::Error`] in
+/// [`Error::QueryFailed`] so the logical query name, a short fingerprint of
+/// its arguments, and the elapsed time survive the conversion to
+/// [`Error`] instead of being lost behind a bare `#[from] sqlx::Error`.
+///
+/// `query` should be a short, stable name for the call site (e.g.
+/// `"topic_from_query_filter"`), not the literal SQL text. `fingerprint`
+/// should summarize the arguments without reproducing potentially
+/// sensitive values verbatim (e.g. a count of bound filters, not their
+/// contents).
+pub(crate) async fn instrument<T>(
+    query: &'static str,
+    fingerprint: impl std::fmt::Display,
+    fut: impl std::future::Future<Output = Result<T, sqlx::Error>>,
+) -> Result<T, Error> {
+    let started_at = std::time::Instant::now();
+    let res = fut.await;
+    let elapsed = started_at.elapsed();
+
+    match res {
+        Ok(value) => {
+            if elapsed > SLOW_QUERY_WARN_THRESHOLD {
+                warn!("query `{query}` ({fingerprint}) took {elapsed:?}, exceeding the slow-query threshold");
+            } else {
+                trace!("query `{query}` ({fingerprint}) took {elapsed:?}");
+            }
+            Ok(value)
+        }
+        Err(source) => Err(Error::QueryFailed {
+            query,
+            fingerprint: fingerprint.to_string(),
+            elapsed,
+            source,
+        }),
+    }
+}
+
 /// A trait for types that can provide a [`sqlx::Executor`].
 ///
 /// This trait establishes a generic contract, allowing functions to operate
 /// on any type that can supply the necessary execution interface.
+///
+/// `AsExec` does **not** distinguish [`Tx`] from [`Cx`] at the type level --
+/// both implement it identically, and nothing here stops a `Cx` from
+/// running a write. The read/write split is a naming convention enforced by
+/// callers picking [`Repository::transaction`] for writes and
+/// [`Repository::connection`] for reads, which matters once
+/// `Config::replica_urls` is configured: `connection()` can then hand back a
+/// replica pool that rejects writes outright. Every call site passing a
+/// `Cx` must actually be read-only.
 pub trait AsExec {
     /// Returns a reference to the underlying execution interface.
     fn as_exec(&mut self) -> impl sqlx::Executor<'_, Database = Database>;
@@ -36,6 +92,17 @@ pub struct Tx<'a> {
 }
 
 impl<'a> Tx<'a> {
+    /// Begins a transaction directly against `pool`, for callers that only
+    /// have a bare pool rather than a full [`Repository`] -- e.g. the
+    /// `mosaicod migrate` CLI subcommand (see `super::migrator`), which
+    /// deliberately avoids constructing a [`Repository`] so it doesn't also
+    /// trigger [`Repository::try_new`]'s own implicit migration step.
+    pub async fn begin(pool: &'a Pool<Database>) -> Result<Self, Error> {
+        Ok(Self {
+            inner: pool.begin().await?,
+        })
+    }
+
     pub async fn commit(self) -> Result<(), Error> {
         self.inner.commit().await?;
         Ok(())
@@ -54,6 +121,7 @@ impl<'a> AsExec for Tx<'a> {
 }
 
 /// The **Connection** truct, designed to hold a reference to a core resource pool.
+#[derive(Clone, Copy)]
 pub struct Cx<'a> {
     inner: &'a Pool<Database>,
 }
@@ -68,33 +136,149 @@ impl<'a> AsExec for Cx<'a> {
     }
 }
 
+/// The database backend a [`Config`] connects to.
+///
+/// Only [`Backend::Postgres`] is wired up to the query layer today: every
+/// function under `sql_models` is built on `sqlx::query_as!`/`sqlx::query!`,
+/// which are checked at compile time against a concrete Postgres schema.
+/// [`Backend::Sqlite`] is recognized here so configuration and connection
+/// pooling can be written against the backend up front, but
+/// [`Repository::try_new`] rejects it until a SQLite-specific query layer
+/// exists alongside the Postgres one. [`Backend::Embedded`] has a real
+/// implementation — [`super::embedded::EmbeddedRepository`] — but it
+/// implements [`super::RepoBackend`] directly rather than going through
+/// [`Repository`], so `try_new` rejects it too; see that module for how to
+/// construct one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Postgres,
+    Sqlite,
+    Embedded,
+}
+
+impl Backend {
+    /// Infers the backend from a database URL's scheme (`postgres://`, `sqlite://`, `embedded://`, ...).
+    pub fn from_url(url: &Url) -> Result<Self, Error> {
+        match url.scheme() {
+            "postgres" | "postgresql" => Ok(Self::Postgres),
+            "sqlite" => Ok(Self::Sqlite),
+            "embedded" => Ok(Self::Embedded),
+            scheme => Err(Error::UnknownBackend(scheme.to_string())),
+        }
+    }
+}
+
 /// Configuration structure for initializing the [`Repository`].
 pub struct Config {
     pub db_url: Url,
+    /// Number of background workers polling the durable job queue (reindex
+    /// jobs, ...). `0` disables the job queue entirely.
+    pub job_workers: usize,
+    /// How often an idle job worker polls for new work, and how often a
+    /// claimed job's heartbeat is expected to be refreshed.
+    pub job_heartbeat_interval: std::time::Duration,
+    /// How long a claimed job's heartbeat may go stale before the sweeper
+    /// assumes its worker crashed and resets it to be claimed again.
+    pub job_heartbeat_timeout: std::time::Duration,
+    /// Number of times a job may be claimed and fail before it's left
+    /// alone as a dead letter instead of being rescheduled.
+    pub job_max_retries: u32,
+    /// How long a single poll of a running job's future may take before a
+    /// warning is logged, to surface accidental blocking in async code.
+    pub job_poll_warn_threshold: std::time::Duration,
+    /// Maximum number of pooled connections held open to the database.
+    pub pool_max_connections: u32,
+    /// Minimum number of idle connections the pool keeps warm.
+    pub pool_min_connections: u32,
+    /// How long to wait for a connection to become available before
+    /// giving up and returning an error.
+    pub pool_acquire_timeout: std::time::Duration,
+    /// How long a connection may sit idle in the pool before it's closed.
+    /// `None` keeps idle connections open indefinitely.
+    pub pool_idle_timeout: Option<std::time::Duration>,
+    /// Maximum lifetime of a pooled connection, closed and replaced once
+    /// exceeded even if it's still healthy. `None` never recycles a
+    /// connection on age alone.
+    pub pool_max_lifetime: Option<std::time::Duration>,
+    /// Whether a connection is pinged with a lightweight health check
+    /// before being handed out of the pool, catching one dropped by the
+    /// server (e.g. after `pool_max_lifetime`/`pool_idle_timeout` elsewhere,
+    /// or a network blip) before it fails the caller's actual query.
+    pub pool_test_before_acquire: bool,
+    /// Read-replica database URLs. When non-empty, [`Repository::connection`]
+    /// round-robins reads across a pool per replica instead of the primary;
+    /// [`Repository::transaction`] always targets the primary regardless,
+    /// since replicas may lag behind it and writes can't be replayed there.
+    pub replica_urls: Vec<Url>,
+    /// Base delay before the first connection retry attempt. Doubled after
+    /// each subsequent transient failure, up to `connect_retry_max_backoff`.
+    /// Each actual wait is jittered to between half and all of this delay.
+    pub connect_retry_initial_backoff: std::time::Duration,
+    /// Ceiling the exponential backoff delay is capped at, no matter how
+    /// many transient failures precede it.
+    pub connect_retry_max_backoff: std::time::Duration,
+    /// Total time, from the first connection attempt, `connect_with_retry`
+    /// is allowed to keep retrying transient failures before giving up.
+    pub connect_retry_max_elapsed: std::time::Duration,
+}
+
+impl Config {
+    /// Returns the [`Backend`] this configuration targets, inferred from `db_url`'s scheme.
+    pub fn backend(&self) -> Result<Backend, Error> {
+        Backend::from_url(&self.db_url)
+    }
 }
 
 #[derive(Clone)]
 pub struct Repository {
     pub(super) pool: Pool<Database>,
+    /// One pool per configured read replica (`Config::replica_urls`), empty
+    /// if none are configured.
+    replica_pools: Arc<Vec<Pool<Database>>>,
+    /// Shared across clones so round-robin position advances consistently
+    /// no matter which clone of the `Repository` handle serves a request.
+    replica_cursor: Arc<AtomicUsize>,
 }
 
 impl Repository {
     pub async fn try_new(config: &Config) -> Result<Self, Error> {
+        match config.backend()? {
+            Backend::Sqlite => return Err(Error::UnsupportedBackend(Backend::Sqlite)),
+            Backend::Embedded => return Err(Error::UnsupportedBackend(Backend::Embedded)),
+            Backend::Postgres => {}
+        }
+
         debug!("creating database connection pool");
-        let pool = sqlx::postgres::PgPoolOptions::new()
-            .connect(config.db_url.as_str())
-            .await?;
+        let pool_options = sqlx::postgres::PgPoolOptions::new()
+            .max_connections(config.pool_max_connections)
+            .min_connections(config.pool_min_connections)
+            .acquire_timeout(config.pool_acquire_timeout)
+            .idle_timeout(config.pool_idle_timeout)
+            .max_lifetime(config.pool_max_lifetime)
+            .test_before_acquire(config.pool_test_before_acquire);
+        let pool = connect_with_retry(pool_options.clone(), &config.db_url, config).await?;
 
         debug!("running migrations");
         sqlx::migrate!().run(&pool).await?;
 
-        Ok(Self { pool })
+        let mut replica_pools = Vec::with_capacity(config.replica_urls.len());
+        for replica_url in &config.replica_urls {
+            debug!("creating read-replica connection pool for {replica_url}");
+            replica_pools.push(connect_with_retry(pool_options.clone(), replica_url, config).await?);
+        }
+
+        Ok(Self {
+            pool,
+            replica_pools: Arc::new(replica_pools),
+            replica_cursor: Arc::new(AtomicUsize::new(0)),
+        })
     }
 
     /// Builds a transaction.
     ///
     /// This call should be used when performing **write** operations on the
-    /// repository.
+    /// repository. Always targets the primary pool, even when read replicas
+    /// are configured.
     pub async fn transaction(&self) -> Result<Tx<'_>, Error> {
         Ok(Tx {
             inner: self.pool.begin().await?,
@@ -103,9 +287,102 @@ impl Repository {
 
     /// Returns a connection to perform operations on the repository.
     ///
-    /// This call should be used when performing **read-only** operations on the repository.
+    /// This call should be used when performing **read-only** operations on
+    /// the repository. Round-robins across `Config::replica_urls`'s pools
+    /// when any are configured, falling back to the primary pool otherwise.
     pub fn connection(&self) -> Cx<'_> {
-        Cx { inner: &self.pool }
+        if self.replica_pools.is_empty() {
+            return Cx { inner: &self.pool };
+        }
+
+        let idx = self.replica_cursor.fetch_add(1, Ordering::Relaxed) % self.replica_pools.len();
+        Cx {
+            inner: &self.replica_pools[idx],
+        }
+    }
+
+    /// Opens a [`sqlx::postgres::PgListener`] subscribed to the given Postgres
+    /// `NOTIFY` channel.
+    ///
+    /// Used to implement push-based subscriptions on top of tables that are
+    /// also written through regular `INSERT`s (see `sql_models::notifies`).
+    pub async fn listen(&self, channel: &str) -> Result<sqlx::postgres::PgListener, Error> {
+        let mut listener = sqlx::postgres::PgListener::connect_with(&self.pool).await?;
+        listener.listen(channel).await?;
+        Ok(listener)
+    }
+}
+
+/// Attempts to establish a connection pool against `db_url`, retrying
+/// transient failures with exponential backoff (doubling each time, capped
+/// at `config.connect_retry_max_backoff`) until `config.connect_retry_max_elapsed`
+/// has passed since the first attempt.
+///
+/// Used for both the primary pool and, with the same `options`/retry policy,
+/// each of `config.replica_urls`'s pools.
+///
+/// The database may not be reachable yet when the server starts (e.g. in a
+/// compose/orchestrated deployment where containers race to come up); this
+/// gives it a bounded grace period instead of failing startup immediately.
+/// Only [`is_transient`] errors are retried — anything else (auth failure,
+/// bad database name, ...) is permanent and returned immediately, since no
+/// amount of waiting will fix it.
+async fn connect_with_retry(
+    options: sqlx::postgres::PgPoolOptions,
+    db_url: &Url,
+    config: &Config,
+) -> Result<Pool<Database>, Error> {
+    let started_at = std::time::Instant::now();
+    let mut backoff = config.connect_retry_initial_backoff;
+    loop {
+        match options.clone().connect(db_url.as_str()).await {
+            Ok(pool) => return Ok(pool),
+            Err(err)
+                if is_transient(&err) && started_at.elapsed() < config.connect_retry_max_elapsed =>
+            {
+                let wait = backoff.mul_f64(jitter_factor());
+                warn!(
+                    "failed to connect to the database ({:?} elapsed): {}; retrying in {:?}",
+                    started_at.elapsed(),
+                    err,
+                    wait
+                );
+                tokio::time::sleep(wait).await;
+                backoff = std::cmp::min(config.connect_retry_max_backoff, backoff * 2);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// Deterministic-enough jitter without pulling in a `rand` dependency: mixes
+/// the current time's sub-millisecond component into a factor in `[0.5, 1.0)`.
+/// Mirrors `store::retry::jitter_factor`, spreading out retries from servers
+/// that all started racing the same not-yet-ready database at once.
+fn jitter_factor() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    0.5 + 0.5 * (nanos % 1_000_000) as f64 / 1_000_000.0
+}
+
+/// Whether `err` represents a transient connectivity failure (the database
+/// wasn't reachable yet, or dropped an in-progress connection attempt)
+/// rather than a permanent one (bad credentials, unknown database, ...).
+///
+/// Only [`sqlx::Error::Io`] errors are ever considered transient; every
+/// other variant (including [`sqlx::Error::Database`], which covers
+/// Postgres rejecting the connection outright) is treated as permanent.
+fn is_transient(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(io_err) => matches!(
+            io_err.kind(),
+            std::io::ErrorKind::ConnectionRefused
+                | std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+        ),
+        _ => false,
     }
 }
 
@@ -123,7 +400,11 @@ pub mod testing {
         /// Creates a new [`Repository`] instance for testing using the provided database pool.
         pub fn new(pool: sqlx::Pool<super::Database>) -> Self {
             Self {
-                inner: super::Repository { pool },
+                inner: super::Repository {
+                    pool,
+                    replica_pools: std::sync::Arc::new(Vec::new()),
+                    replica_cursor: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+                },
             }
         }
 
@@ -0,0 +1,159 @@
+//! Request-scoped loaders that coalesce repeated single-key lookups (e.g.
+//! one `TopicRecord` per topic in a sequence, then that topic's notifies)
+//! into a single `WHERE id = ANY($1)` query, and cache results so the same
+//! key is never fetched twice through the same loader.
+//!
+//! Unlike a classic (e.g. GraphQL) dataloader that defers resolution to the
+//! end of an event-loop tick to batch concurrent callers automatically,
+//! callers here explicitly [`TopicLoader::queue`] every key they'll need
+//! before the first `load`/`load_many` call forces resolution. This repo's
+//! action handlers already know their whole fan-out list up front (e.g.
+//! every topic belonging to a sequence) rather than discovering it across
+//! independently scheduled tasks, so there's no tick to hook a deferred
+//! batch into -- an explicit queue gets the same one-query-per-batch result
+//! without it.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::core::Cx;
+use super::{sql_models, Error};
+
+/// Batches [`sql_models::TopicRecord`] lookups by `topic_id`. Obtained via
+/// [`Cx::topic_loader`].
+pub struct TopicLoader<'a> {
+    cx: Cx<'a>,
+    cache: RefCell<HashMap<i32, sql_models::TopicRecord>>,
+    pending: RefCell<Vec<i32>>,
+}
+
+impl<'a> TopicLoader<'a> {
+    pub(super) fn new(cx: Cx<'a>) -> Self {
+        Self {
+            cx,
+            cache: RefCell::new(HashMap::new()),
+            pending: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Marks `topic_id` as needed, without fetching it yet -- lets a caller
+    /// queue every id it knows it'll need (e.g. while iterating a sequence's
+    /// topics) before the first `load`/`load_many` resolves them all in one
+    /// query.
+    pub fn queue(&self, topic_id: i32) {
+        if !self.cache.borrow().contains_key(&topic_id) {
+            self.pending.borrow_mut().push(topic_id);
+        }
+    }
+
+    /// Returns the topic for `topic_id`, resolving it -- and any other
+    /// queued ids -- in a single batched query if it isn't already cached.
+    pub async fn load(&self, topic_id: i32) -> Result<Option<sql_models::TopicRecord>, Error> {
+        self.queue(topic_id);
+        self.resolve_pending().await?;
+        Ok(self.cache.borrow().get(&topic_id).cloned())
+    }
+
+    /// Returns the topics for `topic_ids`, resolving any that aren't
+    /// already cached in one batched query. Ids with no matching topic are
+    /// silently omitted, same as [`sql_models::topic_find_by_ids`].
+    pub async fn load_many(
+        &self,
+        topic_ids: &[i32],
+    ) -> Result<Vec<sql_models::TopicRecord>, Error> {
+        for &id in topic_ids {
+            self.queue(id);
+        }
+        self.resolve_pending().await?;
+
+        let cache = self.cache.borrow();
+        Ok(topic_ids.iter().filter_map(|id| cache.get(id).cloned()).collect())
+    }
+
+    async fn resolve_pending(&self) -> Result<(), Error> {
+        let ids: Vec<i32> = self.pending.borrow_mut().drain(..).collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut cx = self.cx;
+        let records = sql_models::topic_find_by_ids(&mut cx, &ids).await?;
+
+        let mut cache = self.cache.borrow_mut();
+        for record in records {
+            cache.insert(record.topic_id, record);
+        }
+
+        Ok(())
+    }
+}
+
+/// Batches [`sql_models::TopicNotify`] lookups by `topic_id`. Obtained via
+/// [`Cx::notification_loader`].
+pub struct NotificationLoader<'a> {
+    cx: Cx<'a>,
+    cache: RefCell<HashMap<i32, Vec<sql_models::TopicNotify>>>,
+    pending: RefCell<Vec<i32>>,
+}
+
+impl<'a> NotificationLoader<'a> {
+    pub(super) fn new(cx: Cx<'a>) -> Self {
+        Self {
+            cx,
+            cache: RefCell::new(HashMap::new()),
+            pending: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Marks `topic_id`'s notifies as needed, without fetching them yet.
+    pub fn queue(&self, topic_id: i32) {
+        if !self.cache.borrow().contains_key(&topic_id) {
+            self.pending.borrow_mut().push(topic_id);
+        }
+    }
+
+    /// Returns every notify for `topic_id`, resolving it -- and any other
+    /// queued topic ids -- in a single batched query if it isn't already
+    /// cached.
+    pub async fn load(&self, topic_id: i32) -> Result<Vec<sql_models::TopicNotify>, Error> {
+        self.queue(topic_id);
+        self.resolve_pending().await?;
+        Ok(self.cache.borrow().get(&topic_id).cloned().unwrap_or_default())
+    }
+
+    async fn resolve_pending(&self) -> Result<(), Error> {
+        let ids: Vec<i32> = self.pending.borrow_mut().drain(..).collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut cx = self.cx;
+        let notifies = sql_models::topic_notifies_find_by_topic_ids(&mut cx, &ids).await?;
+
+        let mut cache = self.cache.borrow_mut();
+        // Every queued id needs an entry even if it has no notifies, so a
+        // later `load` for it is answered from cache rather than re-queued.
+        for &id in &ids {
+            cache.entry(id).or_default();
+        }
+        for notify in notifies {
+            cache.entry(notify.topic_id).or_default().push(notify);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Cx<'a> {
+    /// Returns a [`TopicLoader`] scoped to this connection, batching
+    /// `TopicRecord` lookups issued through it into `ANY($1)` queries.
+    pub fn topic_loader(&self) -> TopicLoader<'a> {
+        TopicLoader::new(*self)
+    }
+
+    /// Returns a [`NotificationLoader`] scoped to this connection, batching
+    /// per-topic notify lookups issued through it into `ANY($1)` queries.
+    pub fn notification_loader(&self) -> NotificationLoader<'a> {
+        NotificationLoader::new(*self)
+    }
+}
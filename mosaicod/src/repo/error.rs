@@ -6,10 +6,35 @@ use crate::query;
 pub enum Error {
     /// An error occurred in the underlying SQL database backend (e.g., connection, query execution).
     #[error("backend error :: {0}")]
-    BackendError(#[from] sqlx::Error),
+    BackendError(sqlx::Error),
+    /// The connection pool had no connection available within
+    /// [`super::core::Config::pool_acquire_timeout`]. Distinguished from
+    /// [`Self::BackendError`] so callers (see
+    /// `FacadeError::RepositoryBusy`) can back off instead of treating this
+    /// like any other database failure.
+    #[error("connection pool exhausted, no connection became available in time")]
+    PoolExhausted,
+    /// An instrumented query (see [`super::core::instrument`]) failed,
+    /// retaining the logical query name, an argument fingerprint, and how
+    /// long it ran before failing — context a bare [`Self::BackendError`]
+    /// would otherwise lose.
+    #[error("query `{query}` ({fingerprint}) failed after {elapsed:?} :: {source}")]
+    QueryFailed {
+        query: &'static str,
+        fingerprint: String,
+        elapsed: std::time::Duration,
+        #[source]
+        source: sqlx::Error,
+    },
     /// An error occurred during database schema migration.
     #[error("migration error :: {0}")]
     MigrationError(#[from] sqlx::migrate::MigrateError),
+    /// Returned by [`super::migrator::migrate_down`]: [`super::migrate`]'s
+    /// registry applies forward-only, idempotent migrations with no
+    /// companion rollback step, so there is nothing for `migrate_down` to
+    /// revert.
+    #[error("migrations in this registry are forward-only and cannot be reverted")]
+    MigrationDownUnsupported,
     /// An error occurred during serialization or deserialization of data,
     /// typically to or from JSON in the database.
     #[error("serialization error :: {0}")]
@@ -29,4 +54,24 @@ pub enum Error {
     /// The query received contains an unsupported operation
     #[error("query error :: {0}")]
     QueryError(#[from] query::Error),
+    /// The database URL scheme does not map to a known [`super::core::Backend`].
+    #[error("unrecognized database backend for scheme `{0}`")]
+    UnknownBackend(String),
+    /// The database URL maps to a [`super::core::Backend`] that is not yet
+    /// wired up to the query layer.
+    #[error("`{0:?}` backend is not yet supported by the query layer")]
+    UnsupportedBackend(super::core::Backend),
+    /// An error occurred in the embedded `sled` backend (see
+    /// [`super::backend::embedded`]).
+    #[error("embedded backend error :: {0}")]
+    SledError(#[from] sled::Error),
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(source: sqlx::Error) -> Self {
+        match source {
+            sqlx::Error::PoolTimedOut => Error::PoolExhausted,
+            source => Error::BackendError(source),
+        }
+    }
 }
@@ -10,11 +10,25 @@ impl<'a> FacadeChunk<'a> {
     pub async fn create(
         topic_id: i32,
         datafile: impl AsRef<std::path::Path>,
+        content_digest: crate::rw::ContentDigest,
         repo: &'a repo::Repository,
     ) -> Result<Self, FacadeError> {
         let mut tx = repo.transaction().await?;
 
-        let chunk = repo::chunk_create(&mut tx, &repo::Chunk::new(topic_id, datafile)).await?;
+        let new_chunk = repo::Chunk::new(topic_id, datafile, content_digest);
+
+        // Tracked independently of `chunk_t`'s own row, so the refcount
+        // reflects every topic that shares this digest, not just this one
+        // (see `repo::sql_models::ChunkRef`).
+        repo::chunk_ref_upsert(
+            &mut tx,
+            &new_chunk.content_digest,
+            &new_chunk.content_digest_algo,
+            &new_chunk.data_file().to_string_lossy(),
+        )
+        .await?;
+
+        let chunk = repo::chunk_create(&mut tx, &new_chunk).await?;
 
         Ok(Self { tx, chunk })
     }
@@ -41,9 +55,32 @@ impl<'a> FacadeChunk<'a> {
                         stats.min.to_owned(),
                         stats.max.to_owned(),
                         stats.has_null,
+                        stats.has_non_null,
                     )?,
                 )
                 .await?;
+
+                repo::column_chunk_bloom_create(
+                    &mut self.tx,
+                    &repo::ColumnChunkBloom::new(
+                        column.column_id,
+                        self.chunk.chunk_id,
+                        stats.bloom.to_bytes(),
+                    ),
+                )
+                .await?;
+
+                if let Some(values) = stats.dictionary.values() {
+                    repo::column_chunk_dictionary_create(
+                        &mut self.tx,
+                        &repo::ColumnChunkDictionary::new(
+                            column.column_id,
+                            self.chunk.chunk_id,
+                            values.iter().cloned().collect(),
+                        ),
+                    )
+                    .await?;
+                }
             }
             types::Stats::Numeric(stats) => {
                 repo::column_chunk_numeric_create(
@@ -55,6 +92,17 @@ impl<'a> FacadeChunk<'a> {
                         stats.max,
                         stats.has_null,
                         stats.has_nan,
+                        stats.has_non_null,
+                    ),
+                )
+                .await?;
+
+                repo::column_chunk_bloom_create(
+                    &mut self.tx,
+                    &repo::ColumnChunkBloom::new(
+                        column.column_id,
+                        self.chunk.chunk_id,
+                        stats.bloom.to_bytes(),
                     ),
                 )
                 .await?;
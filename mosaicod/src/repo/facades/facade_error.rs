@@ -17,7 +17,12 @@ pub enum FacadeError {
     #[error("metadata error :: {0}")]
     MetadataError(#[from] types::MetadataError),
     #[error("repository error :: {0}")]
-    RepositoryError(#[from] repo::Error),
+    RepositoryError(repo::Error),
+    /// The connection pool had no connection available in time (see
+    /// [`repo::Error::PoolExhausted`]); callers should back off and retry
+    /// rather than treating this like an ordinary repository error.
+    #[error("repository busy, no database connection available -- back off and retry")]
+    RepositoryBusy,
     #[error("sequence locked, unable to perform modifications")]
     SequenceLocked,
     #[error("topic locked, unable to perform modifications")]
@@ -28,4 +33,18 @@ pub enum FacadeError {
     Unimplemented,
     #[error("unauthorized")]
     Unauthorized,
+    /// Returned when a topic is configured for encryption (it carries a
+    /// `types::EncryptionInfo`) but this process has no `MOSAICO_MASTER_KEY`
+    /// loaded to derive its per-topic cipher from.
+    #[error("topic is encrypted but no master key is configured on this server")]
+    MissingMasterKey,
+}
+
+impl From<repo::Error> for FacadeError {
+    fn from(source: repo::Error) -> Self {
+        match source {
+            repo::Error::PoolExhausted => FacadeError::RepositoryBusy,
+            source => FacadeError::RepositoryError(source),
+        }
+    }
 }
@@ -0,0 +1,135 @@
+use crate::repo;
+
+use super::FacadeError;
+
+/// A job claimed off a queue, ready to be run by a worker.
+pub struct ClaimedJob {
+    pub id: uuid::Uuid,
+    pub job: serde_json::Value,
+}
+
+/// Status of a job looked up by id via [`FacadeJobQueue::find`].
+pub enum JobState {
+    /// Still waiting to be claimed.
+    Pending { retries: i32 },
+    /// Claimed by a worker and currently running (or, if its worker
+    /// crashed, waiting for the sweeper to reclaim it).
+    Running { retries: i32 },
+    /// No row exists for this id: either it never existed, or it ran to
+    /// completion (`job_complete` deletes the row rather than marking it
+    /// done in place, so this is indistinguishable from "not found").
+    DoneOrUnknown,
+}
+
+/// Outcome of recording a failed job attempt, returned by
+/// [`FacadeJobQueue::fail`] so the caller can tell a job that will be
+/// retried apart from one that just exhausted its retry budget.
+pub struct JobFailure {
+    /// Total number of attempts recorded for this job so far, including
+    /// the one that just failed.
+    pub retries: i32,
+}
+
+/// Thin facade over the `job_queue_t` table.
+///
+/// Workers and enqueuers go through this rather than `sql_models::job_*`
+/// directly, keeping the claim/heartbeat/complete contract (and its
+/// transaction boundaries) in one place.
+pub struct FacadeJobQueue;
+
+impl FacadeJobQueue {
+    /// Enqueues `job` on `queue`. Can be called from within an existing
+    /// transaction, so a job can be enqueued atomically alongside the work
+    /// that triggered it (e.g. a chunk creation).
+    pub async fn enqueue(
+        exe: &mut impl repo::AsExec,
+        queue: &str,
+        job: &serde_json::Value,
+    ) -> Result<(), FacadeError> {
+        repo::job_enqueue(exe, queue, job).await?;
+        Ok(())
+    }
+
+    /// Claims the next `new` job on `queue`, if any, that hasn't exhausted
+    /// `max_retries` or isn't still waiting out its backoff window.
+    pub async fn claim_next(
+        repo: &repo::Repository,
+        queue: &str,
+        max_retries: u32,
+    ) -> Result<Option<ClaimedJob>, FacadeError> {
+        let mut tx = repo.transaction().await?;
+
+        let Some(row) = repo::job_claim_next(&mut tx, queue, max_retries as i32).await? else {
+            tx.commit().await?;
+            return Ok(None);
+        };
+
+        tx.commit().await?;
+
+        Ok(Some(ClaimedJob {
+            id: row.job_queue_id,
+            job: row.job,
+        }))
+    }
+
+    /// Looks up the current state of a previously enqueued job, for clients
+    /// polling the `job_id` returned by a deferred action's `JobAccepted`.
+    pub async fn find(repo: &repo::Repository, id: uuid::Uuid) -> Result<JobState, FacadeError> {
+        let mut cx = repo.connection();
+        let Some(row) = repo::job_find(&mut cx, id).await? else {
+            return Ok(JobState::DoneOrUnknown);
+        };
+
+        Ok(match row.status() {
+            repo::JobStatus::New => JobState::Pending {
+                retries: row.retries,
+            },
+            repo::JobStatus::Running => JobState::Running {
+                retries: row.retries,
+            },
+        })
+    }
+
+    /// Deletes a successfully completed job.
+    ///
+    /// Writes (unlike [`Self::find`]) so it always goes through
+    /// [`repo::Repository::transaction`], not [`repo::Repository::connection`]:
+    /// once read replicas are configured, `connection()` can hand back a
+    /// hot-standby pool that rejects this `DELETE` outright.
+    pub async fn complete(repo: &repo::Repository, id: uuid::Uuid) -> Result<(), FacadeError> {
+        let mut tx = repo.transaction().await?;
+        repo::job_complete(&mut tx, id).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Records a failed attempt at `id`: bumps its retry count and
+    /// reschedules it with exponential backoff (capped, see
+    /// `job_claim_next`) instead of leaving it `running` for the sweeper to
+    /// eventually reclaim.
+    ///
+    /// Writes, so this goes through [`repo::Repository::transaction`] rather
+    /// than [`repo::Repository::connection`] -- see [`Self::complete`].
+    pub async fn fail(repo: &repo::Repository, id: uuid::Uuid) -> Result<JobFailure, FacadeError> {
+        let mut tx = repo.transaction().await?;
+        let row = repo::job_fail(&mut tx, id).await?;
+        tx.commit().await?;
+        Ok(JobFailure {
+            retries: row.retries,
+        })
+    }
+
+    /// Reclaims jobs whose worker stopped heartbeating at least `timeout` ago.
+    ///
+    /// Writes, so this goes through [`repo::Repository::transaction`] rather
+    /// than [`repo::Repository::connection`] -- see [`Self::complete`].
+    pub async fn sweep_stale(
+        repo: &repo::Repository,
+        timeout: chrono::Duration,
+    ) -> Result<u64, FacadeError> {
+        let mut tx = repo.transaction().await?;
+        let n = repo::job_sweep_stale(&mut tx, timeout).await?;
+        tx.commit().await?;
+        Ok(n)
+    }
+}
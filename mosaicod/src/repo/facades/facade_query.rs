@@ -0,0 +1,203 @@
+//! Cross-topic filtered chunk reading, decoupled from a single
+//! [`super::FacadeTopic`] since a [`query::OntologyFilter`] may match chunks
+//! belonging to several topics at once (see [`repo::chunks_from_filters`]'s
+//! `on_topics` parameter).
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+use futures::{Stream, StreamExt};
+
+use super::FacadeError;
+use crate::{params, query, repo, rw, store, types};
+
+/// How many chunks' raw data files are fetched from the store concurrently,
+/// ahead of the one currently being decoded and yielded by a
+/// [`BatchReader`].
+const BATCH_READER_PREFETCH: usize = 4;
+
+pub struct FacadeQuery {
+    store: store::StoreRef,
+    repo: repo::Repository,
+}
+
+impl FacadeQuery {
+    pub fn new(store: store::StoreRef, repo: repo::Repository) -> Self {
+        Self { store, repo }
+    }
+
+    /// Streams decoded [`RecordBatch`]es out of every chunk matching
+    /// `filter`, optionally restricted to `on_topics`.
+    ///
+    /// The candidate chunk list is resolved once up front via
+    /// [`repo::chunks_from_filters`]; each chunk's data file is then read
+    /// from the store and decoded through [`rw::ChunkReader`] lazily, one
+    /// at a time, with up to [`BATCH_READER_PREFETCH`] chunks fetched
+    /// concurrently ahead of the one currently being decoded -- so a caller
+    /// can drain chunks across many topics without ever holding more than a
+    /// handful of chunk buffers in memory at once.
+    ///
+    /// Pruning only happens at chunk granularity, the same statistics
+    /// `repo::chunks_from_filters` already uses to build its candidate
+    /// list: unlike `query::TimeseriesGw::read`, chunks here are decoded
+    /// directly rather than through DataFusion, so rows inside a surviving
+    /// chunk aren't re-checked against `filter` row by row.
+    pub async fn batch_reader(
+        &self,
+        filter: query::OntologyFilter,
+        on_topics: Option<Vec<types::TopicResourceLocator>>,
+    ) -> Result<BatchReader, FacadeError> {
+        let mut cx = self.repo.connection();
+
+        let topics = match on_topics {
+            Some(locators) => {
+                let mut records = Vec::with_capacity(locators.len());
+                for locator in &locators {
+                    records.push(repo::topic_find_by_locator(&mut cx, locator).await?);
+                }
+                Some(records)
+            }
+            None => None,
+        };
+
+        let chunks = repo::chunks_from_filters(&mut cx, filter.into(), topics.as_ref()).await?;
+
+        // Resolved once up front rather than cached lazily as chunks are
+        // polled, since that would need the cache shared (and locked)
+        // across the concurrently-prefetched futures below.
+        let topics = match topics {
+            Some(records) => records,
+            None => {
+                let ids: Vec<i32> = chunks.iter().map(|c| c.topic_id).collect();
+                repo::topic_find_by_ids(&mut cx, &ids).await?
+            }
+        };
+
+        let mut contexts = HashMap::with_capacity(topics.len());
+        for topic in topics {
+            let format = topic.serialization_format().ok_or_else(|| {
+                FacadeError::MissingMetadataField("serialization_format".to_string())
+            })?;
+            let cipher = self.chunk_cipher(&topic).await?;
+            contexts.insert(topic.topic_id, (format, cipher));
+        }
+
+        Ok(BatchReader::new(self.store.clone(), chunks, contexts))
+    }
+
+    /// Groups several independent [`Self::batch_reader`] queries into a
+    /// single stream, interleaving chunks from each as they become ready
+    /// instead of draining one query fully before starting the next.
+    pub async fn read_batch(&self, queries: Vec<ReadBatch>) -> Result<BatchReader, FacadeError> {
+        let mut readers: Vec<Pin<Box<dyn Stream<Item = Result<RecordBatch, FacadeError>> + Send>>> =
+            Vec::with_capacity(queries.len());
+        for query in queries {
+            let reader = self.batch_reader(query.filter, query.on_topics).await?;
+            readers.push(Box::pin(reader));
+        }
+
+        Ok(BatchReader {
+            stream: Box::pin(futures::stream::select_all(readers)),
+        })
+    }
+
+    /// Resolves the [`rw::ChunkCipher`] a topic's chunks were encrypted
+    /// with, if any, from its metadata file -- same lookup as
+    /// [`super::FacadeTopic::arrow_schema`], reused here since a
+    /// `BatchReader` may need it for several distinct topics at once.
+    async fn chunk_cipher(
+        &self,
+        topic: &repo::TopicRecord,
+    ) -> Result<Option<rw::ChunkCipher>, FacadeError> {
+        let handle = super::FacadeTopic::new(
+            topic.topic_name.clone(),
+            self.store.clone(),
+            self.repo.clone(),
+        );
+        let metadata = handle.metadata().await?;
+
+        match metadata.properties.encryption {
+            Some(encryption) => {
+                let master_key = params::master_key().ok_or(FacadeError::MissingMasterKey)?;
+                Ok(Some(
+                    rw::MasterKey::new(master_key).derive_cipher(&encryption.key_id),
+                ))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// One independent filter query to group into [`FacadeQuery::read_batch`]'s
+/// interleaved stream.
+pub struct ReadBatch {
+    pub filter: query::OntologyFilter,
+    pub on_topics: Option<Vec<types::TopicResourceLocator>>,
+}
+
+/// Lazily decoded stream of [`RecordBatch`]es over a set of chunks, built by
+/// [`FacadeQuery::batch_reader`] or [`FacadeQuery::read_batch`].
+pub struct BatchReader {
+    stream: Pin<Box<dyn Stream<Item = Result<RecordBatch, FacadeError>> + Send>>,
+}
+
+impl BatchReader {
+    fn new(
+        store: store::StoreRef,
+        chunks: Vec<repo::Chunk>,
+        contexts: HashMap<i32, (rw::Format, Option<rw::ChunkCipher>)>,
+    ) -> Self {
+        let contexts = Arc::new(contexts);
+
+        let stream = futures::stream::iter(chunks)
+            .map(move |chunk| {
+                let store = store.clone();
+                let contexts = contexts.clone();
+
+                async move {
+                    let (format, cipher) = contexts.get(&chunk.topic_id).cloned().ok_or_else(|| {
+                        FacadeError::NotFound(format!(
+                            "topic `{}` for chunk `{}`",
+                            chunk.topic_id, chunk.chunk_uuid
+                        ))
+                    })?;
+
+                    let buffer = store.read_bytes(chunk.data_file()).await?;
+                    let buffer = bytes::Bytes::from_owner(buffer);
+
+                    let reader = match &cipher {
+                        Some(cipher) => rw::ChunkReader::new_with_cipher(format, buffer, cipher)?,
+                        None => rw::ChunkReader::new(format, buffer)?,
+                    };
+
+                    Ok::<_, FacadeError>(reader)
+                }
+            })
+            .buffered(BATCH_READER_PREFETCH)
+            .flat_map(|reader| match reader {
+                Ok(reader) => futures::stream::iter(
+                    reader
+                        .map(|batch| batch.map_err(FacadeError::from))
+                        .collect::<Vec<_>>(),
+                ),
+                Err(e) => futures::stream::iter(vec![Err(e)]),
+            });
+
+        Self {
+            stream: Box::pin(stream),
+        }
+    }
+}
+
+impl Stream for BatchReader {
+    type Item = Result<RecordBatch, FacadeError>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+}
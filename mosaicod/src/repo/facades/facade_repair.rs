@@ -0,0 +1,261 @@
+//! Online consistency-check and repair for the chunk catalog.
+//!
+//! [`FacadeRepair::scan`] reconciles the `chunk_t` catalog against what
+//! actually exists in the `store`, and the sequence/topic catalog against
+//! itself, without holding a single long-lived transaction: the chunk
+//! catalog is walked in bounded pages (see `repo::chunk_find_page`), each
+//! page checked and (optionally) repaired through its own short
+//! transaction. It also resyncs the content-addressed dedup ledger
+//! (`chunk_ref_t`), recomputing each entry's live refcount and reclaiming
+//! data files no `chunk_t` row references anymore (see
+//! [`FacadeRepair::scan_chunk_refs`]).
+
+use std::collections::{HashMap, HashSet};
+
+use log::warn;
+
+use crate::{
+    repo, store,
+    types::{self, Resource},
+};
+
+use super::FacadeError;
+
+/// Number of chunk rows fetched per page while walking the catalog. Kept
+/// small enough that a single page's work (one store round-trip per chunk)
+/// never dominates the scan's overall latency.
+const CHUNK_PAGE_SIZE: i64 = 500;
+
+/// Number of chunk-ref rows fetched per page while walking the dedup
+/// ledger. See [`CHUNK_PAGE_SIZE`].
+const CHUNK_REF_PAGE_SIZE: i64 = 500;
+
+pub struct FacadeRepair {
+    store: store::StoreRef,
+    repo: repo::Repository,
+}
+
+impl FacadeRepair {
+    pub fn new(store: store::StoreRef, repo: repo::Repository) -> Self {
+        Self { store, repo }
+    }
+
+    /// Runs a full consistency scan, returning a [`types::RepairReport`].
+    ///
+    /// With `dry_run = true` (the default for a first look at a
+    /// repository), nothing is deleted -- the report just lists what the
+    /// scan found. With `dry_run = false`, orphaned data files are deleted
+    /// from the store and catalog rows pointing at missing files are
+    /// deleted from the repository as the scan encounters them; misplaced
+    /// topics and empty sequences are always report-only.
+    pub async fn scan(&self, dry_run: bool) -> Result<types::RepairReport, FacadeError> {
+        let mut report = types::RepairReport::default();
+        let mut known_data_files: HashMap<i32, HashSet<String>> = HashMap::new();
+
+        self.scan_chunks(dry_run, &mut report, &mut known_data_files)
+            .await?;
+        self.scan_orphaned_data_files(dry_run, &mut report, &known_data_files)
+            .await?;
+        self.scan_sequences_and_topics(&mut report).await?;
+        self.scan_chunk_refs(dry_run, &mut report).await?;
+
+        Ok(report)
+    }
+
+    /// Walks `chunk_t` page by page, checking each chunk's data file still
+    /// exists in the store and recording every data file seen (keyed by
+    /// `topic_id`) for [`Self::scan_orphaned_data_files`] to cross-reference.
+    async fn scan_chunks(
+        &self,
+        dry_run: bool,
+        report: &mut types::RepairReport,
+        known_data_files: &mut HashMap<i32, HashSet<String>>,
+    ) -> Result<(), FacadeError> {
+        let mut after_chunk_id = 0;
+
+        loop {
+            let mut cx = self.repo.connection();
+            let chunks = repo::chunk_find_page(&mut cx, after_chunk_id, CHUNK_PAGE_SIZE).await?;
+            let page_len = chunks.len();
+
+            for chunk in &chunks {
+                after_chunk_id = chunk.chunk_id;
+                let data_file = chunk.data_file().to_string_lossy().into_owned();
+
+                known_data_files
+                    .entry(chunk.topic_id)
+                    .or_default()
+                    .insert(data_file.clone());
+
+                match self.store.size(chunk.data_file()).await {
+                    Ok(_) => {}
+                    Err(err) if err.is_not_found() => {
+                        if !dry_run {
+                            let mut tx = self.repo.transaction().await?;
+                            // SAFETY: the store lookup above confirmed the
+                            // data file this row points at no longer
+                            // exists, so the row can't be recovered.
+                            unsafe {
+                                repo::chunk_delete(&mut tx, chunk.chunk_id).await?;
+                            }
+                            tx.commit().await?;
+                        }
+                        report.missing_data_files.push(data_file);
+                    }
+                    Err(err) => return Err(err.into()),
+                }
+            }
+
+            if (page_len as i64) < CHUNK_PAGE_SIZE {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// For every topic with a known serialization format, lists its data
+    /// files in the store and flags any not seen while walking the catalog
+    /// in [`Self::scan_chunks`].
+    async fn scan_orphaned_data_files(
+        &self,
+        dry_run: bool,
+        report: &mut types::RepairReport,
+        known_data_files: &HashMap<i32, HashSet<String>>,
+    ) -> Result<(), FacadeError> {
+        let mut cx = self.repo.connection();
+        let topics = repo::topic_find_all(&mut cx).await?;
+        let empty = HashSet::new();
+
+        for topic in topics {
+            let Some(format) = topic.serialization_format() else {
+                // No serialization format on record yet (topic created but
+                // never written to): nothing to cross-reference.
+                continue;
+            };
+
+            let known = known_data_files.get(&topic.topic_id).unwrap_or(&empty);
+            let locator = types::TopicResourceLocator::from(&topic.topic_name);
+            let files = self
+                .store
+                .list(locator.name(), Some(&format.as_extension()))
+                .await?;
+
+            for file in files {
+                if known.contains(&file) {
+                    continue;
+                }
+
+                if !dry_run {
+                    if let Err(err) = self.store.delete(&file).await {
+                        warn!("unable to delete orphaned data file `{}`: {}", file, err);
+                        continue;
+                    }
+                }
+                report.orphaned_data_files.push(file);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Flags topics that aren't a sub-resource of any known sequence, and
+    /// sequences with no topics underneath them.
+    async fn scan_sequences_and_topics(
+        &self,
+        report: &mut types::RepairReport,
+    ) -> Result<(), FacadeError> {
+        let mut cx = self.repo.connection();
+        let sequences = repo::sequence_find_all(&mut cx).await?;
+        let topics = repo::topic_find_all(&mut cx).await?;
+
+        let seq_locators: Vec<types::SequenceResourceLocator> = sequences
+            .into_iter()
+            .map(|s| types::SequenceResourceLocator::from(s.sequence_name))
+            .collect();
+        let topic_locators: Vec<types::TopicResourceLocator> = topics
+            .into_iter()
+            .map(|t| types::TopicResourceLocator::from(t.topic_name))
+            .collect();
+
+        for seq in &seq_locators {
+            let has_topics = topic_locators.iter().any(|t| t.is_sub_resource(seq));
+            if !has_topics {
+                report.empty_sequences.push(seq.name().clone());
+            }
+        }
+
+        for topic in &topic_locators {
+            let under_a_sequence = seq_locators.iter().any(|seq| topic.is_sub_resource(seq));
+            if !under_a_sequence {
+                report.misplaced_topics.push(topic.name().clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Walks `chunk_ref_t` page by page, recomputing each entry's true
+    /// refcount from the live `chunk_t` rows that reference its digest
+    /// (`repo::chunk_count_by_digest`). Any entry whose recomputed count has
+    /// dropped to zero -- every referencing chunk was deleted, by a topic
+    /// deletion or by [`Self::scan_chunks`] -- is an orphaned dedup entry:
+    /// its data file is no longer reachable from the catalog at all, so
+    /// it's reclaimed the same way [`Self::scan_chunks`] reclaims a missing
+    /// chunk row.
+    async fn scan_chunk_refs(
+        &self,
+        dry_run: bool,
+        report: &mut types::RepairReport,
+    ) -> Result<(), FacadeError> {
+        let mut after_chunk_ref_id = 0;
+
+        loop {
+            let mut cx = self.repo.connection();
+            let refs = repo::chunk_ref_find_page(&mut cx, after_chunk_ref_id, CHUNK_REF_PAGE_SIZE)
+                .await?;
+            let page_len = refs.len();
+
+            for chunk_ref in &refs {
+                after_chunk_ref_id = chunk_ref.chunk_ref_id;
+
+                let live = repo::chunk_count_by_digest(
+                    &mut cx,
+                    &chunk_ref.content_digest_algo,
+                    &chunk_ref.content_digest,
+                )
+                .await?;
+
+                if live > 0 {
+                    continue;
+                }
+
+                let data_file = chunk_ref.data_file().to_string_lossy().into_owned();
+
+                if !dry_run {
+                    if let Err(err) = self.store.delete(chunk_ref.data_file()).await {
+                        if !err.is_not_found() {
+                            return Err(err.into());
+                        }
+                    }
+
+                    let mut tx = self.repo.transaction().await?;
+                    // SAFETY: `live == 0` above confirmed no `chunk_t` row
+                    // references this digest anymore.
+                    unsafe {
+                        repo::chunk_ref_delete(&mut tx, chunk_ref.chunk_ref_id).await?;
+                    }
+                    tx.commit().await?;
+                }
+
+                report.reclaimed_chunk_refs.push(data_file);
+            }
+
+            if (page_len as i64) < CHUNK_REF_PAGE_SIZE {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -8,7 +8,7 @@
 use log::trace;
 
 use crate::{
-    marshal, repo, store,
+    marshal, metrics, query, repo, store,
     types::{self, Resource},
 };
 
@@ -43,6 +43,15 @@ impl FacadeSequence {
     pub async fn create(
         &self,
         metadata: Option<SequenceMetadata>,
+    ) -> Result<types::ResourceId, FacadeError> {
+        let result = self.create_inner(metadata).await;
+        metrics::record_sequence_lifecycle("create", result.is_ok());
+        result
+    }
+
+    async fn create_inner(
+        &self,
+        metadata: Option<SequenceMetadata>,
     ) -> Result<types::ResourceId, FacadeError> {
         let mut tx = self.repo.transaction().await?;
 
@@ -91,6 +100,12 @@ impl FacadeSequence {
     ///
     /// Calling lock on a locked sequence returns a [`HandleError::SequenceLocked`] error.
     pub async fn lock(&self) -> Result<(), FacadeError> {
+        let result = self.lock_inner().await;
+        metrics::record_sequence_lifecycle("lock", result.is_ok());
+        result
+    }
+
+    async fn lock_inner(&self) -> Result<(), FacadeError> {
         let mut tx = self.repo.transaction().await?;
 
         // check that the sequence is currently unlocked
@@ -112,6 +127,8 @@ impl FacadeSequence {
 
         tx.commit().await?;
 
+        metrics::record_lock("sequence");
+
         Ok(())
     }
 
@@ -124,11 +141,15 @@ impl FacadeSequence {
         let mut tx = self.repo.transaction().await?;
 
         let record = repo::sequence_find_by_locator(&mut tx, &self.locator).await?;
-        let notify = repo::SequenceNotify::new(record.sequence_id, ntype, Some(msg));
+        let sequence_id = record.sequence_id;
+        let notify = repo::SequenceNotify::new(sequence_id, ntype, Some(msg));
         let notify = repo::sequence_notify_create(&mut tx, &notify).await?;
 
         tx.commit().await?;
 
+        repo::notify_watch::publish(sequence_id);
+        metrics::record_notify("sequence", "create");
+
         Ok(notify.into_types(self.locator.clone()))
     }
 
@@ -143,6 +164,60 @@ impl FacadeSequence {
             .collect())
     }
 
+    /// Long-polls for notifications created after `since` (a
+    /// [`repo::SequenceNotify`]'s id, `0` to see everything), blocking up to
+    /// `timeout` if none are newer yet.
+    ///
+    /// Wakes as soon as a commit through [`Self::notify`] publishes to
+    /// [`repo::notify_watch`], then re-checks the database -- this both
+    /// covers a notify published in the gap between a caller's last poll and
+    /// this call, and double-checks a wakeup that might have raced with
+    /// [`repo::notify_watch::publish`] (see its doc comment).
+    ///
+    /// Returns the new notifications (oldest first) and the cursor to pass
+    /// as `since` on the next call, so a caller can chain calls to keep
+    /// following the sequence without busy-polling.
+    pub async fn notify_poll(
+        &self,
+        since: i32,
+        timeout: std::time::Duration,
+    ) -> Result<(i32, Vec<types::Notify>), FacadeError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        let sequence_id = {
+            let mut cx = self.repo.connection();
+            repo::sequence_find_by_locator(&mut cx, &self.locator)
+                .await?
+                .sequence_id
+        };
+
+        loop {
+            let mut cx = self.repo.connection();
+            let notifies =
+                repo::sequence_notifies_find_since(&mut cx, sequence_id, since).await?;
+
+            if let Some(last) = notifies.last() {
+                // Every row here came straight from the database, so it
+                // always has an id.
+                let next_cursor = last.id().unwrap();
+                return Ok((
+                    next_cursor,
+                    notifies
+                        .into_iter()
+                        .map(|n| n.into_types(self.locator.clone()))
+                        .collect(),
+                ));
+            }
+
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                return Ok((since, Vec::new()));
+            }
+
+            repo::notify_watch::wait(sequence_id, remaining).await;
+        }
+    }
+
     /// Deletes all the notifications associated with the sequence
     pub async fn notify_purge(&self) -> Result<(), FacadeError> {
         let mut trans = self.repo.transaction().await?;
@@ -154,6 +229,7 @@ impl FacadeSequence {
             repo::sequence_notify_delete(&mut trans, notify.id().unwrap()).await?;
         }
         trans.commit().await?;
+        metrics::record_notify("sequence", "purge");
         Ok(())
     }
 
@@ -199,6 +275,12 @@ impl FacadeSequence {
     /// This operation will only succeed if the sequence is locked.  
     /// If the sequence is not locked, the function returns a [`HandleError::SequenceLocked`] error.
     pub async fn delete(self) -> Result<(), FacadeError> {
+        let result = self.delete_inner().await;
+        metrics::record_sequence_lifecycle("delete", result.is_ok());
+        result
+    }
+
+    async fn delete_inner(self) -> Result<(), FacadeError> {
         let mut tx = self.repo.transaction().await?;
 
         let srecord = repo::sequence_find_by_locator(&mut tx, &self.locator).await?;
@@ -206,9 +288,41 @@ impl FacadeSequence {
             return Err(FacadeError::SequenceLocked);
         }
 
+        let sequence_id = srecord.sequence_id;
+
         // Retrieve topics data and deletes it
         let topics = self.topic_list().await?;
+        let topic_names: Vec<String> = topics.iter().map(|loc| loc.name().clone()).collect();
+
+        // Durably records every topic this delete needs to tear down before
+        // touching any of them, so a crash partway through leaves a
+        // checkpoint `resume_jobs` can pick back up from instead of leaving
+        // the sequence half-removed with no record of what's left. A no-op
+        // if this sequence's delete job already exists (a resumed delete).
+        // A write, so it goes through `transaction()` rather than
+        // `connection()`: once read replicas are configured, `connection()`
+        // can hand back a hot-standby pool that rejects this `INSERT`.
+        {
+            let mut job_tx = self.repo.transaction().await?;
+            repo::sequence_delete_job_start(&mut job_tx, sequence_id, &topic_names).await?;
+            job_tx.commit().await?;
+        }
+
+        let pending: std::collections::HashSet<String> = {
+            let mut cx = self.repo.connection();
+            repo::sequence_delete_job_pending_topics(&mut cx, sequence_id)
+                .await?
+                .into_iter()
+                .collect()
+        };
+
         for topic_loc in topics {
+            if !pending.contains(topic_loc.name()) {
+                // Already torn down by an earlier, interrupted attempt.
+                continue;
+            }
+            let topic_name = topic_loc.name().clone();
+
             let thandle = FacadeTopic::new(topic_loc.into(), self.store.clone(), self.repo.clone());
 
             // For this special case we allow an unsafe delete since the sequence is still unlocked (previous check).
@@ -217,16 +331,57 @@ impl FacadeSequence {
             unsafe {
                 thandle.delete_unsafe().await?;
             }
+
+            // A write, so it goes through `transaction()` -- see the
+            // `sequence_delete_job_start` call above.
+            let mut job_tx = self.repo.transaction().await?;
+            repo::sequence_delete_job_mark_topic_done(&mut job_tx, sequence_id, &topic_name).await?;
+            job_tx.commit().await?;
         }
 
-        // Delete sequence data
+        // Delete sequence data. Only reached once every topic's sub-task is
+        // done, and committed in the same transaction as the job's
+        // checkpoint being cleared, so a crash here either leaves every
+        // topic already deleted with the job still `running` (a cheap
+        // resume: nothing left to tear down, just the finalize step) or
+        // finishes atomically.
         repo::sequence_delete_unlocked(&mut tx, &self.locator).await?;
         self.store.delete_recursive(self.locator.name()).await?;
+        repo::sequence_delete_job_complete(&mut tx, sequence_id).await?;
 
         tx.commit().await?;
         Ok(())
     }
 
+    /// Progress of this sequence's in-flight delete job (topics torn down
+    /// vs total), or `None` if no delete has ever been started for it.
+    pub async fn delete_progress(&self) -> Result<Option<types::SequenceDeleteProgress>, FacadeError> {
+        let mut cx = self.repo.connection();
+        let record = repo::sequence_find_by_locator(&mut cx, &self.locator).await?;
+        Ok(repo::sequence_delete_job_find(&mut cx, record.sequence_id).await?)
+    }
+
+    /// Resumes every delete job left `running` by a process that crashed
+    /// mid-[`Self::delete`], continuing each from its last completed topic.
+    ///
+    /// Intended to be called once at startup, before the server starts
+    /// accepting new requests.
+    pub async fn resume_jobs(repo: repo::Repository, store: store::StoreRef) -> Result<(), FacadeError> {
+        let sequence_ids = {
+            let mut cx = repo.connection();
+            repo::sequence_delete_jobs_find_incomplete(&mut cx).await?
+        };
+
+        for sequence_id in sequence_ids {
+            let mut cx = repo.connection();
+            let record = repo::sequence_find_by_id(&mut cx, sequence_id).await?;
+            let handle = FacadeSequence::new(record.sequence_name, store.clone(), repo.clone());
+            handle.delete().await?;
+        }
+
+        Ok(())
+    }
+
     /// Computes system info for the sequence
     pub async fn system_info(&self) -> Result<types::SequenceSystemInfo, FacadeError> {
         let mut cx = self.repo.connection();
@@ -259,4 +414,23 @@ impl FacadeSequence {
             .map(|record| types::SequenceResourceLocator::from(record.sequence_name))
             .collect())
     }
+
+    /// Retrieves every sequence matching `filter`'s clauses (name, creation,
+    /// `user_metadata`, `since`), pushed down to `sequence_from_query_filter`
+    /// instead of filtering [`Self::all`]'s full result set in memory.
+    ///
+    /// Returns every sequence, same as [`Self::all`], if `filter` has no
+    /// clauses set.
+    pub async fn search(
+        repo: repo::Repository,
+        filter: query::SequenceFilter,
+    ) -> Result<Vec<types::SequenceResourceLocator>, FacadeError> {
+        let mut cx = repo.connection();
+        let records = repo::sequence_from_query_filter(&mut cx, filter).await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| types::SequenceResourceLocator::from(record.sequence_name))
+            .collect())
+    }
 }
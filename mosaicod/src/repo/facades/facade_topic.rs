@@ -2,7 +2,7 @@ use super::FacadeError;
 use crate::rw;
 use crate::traits::AsExtension;
 use crate::{
-    marshal, repo, store,
+    marshal, metrics, params, repo, store,
     types::{self, Resource},
 };
 use arrow::datatypes::SchemaRef;
@@ -75,6 +75,8 @@ impl FacadeTopic {
 
         tx.commit().await?;
 
+        repo::watch::publish(repo::watch::WatchEventKind::Created, self.locator.name());
+
         Ok(record.into())
     }
 
@@ -130,6 +132,11 @@ impl FacadeTopic {
 
         tx.commit().await?;
 
+        repo::watch::publish(
+            repo::watch::WatchEventKind::MetadataUpdated,
+            self.locator.name(),
+        );
+
         Ok(())
     }
 
@@ -151,11 +158,20 @@ impl FacadeTopic {
 
         tx.commit().await?;
 
+        metrics::record_lock("topic");
+
+        repo::watch::publish(repo::watch::WatchEventKind::Locked, self.locator.name());
+
         Ok(())
     }
 
     /// Reads and deserializes the [`TopicMetadata`] associated with this topic.
     ///
+    /// Transparently decrypts the file if it carries a [`rw::ChunkCipher`]
+    /// header (see [`FacadeTopic::metadata_write_to_store`]). Older
+    /// metadata files written before encryption was enabled are plain
+    /// JSON and are read as-is.
+    ///
     /// # Errors
     ///
     /// Returns [`HandleError::ReadError`] if reading or deserializing fails.
@@ -163,6 +179,14 @@ impl FacadeTopic {
         let path = self.locator.metadata();
         let bytes = self.store.read_bytes(path).await?;
 
+        let bytes = if rw::ChunkCipher::is_encrypted(&bytes) {
+            let master_key = params::master_key().ok_or(FacadeError::MissingMasterKey)?;
+            let cipher = rw::MasterKey::new(master_key).derive_cipher(rw::METADATA_KEY_ID);
+            cipher.decrypt(&bytes)?
+        } else {
+            bytes
+        };
+
         let data: marshal::JsonTopicMetadata = bytes.try_into()?;
 
         Ok(data.into())
@@ -177,12 +201,28 @@ impl FacadeTopic {
         // Build a chunk reader reading in memory a file
         // (cabba) TODO: avoid reading the whole file, get from store only the header
         let buffer = self.store.read_bytes(path).await?;
-        let reader = rw::ChunkReader::new(format, bytes::Bytes::from_owner(buffer))?;
+        let buffer = bytes::Bytes::from_owner(buffer);
+
+        let reader = match self.metadata().await?.properties.encryption {
+            Some(encryption) => {
+                let master_key = params::master_key().ok_or(FacadeError::MissingMasterKey)?;
+                let cipher = rw::MasterKey::new(master_key).derive_cipher(&encryption.key_id);
+                rw::ChunkReader::new_with_cipher(format, buffer, &cipher)?
+            }
+            None => rw::ChunkReader::new(format, buffer)?,
+        };
+
         Ok(reader.schema())
     }
 
     /// Serializes and writes [`TopicMetadata`] to the object store.
     ///
+    /// Unlike chunk data, metadata encryption isn't a per-topic opt-in: it's
+    /// encrypted whenever this server has a master key configured (see
+    /// [`rw::METADATA_KEY_ID`]), regardless of whether the topic itself
+    /// requested chunk encryption, since metadata isn't the kind of thing a
+    /// topic can reasonably leave unprotected on its own.
+    ///
     /// # Errors
     ///
     /// Returns [`HandleError::NotFound`] or [`HandleError::WriteError`] if serialization or writing fails.
@@ -193,18 +233,225 @@ impl FacadeTopic {
         let json_mdata = marshal::JsonTopicMetadata::from(metadata);
         let bytes: Vec<u8> = json_mdata.try_into()?;
 
+        let bytes = match params::master_key() {
+            Some(master_key) => rw::MasterKey::new(master_key)
+                .derive_cipher(rw::METADATA_KEY_ID)
+                .encrypt(&bytes),
+            None => bytes,
+        };
+
         self.store.write_bytes(&path, bytes).await?;
 
         Ok(())
     }
 
     pub fn writer(&self, format: rw::Format) -> rw::ChunkedWriter<'_, store::Store> {
+        self.writer_on(self.store.as_ref(), format)
+    }
+
+    /// Like [`Self::writer`], but writes through an explicitly borrowed
+    /// `store` instead of `self`'s, so the returned writer isn't tied to
+    /// this handle's lifetime.
+    ///
+    /// Useful when several [`FacadeTopic`] handles are kept alive past a
+    /// single call, e.g. multiplexing more than one topic's writer over a
+    /// single upload stream.
+    pub fn writer_on<'a>(
+        &self,
+        store: &'a store::Store,
+        format: rw::Format,
+    ) -> rw::ChunkedWriter<'a, store::Store> {
+        let repo = self.repo.clone();
+        let locator = self.locator.clone();
+
         rw::ChunkedWriter::new(
-            self.store.as_ref(),
+            store,
             self.path(),
             format,
             |path, format, idx| types::TopicResourceLocator::from(path).datafile(idx, format),
         )
+        .on_chunk_dedup_check(move |digest| {
+            let repo = repo.clone();
+            let locator = locator.clone();
+
+            async move {
+                let mut cx = repo.connection();
+                let record = repo::topic_find_by_locator(&mut cx, &locator).await?;
+                let existing =
+                    repo::chunk_find_by_digest(&mut cx, record.topic_id, digest.as_bytes())
+                        .await?;
+
+                // Deliberately scoped to this topic's own chunks only --
+                // `chunk_ref_t` also tracks digests from every *other* topic,
+                // but reusing one of those data files here would make this
+                // topic's `chunk_t` row and that other topic's physically
+                // share a file on disk. `FacadeTopic::delete`/`delete_unsafe`
+                // and `FacadeSequence::delete` remove a topic's whole
+                // directory tree unconditionally (no `chunk_ref_t` refcount
+                // check), so the first of the two topics to be deleted would
+                // silently destroy the data still live under the other's
+                // row. Widening this to cross-topic reuse needs that
+                // deletion path to consult `chunk_ref_t` and only physically
+                // remove a data file once its live refcount is 0 (the way
+                // `FacadeRepair::scan_chunk_refs` already does for orphaned
+                // entries) -- a storage-layout change, not something to
+                // reintroduce here on its own. See also the doc comment on
+                // `sql_models::pg_queries::chunk_ref_upsert`, which doesn't
+                // update `data_file` on conflict and would need revisiting
+                // at the same time.
+                let existing = existing.map(|chunk| chunk.data_file().to_path_buf());
+
+                Ok(existing)
+            }
+        })
+    }
+
+    /// Target size (bytes), measured by [`rw::ChunkWriter::memory_size`], for
+    /// a chunk produced by [`Self::compact`]. Old chunks are merged into the
+    /// current output chunk until this is crossed, then it's flushed and a
+    /// new one started.
+    const COMPACT_TARGET_BYTES: usize = 64 * 1024 * 1024;
+
+    /// Merges this (locked) topic's small data files into fewer, larger
+    /// ones.
+    ///
+    /// Every existing chunk is read back through [`rw::ChunkReader`] and its
+    /// batches re-written through [`rw::ChunkWriter`] in chunk order,
+    /// flushing a new merged chunk every time [`Self::COMPACT_TARGET_BYTES`]
+    /// is crossed. Each merged chunk is committed the same way a normal
+    /// upload commits one -- `repo::FacadeChunk::create` + `push_stats` +
+    /// `finalize`, one transaction per chunk -- before any old chunk is
+    /// touched; only once every merged chunk is durably recorded are the old
+    /// `chunk_t` rows (and, via `ON DELETE CASCADE`, their
+    /// `column_chunk_*` rows) removed, together in a single transaction.
+    ///
+    /// The old chunks' data files aren't deleted from the store here: once
+    /// their `chunk_t` rows are gone they become unreferenced
+    /// `chunk_ref_t` entries, reclaimed the same way any other orphaned
+    /// dedup entry is, by `repo::FacadeRepair::scan`.
+    ///
+    /// Scope note: this doesn't attempt to preserve continuity of
+    /// `TopicSystemInfo::merkle_root` across the rewrite. That root is an
+    /// append-only hash over each chunk's content digest in creation order
+    /// (`types::MerkleTree`), and compaction necessarily produces chunks
+    /// with different digests than the ones they replace, so a
+    /// previously issued `merkle_proof` for this topic stops verifying
+    /// once it's compacted. Recomputing the tree without invalidating
+    /// outstanding proofs would need a dedicated proof-format redesign and
+    /// is left for follow-up work.
+    pub async fn compact(&self) -> Result<types::CompactionReport, FacadeError> {
+        if !self.is_locked().await? {
+            return Err(FacadeError::TopicUnlocked);
+        }
+
+        let metadata = self.metadata().await?;
+        let format = metadata.properties.serialization_format;
+        let cipher = match &metadata.properties.encryption {
+            Some(encryption) => {
+                let master_key = params::master_key().ok_or(FacadeError::MissingMasterKey)?;
+                Some(rw::MasterKey::new(master_key).derive_cipher(&encryption.key_id))
+            }
+            None => None,
+        };
+
+        let mut cx = self.repo.connection();
+        let record = repo::topic_find_by_locator(&mut cx, &self.locator).await?;
+        let old_chunks = repo::chunk_find_by_topic_ordered(&mut cx, record.topic_id).await?;
+
+        let mut report = types::CompactionReport::default();
+        if old_chunks.len() < 2 {
+            // Nothing worth merging.
+            return Ok(report);
+        }
+
+        let schema = self.arrow_schema(format).await?;
+        let mut next_chunk_number = old_chunks.len();
+        let make_writer = |schema: &SchemaRef| -> Result<rw::ChunkWriter, FacadeError> {
+            let writer = rw::ChunkWriter::try_new(schema.clone(), format)?;
+            Ok(match &cipher {
+                Some(cipher) => writer.with_cipher(cipher.clone()),
+                None => writer,
+            })
+        };
+
+        let mut writer = make_writer(&schema)?;
+        for chunk in &old_chunks {
+            let buffer = self.store.read_bytes(chunk.data_file()).await?;
+            let buffer = bytes::Bytes::from_owner(buffer);
+            let reader = match &cipher {
+                Some(cipher) => rw::ChunkReader::new_with_cipher(format, buffer, cipher)?,
+                None => rw::ChunkReader::new(format, buffer)?,
+            };
+
+            for batch in reader {
+                writer.write(&batch?)?;
+            }
+
+            if writer.memory_size() >= Self::COMPACT_TARGET_BYTES {
+                let flushed = std::mem::replace(&mut writer, make_writer(&schema)?);
+                self.write_compacted_chunk(
+                    flushed,
+                    record.topic_id,
+                    &metadata.properties.ontology_tag,
+                    &mut next_chunk_number,
+                )
+                .await?;
+                report.chunks_written += 1;
+            }
+        }
+
+        if writer.memory_size() > 0 {
+            self.write_compacted_chunk(
+                writer,
+                record.topic_id,
+                &metadata.properties.ontology_tag,
+                &mut next_chunk_number,
+            )
+            .await?;
+            report.chunks_written += 1;
+        }
+
+        let mut tx = self.repo.transaction().await?;
+        for chunk in &old_chunks {
+            // SAFETY: every old chunk's batches were just durably rewritten
+            // into the merged chunks committed above, so the catalog's only
+            // remaining reference to this chunk's data file can safely go
+            // away.
+            unsafe {
+                repo::chunk_delete(&mut tx, chunk.chunk_id).await?;
+            }
+        }
+        tx.commit().await?;
+        report.chunks_merged = old_chunks.len();
+
+        Ok(report)
+    }
+
+    /// Finalizes one merged chunk produced by [`Self::compact`]: serializes
+    /// it to the next unused chunk path for this topic and commits its
+    /// catalog row and stats the same way `FacadeChunk` commits any other
+    /// chunk.
+    async fn write_compacted_chunk(
+        &self,
+        writer: rw::ChunkWriter,
+        topic_id: i32,
+        ontology_tag: &str,
+        next_chunk_number: &mut usize,
+    ) -> Result<(), FacadeError> {
+        let format = writer.format;
+        let (buffer, stats, digest) = writer.finalize()?;
+
+        let path = self.locator.datafile(*next_chunk_number, &format);
+        *next_chunk_number += 1;
+        self.store.write_bytes(&path, buffer).await?;
+
+        let mut handle = repo::FacadeChunk::create(topic_id, &path, digest, &self.repo).await?;
+        for (field, stat) in stats.stats {
+            handle.push_stats(ontology_tag, &field, stat).await?;
+        }
+        handle.finalize().await?;
+
+        Ok(())
     }
 
     pub async fn delete(self) -> Result<(), FacadeError> {
@@ -218,6 +465,8 @@ impl FacadeTopic {
 
         tx.commit().await?;
 
+        repo::watch::publish(repo::watch::WatchEventKind::Deleted, self.locator.name());
+
         Ok(())
     }
 
@@ -237,6 +486,8 @@ impl FacadeTopic {
 
         tx.commit().await?;
 
+        repo::watch::publish(repo::watch::WatchEventKind::Deleted, self.locator.name());
+
         Ok(())
     }
 
@@ -254,6 +505,8 @@ impl FacadeTopic {
 
         tx.commit().await?;
 
+        metrics::record_notify("topic", "create");
+
         Ok(notify.into_types(self.locator.clone()))
     }
 
@@ -267,6 +520,19 @@ impl FacadeTopic {
             .collect())
     }
 
+    /// Opens a real-time subscription streaming new notifications for this topic
+    /// as they are created, built on top of Postgres `LISTEN`/`NOTIFY`.
+    pub async fn notify_subscribe(
+        &self,
+    ) -> Result<impl futures::Stream<Item = Result<types::Notify, FacadeError>>, FacadeError> {
+        let loc = self.locator.clone();
+        let stream = repo::topic_notify_subscribe(self.repo.clone(), loc.clone()).await?;
+
+        Ok(futures::StreamExt::map(stream, move |notify| {
+            Ok(notify?.into_types(loc.clone()))
+        }))
+    }
+
     /// Deletes all the notifications associated with the sequence
     pub async fn notify_purge(&self) -> Result<(), FacadeError> {
         let mut tx = self.repo.transaction().await?;
@@ -278,9 +544,34 @@ impl FacadeTopic {
             repo::topic_notify_delete(&mut tx, notify.id().unwrap()).await?;
         }
         tx.commit().await?;
+        metrics::record_notify("topic", "purge");
         Ok(())
     }
 
+    /// Builds a [`types::MerkleProof`] that the `chunk_index`-th chunk
+    /// uploaded to this topic (`0`-based, in upload order) belongs to its
+    /// Merkle tree, alongside the tree's current root.
+    ///
+    /// Returns `Ok(None)` if `chunk_index` is out of range. Rebuilds the
+    /// proof from every chunk's persisted [`rw::ContentDigest`] rather than
+    /// the compact peak vector `system_info` reads, since a proof needs the
+    /// internal nodes of the subtree `chunk_index` falls into.
+    pub async fn merkle_proof(
+        &self,
+        chunk_index: usize,
+    ) -> Result<Option<(types::MerkleProof, [u8; 32])>, FacadeError> {
+        let mut cx = self.repo.connection();
+        let record = repo::topic_find_by_locator(&mut cx, &self.locator).await?;
+
+        let chunks = repo::chunk_find_by_topic_ordered(&mut cx, record.topic_id).await?;
+        let digests = chunks
+            .iter()
+            .map(|c| rw::ContentDigest::try_from(c.content_digest.as_slice()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(types::merkle_proof(&digests, chunk_index))
+    }
+
     /// Computes system info for the topic
     pub async fn system_info(&self) -> Result<types::TopicSystemInfo, FacadeError> {
         // (cabba) TODO: avoid transactions for this kind of queries?
@@ -301,13 +592,115 @@ impl FacadeTopic {
             total_size = self.store.size(file).await?;
         }
 
+        let merkle_root = repo::topic_merkle_find(&mut cx, record.topic_id)
+            .await?
+            .and_then(|peaks| types::MerkleTree::from_bytes(&peaks))
+            .and_then(|tree| tree.root())
+            .map(|root| {
+                rw::ContentDigest::try_from(&root[..])
+                    .expect("a Merkle root is always 32 bytes")
+                    .to_string()
+            });
+
         Ok(types::TopicSystemInfo {
             chunks_number: datafiles.len(),
             is_locked: record.is_locked(),
             total_size_bytes: total_size,
             created_datetime: record.creation_timestamp().into(),
+            merkle_root,
         })
     }
-}
 
-// Batch Reader needs to implement Stream trait
+    /// Checkpoint and progress of this topic's upload job, for a client
+    /// polling a long-running or resumed upload. If no job has ever started
+    /// for this topic, reports a not-yet-started `Queued` status rather than
+    /// an error.
+    pub async fn upload_status(&self) -> Result<types::TopicUploadStatus, FacadeError> {
+        let mut cx = self.repo.connection();
+        let record = repo::topic_find_by_locator(&mut cx, &self.locator).await?;
+
+        Ok(
+            repo::upload_job_find(&mut cx, record.topic_id)
+                .await?
+                .unwrap_or(types::TopicUploadStatus {
+                    state: types::UploadJobState::Queued,
+                    chunks_written: 0,
+                    bytes_written: 0,
+                    current_file: None,
+                }),
+        )
+    }
+
+    /// Walks this topic's chunks, re-reading each from the store and
+    /// recomputing its digest, so silent corruption that leaves a data file
+    /// in place but alters its bytes is caught (unlike `repo::FacadeRepair`,
+    /// which only checks that the file still exists). A mismatch or a
+    /// missing file is recorded both in the returned report and as a
+    /// `TopicNotify` of type `Error` naming the chunk's uuid, so an operator
+    /// polling notifications (rather than running this scan themselves)
+    /// still learns about it.
+    pub async fn verify(&self) -> Result<types::ChunkVerifyReport, FacadeError> {
+        let mut cx = self.repo.connection();
+        let record = repo::topic_find_by_locator(&mut cx, &self.locator).await?;
+        let chunks = repo::chunk_find_by_topic_ordered(&mut cx, record.topic_id).await?;
+
+        let mut report = types::ChunkVerifyReport::default();
+        let mut actual_total_bytes = 0usize;
+
+        for chunk in &chunks {
+            let bytes = match self.store.read_bytes(chunk.data_file()).await {
+                Ok(bytes) => bytes,
+                Err(err) if err.is_not_found() => {
+                    report.missing_chunks.push(chunk.chunk_uuid);
+                    self.notify(
+                        types::NotifyType::Error,
+                        format!("chunk `{}` verify: data file missing", chunk.chunk_uuid),
+                    )
+                    .await?;
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            let Ok(algo) = chunk.content_digest_algo.parse::<rw::DigestAlgo>() else {
+                report.unsupported_digest_algo.push(chunk.chunk_uuid);
+                continue;
+            };
+            actual_total_bytes += bytes.len();
+
+            let matches = match algo {
+                rw::DigestAlgo::Blake3 => {
+                    rw::ContentDigest::of(&bytes).as_bytes().as_slice()
+                        == chunk.content_digest.as_slice()
+                }
+            };
+            if !matches {
+                report.digest_mismatches.push(chunk.chunk_uuid);
+                self.notify(
+                    types::NotifyType::Error,
+                    format!(
+                        "chunk `{}` verify: content digest mismatch",
+                        chunk.chunk_uuid
+                    ),
+                )
+                .await?;
+            }
+        }
+
+        if let Some(status) = repo::upload_job_find(&mut cx, record.topic_id).await? {
+            if status.bytes_written != actual_total_bytes {
+                report.size_drift = Some((status.bytes_written, actual_total_bytes));
+                self.notify(
+                    types::NotifyType::Error,
+                    format!(
+                        "topic `{}` verify: size drift, expected {} bytes but found {}",
+                        self.locator, status.bytes_written, actual_total_bytes
+                    ),
+                )
+                .await?;
+            }
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,23 @@
+mod facade_chunk;
+pub use facade_chunk::*;
+
+mod facade_error;
+pub use facade_error::*;
+
+mod facade_job_queue;
+pub use facade_job_queue::*;
+
+mod facade_layer;
+pub use facade_layer::*;
+
+mod facade_query;
+pub use facade_query::*;
+
+mod facade_repair;
+pub use facade_repair::*;
+
+mod facade_sequence;
+pub use facade_sequence::*;
+
+mod facade_topic;
+pub use facade_topic::*;
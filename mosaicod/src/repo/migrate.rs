@@ -0,0 +1,118 @@
+//! Versioned schema migrations, applied once per server startup in place of
+//! a bare [`super::layer_bootstrap`] call.
+//!
+//! Migrations are embedded Rust functions rather than `.sql` files, recorded
+//! by id/name/timestamp in `schema_migrations_t` so a given server only ever
+//! applies a migration once, regardless of how many times it restarts. New
+//! migrations are appended to [`MIGRATIONS`]; existing ids are never
+//! reordered or reused, since they've already been recorded by servers that
+//! ran an earlier release.
+
+use std::collections::HashSet;
+
+use log::info;
+
+use super::{AsExec, Error, Tx};
+use crate::types;
+
+/// A Postgres advisory lock key, scoped to this transaction, serializing
+/// concurrent server instances that race to migrate on startup -- picked
+/// arbitrarily, just needs to not collide with another advisory lock user.
+const MIGRATION_LOCK_KEY: i64 = 0x6d6f7361_69636f00;
+
+/// Ordered registry of every migration this server knows how to apply:
+/// `(id, name)`. `id`s are monotonically increasing and permanent once
+/// released. See [`apply`] for what each one actually does.
+const MIGRATIONS: &[(i64, &str)] = &[(1, "layer_bootstrap")];
+
+/// The full migration registry, for callers (like `super::migrator`) that
+/// need to report on or select from it without re-deriving [`MIGRATIONS`].
+pub(crate) fn registry() -> &'static [(i64, &'static str)] {
+    MIGRATIONS
+}
+
+/// Applies migration `id`'s body. Kept as a plain match (rather than a
+/// registry of function pointers) since each migration's signature differs
+/// only in what it calls, and this codebase already favors explicit
+/// dispatch over boxed generics for this kind of one-off fan-out.
+async fn apply(tx: &mut Tx<'_>, id: i64) -> Result<(), Error> {
+    match id {
+        1 => super::layer_bootstrap(tx).await,
+        _ => unreachable!("migration {id} is listed in MIGRATIONS but not implemented in apply()"),
+    }
+}
+
+/// Creates `schema_migrations_t` if it doesn't exist yet, takes an advisory
+/// lock for the rest of `tx` so concurrently-running migrators (another
+/// starting server, or a `mosaicod migrate` invocation; see
+/// `super::migrator`) don't race applying the same migration twice, and
+/// returns every migration id already recorded as applied.
+pub(crate) async fn ensure_table_and_lock(tx: &mut Tx<'_>) -> Result<HashSet<i64>, Error> {
+    sqlx::query!(
+        r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations_t (
+                migration_id BIGINT PRIMARY KEY,
+                migration_name TEXT NOT NULL,
+                applied_unix_tstamp BIGINT NOT NULL
+            )
+        "#
+    )
+    .execute(tx.as_exec())
+    .await?;
+
+    sqlx::query!("SELECT pg_advisory_xact_lock($1)", MIGRATION_LOCK_KEY)
+        .execute(tx.as_exec())
+        .await?;
+
+    Ok(sqlx::query_scalar!("SELECT migration_id FROM schema_migrations_t")
+        .fetch_all(tx.as_exec())
+        .await?
+        .into_iter()
+        .collect())
+}
+
+/// Applies migration `id`/`name` and records it in `schema_migrations_t` in
+/// the same transaction -- shared by [`migrate`] (which applies every
+/// pending migration unconditionally) and `super::migrator::migrate_up`
+/// (which lets an operator stop partway through, via `--to`).
+///
+/// Callers are expected to commit `tx` once they're done -- a crash between
+/// a migration's own statements and its `schema_migrations_t` row insert
+/// re-applies that one migration on the next startup, so migration bodies
+/// (like [`super::layer_bootstrap`]) must stay idempotent.
+pub(crate) async fn apply_and_record(tx: &mut Tx<'_>, id: i64, name: &str) -> Result<(), Error> {
+    info!("applying migration {id} ({name})");
+    apply(tx, id).await?;
+
+    let applied_unix_tstamp: i64 = types::Timestamp::now().into();
+    sqlx::query!(
+        r#"
+            INSERT INTO schema_migrations_t (migration_id, migration_name, applied_unix_tstamp)
+            VALUES ($1, $2, $3)
+        "#,
+        id,
+        name,
+        applied_unix_tstamp,
+    )
+    .execute(tx.as_exec())
+    .await?;
+
+    Ok(())
+}
+
+/// Brings the schema up to date: applies every migration in [`MIGRATIONS`]
+/// not already recorded, in order. See [`ensure_table_and_lock`] and
+/// [`apply_and_record`] for the mechanics.
+pub async fn migrate(tx: &mut Tx<'_>) -> Result<(), Error> {
+    let applied = ensure_table_and_lock(tx).await?;
+
+    for (id, name) in MIGRATIONS {
+        if applied.contains(id) {
+            continue;
+        }
+
+        apply_and_record(tx, *id, name).await?;
+    }
+
+    Ok(())
+}
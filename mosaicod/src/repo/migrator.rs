@@ -0,0 +1,90 @@
+//! Explicit operator control over [`super::migrate`]'s migration registry,
+//! for the `mosaicod migrate` CLI subcommand.
+//!
+//! `mosaicod run` already applies every pending migration in
+//! [`super::migrate::migrate`] unconditionally on every connect; this
+//! module instead drives the same registry as a deliberate step -- apply up
+//! to a specific version ahead of a deploy, or inspect what's applied --
+//! without requiring a running server.
+//!
+//! This used to be built on `sqlx::migrate!()`'s own `.sql`-file migrator
+//! (a versioned `migrations/` directory tracked in `_sqlx_migrations`), but
+//! this tree has never had a `migrations/` directory or `.sql` files
+//! checked in, so that macro had nothing to embed and could not build. It's
+//! reimplemented here directly against [`super::migrate`]'s registry, which
+//! already works (see the startup call in `server::core`, right after
+//! `Repository::try_new`), instead of
+//! carrying a second, non-functional migration system alongside it.
+//!
+//! One consequence of that registry's design (embedded, idempotent Rust
+//! functions rather than paired up/down `.sql` files -- see its module
+//! doc) is that it has no rollback step, so [`migrate_down`] cannot do
+//! anything useful; see its own doc comment.
+
+use super::core::Database;
+use super::migrate;
+use super::{Error, Tx};
+
+/// One migration from [`super::migrate`]'s registry, and whether it's
+/// currently applied. Returned by [`migration_status`].
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub id: i64,
+    pub name: String,
+    pub applied: bool,
+}
+
+/// Applies every pending migration from [`super::migrate`]'s registry, up
+/// to and including `to` if given (every pending migration otherwise),
+/// oldest first.
+pub async fn migrate_up(pool: &sqlx::Pool<Database>, to: Option<i64>) -> Result<(), Error> {
+    let mut tx = Tx::begin(pool).await?;
+    let applied = migrate::ensure_table_and_lock(&mut tx).await?;
+
+    let mut pending: Vec<(i64, &'static str)> = migrate::registry()
+        .iter()
+        .copied()
+        .filter(|(id, _)| !applied.contains(id))
+        .collect();
+    pending.sort_by_key(|(id, _)| *id);
+
+    for (id, name) in pending {
+        if to.is_some_and(|to| id > to) {
+            break;
+        }
+
+        migrate::apply_and_record(&mut tx, id, name).await?;
+    }
+
+    tx.commit().await
+}
+
+/// Always fails: [`super::migrate`]'s registry applies forward-only,
+/// idempotent migrations (see its module doc) with no companion rollback
+/// step, unlike the `.sql`-file migrator this replaced. Kept as a real
+/// function, rather than dropping the `down` subcommand outright, so an
+/// operator running it gets an explicit, actionable error instead of it
+/// silently disappearing.
+pub async fn migrate_down(_pool: &sqlx::Pool<Database>, _to: i64) -> Result<(), Error> {
+    Err(Error::MigrationDownUnsupported)
+}
+
+/// Reports every migration in [`super::migrate`]'s registry against
+/// whether it's currently applied, oldest first.
+pub async fn migration_status(pool: &sqlx::Pool<Database>) -> Result<Vec<MigrationStatus>, Error> {
+    let mut tx = Tx::begin(pool).await?;
+    let applied = migrate::ensure_table_and_lock(&mut tx).await?;
+    tx.rollback().await?;
+
+    let mut statuses: Vec<MigrationStatus> = migrate::registry()
+        .iter()
+        .map(|(id, name)| MigrationStatus {
+            id: *id,
+            name: name.to_string(),
+            applied: applied.contains(id),
+        })
+        .collect();
+    statuses.sort_by_key(|s| s.id);
+
+    Ok(statuses)
+}
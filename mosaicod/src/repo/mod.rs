@@ -1,18 +1,34 @@
 pub mod core;
 pub use core::{AsExec, Config, Cx, Database, Repository, Tx, UNREGISTERED};
 
+mod backend;
+pub use backend::{embedded, RepoBackend, TopicBackend};
+
 mod facades;
 pub use facades::*;
 
+mod dataloader;
+pub use dataloader::{NotificationLoader, TopicLoader};
+
 // Exported queries
 //
 // We expose a minimal set of queries to ensure that database logic and
 // operations remain encapsulated within the facade layer.
 pub use sql_models::{get_resource_locator_from_name, layer_bootstrap, sequence_find_all};
 
+mod migrate;
+pub use migrate::migrate;
+
+mod migrator;
+pub use migrator::{migrate_down, migrate_up, migration_status, MigrationStatus};
+
 mod error;
 pub use error::Error;
 
+pub mod watch;
+
+pub mod notify_watch;
+
 #[cfg(test)]
 pub use core::testing;
 
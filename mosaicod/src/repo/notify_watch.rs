@@ -0,0 +1,55 @@
+//! Wakes a [`super::FacadeSequence::notify_poll`] long-poll as soon as a
+//! `sequence_notify_create` commits, instead of the poller re-opening a
+//! transaction on a timer.
+//!
+//! Deliberately process-local rather than routed through Postgres
+//! `LISTEN`/`NOTIFY`, the same tradeoff [`super::watch`] makes: every write
+//! that matters here already goes through this process, so there's no need
+//! to pay for a round trip to the database just to wake a waiter in the
+//! same binary. Unlike [`super::watch`], no event history is kept here --
+//! the database is already the source of truth for a sequence's notifies,
+//! so a wakeup just means "go re-check", not "here's what changed".
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+fn registry() -> &'static Mutex<HashMap<i32, Arc<Notify>>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<i32, Arc<Notify>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn notify_for(sequence_id: i32) -> Arc<Notify> {
+    registry()
+        .lock()
+        .expect("sequence notify watch registry poisoned")
+        .entry(sequence_id)
+        .or_insert_with(|| Arc::new(Notify::new()))
+        .clone()
+}
+
+/// Wakes anyone blocked in [`wait`] for `sequence_id`.
+///
+/// Called by [`super::FacadeSequence::notify`] after its
+/// `sequence_notify_create` transaction commits.
+pub fn publish(sequence_id: i32) {
+    notify_for(sequence_id).notify_waiters();
+}
+
+/// Waits up to `timeout` for [`publish`] to be called for `sequence_id`.
+///
+/// A `publish` landing in the small window between the caller's last
+/// database check and this call is missed (`notify_waiters` only wakes
+/// already-registered waiters); the caller degrades to waiting out the full
+/// `timeout` in that case rather than hanging indefinitely, since it's
+/// expected to always re-check the database afterwards regardless of
+/// whether this returns due to a wakeup or a timeout.
+pub async fn wait(sequence_id: i32, timeout: Duration) {
+    let notified = notify_for(sequence_id).notified();
+    tokio::select! {
+        _ = notified => {}
+        _ = tokio::time::sleep(timeout) => {}
+    }
+}
@@ -27,15 +27,28 @@ pub struct Chunk {
     pub chunk_uuid: uuid::Uuid,
     pub topic_id: i32,
     pub(super) data_file: String,
+    /// Digest of the serialized chunk buffer, used to detect corruption on
+    /// read and to recognize identical chunks by content.
+    pub content_digest: Vec<u8>,
+    /// Algorithm `content_digest` was computed with (see
+    /// [`crate::rw::DigestAlgo`]), stored alongside it so the scheme can
+    /// evolve without breaking how older chunks are verified.
+    pub content_digest_algo: String,
 }
 
 impl Chunk {
-    pub fn new(topic_id: i32, data_file: impl AsRef<std::path::Path>) -> Self {
+    pub fn new(
+        topic_id: i32,
+        data_file: impl AsRef<std::path::Path>,
+        content_digest: crate::rw::ContentDigest,
+    ) -> Self {
         Self {
             chunk_id: repo::UNREGISTERED,
             chunk_uuid: uuid::Uuid::new_v4(),
             topic_id,
             data_file: data_file.as_ref().to_string_lossy().to_string(),
+            content_digest_algo: content_digest.algo().to_string(),
+            content_digest: content_digest.into(),
         }
     }
 
@@ -44,6 +57,29 @@ impl Chunk {
     }
 }
 
+/// Tracks how many [`Chunk`] rows, across every topic, point at the same
+/// physical `data_file` because they share a content digest.
+///
+/// Maintained alongside `chunk_t` rather than derived on the fly: every
+/// [`Chunk`] creation touches the matching [`ChunkRef`] (see
+/// `repo::chunk_ref_upsert`), and `repo::FacadeRepair::scan` periodically
+/// recomputes it from `chunk_t` to catch drift and reclaim entries whose
+/// refcount has dropped to zero.
+#[derive(Debug)]
+pub struct ChunkRef {
+    pub chunk_ref_id: i32,
+    pub content_digest: Vec<u8>,
+    pub content_digest_algo: String,
+    pub data_file: String,
+    pub refcount: i32,
+}
+
+impl ChunkRef {
+    pub fn data_file(&self) -> &std::path::Path {
+        std::path::Path::new(&self.data_file)
+    }
+}
+
 /// Chunk of literal data associated with a column.
 #[derive(Debug)]
 pub struct ColumnChunkLiteral {
@@ -56,6 +92,7 @@ pub struct ColumnChunkLiteral {
     pub max_value: String,
 
     pub has_null: bool,
+    pub has_non_null: bool,
 }
 
 impl ColumnChunkLiteral {
@@ -65,6 +102,7 @@ impl ColumnChunkLiteral {
         min_value: String,
         max_value: String,
         has_null: bool,
+        has_non_null: bool,
     ) -> Result<Self, repo::Error> {
         Ok(Self {
             column_id,
@@ -72,6 +110,7 @@ impl ColumnChunkLiteral {
             min_value,
             max_value,
             has_null,
+            has_non_null,
         })
     }
 }
@@ -89,6 +128,49 @@ pub struct ColumnChunkNumeric {
 
     pub has_null: bool,
     pub has_nan: bool,
+    pub has_non_null: bool,
+}
+
+/// Serialized bloom filter for a column's values within a chunk, used to
+/// prune chunks on equality predicates without scanning the data file.
+#[derive(Debug)]
+pub struct ColumnChunkBloom {
+    pub column_id: i32,
+    pub chunk_id: i32,
+
+    /// Raw little-endian bytes of the serialized [`crate::types::BloomFilter`].
+    pub bloom_filter: Vec<u8>,
+}
+
+impl ColumnChunkBloom {
+    pub fn new(column_id: i32, chunk_id: i32, bloom_filter: Vec<u8>) -> Self {
+        Self {
+            column_id,
+            chunk_id,
+            bloom_filter,
+        }
+    }
+}
+
+/// Exact distinct-value dictionary for a column's values within a chunk,
+/// persisted only while the column stayed under the cardinality threshold.
+#[derive(Debug)]
+pub struct ColumnChunkDictionary {
+    pub column_id: i32,
+    pub chunk_id: i32,
+
+    /// The distinct values observed for this column in this chunk.
+    pub values: Vec<String>,
+}
+
+impl ColumnChunkDictionary {
+    pub fn new(column_id: i32, chunk_id: i32, values: Vec<String>) -> Self {
+        Self {
+            column_id,
+            chunk_id,
+            values,
+        }
+    }
 }
 
 impl ColumnChunkNumeric {
@@ -99,6 +181,7 @@ impl ColumnChunkNumeric {
         max: f64,
         has_null: bool,
         has_nan: bool,
+        has_non_null: bool,
     ) -> Self {
         Self {
             column_id,
@@ -107,6 +190,7 @@ impl ColumnChunkNumeric {
             max_value: max,
             has_null,
             has_nan,
+            has_non_null,
         }
     }
 }
@@ -0,0 +1,62 @@
+/// Status of a [`JobQueueRow`].
+///
+/// Stored as a raw string since the sqlx driver cannot interact directly
+/// with enums, following the same convention as `TopicNotify::notify_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    New,
+    Running,
+}
+
+impl std::fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::New => write!(f, "new"),
+            Self::Running => write!(f, "running"),
+        }
+    }
+}
+
+impl std::str::FromStr for JobStatus {
+    type Err = std::io::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "new" => Ok(Self::New),
+            "running" => Ok(Self::Running),
+            _ => Err(std::io::Error::other(format!(
+                "unknown job status `{}`",
+                value
+            ))),
+        }
+    }
+}
+
+/// A durably queued unit of work, backed by the `job_queue_t` table.
+///
+/// Rows are claimed with `FOR UPDATE SKIP LOCKED` so multiple workers can
+/// poll the same `queue` concurrently without claiming the same job twice,
+/// and deleted on success; a row whose `heartbeat` goes stale (its worker
+/// crashed mid-job) is reclaimed by the sweeper rather than lost.
+#[derive(Debug)]
+pub struct JobQueueRow {
+    pub job_queue_id: uuid::Uuid,
+    pub queue: String,
+    pub job: serde_json::Value,
+    pub status: String,
+    /// Number of times this job has previously been claimed and failed.
+    /// Bumped by [`super::pg_queries::job_fail`] and used both to compute
+    /// the exponential backoff before the next claim and to cap retries
+    /// (see `job_claim_next`'s `retries < max_retries` filter).
+    pub retries: i32,
+    pub heartbeat: Option<chrono::DateTime<chrono::Utc>>,
+    pub created: chrono::DateTime<chrono::Utc>,
+}
+
+impl JobQueueRow {
+    pub fn status(&self) -> JobStatus {
+        // A row only ever carries a status produced by `JobStatus::to_string`,
+        // so parsing it back can't fail.
+        self.status.parse().unwrap()
+    }
+}
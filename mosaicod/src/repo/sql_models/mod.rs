@@ -3,6 +3,9 @@
 mod data_catalog;
 pub use data_catalog::*;
 
+mod job_queue;
+pub use job_queue::*;
+
 mod layers;
 pub use layers::*;
 
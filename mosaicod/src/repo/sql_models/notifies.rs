@@ -62,7 +62,7 @@ impl SequenceNotify {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TopicNotify {
     pub(super) topic_notify_id: i32,
     pub topic_id: i32,
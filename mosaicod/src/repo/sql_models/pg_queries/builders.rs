@@ -2,13 +2,23 @@ use crate::query;
 
 // (cabba) TODO: this code is dog shit, we need to fix it ASAP
 
+/// Subquery selecting every chunk in scope, used as the left-hand side of
+/// [`query::OntologyExpr::Not`]'s `EXCEPT`.
+const CHUNK_UNIVERSE: &str = "SELECT chunk_id FROM chunk_t";
+
+/// Largest `$in` list still compiled as an `OR` of exact per-value range
+/// checks. Longer lists fall back to a single min/max span prune, which is
+/// looser (it also selects chunks that only overlap the span without
+/// holding a listed value) but avoids one clause per list entry.
+const IN_PRECISE_THRESHOLD: usize = 8;
+
 pub struct ChunkQueryBuilder {
     placeholder_counter: usize,
 }
 
 impl ChunkQueryBuilder {
     pub fn build(
-        filter: query::OntologyFilter,
+        filter: query::OntologyExpr,
         on_topic_ids: Vec<i64>,
     ) -> Result<(String, Vec<query::Value>), query::Error> {
         let mut qb = query::ClausesCompiler::new();
@@ -31,14 +41,43 @@ impl ChunkQueryBuilder {
             placeholder_counter: pidx,
         };
 
-        qb = qb.filter(filter.into_iterator(), &mut qb_chunk);
+        // `compile_leaf` shares `qb_chunk` across every leaf of the tree, so
+        // the `$N` placeholder indices it hands out stay monotonic no
+        // matter how the leaves are nested under And/Or/Not.
+        let ontology_result = filter.compile(CHUNK_UNIVERSE, &mut |leaf| {
+            query::ClausesCompiler::new()
+                .filter(
+                    leaf.iter().map(|(field, op)| (field.clone(), op.clone())),
+                    &mut qb_chunk,
+                )
+                .compile()
+        })?;
+
+        let topic_result = qb.compile()?;
+
+        let mut clauses = topic_result.clauses;
+        clauses.extend(ontology_result.clauses);
+
+        let mut values = topic_result.values;
+        values.extend(ontology_result.values);
 
-        let qr = qb.compile()?;
-        let joined_clauses = qr.clauses.join(" INTERSECT ");
+        let joined_clauses = clauses.join(" INTERSECT ");
 
         let query = build_query(joined_clauses);
 
-        Ok((query, qr.values))
+        Ok((query, values))
+    }
+
+    /// Like [`Self::build`], but also returns a [`QueryPlan`] describing
+    /// which predicates compiled into zone-map/bloom prunes versus fell
+    /// back to a full per-column scan.
+    pub fn explain(
+        filter: query::OntologyExpr,
+        on_topic_ids: Vec<i64>,
+    ) -> Result<(String, Vec<query::Value>, QueryPlan), query::Error> {
+        let plan = QueryPlan::from_expr(&filter);
+        let (query, values) = Self::build(filter, on_topic_ids)?;
+        Ok((query, values, plan))
     }
 
     fn consume_placeholder(&mut self) -> String {
@@ -46,6 +85,27 @@ impl ChunkQueryBuilder {
         self.placeholder_counter += 1;
         p
     }
+
+    /// Standalone NaN-existence pruning clause for numeric columns.
+    ///
+    /// Kept outside `compile_clause` rather than folded into `Op::Ex`/
+    /// `Op::Nex`: `has_nan` only exists on `column_chunk_numeric_t`, and
+    /// `query::Op` has no variant for it yet, so a caller that already knows
+    /// the field is numeric can reach for this directly.
+    pub fn compile_nan_clause(field: &str) -> query::CompiledClause {
+        let column_name = column_table_name_by_value(&query::Value::Float(0.0));
+
+        let clause = format!(
+            r#"
+            SELECT chunk_id FROM chunk_t
+            JOIN column_chunk_numeric_t __stats__ USING(chunk_id)
+            JOIN column_t __column__ USING(column_id)
+            WHERE {column_name} = {field} AND __stats__.has_nan = true
+            "#
+        );
+
+        query::CompiledClause::new(clause, Vec::new())
+    }
 }
 
 pub fn build_query(joined_clauses: String) -> String {
@@ -81,6 +141,214 @@ fn column_table_name_by_value(_v: &query::Value) -> String {
     "(__column__.ontology_tag || '.' || __column__.column_name)".into()
 }
 
+/// Builds the existence-check clause shared by [`query::Op::Ex`]/
+/// [`query::Op::Nex`].
+///
+/// Every other operator picks a single stats table by looking at the
+/// compared value's [`query::Value`] variant, but `Ex`/`Nex` carry no value
+/// to do that with, so the field could be numeric or text. Both
+/// `column_chunk_numeric_t` and `column_chunk_literal_t` record `has_null`/
+/// `has_non_null`, so we check both and union the matches.
+fn build_existence_clause(field: &str, condition: &str) -> String {
+    let column_name = column_table_name_by_value(&query::Value::Integer(0));
+
+    let numeric = format!(
+        r#"
+        SELECT chunk_id FROM chunk_t
+        JOIN column_chunk_numeric_t __stats__ USING(chunk_id)
+        JOIN column_t __column__ USING(column_id)
+        WHERE {column_name} = {field} AND {condition}
+        "#
+    );
+    let literal = format!(
+        r#"
+        SELECT chunk_id FROM chunk_t
+        JOIN column_chunk_literal_t __stats__ USING(chunk_id)
+        JOIN column_t __column__ USING(column_id)
+        WHERE {column_name} = {field} AND {condition}
+        "#
+    );
+
+    format!("{numeric} UNION {literal}")
+}
+
+/// Literal prefix of a [`query::MatchPattern`], for chunk pruning.
+///
+/// Only a glob has a literal prefix worth pruning on: it's first translated
+/// to the SQL `LIKE` form (`*`/`?` become `%`/`_`) and the run of characters
+/// before the first unescaped `%`/`_` is taken, unescaping `\%`/`\_`/`\\`
+/// along the way. A regex carries no such prefix, so it always returns
+/// `None`. `None` also covers a glob that starts with a wildcard.
+fn match_literal_prefix(pattern: &query::MatchPattern) -> Option<String> {
+    let like = match pattern {
+        query::MatchPattern::Glob(glob) => query::glob_to_sql_like(glob),
+        query::MatchPattern::Regex(_) => return None,
+    };
+
+    let mut prefix = String::new();
+    let mut chars = like.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped) => prefix.push(escaped),
+                None => break,
+            },
+            '%' | '_' => break,
+            _ => prefix.push(c),
+        }
+    }
+
+    if prefix.is_empty() { None } else { Some(prefix) }
+}
+
+/// Exclusive upper bound of the half-open byte range that contains every
+/// string starting with `prefix`: `prefix` with its final byte incremented,
+/// carrying into (and truncating) any trailing `0xFF` bytes.
+///
+/// Matching stays byte-ordering based, like the existing `TextStats`
+/// min/max comparisons, so this works on UTF-8 bytes rather than chars.
+/// Returns `None` if `prefix` is all `0xFF` bytes and carries all the way
+/// out -- no finite successor exists, so the caller should drop the upper
+/// bound rather than guess one (0xFF never appears in valid UTF-8 anyway,
+/// so in practice this is unreachable).
+fn succ_string(prefix: &str) -> Option<String> {
+    let mut bytes = prefix.as_bytes().to_vec();
+
+    while let Some(&last) = bytes.last() {
+        if last == 0xFF {
+            bytes.pop();
+        } else {
+            *bytes.last_mut().expect("checked by the while-let above") += 1;
+            return Some(String::from_utf8_lossy(&bytes).into_owned());
+        }
+    }
+
+    None
+}
+
+/// Diagnostic summary of a single compiled predicate, as produced by
+/// [`QueryPlan::from_expr`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedClause {
+    pub field: String,
+    /// Surface operator name (`"="`, `"BETWEEN"`, ...), for display.
+    pub op: &'static str,
+    /// Whether this predicate narrows the candidate set via a zone-map (or
+    /// bloom) check, as opposed to falling back to a full scan of every
+    /// chunk recording this column.
+    pub pruning: bool,
+}
+
+/// Static, pre-execution summary of how a filter would compile, analogous
+/// to a database's `EXPLAIN` plan. Built from a borrow of the filter before
+/// [`ChunkQueryBuilder::build`] consumes it, so it reflects exactly what
+/// `build` is about to do without needing to thread plan-collection state
+/// through `ClausesCompiler`/`compile_clause`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryPlan {
+    pub clauses: Vec<PlannedClause>,
+    /// Rough heuristic for the fraction of chunks expected to survive
+    /// pruning, as the product of a fixed per-operator selectivity factor.
+    /// This is a planning-time guess, not a measurement -- pair it with a
+    /// `PruningReport` from an actual run to see the real number.
+    pub estimated_selectivity: f64,
+    /// Number of clauses that don't prune: a `Match` with no literal prefix
+    /// (a full scan of every chunk recording its column), or an operator
+    /// `compile_clause` rejects (`NIN`/`LIKE`/`ILIKE`/`REGEX`), which makes
+    /// `build` itself fail.
+    pub fallbacks: usize,
+}
+
+impl QueryPlan {
+    fn from_expr(expr: &query::OntologyExpr) -> Self {
+        let mut clauses = Vec::new();
+        collect_planned_clauses(expr, &mut clauses);
+
+        let fallbacks = clauses.iter().filter(|c| !c.pruning).count();
+        let estimated_selectivity = clauses
+            .iter()
+            .map(|c| selectivity_factor(c.op, c.pruning))
+            .product();
+
+        Self {
+            clauses,
+            estimated_selectivity,
+            fallbacks,
+        }
+    }
+}
+
+fn collect_planned_clauses(expr: &query::OntologyExpr, out: &mut Vec<PlannedClause>) {
+    match expr {
+        query::OntologyExpr::Leaf(filter) => {
+            for (field, op) in filter.iter() {
+                out.push(planned_clause(field.value(), op));
+            }
+        }
+        query::OntologyExpr::Not(inner) => collect_planned_clauses(inner, out),
+        query::OntologyExpr::And(items) | query::OntologyExpr::Or(items) => {
+            for item in items {
+                collect_planned_clauses(item, out);
+            }
+        }
+    }
+}
+
+/// Classifies a single `(field, op)` leaf entry the same way
+/// [`ChunkQueryBuilder::compile_clause`] would compile it, without actually
+/// consuming placeholders or building SQL.
+fn planned_clause(field: &str, op: &query::Op<query::Value>) -> PlannedClause {
+    let (name, pruning): (&'static str, bool) = match op {
+        query::Op::Eq(_) => ("=", true),
+        query::Op::Leq(_) => ("<=", true),
+        query::Op::Geq(_) => (">=", true),
+        query::Op::Lt(_) => ("<", true),
+        query::Op::Gt(_) => (">", true),
+        query::Op::Between(_) => ("BETWEEN", true),
+        query::Op::In(_) => ("IN", true),
+        query::Op::Ex => ("EXISTS", true),
+        query::Op::Nex => ("NOT EXISTS", true),
+        query::Op::Match(query::Value::Text(raw)) => (
+            "MATCH",
+            match_literal_prefix(&query::MatchPattern::parse(raw)).is_some(),
+        ),
+        query::Op::Match(_) => ("MATCH", false),
+        query::Op::Neq(_) => ("!=", true),
+        query::Op::Nin(_) => ("NIN", false),
+        query::Op::Like(_) => ("LIKE", false),
+        query::Op::Ilike(_) => ("ILIKE", false),
+        query::Op::Regex(_) => ("REGEX", false),
+    };
+
+    PlannedClause {
+        field: field.to_string(),
+        op: name,
+        pruning,
+    }
+}
+
+/// Fixed per-operator selectivity used to build
+/// [`QueryPlan::estimated_selectivity`]. These aren't measured from real
+/// statistics -- a genuine per-chunk estimate would require loading the
+/// zone-map stats themselves, which is exactly what running the query and
+/// reading back a `PruningReport` gives you instead.
+fn selectivity_factor(op: &'static str, pruning: bool) -> f64 {
+    if !pruning {
+        return 1.0;
+    }
+
+    match op {
+        "=" | "IN" | "MATCH" => 0.1,
+        "BETWEEN" | "<" | "<=" | ">" | ">=" => 0.3,
+        "EXISTS" | "NOT EXISTS" => 0.5,
+        // Only prunes chunks that are a single constant value, which is rare
+        // -- nearly every chunk survives, so this barely moves the estimate.
+        "!=" => 0.9,
+        _ => 1.0,
+    }
+}
+
 impl query::CompileClause for ChunkQueryBuilder {
     fn compile_clause<V>(
         &mut self,
@@ -101,7 +369,21 @@ impl query::CompileClause for ChunkQueryBuilder {
                 );
                 query::CompiledClause::new(build_clause(clause, &v), vec![v])
             }
-            query::Op::Neq(_) => return Err(query::Error::unsupported_op(field.into())),
+            query::Op::Neq(v) => {
+                let v = v.into();
+                let p = self.consume_placeholder();
+                let column_name = column_table_name_by_value(&v);
+
+                // A chunk can only be pruned if it provably holds nothing but
+                // the excluded value, i.e. its min and max both equal it.
+                // Anything else -- including chunks with no stats at all --
+                // must be kept, since the excluded value could still be one
+                // of several values the chunk contains.
+                let clause = format!(
+                    "{column_name} = {field} AND NOT (__stats__.min_value = {p} AND __stats__.max_value = {p})"
+                );
+                query::CompiledClause::new(build_clause(clause, &v), vec![v])
+            }
             query::Op::Leq(v) => {
                 let v = v.into();
                 let p = self.consume_placeholder();
@@ -135,8 +417,17 @@ impl query::CompileClause for ChunkQueryBuilder {
                 query::CompiledClause::new(build_clause(clause, &v), vec![v])
             }
 
-            query::Op::Ex => return Err(query::Error::unsupported_op(field.into())),
-            query::Op::Nex => return Err(query::Error::unsupported_op(field.into())),
+            query::Op::Ex => {
+                // IS NOT NULL: prune chunks where every value is null, i.e.
+                // keep only chunks that recorded at least one non-null value.
+                let clause = build_existence_clause(field, "__stats__.has_non_null = true");
+                query::CompiledClause::new(clause, Vec::new())
+            }
+            query::Op::Nex => {
+                // IS NULL: prune chunks that never recorded a null value.
+                let clause = build_existence_clause(field, "__stats__.has_null = true");
+                query::CompiledClause::new(clause, Vec::new())
+            }
 
             query::Op::Between(range) => {
                 let vmin = range.min.into();
@@ -152,8 +443,102 @@ impl query::CompileClause for ChunkQueryBuilder {
                 query::CompiledClause::new(build_clause(clause, &vmin), vec![vmin, vmax])
             }
 
-            query::Op::In(_) => return Err(query::Error::unsupported_op(field.into())),
-            query::Op::Match(_) => return Err(query::Error::unsupported_op(field.into())),
+            query::Op::In(items) => {
+                if items.is_empty() {
+                    return Ok(query::CompiledClause::empty());
+                }
+
+                let values: Vec<query::Value> = items.into_iter().map(Into::into).collect();
+                let column_name = column_table_name_by_value(&values[0]);
+
+                if values.len() <= IN_PRECISE_THRESHOLD {
+                    // Precise mode: a chunk only matches if its range could
+                    // actually contain one of the listed values, not merely
+                    // overlap their span.
+                    let mut per_value = Vec::with_capacity(values.len());
+                    for v in &values {
+                        let p = self.consume_placeholder();
+                        per_value.push(format!(
+                            "{column_name} = {field} AND __stats__.min_value <= {p} AND __stats__.max_value >= {p}"
+                        ));
+                    }
+
+                    let clause = per_value.join(" OR ");
+                    query::CompiledClause::new(build_clause(clause, &values[0]), values)
+                } else {
+                    // Zone-map prune: a chunk can only contain a member of the
+                    // list if its range overlaps the list's min/max span.
+                    let vmin = values
+                        .iter()
+                        .min_by(|a, b| a.partial_cmp(b).unwrap())
+                        .unwrap()
+                        .clone();
+                    let vmax = values
+                        .iter()
+                        .max_by(|a, b| a.partial_cmp(b).unwrap())
+                        .unwrap()
+                        .clone();
+                    let pmin = self.consume_placeholder();
+                    let pmax = self.consume_placeholder();
+
+                    let clause = format!(
+                        "{column_name} = {field} AND __stats__.min_value <= {pmax} AND __stats__.max_value >= {pmin}"
+                    );
+
+                    query::CompiledClause::new(build_clause(clause, &vmin), vec![vmin, vmax])
+                }
+            }
+            query::Op::Match(v) => {
+                let v = v.into();
+                let query::Value::Text(raw) = v else {
+                    return Err(query::Error::unsupported_op(field.into()));
+                };
+
+                let column_name = column_table_name_by_value(&query::Value::Text(raw.clone()));
+                let pattern = query::MatchPattern::parse(&raw);
+
+                match match_literal_prefix(&pattern) {
+                    Some(prefix) => match succ_string(&prefix) {
+                        Some(succ) => {
+                            let p_prefix = self.consume_placeholder();
+                            let p_succ = self.consume_placeholder();
+
+                            let clause = format!(
+                                "{column_name} = {field} AND __stats__.max_value >= {p_prefix} AND __stats__.min_value < {p_succ}"
+                            );
+                            query::CompiledClause::new(
+                                build_clause(clause, &query::Value::Text(raw)),
+                                vec![query::Value::Text(prefix), query::Value::Text(succ)],
+                            )
+                        }
+                        None => {
+                            let p_prefix = self.consume_placeholder();
+
+                            let clause = format!(
+                                "{column_name} = {field} AND __stats__.max_value >= {p_prefix}"
+                            );
+                            query::CompiledClause::new(
+                                build_clause(clause, &query::Value::Text(raw)),
+                                vec![query::Value::Text(prefix)],
+                            )
+                        }
+                    },
+                    None => {
+                        // No literal prefix to prune on (wildcard-first glob, or
+                        // an arbitrary regex) -- every chunk recording this
+                        // column could contain a match.
+                        let clause = format!("{column_name} = {field}");
+                        query::CompiledClause::new(
+                            build_clause(clause, &query::Value::Text(raw)),
+                            Vec::new(),
+                        )
+                    }
+                }
+            }
+            query::Op::Nin(_) => return Err(query::Error::unsupported_op(field.into())),
+            query::Op::Like(_) => return Err(query::Error::unsupported_op(field.into())),
+            query::Op::Ilike(_) => return Err(query::Error::unsupported_op(field.into())),
+            query::Op::Regex(_) => return Err(query::Error::unsupported_op(field.into())),
         };
 
         Ok(clause)
@@ -24,12 +24,19 @@ impl JsonQueryCompiler {
 
 pub struct SqlQueryCompiler {
     placeholder_counter: usize,
+    /// `Some(config)` makes `Op::Match` lower to a `tsvector`/`tsquery`
+    /// full-text match against a GIN-indexed column instead of the default
+    /// glob/regex `LIKE` translation -- `config` is the Postgres text search
+    /// configuration name (`"simple"`, `"english"`, ...) controlling
+    /// stemming. See [`Self::with_fts`].
+    fts: Option<&'static str>,
 }
 
 impl SqlQueryCompiler {
     pub fn new() -> Self {
         Self {
             placeholder_counter: 1,
+            fts: None,
         }
     }
 
@@ -38,6 +45,17 @@ impl SqlQueryCompiler {
         self
     }
 
+    /// Opts `Op::Match` into full-text search: instead of translating the
+    /// pattern to a `LIKE`/`~` clause, it's bound as the raw search string
+    /// to `to_tsvector(config, field) @@ plainto_tsquery(config, $N)`, so a
+    /// GIN index on `to_tsvector(config, field)` can be used and results can
+    /// be ranked. `config` picks the Postgres text search configuration
+    /// (e.g. `"simple"` for no stemming, `"english"` to stem/drop stopwords).
+    pub fn with_fts(mut self, config: &'static str) -> Self {
+        self.fts = Some(config);
+        self
+    }
+
     fn consume_placeholder(&mut self) -> String {
         let p = format!("${}", self.placeholder_counter);
         self.placeholder_counter += 1;
@@ -136,14 +154,62 @@ impl query::CompileClause for SqlQueryCompiler {
             }
             query::Op::Match(v) => {
                 let value: query::Value = v.into();
-                if let query::Value::Text(text) = value {
-                    let value = query::Value::Text(format!("%{}%", text));
-                    let clause = format!("{} LIKE {}", field, self.consume_placeholder());
-                    query::CompiledClause::new(clause, vec![value])
-                } else {
+                let query::Value::Text(text) = value else {
                     return Err(query::Error::unsupported_op(field.to_string()));
+                };
+
+                if let Some(config) = self.fts {
+                    let clause = format!(
+                        "to_tsvector('{config}', {field}) @@ plainto_tsquery('{config}', {})",
+                        self.consume_placeholder()
+                    );
+                    query::CompiledClause::new(clause, vec![query::Value::Text(text)])
+                } else {
+                    match query::MatchPattern::parse(&text) {
+                        query::MatchPattern::Glob(glob) => {
+                            let value = query::Value::Text(query::glob_to_sql_like(&glob));
+                            let clause = format!(
+                                "{field} LIKE {} ESCAPE '\\'",
+                                self.consume_placeholder()
+                            );
+                            query::CompiledClause::new(clause, vec![value])
+                        }
+                        query::MatchPattern::Regex(pattern) => {
+                            let value = query::Value::Text(pattern);
+                            let clause = format!("{field} ~ {}", self.consume_placeholder());
+                            query::CompiledClause::new(clause, vec![value])
+                        }
+                    }
                 }
             }
+            query::Op::Nin(items) => {
+                if items.is_empty() {
+                    return Ok(query::CompiledClause::empty());
+                }
+
+                let values: Vec<query::Value> = items.into_iter().map(Into::into).collect();
+                let placeholders: Vec<String> =
+                    values.iter().map(|_| self.consume_placeholder()).collect();
+
+                let clause = format!("{} NOT IN ({})", field, placeholders.join(", "));
+
+                query::CompiledClause::new(clause, values)
+            }
+            query::Op::Like(v) => {
+                let value: query::Value = v.into();
+                let clause = format!("{field} LIKE {} ESCAPE '\\'", self.consume_placeholder());
+                query::CompiledClause::new(clause, vec![value])
+            }
+            query::Op::Ilike(v) => {
+                let value: query::Value = v.into();
+                let clause = format!("{field} ILIKE {} ESCAPE '\\'", self.consume_placeholder());
+                query::CompiledClause::new(clause, vec![value])
+            }
+            query::Op::Regex(v) => {
+                let value: query::Value = v.into();
+                let clause = format!("{field} ~ {}", self.consume_placeholder());
+                query::CompiledClause::new(clause, vec![value])
+            }
         };
 
         Ok(r)
@@ -290,8 +356,44 @@ mod internal {
 
                     query::CompiledClause::new(clause, vec![min, max])
                 }
-                query::Op::In(_) => return Err(query::Error::unsupported_op(field.to_string())),
-                query::Op::Match(_) => return Err(query::Error::unsupported_op(field.to_string())),
+                query::Op::In(items) => {
+                    if items.is_empty() {
+                        return Ok(query::CompiledClause::empty());
+                    }
+
+                    let values: Vec<query::Value> = items.into_iter().map(Into::into).collect();
+                    let field = self.fmt_value(field, &values[0]);
+                    let placeholders: Vec<String> =
+                        values.iter().map(|_| self.consume_placeholder()).collect();
+
+                    let clause = format!("{field} IN ({})", placeholders.join(", "));
+
+                    query::CompiledClause::new(clause, values)
+                }
+                query::Op::Match(v) => {
+                    let value: query::Value = v.into();
+                    let query::Value::Text(text) = value else {
+                        return Err(query::Error::unsupported_op(field.to_string()));
+                    };
+
+                    // Substring containment, not a glob: escape any literal
+                    // `%`, `_` or `\` already in `text` (so they match
+                    // themselves rather than being read as LIKE wildcards),
+                    // then wrap in `%...%`. Deliberately not
+                    // `glob_to_sql_like`, which would also treat a literal
+                    // `*`/`?` in `text` as a wildcard.
+                    let like_pattern = format!("%{}%", query::escape_like_literal(&text));
+                    let clause = format!(
+                        "{} LIKE {} ESCAPE '\\'",
+                        self.fmt_value(field, &query::Value::Text(text.clone())),
+                        self.consume_placeholder()
+                    );
+                    query::CompiledClause::new(clause, vec![query::Value::Text(like_pattern)])
+                }
+                query::Op::Nin(_) => return Err(query::Error::unsupported_op(field.to_string())),
+                query::Op::Like(_) => return Err(query::Error::unsupported_op(field.to_string())),
+                query::Op::Ilike(_) => return Err(query::Error::unsupported_op(field.to_string())),
+                query::Op::Regex(_) => return Err(query::Error::unsupported_op(field.to_string())),
             };
 
             Ok(r)
@@ -321,7 +423,7 @@ mod tests {
         let mut fmt = SqlQueryCompiler::new();
 
         let qr = ClausesCompiler::new()
-            .expr("my-field", Op::Gt("topic-name".to_string()), &mut fmt)
+            .expr("my-field", Op::Gt(query::Value::Boolean(true)), &mut fmt)
             .compile();
 
         assert!(qr.is_err());
@@ -335,7 +437,7 @@ mod tests {
         let qr = ClausesCompiler::new()
             .expr(
                 "topic.topic_name",
-                Op::Match("my-topic".to_string()),
+                Op::Match("*my-topic*".to_string()),
                 &mut fmt,
             )
             .expr(
@@ -351,7 +453,7 @@ mod tests {
         if let Some(idx) = qr
             .clauses
             .iter()
-            .position(|c| c == r#"topic.topic_name LIKE $1"#)
+            .position(|c| c == r#"topic.topic_name LIKE $1 ESCAPE '\'"#)
         {
             assert_eq!(qr.values[idx], query::Value::Text("%my-topic%".to_string()));
         } else {
@@ -372,6 +474,122 @@ mod tests {
         }
     }
 
+    #[test]
+    fn match_regex_uses_posix_operator() {
+        let mut fmt = SqlQueryCompiler::new();
+
+        let qr = ClausesCompiler::new()
+            .expr(
+                "topic.topic_name",
+                Op::Match("^image\\.\\d+$".to_string()),
+                &mut fmt,
+            )
+            .compile()
+            .expect("problem building query");
+
+        assert_eq!(qr.clauses[0], "topic.topic_name ~ $1");
+        assert_eq!(
+            qr.values[0],
+            query::Value::Text("^image\\.\\d+$".to_string())
+        );
+    }
+
+    #[test]
+    fn match_uses_fts_when_opted_in() {
+        let mut fmt = SqlQueryCompiler::new().with_fts("english");
+
+        let qr = ClausesCompiler::new()
+            .expr(
+                "topic.topic_name",
+                Op::Match("lidar scan".to_string()),
+                &mut fmt,
+            )
+            .compile()
+            .expect("problem building query");
+
+        assert_eq!(
+            qr.clauses[0],
+            "to_tsvector('english', topic.topic_name) @@ plainto_tsquery('english', $1)"
+        );
+        assert_eq!(qr.values[0], query::Value::Text("lidar scan".to_string()));
+    }
+
+    #[test]
+    fn nin_compiles_to_not_in() {
+        let mut fmt = SqlQueryCompiler::new();
+
+        let qr = ClausesCompiler::new()
+            .expr(
+                "topic.ontology_tag",
+                Op::Nin(vec!["image".to_string(), "lidar".to_string()]),
+                &mut fmt,
+            )
+            .compile()
+            .expect("problem building query");
+
+        assert_eq!(qr.clauses[0], "topic.ontology_tag NOT IN ($1, $2)");
+    }
+
+    #[test]
+    fn like_and_ilike_pass_the_pattern_through_unchanged() {
+        let mut fmt = SqlQueryCompiler::new();
+
+        let qr = ClausesCompiler::new()
+            .expr(
+                "topic.topic_name",
+                Op::Like("%my-topic%".to_string()),
+                &mut fmt,
+            )
+            .expr(
+                "topic.ontology_tag",
+                Op::Ilike("IMAGE%".to_string()),
+                &mut fmt,
+            )
+            .compile()
+            .expect("problem building query");
+
+        assert_eq!(qr.clauses[0], r#"topic.topic_name LIKE $1 ESCAPE '\'"#);
+        assert_eq!(qr.values[0], query::Value::Text("%my-topic%".to_string()));
+
+        assert_eq!(qr.clauses[1], r#"topic.ontology_tag ILIKE $2 ESCAPE '\'"#);
+        assert_eq!(qr.values[1], query::Value::Text("IMAGE%".to_string()));
+    }
+
+    #[test]
+    fn regex_compiles_to_posix_operator() {
+        let mut fmt = SqlQueryCompiler::new();
+
+        let qr = ClausesCompiler::new()
+            .expr(
+                "topic.topic_name",
+                Op::Regex(r"image\.\d+".to_string()),
+                &mut fmt,
+            )
+            .compile()
+            .expect("problem building query");
+
+        assert_eq!(qr.clauses[0], "topic.topic_name ~ $1");
+        assert_eq!(qr.values[0], query::Value::Text(r"image\.\d+".to_string()));
+    }
+
+    #[test]
+    fn text_range_compiles_lexicographically() {
+        let mut fmt = SqlQueryCompiler::new();
+
+        let range =
+            query::Range::try_new("image.".to_string(), "image/".to_string()).expect("range");
+
+        let qr = ClausesCompiler::new()
+            .expr("topic.topic_name", Op::Between(range), &mut fmt)
+            .compile()
+            .expect("problem building query");
+
+        assert_eq!(
+            qr.clauses[0],
+            "(topic.topic_name >= $1) AND (topic.topic_name <= $2)"
+        );
+    }
+
     #[test]
     fn user_metadata() {
         let mdata: HashMap<query::OntologyField, query::Op<query::Value>> = HashMap::from([
@@ -424,4 +642,147 @@ mod tests {
             panic!("match not found");
         }
     }
+
+    #[test]
+    fn user_metadata_in_casts_by_the_bound_value_type() {
+        let mdata: HashMap<query::OntologyField, query::Op<query::Value>> = HashMap::from([(
+            query::OntologyField::try_new("my.custom.field".into()).unwrap(),
+            query::Op::In(vec![query::Value::Float(1.0), query::Value::Float(2.0)]),
+        )]);
+        let kv = query::OntologyFilter::new(mdata);
+
+        let mut fmt = JsonQueryCompiler::new();
+
+        let qr = ClausesCompiler::new()
+            .filter(
+                kv.into_iterator(),
+                fmt.with_field_and_placeholder("topic.user_metadata".into(), 1),
+            )
+            .compile()
+            .expect("problem building query");
+
+        assert_eq!(
+            qr.clauses[0],
+            "(topic.user_metadata #>> '{my,custom,field}')::numeric IN ($1, $2)"
+        );
+        assert_eq!(qr.values[0], query::Value::Float(1.0));
+        assert_eq!(qr.values[1], query::Value::Float(2.0));
+    }
+
+    #[test]
+    fn user_metadata_in_empty_is_dropped() {
+        let mdata: HashMap<query::OntologyField, query::Op<query::Value>> = HashMap::from([(
+            query::OntologyField::try_new("my.custom.field".into()).unwrap(),
+            query::Op::In(vec![]),
+        )]);
+        let kv = query::OntologyFilter::new(mdata);
+
+        let mut fmt = JsonQueryCompiler::new();
+
+        let qr = ClausesCompiler::new()
+            .filter(
+                kv.into_iterator(),
+                fmt.with_field_and_placeholder("topic.user_metadata".into(), 1),
+            )
+            .compile()
+            .expect("problem building query");
+
+        assert!(qr.is_unfiltered());
+    }
+
+    #[test]
+    fn user_metadata_match_compiles_to_like_with_wildcards() {
+        let mdata: HashMap<query::OntologyField, query::Op<query::Value>> = HashMap::from([(
+            query::OntologyField::try_new("my.custom.field".into()).unwrap(),
+            query::Op::Match("lidar".to_string()),
+        )]);
+        let kv = query::OntologyFilter::new(mdata);
+
+        let mut fmt = JsonQueryCompiler::new();
+
+        let qr = ClausesCompiler::new()
+            .filter(
+                kv.into_iterator(),
+                fmt.with_field_and_placeholder("topic.user_metadata".into(), 1),
+            )
+            .compile()
+            .expect("problem building query");
+
+        assert_eq!(
+            qr.clauses[0],
+            "topic.user_metadata #>> '{my,custom,field}' LIKE $1 ESCAPE '\\'"
+        );
+        assert_eq!(qr.values[0], query::Value::Text("%lidar%".to_string()));
+    }
+
+    #[test]
+    fn user_metadata_match_escapes_literal_percent_and_underscore() {
+        let mdata: HashMap<query::OntologyField, query::Op<query::Value>> = HashMap::from([(
+            query::OntologyField::try_new("my.custom.field".into()).unwrap(),
+            query::Op::Match("a_b".to_string()),
+        )]);
+        let kv = query::OntologyFilter::new(mdata);
+
+        let mut fmt = JsonQueryCompiler::new();
+
+        let qr = ClausesCompiler::new()
+            .filter(
+                kv.into_iterator(),
+                fmt.with_field_and_placeholder("topic.user_metadata".into(), 1),
+            )
+            .compile()
+            .expect("problem building query");
+
+        // `_` must be escaped so the pattern only matches a literal "a_b",
+        // not e.g. "axb" -- an unescaped `_` is a LIKE wildcard for any
+        // single character.
+        assert_eq!(qr.values[0], query::Value::Text("%a\\_b%".to_string()));
+
+        let mdata: HashMap<query::OntologyField, query::Op<query::Value>> = HashMap::from([(
+            query::OntologyField::try_new("my.custom.field".into()).unwrap(),
+            query::Op::Match("50%".to_string()),
+        )]);
+        let kv = query::OntologyFilter::new(mdata);
+
+        let mut fmt = JsonQueryCompiler::new();
+
+        let qr = ClausesCompiler::new()
+            .filter(
+                kv.into_iterator(),
+                fmt.with_field_and_placeholder("topic.user_metadata".into(), 1),
+            )
+            .compile()
+            .expect("problem building query");
+
+        // same for a literal `%`: must be escaped so it matches "50%"
+        // itself, not "any run of characters after 50".
+        assert_eq!(qr.values[0], query::Value::Text("%50\\%%".to_string()));
+    }
+
+    #[test]
+    fn user_metadata_match_treats_star_and_question_mark_as_literal() {
+        let mdata: HashMap<query::OntologyField, query::Op<query::Value>> = HashMap::from([(
+            query::OntologyField::try_new("my.custom.field".into()).unwrap(),
+            query::Op::Match("50*off?".to_string()),
+        )]);
+        let kv = query::OntologyFilter::new(mdata);
+
+        let mut fmt = JsonQueryCompiler::new();
+
+        let qr = ClausesCompiler::new()
+            .filter(
+                kv.into_iterator(),
+                fmt.with_field_and_placeholder("topic.user_metadata".into(), 1),
+            )
+            .compile()
+            .expect("problem building query");
+
+        // `Op::Match` on JSONB is plain substring containment, not a glob:
+        // a literal `*`/`?` in the search text must not turn into a LIKE
+        // wildcard (unlike the glob-based Match arm used elsewhere).
+        assert_eq!(
+            qr.values[0],
+            query::Value::Text("%50*off?%".to_string())
+        );
+    }
 }
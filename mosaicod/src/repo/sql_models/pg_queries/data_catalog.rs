@@ -1,8 +1,11 @@
+use std::collections::HashMap;
+
 use sqlx::{Row, postgres::PgRow};
 
 use crate::{
     query,
     repo::{self, sql_models},
+    types,
 };
 
 pub async fn column_get_or_create(
@@ -12,40 +15,258 @@ pub async fn column_get_or_create(
 ) -> Result<sql_models::Column, repo::Error> {
     // The UPDATE part of the query is a no-op update: it forces the query to return the existing row
     // from the COLUMN table without changing any data.
-    let res = sqlx::query_as!(
-        sql_models::Column,
-        r#"INSERT INTO column_t (column_name, ontology_tag)
-        VALUES ($1, $2)
-        ON CONFLICT (column_name, ontology_tag)
-        DO UPDATE SET
-            column_name = EXCLUDED.column_name  -- no-op
-        RETURNING *"#,
-        column_name,
-        ontology_tag,
+    repo::core::instrument(
+        "column_get_or_create",
+        format!("column_name={column_name:?}, ontology_tag={ontology_tag:?}"),
+        sqlx::query_as!(
+            sql_models::Column,
+            r#"INSERT INTO column_t (column_name, ontology_tag)
+            VALUES ($1, $2)
+            ON CONFLICT (column_name, ontology_tag)
+            DO UPDATE SET
+                column_name = EXCLUDED.column_name  -- no-op
+            RETURNING *"#,
+            column_name,
+            ontology_tag,
+        )
+        .fetch_one(exec.as_exec()),
     )
-    .fetch_one(exec.as_exec())
-    .await?;
-    Ok(res)
+    .await
 }
 
 pub async fn chunk_create(
     exec: &mut impl repo::AsExec,
     chunk: &sql_models::Chunk,
 ) -> Result<sql_models::Chunk, repo::Error> {
+    repo::core::instrument(
+        "chunk_create",
+        format!("topic_id={}", chunk.topic_id),
+        sqlx::query_as!(
+            sql_models::Chunk,
+            r#"INSERT INTO chunk_t(chunk_uuid, topic_id, data_file, content_digest, content_digest_algo)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *"#,
+            chunk.chunk_uuid,
+            chunk.topic_id,
+            chunk.data_file,
+            chunk.content_digest,
+            chunk.content_digest_algo,
+        )
+        .fetch_one(exec.as_exec()),
+    )
+    .await
+}
+
+/// Returns up to `limit` chunks ordered by `chunk_id`, starting after
+/// `after_chunk_id`. `0` fetches the first page.
+///
+/// Used by [`repo::FacadeRepair`] to walk the full chunk catalog in bounded
+/// pages instead of holding one long transaction/result set for a scan over
+/// a potentially large repository.
+pub async fn chunk_find_page(
+    exec: &mut impl repo::AsExec,
+    after_chunk_id: i32,
+    limit: i64,
+) -> Result<Vec<sql_models::Chunk>, repo::Error> {
     let res = sqlx::query_as!(
         sql_models::Chunk,
-        r#"INSERT INTO chunk_t(chunk_uuid, topic_id, data_file)
-        VALUES ($1, $2, $3)
+        r#"SELECT * FROM chunk_t
+        WHERE chunk_id > $1
+        ORDER BY chunk_id
+        LIMIT $2"#,
+        after_chunk_id,
+        limit,
+    )
+    .fetch_all(exec.as_exec())
+    .await?;
+    Ok(res)
+}
+
+/// Returns every chunk belonging to `topic_id`, ordered by `chunk_id` --
+/// i.e. upload order, which is also leaf order in the topic's
+/// [`crate::types::MerkleTree`].
+pub async fn chunk_find_by_topic_ordered(
+    exec: &mut impl repo::AsExec,
+    topic_id: i32,
+) -> Result<Vec<sql_models::Chunk>, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::Chunk,
+        r#"SELECT * FROM chunk_t
+        WHERE topic_id = $1
+        ORDER BY chunk_id"#,
+        topic_id,
+    )
+    .fetch_all(exec.as_exec())
+    .await?;
+    Ok(res)
+}
+
+/// Looks up a chunk of `topic_id` by its content digest, so an identical
+/// chunk payload can be recognized and its data file reused instead of
+/// physically rewritten (see `rw::ChunkedWriter::on_chunk_dedup_check`).
+///
+/// Scoped to a single topic rather than the whole catalog: a topic's data
+/// files are removed wholesale when the topic is deleted, so sharing a file
+/// across topics would risk one topic's deletion pulling a file out from
+/// under another topic's still-live `Chunk` row.
+pub async fn chunk_find_by_digest(
+    exec: &mut impl repo::AsExec,
+    topic_id: i32,
+    content_digest: &[u8],
+) -> Result<Option<sql_models::Chunk>, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::Chunk,
+        r#"SELECT * FROM chunk_t
+        WHERE topic_id = $1 AND content_digest = $2
+        LIMIT 1"#,
+        topic_id,
+        content_digest,
+    )
+    .fetch_optional(exec.as_exec())
+    .await?;
+    Ok(res)
+}
+
+/// Deletes a chunk record (and its column-chunk stats, via `ON DELETE
+/// CASCADE`) by id, **bypassing any check that its data file still
+/// exists**.
+///
+/// This function is marked `unsafe` because it permanently removes the
+/// catalog's only record of a chunk. It's intended for
+/// [`repo::FacadeRepair`], which calls it only after confirming the chunk's
+/// data file is actually missing from the store.
+pub async unsafe fn chunk_delete(
+    exec: &mut impl repo::AsExec,
+    chunk_id: i32,
+) -> Result<(), repo::Error> {
+    sqlx::query!("DELETE FROM chunk_t WHERE chunk_id=$1", chunk_id)
+        .execute(exec.as_exec())
+        .await?;
+    Ok(())
+}
+
+/// Records that `data_file` (identified by `content_digest`/`content_digest_algo`)
+/// now backs one more [`sql_models::Chunk`] row, creating the [`sql_models::ChunkRef`]
+/// with `refcount = 1` on its first reference.
+///
+/// Called alongside every [`chunk_create`] (see `repo::FacadeChunk::create`),
+/// so `refcount` always tracks how many live `chunk_t` rows share this
+/// digest, regardless of which topic they belong to.
+///
+/// Note the conflict branch only bumps `refcount` and never touches
+/// `data_file`: the first insert for a digest wins that column forever.
+/// That's only safe as long as two `chunk_t` rows sharing a digest
+/// genuinely share one physical file. Dedup (see
+/// `repo::FacadeTopic::writer_on`'s `on_chunk_dedup_check`) is scoped to a
+/// single topic's own chunks for exactly this reason -- don't widen it to
+/// reuse another topic's `chunk_ref_t` entry without also making this
+/// upsert reconcile `data_file` (or making topic/sequence deletion
+/// refcount-aware first), or this row silently starts lying about which
+/// file a digest actually lives in.
+pub async fn chunk_ref_upsert(
+    exec: &mut impl repo::AsExec,
+    content_digest: &[u8],
+    content_digest_algo: &str,
+    data_file: &str,
+) -> Result<sql_models::ChunkRef, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::ChunkRef,
+        r#"INSERT INTO chunk_ref_t (content_digest, content_digest_algo, data_file, refcount)
+        VALUES ($1, $2, $3, 1)
+        ON CONFLICT (content_digest, content_digest_algo)
+        DO UPDATE SET refcount = chunk_ref_t.refcount + 1
         RETURNING *"#,
-        chunk.chunk_uuid,
-        chunk.topic_id,
-        chunk.data_file,
+        content_digest,
+        content_digest_algo,
+        data_file,
     )
     .fetch_one(exec.as_exec())
     .await?;
     Ok(res)
 }
 
+/// Looks up a chunk's reference entry by content digest, independent of
+/// which topic(s) reference it.
+pub async fn chunk_ref_find_by_digest(
+    exec: &mut impl repo::AsExec,
+    content_digest_algo: &str,
+    content_digest: &[u8],
+) -> Result<Option<sql_models::ChunkRef>, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::ChunkRef,
+        r#"SELECT * FROM chunk_ref_t
+        WHERE content_digest_algo = $1 AND content_digest = $2
+        LIMIT 1"#,
+        content_digest_algo,
+        content_digest,
+    )
+    .fetch_optional(exec.as_exec())
+    .await?;
+    Ok(res)
+}
+
+/// Returns up to `limit` chunk-ref entries ordered by `chunk_ref_id`,
+/// starting after `after_chunk_ref_id`. `0` fetches the first page.
+///
+/// Used by [`repo::FacadeRepair`] to walk the full ref ledger in bounded
+/// pages, the same way [`chunk_find_page`] walks `chunk_t`.
+pub async fn chunk_ref_find_page(
+    exec: &mut impl repo::AsExec,
+    after_chunk_ref_id: i32,
+    limit: i64,
+) -> Result<Vec<sql_models::ChunkRef>, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::ChunkRef,
+        r#"SELECT * FROM chunk_ref_t
+        WHERE chunk_ref_id > $1
+        ORDER BY chunk_ref_id
+        LIMIT $2"#,
+        after_chunk_ref_id,
+        limit,
+    )
+    .fetch_all(exec.as_exec())
+    .await?;
+    Ok(res)
+}
+
+/// Counts how many live `chunk_t` rows still reference `content_digest`,
+/// used by [`repo::FacadeRepair`] to tell a [`sql_models::ChunkRef`]'s
+/// recorded `refcount` apart from drift against reality.
+pub async fn chunk_count_by_digest(
+    exec: &mut impl repo::AsExec,
+    content_digest_algo: &str,
+    content_digest: &[u8],
+) -> Result<i64, repo::Error> {
+    let res = sqlx::query!(
+        r#"SELECT COUNT(*) AS "count!" FROM chunk_t
+        WHERE content_digest_algo = $1 AND content_digest = $2"#,
+        content_digest_algo,
+        content_digest,
+    )
+    .fetch_one(exec.as_exec())
+    .await?;
+    Ok(res.count)
+}
+
+/// Deletes a [`sql_models::ChunkRef`] row by id, **bypassing any check that
+/// its refcount is actually zero**.
+///
+/// This function is marked `unsafe` for the same reason as [`chunk_delete`]:
+/// it's intended for [`repo::FacadeRepair`], which calls it only after
+/// recomputing the live reference count itself.
+pub async unsafe fn chunk_ref_delete(
+    exec: &mut impl repo::AsExec,
+    chunk_ref_id: i32,
+) -> Result<(), repo::Error> {
+    sqlx::query!(
+        "DELETE FROM chunk_ref_t WHERE chunk_ref_id=$1",
+        chunk_ref_id
+    )
+    .execute(exec.as_exec())
+    .await?;
+    Ok(())
+}
+
 pub async fn column_chunk_literal_create(
     exec: &mut impl repo::AsExec,
     val: &sql_models::ColumnChunkLiteral,
@@ -55,15 +276,16 @@ pub async fn column_chunk_literal_create(
         r#"INSERT INTO column_chunk_literal_t(
             column_id, chunk_id,
             min_value, max_value,
-            has_null 
+            has_null, has_non_null
         )
-        VALUES ($1, $2, $3, $4, $5)
+        VALUES ($1, $2, $3, $4, $5, $6)
         RETURNING *"#,
         val.column_id,
         val.chunk_id,
         val.min_value,
         val.max_value,
         val.has_null,
+        val.has_non_null,
     )
     .fetch_one(exec.as_exec())
     .await?;
@@ -79,9 +301,9 @@ pub async fn column_chunk_numeric_create(
         r#"INSERT INTO column_chunk_numeric_t(
             column_id, chunk_id,
             min_value, max_value,
-            has_null, has_nan
+            has_null, has_nan, has_non_null
         )
-        VALUES ($1, $2, $3, $4, $5, $6)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         RETURNING *"#,
         val.column_id,
         val.chunk_id,
@@ -89,19 +311,160 @@ pub async fn column_chunk_numeric_create(
         val.max_value,
         val.has_null,
         val.has_nan,
+        val.has_non_null,
     )
     .fetch_one(exec.as_exec())
     .await?;
     Ok(res)
 }
 
+pub async fn column_chunk_bloom_create(
+    exec: &mut impl repo::AsExec,
+    val: &sql_models::ColumnChunkBloom,
+) -> Result<sql_models::ColumnChunkBloom, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::ColumnChunkBloom,
+        r#"INSERT INTO column_chunk_bloom_t(
+            column_id, chunk_id,
+            bloom_filter
+        )
+        VALUES ($1, $2, $3)
+        RETURNING *"#,
+        val.column_id,
+        val.chunk_id,
+        val.bloom_filter,
+    )
+    .fetch_one(exec.as_exec())
+    .await?;
+    Ok(res)
+}
+
+/// Fetches the per-chunk bloom filter bytes recorded for `field` across
+/// `chunk_ids`, keyed by `chunk_id`. Chunks with no matching row (e.g.
+/// ingested before bloom filters existed for this column type) are simply
+/// absent from the result.
+pub async fn column_chunk_bloom_fetch(
+    exec: &mut impl repo::AsExec,
+    field: &str,
+    chunk_ids: &[i32],
+) -> Result<HashMap<i32, Vec<u8>>, repo::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT __bloom__.chunk_id, __bloom__.bloom_filter
+        FROM column_chunk_bloom_t __bloom__
+        JOIN column_t __column__ USING(column_id)
+        WHERE (__column__.ontology_tag || '.' || __column__.column_name) = $1
+          AND __bloom__.chunk_id = ANY($2)"#,
+        field,
+        chunk_ids,
+    )
+    .fetch_all(exec.as_exec())
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| (r.chunk_id, r.bloom_filter))
+        .collect())
+}
+
+pub async fn column_chunk_dictionary_create(
+    exec: &mut impl repo::AsExec,
+    val: &sql_models::ColumnChunkDictionary,
+) -> Result<sql_models::ColumnChunkDictionary, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::ColumnChunkDictionary,
+        r#"INSERT INTO column_chunk_dictionary_t(
+            column_id, chunk_id,
+            values
+        )
+        VALUES ($1, $2, $3)
+        RETURNING *"#,
+        val.column_id,
+        val.chunk_id,
+        &val.values,
+    )
+    .fetch_one(exec.as_exec())
+    .await?;
+    Ok(res)
+}
+
+pub use super::{PlannedClause, QueryPlan};
+
+/// Post-execution pruning outcome for a compiled chunk query: how many
+/// chunks existed in scope versus how many the compiled query actually
+/// returned as candidates, counted against `chunk_t`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruningReport {
+    pub chunks_total: i64,
+    pub chunks_scanned: i64,
+    pub chunks_pruned: i64,
+}
+
+impl PruningReport {
+    fn new(chunks_total: i64, chunks_scanned: i64) -> Self {
+        Self {
+            chunks_total,
+            chunks_scanned,
+            chunks_pruned: chunks_total - chunks_scanned,
+        }
+    }
+}
+
+/// Total number of chunks in scope, optionally restricted to `on_topics` --
+/// the denominator a [`PruningReport`] measures pruning against.
+async fn chunks_total_in_scope(
+    exec: &mut impl repo::AsExec,
+    on_topics: Option<&Vec<sql_models::TopicRecord>>,
+) -> Result<i64, repo::Error> {
+    let ids: Vec<i64> = on_topics
+        .map(|topics| topics.iter().map(|t| t.topic_id as i64).collect())
+        .unwrap_or_default();
+
+    let count = if ids.is_empty() {
+        sqlx::query_scalar!("SELECT COUNT(*) FROM chunk_t")
+            .fetch_one(exec.as_exec())
+            .await?
+    } else {
+        sqlx::query_scalar!("SELECT COUNT(*) FROM chunk_t WHERE topic_id = ANY($1)", &ids)
+            .fetch_one(exec.as_exec())
+            .await?
+    };
+
+    Ok(count.unwrap_or(0))
+}
+
+/// Like [`chunks_from_filters`], but also returns a [`QueryPlan`] (the
+/// static pre-execution pruning summary) and a [`PruningReport`] (the
+/// actual chunk counts before/after running the compiled query) -- the
+/// visibility needed to tell whether a filter's statistics are actually
+/// helping, analogous to a database's `EXPLAIN` tooling.
+pub async fn explain_chunks_from_filters(
+    exec: &mut impl repo::AsExec,
+    filter: query::OntologyExpr,
+    on_topics: Option<&Vec<sql_models::TopicRecord>>,
+) -> Result<(Vec<sql_models::Chunk>, QueryPlan, PruningReport), repo::Error> {
+    let ids: Vec<i64> = on_topics
+        .map(|topics| topics.iter().map(|t| t.topic_id as i64).collect())
+        .unwrap_or_default();
+    let (_, _, plan) = super::ChunkQueryBuilder::explain(filter.clone(), ids)?;
+
+    let chunks_total = chunks_total_in_scope(exec, on_topics).await?;
+    let chunks = chunks_from_filters(exec, filter, on_topics).await?;
+    let report = PruningReport::new(chunks_total, chunks.len() as i64);
+
+    Ok((chunks, plan, report))
+}
+
 /// Returns the list of chunks matching the provided `filter` criteria.
 /// Optionally the query can be fitlered across a list of topics (`on_topics`).
 pub async fn chunks_from_filters(
     exec: &mut impl repo::AsExec,
-    filter: query::OntologyFilter,
+    filter: query::OntologyExpr,
     on_topics: Option<&Vec<sql_models::TopicRecord>>, // (cabba) TODO: pas only topic names or ids?
 ) -> Result<Vec<sql_models::Chunk>, repo::Error> {
+    // `ChunkQueryBuilder::build` consumes `filter`, so any bloom-prunable
+    // equality checks need to be read out of it up front.
+    let bloom_checks = bloom_refinable_checks(&filter);
+
     // Collect topic ids, if any
     let ids: Vec<i64> = if let Some(topics) = on_topics {
         topics.iter().map(|t| t.topic_id as i64).collect()
@@ -111,7 +474,7 @@ pub async fn chunks_from_filters(
 
     let (query, values) = super::ChunkQueryBuilder::build(filter, ids)?;
 
-    dbg!(&query);
+    let value_count = values.len();
 
     let mut r = sqlx::query(&query);
 
@@ -125,8 +488,91 @@ pub async fn chunks_from_filters(
         }
     }
 
-    let r = r.map(cast_chunk_data).fetch_all(exec.as_exec()).await?;
-    r.into_iter().collect()
+    let r = repo::core::instrument(
+        "chunks_from_filters",
+        format!("{value_count} bound value(s)"),
+        r.map(cast_chunk_data).fetch_all(exec.as_exec()),
+    )
+    .await?;
+    let chunks: Vec<sql_models::Chunk> = r.into_iter().collect::<Result<_, _>>()?;
+
+    refine_with_bloom(exec, chunks, &bloom_checks).await
+}
+
+/// Collects the `Eq`/`In` checks of `filter` that are safe to additionally
+/// prune with a loaded bloom filter.
+///
+/// Zone-map pruning (min/max) is nearly useless for `Eq`/`In` on
+/// high-cardinality columns, so a bloom filter check is applied as an extra
+/// pass after the zone-map query runs. This is only done for a bare
+/// [`query::OntologyExpr::Leaf`] -- its fields are implicitly `AND`ed
+/// together, so narrowing its own candidate set is always safe. A leaf
+/// nested under `Or`/`Not` can't be narrowed this way without re-deriving
+/// per-branch candidate sets, so those fall back to zone-map-only pruning
+/// (still correct, just less aggressive).
+fn bloom_refinable_checks(filter: &query::OntologyExpr) -> Vec<(String, Vec<query::Value>)> {
+    let query::OntologyExpr::Leaf(ontology_filter) = filter else {
+        return Vec::new();
+    };
+
+    ontology_filter
+        .iter()
+        .filter_map(|(field, op)| match op {
+            query::Op::Eq(v) => Some((field.value().to_string(), vec![v.clone()])),
+            query::Op::In(values) => Some((field.value().to_string(), values.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Narrows `chunks` using the loaded per-chunk bloom filters for each
+/// `(field, values)` check, dropping chunks whose filter reports every
+/// value definitely absent. False positives from the bloom filter are
+/// expected and harmless here -- the chunk is simply read and its rows
+/// filtered exactly further downstream.
+async fn refine_with_bloom(
+    exec: &mut impl repo::AsExec,
+    mut chunks: Vec<sql_models::Chunk>,
+    checks: &[(String, Vec<query::Value>)],
+) -> Result<Vec<sql_models::Chunk>, repo::Error> {
+    for (field, values) in checks {
+        if chunks.is_empty() {
+            break;
+        }
+
+        let chunk_ids: Vec<i32> = chunks.iter().map(|c| c.chunk_id).collect();
+        let blooms = column_chunk_bloom_fetch(exec, field, &chunk_ids).await?;
+
+        chunks.retain(|chunk| {
+            let Some(bytes) = blooms.get(&chunk.chunk_id) else {
+                // No bloom filter on record for this column/chunk -- keep the
+                // chunk rather than risk a false negative.
+                return true;
+            };
+
+            let Some(bloom) = types::BloomFilter::from_bytes(bytes) else {
+                return true;
+            };
+
+            values
+                .iter()
+                .any(|v| bloom.maybe_contains(bloom_probe_bytes(v)))
+        });
+    }
+
+    Ok(chunks)
+}
+
+/// Byte representation of a query value for bloom membership testing,
+/// matching how [`types::NumericStats::eval`]/[`types::TextStats::eval`]
+/// feed values into the filter at write time.
+fn bloom_probe_bytes(v: &query::Value) -> Vec<u8> {
+    match v {
+        query::Value::Text(s) => s.clone().into_bytes(),
+        query::Value::Integer(i) => (*i as f64).to_le_bytes().to_vec(),
+        query::Value::Float(f) => f.to_le_bytes().to_vec(),
+        query::Value::Boolean(b) => (if *b { 1.0f64 } else { 0.0 }).to_le_bytes().to_vec(),
+    }
 }
 
 fn cast_chunk_data(row: PgRow) -> Result<sql_models::Chunk, repo::Error> {
@@ -135,5 +581,63 @@ fn cast_chunk_data(row: PgRow) -> Result<sql_models::Chunk, repo::Error> {
         chunk_uuid: row.try_get("chunk_uuid")?,
         topic_id: row.try_get("topic_id")?,
         data_file: row.try_get("data_file")?,
+        content_digest: row.try_get("content_digest")?,
+        content_digest_algo: row.try_get("content_digest_algo")?,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap as Map;
+
+    #[test]
+    fn bloom_refinable_checks_collects_eq_and_in_from_a_leaf() {
+        let mut fields = Map::new();
+        fields.insert(
+            query::OntologyField::try_new("topic.label".to_string()).unwrap(),
+            query::Op::Eq(query::Value::Text("a".to_string())),
+        );
+        let filter = query::OntologyExpr::Leaf(query::OntologyFilter::new(fields));
+
+        let checks = bloom_refinable_checks(&filter);
+
+        assert_eq!(
+            checks,
+            vec![(
+                "topic.label".to_string(),
+                vec![query::Value::Text("a".to_string())]
+            )]
+        );
+    }
+
+    #[test]
+    fn bloom_refinable_checks_ignores_non_leaf_expressions() {
+        let mut fields = Map::new();
+        fields.insert(
+            query::OntologyField::try_new("topic.label".to_string()).unwrap(),
+            query::Op::Eq(query::Value::Text("a".to_string())),
+        );
+        let leaf = query::OntologyExpr::Leaf(query::OntologyFilter::new(fields));
+        let filter = query::OntologyExpr::Not(Box::new(leaf));
+
+        assert!(bloom_refinable_checks(&filter).is_empty());
+    }
+
+    #[test]
+    fn bloom_probe_bytes_matches_numeric_stats_insertion() {
+        let mut stats = types::NumericStats::new();
+        stats.eval(&Some(42.0));
+
+        assert!(
+            stats
+                .bloom
+                .maybe_contains(bloom_probe_bytes(&query::Value::Integer(42)))
+        );
+        assert!(
+            stats
+                .bloom
+                .maybe_contains(bloom_probe_bytes(&query::Value::Float(42.0)))
+        );
+    }
+}
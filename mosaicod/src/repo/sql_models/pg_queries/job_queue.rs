@@ -0,0 +1,161 @@
+use log::trace;
+
+use crate::repo::{self, sql_models};
+
+/// Enqueues a new job on `queue`.
+///
+/// The job is picked up by whichever worker next calls [`job_claim_next`]
+/// for the same `queue`, so this can safely be called from within the same
+/// transaction as the work that triggered it.
+pub async fn job_enqueue(
+    exe: &mut impl repo::AsExec,
+    queue: &str,
+    job: &serde_json::Value,
+) -> Result<sql_models::JobQueueRow, repo::Error> {
+    trace!("enqueueing job on `{}`: {}", queue, job);
+    let res = sqlx::query_as!(
+        sql_models::JobQueueRow,
+        r#"
+            INSERT INTO job_queue_t
+                (queue, job, status)
+            VALUES
+                ($1, $2, $3)
+            RETURNING
+                job_queue_id, queue, job, status, retries, heartbeat, created
+    "#,
+        queue,
+        job,
+        sql_models::JobStatus::New.to_string(),
+    )
+    .fetch_one(exe.as_exec())
+    .await?;
+    Ok(res)
+}
+
+/// Atomically claims the oldest still-`new` job on `queue` that hasn't
+/// exhausted `max_retries` and isn't still waiting out its backoff window,
+/// marking it `running` with a fresh heartbeat so the sweeper won't reclaim
+/// it while this worker is still working on it.
+///
+/// A job whose `retries` has reached `max_retries` is left in place
+/// forever (it never matches this query again) rather than deleted, acting
+/// as a simple dead letter without a dedicated status.
+///
+/// `FOR UPDATE SKIP LOCKED` lets multiple workers poll the same queue
+/// concurrently: a row already claimed (locked) by another worker is simply
+/// skipped rather than waited on.
+pub async fn job_claim_next(
+    exe: &mut impl repo::AsExec,
+    queue: &str,
+    max_retries: i32,
+) -> Result<Option<sql_models::JobQueueRow>, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::JobQueueRow,
+        r#"
+            UPDATE job_queue_t
+            SET status = $1, heartbeat = now()
+            WHERE job_queue_id = (
+                SELECT job_queue_id FROM job_queue_t
+                WHERE status = $2
+                  AND queue = $3
+                  AND retries < $4
+                  AND (
+                      heartbeat IS NULL
+                      OR now() >= heartbeat + (LEAST(power(2, retries), 300) * interval '1 second')
+                  )
+                ORDER BY created
+                FOR UPDATE SKIP LOCKED
+                LIMIT 1
+            )
+            RETURNING job_queue_id, queue, job, status, retries, heartbeat, created
+    "#,
+        sql_models::JobStatus::Running.to_string(),
+        sql_models::JobStatus::New.to_string(),
+        queue,
+        max_retries,
+    )
+    .fetch_optional(exe.as_exec())
+    .await?;
+    Ok(res)
+}
+
+/// Looks up a job by id, regardless of queue or status.
+///
+/// Returns `None` once the job has completed, since [`job_complete`] deletes
+/// its row rather than marking it done in place.
+pub async fn job_find(
+    exe: &mut impl repo::AsExec,
+    id: uuid::Uuid,
+) -> Result<Option<sql_models::JobQueueRow>, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::JobQueueRow,
+        r#"
+            SELECT job_queue_id, queue, job, status, retries, heartbeat, created
+            FROM job_queue_t
+            WHERE job_queue_id = $1
+    "#,
+        id,
+    )
+    .fetch_optional(exe.as_exec())
+    .await?;
+    Ok(res)
+}
+
+/// Deletes a completed job.
+///
+/// If the job does not exist, the operation has no effect.
+pub async fn job_complete(exe: &mut impl repo::AsExec, id: uuid::Uuid) -> Result<(), repo::Error> {
+    trace!("completing job `{}`", id);
+    sqlx::query!("DELETE FROM job_queue_t WHERE job_queue_id = $1", id)
+        .execute(exe.as_exec())
+        .await?;
+    Ok(())
+}
+
+/// Records a failed attempt at `id`: bumps `retries`, resets `status` back
+/// to `new` and refreshes `heartbeat` to `now()` so `job_claim_next`'s
+/// backoff window is computed from this failure rather than the original
+/// enqueue time.
+pub async fn job_fail(
+    exe: &mut impl repo::AsExec,
+    id: uuid::Uuid,
+) -> Result<sql_models::JobQueueRow, repo::Error> {
+    trace!("recording failed attempt for job `{}`", id);
+    let res = sqlx::query_as!(
+        sql_models::JobQueueRow,
+        r#"
+            UPDATE job_queue_t
+            SET status = $1, heartbeat = now(), retries = retries + 1
+            WHERE job_queue_id = $2
+            RETURNING job_queue_id, queue, job, status, retries, heartbeat, created
+    "#,
+        sql_models::JobStatus::New.to_string(),
+        id,
+    )
+    .fetch_one(exe.as_exec())
+    .await?;
+    Ok(res)
+}
+
+/// Resets every `running` job whose `heartbeat` is older than `timeout` back
+/// to `new`, so a crashed worker's job is picked up again rather than stuck
+/// forever. Returns the number of jobs reclaimed.
+pub async fn job_sweep_stale(
+    exe: &mut impl repo::AsExec,
+    timeout: chrono::Duration,
+) -> Result<u64, repo::Error> {
+    let threshold = chrono::Utc::now() - timeout;
+    let res = sqlx::query!(
+        r#"
+            UPDATE job_queue_t
+            SET status = $1, heartbeat = NULL
+            WHERE status = $2 AND heartbeat < $3
+    "#,
+        sql_models::JobStatus::New.to_string(),
+        sql_models::JobStatus::Running.to_string(),
+        threshold,
+    )
+    .execute(exe.as_exec())
+    .await?;
+    Ok(res.rows_affected())
+}
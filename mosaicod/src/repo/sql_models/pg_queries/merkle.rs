@@ -0,0 +1,38 @@
+use crate::repo;
+
+/// Persists the serialized peak vector for a topic's [`crate::types::MerkleTree`]
+/// (see [`crate::types::MerkleTree::to_bytes`]), overwriting any prior state.
+///
+/// Called after every chunk is appended so the tree never needs replaying
+/// from the topic's full chunk history to resume.
+pub async fn topic_merkle_upsert(
+    exec: &mut impl repo::AsExec,
+    topic_id: i32,
+    peaks: &[u8],
+) -> Result<(), repo::Error> {
+    sqlx::query!(
+        r#"INSERT INTO topic_merkle_t (topic_id, peaks)
+        VALUES ($1, $2)
+        ON CONFLICT (topic_id) DO UPDATE SET peaks = EXCLUDED.peaks"#,
+        topic_id,
+        peaks,
+    )
+    .execute(exec.as_exec())
+    .await?;
+    Ok(())
+}
+
+/// Returns the serialized peak vector for a topic's Merkle tree, or `None`
+/// if no chunk has been appended to it yet.
+pub async fn topic_merkle_find(
+    exec: &mut impl repo::AsExec,
+    topic_id: i32,
+) -> Result<Option<Vec<u8>>, repo::Error> {
+    let res = sqlx::query!(
+        r#"SELECT peaks FROM topic_merkle_t WHERE topic_id = $1"#,
+        topic_id,
+    )
+    .fetch_optional(exec.as_exec())
+    .await?;
+    Ok(res.map(|row| row.peaks))
+}
@@ -13,12 +13,24 @@ pub use notifies::*;
 mod data_catalog;
 pub use data_catalog::*;
 
+mod job_queue;
+pub use job_queue::*;
+
 mod layers;
 pub use layers::*;
 
 mod group;
 pub use group::*;
 
+mod merkle;
+pub use merkle::*;
+
+mod upload_job;
+pub use upload_job::*;
+
+mod sequence_delete_job;
+pub use sequence_delete_job::*;
+
 mod compilers;
 use compilers::*;
 
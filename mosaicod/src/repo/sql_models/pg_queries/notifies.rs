@@ -1,10 +1,19 @@
-use log::trace;
+use std::collections::VecDeque;
+
+use futures::Stream;
+use log::{trace, warn};
 
 use crate::{
     repo::{self, sql_models},
     types::{self, Resource},
 };
 
+/// Name of the Postgres `NOTIFY` channel used for push-based subscriptions on
+/// a given topic's notifies.
+pub fn topic_notify_channel(topic_id: i32) -> String {
+    format!("topic_notify_{topic_id}")
+}
+
 /// Creates a new notify associated with a topic
 pub async fn topic_notify_create(
     exe: &mut impl repo::AsExec,
@@ -15,10 +24,10 @@ pub async fn topic_notify_create(
         sql_models::TopicNotify,
         r#"
             INSERT INTO topic_notify_t
-                (topic_id, notify_type, msg, creation_unix_tstamp) 
-            VALUES 
-                ($1, $2, $3, $4) 
-            RETURNING 
+                (topic_id, notify_type, msg, creation_unix_tstamp)
+            VALUES
+                ($1, $2, $3, $4)
+            RETURNING
                 *
     "#,
         notify.topic_id,
@@ -28,9 +37,118 @@ pub async fn topic_notify_create(
     )
     .fetch_one(exe.as_exec())
     .await?;
+
+    // Queued until the enclosing transaction commits, at which point subscribers
+    // waiting on `topic_notify_subscribe` are woken up.
+    sqlx::query!(
+        "SELECT pg_notify($1, $2)",
+        topic_notify_channel(res.topic_id),
+        res.topic_notify_id.to_string(),
+    )
+    .execute(exe.as_exec())
+    .await?;
+
+    Ok(res)
+}
+
+/// Finds all notifies for a topic created strictly after `since_unix_tstamp`,
+/// ordered oldest-first. Used by [`topic_notify_subscribe`] to catch up on
+/// anything missed across a dropped listener connection.
+async fn topic_notifies_find_since(
+    exe: &mut impl repo::AsExec,
+    topic_id: i32,
+    since_unix_tstamp: i64,
+) -> Result<Vec<sql_models::TopicNotify>, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::TopicNotify,
+        r#"
+          SELECT * FROM topic_notify_t
+          WHERE topic_id = $1 AND creation_unix_tstamp > $2
+          ORDER BY creation_unix_tstamp ASC
+    "#,
+        topic_id,
+        since_unix_tstamp,
+    )
+    .fetch_all(exe.as_exec())
+    .await?;
     Ok(res)
 }
 
+/// Opens a real-time subscription over a topic's notifies, built on top of
+/// Postgres `LISTEN`/`NOTIFY`.
+///
+/// The returned stream never silently drops notifies: if the underlying
+/// listener connection is lost it is transparently re-established, and any
+/// row created since the last one observed is replayed before resuming live
+/// delivery.
+pub async fn topic_notify_subscribe(
+    repo: repo::Repository,
+    loc: types::TopicResourceLocator,
+) -> Result<impl Stream<Item = Result<sql_models::TopicNotify, repo::Error>>, repo::Error> {
+    let mut cx = repo.connection();
+    let topic = super::topic_find_by_locator(&mut cx, &loc).await?;
+    let topic_id = topic.topic_id;
+
+    // Start the cursor at the newest notify that already exists, so only
+    // rows created after the subscription opened are delivered.
+    let last_seen_tstamp = topic_notifies_find_since(&mut cx, topic_id, 0)
+        .await?
+        .iter()
+        .map(|n| n.creation_unix_tstamp)
+        .max()
+        .unwrap_or(0);
+
+    let listener = repo.listen(&topic_notify_channel(topic_id)).await?;
+
+    struct State {
+        repo: repo::Repository,
+        listener: sqlx::postgres::PgListener,
+        topic_id: i32,
+        last_seen_tstamp: i64,
+        pending: VecDeque<sql_models::TopicNotify>,
+    }
+
+    let state = State {
+        repo,
+        listener,
+        topic_id,
+        last_seen_tstamp,
+        pending: VecDeque::new(),
+    };
+
+    Ok(futures::stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(notify) = state.pending.pop_front() {
+                state.last_seen_tstamp = state.last_seen_tstamp.max(notify.creation_unix_tstamp);
+                return Some((Ok(notify), state));
+            }
+
+            if let Err(err) = state.listener.recv().await {
+                warn!("notify listener connection lost, reconnecting: {}", err);
+                match repo::core::Repository::listen(
+                    &state.repo,
+                    &topic_notify_channel(state.topic_id),
+                )
+                .await
+                {
+                    Ok(listener) => {
+                        state.listener = listener;
+                        continue;
+                    }
+                    Err(err) => return Some((Err(err), state)),
+                }
+            }
+
+            let mut cx = state.repo.connection();
+            match topic_notifies_find_since(&mut cx, state.topic_id, state.last_seen_tstamp).await
+            {
+                Ok(rows) => state.pending.extend(rows),
+                Err(err) => return Some((Err(err), state)),
+            }
+        }
+    }))
+}
+
 /// Find al notifies associated with a topic name
 pub async fn topic_notifies_find_by_locator(
     exe: &mut impl repo::AsExec,
@@ -51,6 +169,24 @@ pub async fn topic_notifies_find_by_locator(
     Ok(res)
 }
 
+/// Finds all notifies belonging to any of `topic_ids`, for
+/// [`repo::NotificationLoader`] to batch per-topic notify lookups into one
+/// query instead of one per topic.
+pub async fn topic_notifies_find_by_topic_ids(
+    exe: &mut impl repo::AsExec,
+    topic_ids: &[i32],
+) -> Result<Vec<sql_models::TopicNotify>, repo::Error> {
+    trace!("searching notifies for topic ids `{:?}`", topic_ids);
+    let res = sqlx::query_as!(
+        sql_models::TopicNotify,
+        "SELECT * FROM topic_notify_t WHERE topic_id = ANY($1)",
+        topic_ids
+    )
+    .fetch_all(exe.as_exec())
+    .await?;
+    Ok(res)
+}
+
 /// Deletes a sequence notify from the repository
 ///
 /// If the notify does not exist, the operation has no effect.
@@ -107,6 +243,31 @@ pub async fn sequence_notifies_find_by_name(
     Ok(res)
 }
 
+/// Finds all notifies for a sequence with id strictly greater than
+/// `since_id`, ordered oldest-first. Used by
+/// [`crate::repo::FacadeSequence::notify_poll`] both for its initial check
+/// and to re-check the database on every wakeup, since
+/// `sequence_notify_id` is already a monotonically increasing cursor.
+pub async fn sequence_notifies_find_since(
+    exe: &mut impl repo::AsExec,
+    sequence_id: i32,
+    since_id: i32,
+) -> Result<Vec<sql_models::SequenceNotify>, repo::Error> {
+    let res = sqlx::query_as!(
+        sql_models::SequenceNotify,
+        r#"
+          SELECT * FROM sequence_notify_t
+          WHERE sequence_id = $1 AND sequence_notify_id > $2
+          ORDER BY sequence_notify_id ASC
+    "#,
+        sequence_id,
+        since_id,
+    )
+    .fetch_all(exe.as_exec())
+    .await?;
+    Ok(res)
+}
+
 /// Deletes a sequence report from the repository
 ///
 /// If the report does not exist, the operation has no effect.
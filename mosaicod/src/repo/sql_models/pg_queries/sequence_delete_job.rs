@@ -0,0 +1,146 @@
+use log::trace;
+
+use crate::{repo, types};
+
+/// Starts (or resumes) the delete-job checkpoint for `sequence_id`: creates
+/// a fresh row recording every topic name in `topics` as not-yet-deleted, or
+/// leaves an existing row untouched so a restarted
+/// [`crate::repo::FacadeSequence::delete`] picks up from whichever topics
+/// are already marked done instead of tearing them down again.
+pub async fn sequence_delete_job_start(
+    exec: &mut impl repo::AsExec,
+    sequence_id: i32,
+    topics: &[String],
+) -> Result<(), repo::Error> {
+    trace!(
+        "starting delete job for sequence `{}` ({} topics)",
+        sequence_id,
+        topics.len()
+    );
+    let topics: serde_json::Value = topics.iter().map(|name| (name.clone(), false)).collect();
+    sqlx::query!(
+        r#"
+            INSERT INTO sequence_delete_job_t (sequence_id, topics, state)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (sequence_id) DO NOTHING
+        "#,
+        sequence_id,
+        topics,
+        types::DeleteJobState::Running.to_string(),
+    )
+    .execute(exec.as_exec())
+    .await?;
+    Ok(())
+}
+
+/// Returns the current progress for `sequence_id`'s delete job, or `None` if
+/// none has ever been started (or it already finished -- see
+/// [`sequence_delete_job_complete`]).
+pub async fn sequence_delete_job_find(
+    exec: &mut impl repo::AsExec,
+    sequence_id: i32,
+) -> Result<Option<types::SequenceDeleteProgress>, repo::Error> {
+    let row = sqlx::query!(
+        r#"SELECT state, topics FROM sequence_delete_job_t WHERE sequence_id = $1"#,
+        sequence_id,
+    )
+    .fetch_optional(exec.as_exec())
+    .await?;
+
+    Ok(row.map(|row| {
+        let done = row
+            .topics
+            .as_object()
+            .map(|obj| obj.values().filter(|v| v.as_bool() == Some(true)).count())
+            .unwrap_or(0);
+        let total = row.topics.as_object().map(|obj| obj.len()).unwrap_or(0);
+
+        types::SequenceDeleteProgress {
+            // Only ever written via `DeleteJobState::to_string`, so parsing
+            // it back can't fail.
+            state: row.state.parse().unwrap(),
+            topics_total: total,
+            topics_done: done,
+        }
+    }))
+}
+
+/// Returns the topic names not yet marked done for `sequence_id`'s delete
+/// job, so a (re)started [`crate::repo::FacadeSequence::delete`] only
+/// tears down what's left.
+pub async fn sequence_delete_job_pending_topics(
+    exec: &mut impl repo::AsExec,
+    sequence_id: i32,
+) -> Result<Vec<String>, repo::Error> {
+    let row = sqlx::query!(
+        r#"SELECT topics FROM sequence_delete_job_t WHERE sequence_id = $1"#,
+        sequence_id,
+    )
+    .fetch_optional(exec.as_exec())
+    .await?;
+
+    Ok(row
+        .and_then(|row| row.topics.as_object().cloned())
+        .map(|obj| {
+            obj.into_iter()
+                .filter(|(_, done)| done.as_bool() != Some(true))
+                .map(|(name, _)| name)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Marks `topic_name` as torn down in `sequence_id`'s delete job checkpoint.
+pub async fn sequence_delete_job_mark_topic_done(
+    exec: &mut impl repo::AsExec,
+    sequence_id: i32,
+    topic_name: &str,
+) -> Result<(), repo::Error> {
+    trace!(
+        "marking topic `{}` done in sequence `{}`'s delete job",
+        topic_name, sequence_id
+    );
+    sqlx::query!(
+        r#"
+            UPDATE sequence_delete_job_t
+            SET topics = jsonb_set(topics, ARRAY[$2], 'true'::jsonb)
+            WHERE sequence_id = $1
+        "#,
+        sequence_id,
+        topic_name,
+    )
+    .execute(exec.as_exec())
+    .await?;
+    Ok(())
+}
+
+/// Deletes a finished delete job's checkpoint row.
+///
+/// If no job exists for `sequence_id`, the operation has no effect.
+pub async fn sequence_delete_job_complete(
+    exec: &mut impl repo::AsExec,
+    sequence_id: i32,
+) -> Result<(), repo::Error> {
+    trace!("completing delete job for sequence `{}`", sequence_id);
+    sqlx::query!(
+        "DELETE FROM sequence_delete_job_t WHERE sequence_id = $1",
+        sequence_id,
+    )
+    .execute(exec.as_exec())
+    .await?;
+    Ok(())
+}
+
+/// Returns the sequence ids of every delete job still `running`, for
+/// [`crate::repo::FacadeSequence::resume_jobs`] to pick back up on startup.
+pub async fn sequence_delete_jobs_find_incomplete(
+    exec: &mut impl repo::AsExec,
+) -> Result<Vec<i32>, repo::Error> {
+    let res = sqlx::query_scalar!(
+        "SELECT sequence_id FROM sequence_delete_job_t WHERE state = $1",
+        types::DeleteJobState::Running.to_string(),
+    )
+    .fetch_all(exec.as_exec())
+    .await?;
+    Ok(res)
+}
@@ -1,10 +1,23 @@
 use log::trace;
+use sqlx::{Row, postgres::PgRow};
 
 use crate::{
+    query,
     repo::{self, Error, sql_models},
     types::{self, Resource},
 };
 
+fn cast_sequence_data(row: PgRow) -> Result<sql_models::SequenceRecord, Error> {
+    Ok(sql_models::SequenceRecord {
+        sequence_id: row.try_get("sequence_id")?,
+        sequence_uuid: row.try_get("sequence_uuid")?,
+        sequence_name: row.try_get("sequence_name")?,
+        locked: row.try_get("locked")?,
+        user_metadata: row.try_get("user_metadata")?,
+        creation_unix_tstamp: row.try_get("creation_unix_tstamp")?,
+    })
+}
+
 /// Find a sequence given its id.
 pub async fn sequence_find_by_id(
     exe: &mut impl repo::AsExec,
@@ -87,6 +100,77 @@ pub async fn sequence_find_all(
     )
 }
 
+/// Returns every sequence matching `filter`'s clauses, compiled through
+/// [`super::SqlQueryCompiler`]/[`super::JsonQueryCompiler`] -- mirrors
+/// [`super::topic_from_query_filter`], but scoped to `sequence_t` alone
+/// since this is used for catalog discovery over sequences, not topics.
+///
+/// Returns every sequence (same as [`sequence_find_all`]) if `filter` has no
+/// clauses set, for backward compatibility with unfiltered listing.
+pub async fn sequence_from_query_filter(
+    exe: &mut impl repo::AsExec,
+    filter: query::SequenceFilter,
+) -> Result<Vec<sql_models::SequenceRecord>, Error> {
+    if filter.is_empty() {
+        return sequence_find_all(exe).await;
+    }
+
+    let select = "SELECT * FROM sequence_t";
+
+    let mut qb = query::ClausesCompiler::new();
+    let mut sql_fmt = super::SqlQueryCompiler::new();
+    let mut json_fmt = super::JsonQueryCompiler::new();
+
+    if let Some(op) = filter.name {
+        qb = qb.expr("sequence_name", op, &mut sql_fmt);
+    }
+
+    if let Some(op) = filter.creation {
+        qb = qb.expr("creation_unix_tstamp", op, &mut sql_fmt);
+    }
+
+    if let Some(mdata) = filter.user_metadata {
+        qb = qb.filter(
+            mdata.into_iterator(),
+            json_fmt.with_field_and_placeholder("user_metadata".into(), sql_fmt.current_placeholder()),
+        );
+    }
+
+    if let Some(since) = filter.since {
+        qb = qb.expr("sequence_id", query::Op::Gt(since), &mut sql_fmt);
+    }
+
+    let qr = qb.compile()?;
+
+    if qr.is_unfiltered() {
+        return Ok(Vec::new());
+    }
+
+    let clause_count = qr.clauses.len();
+    let value_count = qr.values.len();
+
+    let query = format!("{select} WHERE {}", qr.clauses.join(" AND "));
+
+    let mut r = sqlx::query(&query);
+
+    for v in qr.values.into_iter() {
+        match v {
+            query::Value::Integer(v) => r = r.bind(v),
+            query::Value::Float(v) => r = r.bind(v),
+            query::Value::Text(v) => r = r.bind(v),
+            query::Value::Boolean(v) => r = r.bind(v),
+        }
+    }
+
+    let rows = repo::core::instrument(
+        "sequence_from_query_filter",
+        format!("{clause_count} clause(s), {value_count} bound value(s)"),
+        r.map(cast_sequence_data).fetch_all(exe.as_exec()),
+    )
+    .await?;
+    rows.into_iter().collect()
+}
+
 /// Deletes a sequence record from the repository **only if it is unlocked**.
 ///
 /// If the sequence is locked or does not exist, the operation has no effect.
@@ -53,6 +53,22 @@ pub async fn topic_find_by_locator(
     Ok(res)
 }
 
+/// Find every topic belonging to a given sequence.
+pub async fn topic_find_by_sequence_id(
+    exe: &mut impl repo::AsExec,
+    sequence_id: i32,
+) -> Result<Vec<sql_models::TopicRecord>, repo::Error> {
+    trace!("searching topics for sequence id `{}`", sequence_id);
+    let res = sqlx::query_as!(
+        sql_models::TopicRecord,
+        "SELECT * FROM topic_t WHERE sequence_id = $1",
+        sequence_id
+    )
+    .fetch_all(exe.as_exec())
+    .await?;
+    Ok(res)
+}
+
 /// Return all sequences
 pub async fn topic_find_all(
     exe: &mut impl repo::AsExec,
@@ -260,6 +276,10 @@ pub async fn topic_from_query_filter(
                 ),
             );
         }
+
+        if let Some(since) = seq.since {
+            qb = qb.expr("sequence.sequence_id", query::Op::Gt(since), &mut sql_fmt);
+        }
     }
 
     if let Some(top) = filter_top {
@@ -288,6 +308,10 @@ pub async fn topic_from_query_filter(
                 ),
             );
         }
+
+        if let Some(since) = top.since {
+            qb = qb.expr("topic.topic_id", query::Op::Gt(since), &mut sql_fmt);
+        }
     }
 
     let qr = qb.compile()?;
@@ -297,15 +321,15 @@ pub async fn topic_from_query_filter(
         return Ok(Vec::new());
     }
 
+    let clause_count = qr.clauses.len();
+    let value_count = qr.values.len();
+
     let query = if qr.is_unfiltered() {
         select.into()
     } else {
         format!("{select} WHERE {}", qr.clauses.join(" AND "))
     };
 
-    dbg!(&qr.values);
-    dbg!(&query);
-
     let mut r = sqlx::query(&query);
 
     for v in qr.values.into_iter() {
@@ -317,7 +341,11 @@ pub async fn topic_from_query_filter(
         }
     }
 
-    let r = r.map(cast_topic_data).fetch_all(exe.as_exec()).await?;
-    dbg!(r.len());
-    r.into_iter().collect()
+    let rows = repo::core::instrument(
+        "topic_from_query_filter",
+        format!("{clause_count} clause(s), {value_count} bound value(s)"),
+        r.map(cast_topic_data).fetch_all(exe.as_exec()),
+    )
+    .await?;
+    rows.into_iter().collect()
 }
@@ -0,0 +1,100 @@
+use crate::{repo, types};
+
+/// Starts (or resumes) the upload-job checkpoint for `topic_id`: creates a
+/// fresh row at chunk `0` if none exists yet, or flips an existing one back
+/// to `running` while leaving its checkpoint untouched, so the caller picks
+/// up from wherever it left off instead of renumbering from scratch.
+pub async fn upload_job_start(exec: &mut impl repo::AsExec, topic_id: i32) -> Result<(), repo::Error> {
+    sqlx::query!(
+        r#"INSERT INTO upload_job_t (topic_id, state, chunk_serialized_number, bytes_written)
+        VALUES ($1, $2, 0, 0)
+        ON CONFLICT (topic_id) DO UPDATE SET state = EXCLUDED.state"#,
+        topic_id,
+        types::UploadJobState::Running.to_string(),
+    )
+    .execute(exec.as_exec())
+    .await?;
+    Ok(())
+}
+
+/// Returns the current checkpoint and progress for `topic_id`, or `None` if
+/// no upload has ever started for it.
+pub async fn upload_job_find(
+    exec: &mut impl repo::AsExec,
+    topic_id: i32,
+) -> Result<Option<types::TopicUploadStatus>, repo::Error> {
+    let row = sqlx::query!(
+        r#"SELECT state, chunk_serialized_number, bytes_written, current_file
+           FROM upload_job_t WHERE topic_id = $1"#,
+        topic_id,
+    )
+    .fetch_optional(exec.as_exec())
+    .await?;
+
+    Ok(row.map(|row| types::TopicUploadStatus {
+        // Only ever written via `UploadJobState::to_string`, so parsing it
+        // back can't fail.
+        state: row.state.parse().unwrap(),
+        chunks_written: row.chunk_serialized_number as usize,
+        bytes_written: row.bytes_written as usize,
+        current_file: row.current_file,
+    }))
+}
+
+/// Records progress after a chunk is durably persisted: the next chunk
+/// number to resume from, cumulative bytes written, and the most recently
+/// written file (surfaced to a polling client via `upload_job_find`).
+pub async fn upload_job_checkpoint(
+    exec: &mut impl repo::AsExec,
+    topic_id: i32,
+    chunk_serialized_number: i64,
+    bytes_written: i64,
+    current_file: &str,
+) -> Result<(), repo::Error> {
+    sqlx::query!(
+        r#"UPDATE upload_job_t
+           SET chunk_serialized_number = $2, bytes_written = $3, current_file = $4
+           WHERE topic_id = $1"#,
+        topic_id,
+        chunk_serialized_number,
+        bytes_written,
+        current_file,
+    )
+    .execute(exec.as_exec())
+    .await?;
+    Ok(())
+}
+
+async fn upload_job_set_state(
+    exec: &mut impl repo::AsExec,
+    topic_id: i32,
+    state: types::UploadJobState,
+) -> Result<(), repo::Error> {
+    sqlx::query!(
+        "UPDATE upload_job_t SET state = $2 WHERE topic_id = $1",
+        topic_id,
+        state.to_string(),
+    )
+    .execute(exec.as_exec())
+    .await?;
+    Ok(())
+}
+
+/// The upload stopped gracefully before the topic was locked; the
+/// checkpoint is kept as-is so a future [`upload_job_start`] resumes from
+/// it with the topic still unlocked and re-lockable.
+pub async fn upload_job_pause(exec: &mut impl repo::AsExec, topic_id: i32) -> Result<(), repo::Error> {
+    upload_job_set_state(exec, topic_id, types::UploadJobState::Paused).await
+}
+
+/// Marks the job `completed`. The row is kept (rather than deleted) so a
+/// later attempt against the same topic can tell it already finished.
+pub async fn upload_job_complete(exec: &mut impl repo::AsExec, topic_id: i32) -> Result<(), repo::Error> {
+    upload_job_set_state(exec, topic_id, types::UploadJobState::Completed).await
+}
+
+/// Marks the job `failed`. The checkpoint is left untouched so a retry can
+/// still resume from the last chunk durably written.
+pub async fn upload_job_fail(exec: &mut impl repo::AsExec, topic_id: i32) -> Result<(), repo::Error> {
+    upload_job_set_state(exec, topic_id, types::UploadJobState::Failed).await
+}
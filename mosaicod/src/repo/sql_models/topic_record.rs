@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use crate::{marshal, repo, rw, types};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TopicRecord {
     pub topic_id: i32,
     pub topic_uuid: uuid::Uuid,
@@ -74,4 +74,10 @@ impl TopicRecord {
     pub fn creation_timestamp(&self) -> types::Timestamp {
         types::Timestamp::from(self.creation_unix_tstamp)
     }
+
+    /// Raw user metadata blob, for callers that need to re-serialize it
+    /// somewhere other than a Postgres row (e.g. [`repo::backend`]).
+    pub fn user_metadata(&self) -> Option<&serde_json::Value> {
+        self.user_metadata.as_ref()
+    }
 }
@@ -0,0 +1,146 @@
+//! In-process fanout of topic lifecycle/metadata events, used to implement
+//! `do_action`'s `watch` long-poll without requiring clients to busy-poll
+//! [`super::topic_find_all`]/[`super::topic_from_query_filter`].
+//!
+//! Deliberately process-local rather than routed through Postgres
+//! `LISTEN`/`NOTIFY` (contrast [`super::sql_models::topic_notify_subscribe`],
+//! which exists for the same "wake a waiter on change" shape but persists
+//! every event as a row first): every mutation that matters here already
+//! goes through this process, so there's no need to pay for a round trip to
+//! the database just to wake up a caller sitting in the same binary.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+/// Upper bound on how many past events [`watch`] can still catch up on.
+/// Older events are dropped from the front of the history. A caller whose
+/// `since_token` predates what is retained only gets the retained events
+/// that match, and nothing in what [`watch`] returns marks that earlier
+/// ones were dropped.
+const HISTORY_CAP: usize = 1024;
+
+/// What kind of change a [`WatchEvent`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchEventKind {
+    Created,
+    Locked,
+    Deleted,
+    MetadataUpdated,
+}
+
+impl std::fmt::Display for WatchEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Created => write!(f, "created"),
+            Self::Locked => write!(f, "locked"),
+            Self::Deleted => write!(f, "deleted"),
+            Self::MetadataUpdated => write!(f, "metadata_updated"),
+        }
+    }
+}
+
+/// One change to a topic, tagged with the monotonic `token` it was
+/// published at. `token`s only ever increase, so a caller can resume a
+/// watch by remembering the highest one it has seen.
+#[derive(Debug, Clone)]
+pub struct WatchEvent {
+    pub token: u64,
+    pub kind: WatchEventKind,
+    pub topic_name: String,
+}
+
+struct Registry {
+    events: Mutex<VecDeque<WatchEvent>>,
+    next_token: AtomicU64,
+    notify: Notify,
+}
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Registry {
+        events: Mutex::new(VecDeque::new()),
+        next_token: AtomicU64::new(1),
+        notify: Notify::new(),
+    })
+}
+
+/// Records a change to `topic_name` and wakes anyone blocked in [`watch`].
+///
+/// Called by the [`super::facades::FacadeTopic`] methods that perform the
+/// corresponding mutation (`create`, `lock`, `delete`, `update`), after the
+/// underlying transaction commits.
+pub fn publish(kind: WatchEventKind, topic_name: &str) {
+    let registry = registry();
+    let token = registry.next_token.fetch_add(1, Ordering::SeqCst);
+
+    let mut events = registry
+        .events
+        .lock()
+        .expect("topic watch registry poisoned");
+    events.push_back(WatchEvent {
+        token,
+        kind,
+        topic_name: topic_name.to_string(),
+    });
+    while events.len() > HISTORY_CAP {
+        events.pop_front();
+    }
+    drop(events);
+
+    registry.notify.notify_waiters();
+}
+
+/// The current high-water token, i.e. what a caller starting a fresh watch
+/// (rather than resuming one) should pass as `since_token` to only observe
+/// events published after this call.
+pub fn current_token() -> u64 {
+    registry().next_token.load(Ordering::SeqCst).saturating_sub(1)
+}
+
+/// Blocks until a [`WatchEvent`] with `token > since_token` exists for a
+/// topic named in `names` (or for any topic, when `names` is empty), or
+/// `timeout` elapses.
+///
+/// Returns the highest token observed (unchanged from `since_token` if
+/// nothing matched before the deadline) and the matching events, oldest
+/// first, so the caller can immediately re-issue the call with the returned
+/// token to keep watching.
+pub async fn watch(since_token: u64, names: &[String], timeout: Duration) -> (u64, Vec<WatchEvent>) {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        // Registered before the scan so a `publish` landing between the scan
+        // and the wait below still wakes this call.
+        let notified = registry().notify.notified();
+        let matched = {
+            let events = registry()
+                .events
+                .lock()
+                .expect("topic watch registry poisoned");
+            events
+                .iter()
+                .filter(|e| e.token > since_token && (names.is_empty() || names.contains(&e.topic_name)))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        if let Some(newest) = matched.last() {
+            let new_token = newest.token;
+            return (new_token, matched);
+        }
+
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            return (since_token, Vec::new());
+        }
+
+        tokio::select! {
+            _ = notified => {}
+            _ = tokio::time::sleep(remaining) => return (since_token, Vec::new()),
+        }
+    }
+}
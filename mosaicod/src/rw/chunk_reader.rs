@@ -1,24 +1,124 @@
+use std::io::Cursor;
+
+use arrow::array::RecordBatch;
 use arrow::datatypes::SchemaRef;
 use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
 
 use super::{Error, Format};
+
+/// Decodes a serialized chunk buffer into [`RecordBatch`]es, dispatching on
+/// the topic's [`Format`] so callers don't need a format-specific code path.
+///
+/// Every variant's iterator yields the same `Result<RecordBatch, Error>`
+/// item, so a caller (e.g. the Flight `do_get` path) can read a topic
+/// without knowing which format it was written in.
 pub enum Reader {
     /// Parquet file format https://parquet.apache.org/docs/file-format/
     Parquet {
         reader: ParquetRecordBatchReader,
         schema: SchemaRef,
     },
+    /// Arrow IPC stream format.
+    ArrowIpc {
+        reader: arrow::ipc::reader::StreamReader<Cursor<bytes::Bytes>>,
+        schema: SchemaRef,
+    },
+    /// Comma-separated values, with the schema either inferred from the
+    /// buffer or supplied explicitly (e.g. from the topic's recorded Arrow
+    /// schema).
+    Csv {
+        reader: arrow::csv::Reader<Cursor<bytes::Bytes>>,
+        schema: SchemaRef,
+    },
+    /// Newline-delimited JSON records.
+    Json {
+        reader: arrow::json::Reader<Cursor<bytes::Bytes>>,
+        schema: SchemaRef,
+    },
 }
 
 impl Reader {
-    pub fn try_new(_format: Format, buffer: bytes::Bytes) -> Result<Self, Error> {
-        let builder = ParquetRecordBatchReaderBuilder::try_new(buffer)?;
-        Ok(Self::Parquet {
-            schema: builder.schema().clone(),
-            reader: builder.build()?,
-        })
+    /// Builds a [`Reader`] for `buffer` according to `format`.
+    ///
+    /// `schema` is only consulted by [`Format::Csv`] and [`Format::Json`],
+    /// whose buffers don't carry a schema of their own: when given, it's
+    /// used as-is instead of inferring one from the buffer (e.g. the
+    /// topic's already-known Arrow schema, so every chunk after the first
+    /// doesn't need to pay for its own inference pass).
+    pub fn try_new(
+        format: Format,
+        buffer: bytes::Bytes,
+        schema: Option<SchemaRef>,
+    ) -> Result<Self, Error> {
+        match format {
+            Format::Default | Format::Ragged | Format::Image => {
+                let builder = ParquetRecordBatchReaderBuilder::try_new(buffer)?;
+                Ok(Self::Parquet {
+                    schema: builder.schema().clone(),
+                    reader: builder.build()?,
+                })
+            }
+            Format::ArrowIpc => {
+                let reader = arrow::ipc::reader::StreamReader::try_new(Cursor::new(buffer), None)?;
+                let schema = reader.schema();
+                Ok(Self::ArrowIpc { reader, schema })
+            }
+            Format::Csv => {
+                let mut cursor = Cursor::new(buffer);
+                let schema = match schema {
+                    Some(schema) => schema,
+                    None => {
+                        let format = arrow::csv::reader::Format::default().with_header(true);
+                        let (schema, _) = format.infer_schema(&mut cursor, None)?;
+                        std::io::Seek::seek(&mut cursor, std::io::SeekFrom::Start(0))?;
+                        std::sync::Arc::new(schema)
+                    }
+                };
+                let reader = arrow::csv::ReaderBuilder::new(schema.clone())
+                    .with_header(true)
+                    .build(cursor)?;
+                Ok(Self::Csv { reader, schema })
+            }
+            Format::Json => {
+                let mut cursor = Cursor::new(buffer);
+                let schema = match schema {
+                    Some(schema) => schema,
+                    None => {
+                        let (schema, _) =
+                            arrow::json::reader::infer_json_schema(&mut cursor, None)?;
+                        std::io::Seek::seek(&mut cursor, std::io::SeekFrom::Start(0))?;
+                        std::sync::Arc::new(schema)
+                    }
+                };
+                let reader = arrow::json::ReaderBuilder::new(schema.clone()).build(cursor)?;
+                Ok(Self::Json { reader, schema })
+            }
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        match self {
+            Self::Parquet { schema, .. } => schema.clone(),
+            Self::ArrowIpc { schema, .. } => schema.clone(),
+            Self::Csv { schema, .. } => schema.clone(),
+            Self::Json { schema, .. } => schema.clone(),
+        }
     }
 }
+
+impl Iterator for Reader {
+    type Item = Result<RecordBatch, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Parquet { reader, .. } => reader.next().map(|res| res.map_err(Error::from)),
+            Self::ArrowIpc { reader, .. } => reader.next().map(|res| res.map_err(Error::from)),
+            Self::Csv { reader, .. } => reader.next().map(|res| res.map_err(Error::from)),
+            Self::Json { reader, .. } => reader.next().map(|res| res.map_err(Error::from)),
+        }
+    }
+}
+
 pub struct ChunkReader {
     reader: Reader,
 }
@@ -26,13 +126,46 @@ pub struct ChunkReader {
 impl ChunkReader {
     pub fn new(format: Format, buffer: bytes::Bytes) -> Result<Self, Error> {
         Ok(Self {
-            reader: Reader::try_new(format, buffer)?,
+            reader: Reader::try_new(format, buffer, None)?,
         })
     }
 
-    pub fn schema(&self) -> SchemaRef {
-        match &self.reader {
-            Reader::Parquet { schema, .. } => schema.clone(),
+    /// Like [`Self::new`], but supplies an explicit schema for formats
+    /// (`Csv`, `Json`) that would otherwise need to infer one from `buffer`.
+    pub fn new_with_schema(
+        format: Format,
+        buffer: bytes::Bytes,
+        schema: SchemaRef,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            reader: Reader::try_new(format, buffer, Some(schema))?,
+        })
+    }
+
+    /// Creates a [`ChunkReader`], transparently decrypting `buffer` first if it
+    /// carries a [`super::ChunkCipher`] header.
+    pub fn new_with_cipher(
+        format: Format,
+        buffer: bytes::Bytes,
+        cipher: &super::ChunkCipher,
+    ) -> Result<Self, Error> {
+        if super::ChunkCipher::is_encrypted(&buffer) {
+            let decrypted = cipher.decrypt(&buffer)?;
+            Self::new(format, bytes::Bytes::from(decrypted))
+        } else {
+            Self::new(format, buffer)
         }
     }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.reader.schema()
+    }
+}
+
+impl Iterator for ChunkReader {
+    type Item = Result<RecordBatch, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.next()
+    }
 }
@@ -1,4 +1,4 @@
-use super::{Error, Format, writer::Writer};
+use super::{ContentDigest, Error, Format, writer::Writer};
 use crate::types;
 use arrow::{array::RecordBatch, datatypes::Schema, datatypes::SchemaRef};
 use std::sync::Arc;
@@ -14,6 +14,9 @@ pub struct ChunkWriter {
     writer: Writer,
     stats: types::ColumnsStats,
     schema: SchemaRef,
+    /// When set, [`ChunkWriter::finalize`] encrypts the serialized buffer
+    /// before returning it.
+    cipher: Option<super::ChunkCipher>,
 }
 
 impl ChunkWriter {
@@ -27,9 +30,17 @@ impl ChunkWriter {
             format,
             stats: crate::arrow::column_stats_from_schema(&schema),
             schema,
+            cipher: None,
         })
     }
 
+    /// Enables opt-in authenticated encryption of the serialized buffer returned
+    /// by [`ChunkWriter::finalize`].
+    pub fn with_cipher(mut self, cipher: super::ChunkCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
     /// Wrties the provided [`RecordBatch`].
     ///
     /// The `RecordBatch` is serialized according to the writer's format, and the internal statistics
@@ -80,16 +91,27 @@ impl ChunkWriter {
     ///
     /// This method must be called to complete the writing process. It consumes the writer object,
     /// preventing any further writes.
-    pub fn finalize(self) -> Result<(Vec<u8>, types::ColumnsStats), Error> {
+    pub fn finalize(self) -> Result<(Vec<u8>, types::ColumnsStats, ContentDigest), Error> {
         // We are calling `finish`` since the implementation is the same as
         // close but takes no ownership of the writer. And we return the internal data buffer.
-        let buffer = match self.writer {
+        let (buffer, mut stats) = match self.writer {
             Writer::Parquet(w) => {
                 let buffer = w.into_inner()?;
                 (buffer, self.stats)
             }
         };
-        Ok(buffer)
+        stats.finalize_blooms();
+
+        let buffer = match self.cipher {
+            Some(cipher) => cipher.encrypt(&buffer),
+            None => buffer,
+        };
+
+        // The digest is computed over the bytes that will actually be persisted,
+        // so that readers can re-hash the fetched buffer and detect corruption.
+        let digest = ContentDigest::of(&buffer);
+
+        Ok((buffer, stats, digest))
     }
 }
 
@@ -177,6 +199,7 @@ mod tests {
             assert_eq!(s.max, 3.0);
             assert!(!s.has_null);
             assert!(!s.has_nan);
+            assert!(s.has_non_null);
         } else {
             panic!("Missing or incorrect type for `timestamp` stats");
         }
@@ -187,6 +210,7 @@ mod tests {
             assert_eq!(v.max, 1.0);
             assert!(!v.has_null);
             assert!(!v.has_nan);
+            assert!(v.has_non_null);
         } else {
             panic!("Missing or incorrect type for `is_braking` stats");
         }
@@ -197,6 +221,7 @@ mod tests {
             assert_eq!(s.min, "a");
             assert_eq!(s.max, "c");
             assert!(s.has_null);
+            assert!(s.has_non_null);
         } else {
             panic!("Missing or incorrect type for label stats");
         }
@@ -207,6 +232,7 @@ mod tests {
             assert!((s.max - 0.3).abs() < params::EPSILON);
             assert!(!s.has_null);
             assert!(!s.has_nan);
+            assert!(s.has_non_null);
         } else {
             panic!("Missing or incorrect type for pose.x stats");
         }
@@ -217,6 +243,7 @@ mod tests {
             assert!((s.max - 1.3).abs() < params::EPSILON);
             assert!(!s.has_null);
             assert!(!s.has_nan);
+            assert!(s.has_non_null);
         } else {
             panic!("Missing or incorrect type for pose.y stats");
         }
@@ -226,7 +253,7 @@ mod tests {
         assert_eq!(cstats.stats.get("image"), Some(&types::Stats::Unsupported));
 
         // Finalize the writer (optional in test, but good practice)
-        let (buffer, _) = writer.finalize().expect("Failed to finalize writer");
+        let (buffer, _, _) = writer.finalize().expect("Failed to finalize writer");
 
         // Ensure that buffer is not empty
         dbg!(buffer.len());
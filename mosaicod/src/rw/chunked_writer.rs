@@ -6,6 +6,7 @@ use log::debug;
 
 use crate::{traits, types};
 
+use super::ContentDigest;
 use super::Error;
 use super::Format;
 use super::chunk_writer::ChunkWriter;
@@ -15,6 +16,7 @@ type OnChunkCallback = Box<
     dyn Fn(
             std::path::PathBuf,
             types::ColumnsStats,
+            ContentDigest,
         ) -> Pin<Box<dyn Future<Output = Result<(), Box<dyn std::error::Error>>> + Send>>
         + Send
         + Sync,
@@ -23,6 +25,33 @@ type OnChunkCallback = Box<
 /// Callback used to define a format function for files
 type OnFileFormat = Box<dyn Fn(&std::path::Path, &Format, usize) -> std::path::PathBuf + Send>;
 
+/// Content-addressed dedup hook: given a finalized chunk's digest, looks up
+/// whether an identical chunk was already persisted, returning the path it
+/// lives at if so. When it returns `Some`, [`ChunkedWriter::finalize`] skips
+/// the physical write and reuses that path.
+type OnChunkDedupCheck = Box<
+    dyn Fn(
+            ContentDigest,
+        )
+            -> Pin<Box<dyn Future<Output = Result<Option<PathBuf>, Box<dyn std::error::Error>>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// Progress hook fired after each chunk is durably (or dedup-)persisted,
+/// with the number of chunks and cumulative bytes written so far, and the
+/// chunk's path. Lets a caller checkpoint a resumable upload (see
+/// [`ChunkedWriter::resume_from`]) and surface progress to a polling client.
+type OnProgress = Box<
+    dyn Fn(
+            usize,
+            usize,
+            PathBuf,
+        ) -> Pin<Box<dyn Future<Output = Result<(), Box<dyn std::error::Error>>> + Send>>
+        + Send
+        + Sync,
+>;
+
 /// Writes [`RecordBatch`] into multiple chunks to a location. A location is a path like structure.
 /// Internally the [`ChunkedWriter`] can subdivide the batches in multiple files
 pub struct ChunkedWriter<'a, W>
@@ -44,10 +73,23 @@ where
     path: PathBuf,
     /// Number of chunks serialized
     chunk_serialized_number: usize,
+    /// Cumulative byte size of every chunk serialized so far, reported to
+    /// [`OnProgress`] for checkpointing.
+    total_bytes_written: usize,
     /// Function called just before the chunk finalization (and serialization)
     on_chunk_created_clbk: Option<OnChunkCallback>,
     /// Callback used to format data when written
     on_file_format: OnFileFormat,
+    /// When set, every [`ChunkWriter`] this writer creates encrypts its
+    /// buffer on [`ChunkWriter::finalize`].
+    cipher: Option<super::ChunkCipher>,
+    /// When set, consulted on every [`ChunkedWriter::finalize`] to dedup
+    /// identical chunk payloads against what's already persisted.
+    on_chunk_dedup_check: Option<OnChunkDedupCheck>,
+    /// When set, called after every [`ChunkedWriter::finalize`] with the
+    /// running chunk/byte counts, so a resumable upload can checkpoint its
+    /// progress.
+    on_progress: Option<OnProgress>,
 }
 
 impl<'a, W> ChunkedWriter<'a, W>
@@ -70,20 +112,85 @@ where
             format,
             path: path.as_ref().to_path_buf(),
             chunk_serialized_number: 0,
+            total_bytes_written: 0,
             on_chunk_created_clbk: None,
             on_file_format: Box::new(format_callback),
+            cipher: None,
+            on_chunk_dedup_check: None,
+            on_progress: None,
         }
     }
 
+    /// Enables encryption-at-rest for every chunk this writer produces, using
+    /// a cipher scoped to the topic writing through it (see
+    /// [`super::MasterKey::derive_cipher`]).
+    pub fn with_cipher(mut self, cipher: super::ChunkCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Resumes chunk numbering from `chunk_serialized_number` instead of
+    /// `0`, so re-attaching a writer to a topic that already has chunks
+    /// (e.g. after a dropped connection) continues the sequence instead of
+    /// overwriting them.
+    pub fn resume_from(mut self, chunk_serialized_number: usize) -> Self {
+        self.chunk_serialized_number = chunk_serialized_number;
+        self
+    }
+
+    /// Number of chunks finalized so far, including ones reused via a
+    /// dedup hit. Useful to report which chunk an upload failed on.
+    pub fn chunks_written(&self) -> usize {
+        self.chunk_serialized_number
+    }
+
+    /// Registers a content-addressed dedup check, consulted by
+    /// [`ChunkedWriter::finalize`] before every physical write (see
+    /// [`OnChunkDedupCheck`]).
+    pub fn on_chunk_dedup_check<F1, Fut>(mut self, clbk: F1) -> Self
+    where
+        F1: Fn(ContentDigest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Option<PathBuf>, Box<dyn std::error::Error>>> + Send + 'static,
+    {
+        let wrapped = move |digest| {
+            let fut = clbk(digest);
+            Box::pin(fut)
+                as Pin<Box<dyn Future<Output = Result<Option<PathBuf>, Box<dyn std::error::Error>>> + Send>>
+        };
+
+        self.on_chunk_dedup_check = Some(Box::new(wrapped));
+        self
+    }
+
+    /// Registers a progress callback, consulted by [`ChunkedWriter::finalize`]
+    /// after every chunk is persisted (see [`OnProgress`]).
+    pub fn on_progress<F1, Fut>(mut self, clbk: F1) -> Self
+    where
+        F1: Fn(usize, usize, PathBuf) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Box<dyn std::error::Error>>> + Send + 'static,
+    {
+        let wrapped = move |chunks_written, bytes_written, path| {
+            let fut = clbk(chunks_written, bytes_written, path);
+            Box::pin(fut)
+                as Pin<Box<dyn Future<Output = Result<(), Box<dyn std::error::Error>>> + Send>>
+        };
+
+        self.on_progress = Some(Box::new(wrapped));
+        self
+    }
+
     /// Sets a callback function that will be called every time a chunk is produced just before
     /// serialization.
     pub fn on_chunk_created<F1, Fut>(mut self, clbk: F1) -> Self
     where
-        F1: Fn(std::path::PathBuf, types::ColumnsStats) -> Fut + Send + Sync + 'static,
+        F1: Fn(std::path::PathBuf, types::ColumnsStats, ContentDigest) -> Fut
+            + Send
+            + Sync
+            + 'static,
         Fut: Future<Output = Result<(), Box<dyn std::error::Error>>> + Send + 'static,
     {
-        let wrapped = move |path, stats| {
-            let fut = clbk(path, stats);
+        let wrapped = move |path, stats, digest| {
+            let fut = clbk(path, stats, digest);
             Box::pin(fut)
                 as Pin<Box<dyn Future<Output = Result<(), Box<dyn std::error::Error>>> + Send>>
         };
@@ -107,7 +214,13 @@ where
         // chunk produced callback will be triggered
         let mut writer = match self.writer.take() {
             Some(w) => w,
-            None => ChunkWriter::try_new(batch.schema(), self.format)?,
+            None => {
+                let writer = ChunkWriter::try_new(batch.schema(), self.format)?;
+                match &self.cipher {
+                    Some(cipher) => writer.with_cipher(cipher.clone()),
+                    None => writer,
+                }
+            }
         };
 
         writer.write(batch)?;
@@ -126,13 +239,45 @@ where
         // If another write_batch willl be called after this function call
         // will cause the instantiation of another writer.
         if let Some(writer) = self.writer.take() {
-            let path =
-                (self.on_file_format)(&self.path, &writer.format, self.chunk_serialized_number);
-            self.chunk_serialized_number += 1;
+            let format = writer.format;
+            let (buffer, stats, digest) = writer.finalize()?;
+            let bytes_written = buffer.len();
 
-            let (buffer, stats) = writer.finalize()?;
+            let existing = match &self.on_chunk_dedup_check {
+                Some(check) => check(digest)
+                    .await
+                    .map_err(|e| Error::ChunkCreationCallbackError(e.to_string()))?,
+                None => None,
+            };
 
-            self.write_target.write_to_path(&path, buffer).await?;
+            let path = match existing {
+                Some(existing_path) => {
+                    debug!(
+                        "chunk digest={} already stored at `{}`, skipping physical write",
+                        digest,
+                        existing_path.to_string_lossy()
+                    );
+                    existing_path
+                }
+                None => {
+                    let path =
+                        (self.on_file_format)(&self.path, &format, self.chunk_serialized_number);
+                    self.write_target.write_to_path(&path, buffer).await?;
+                    path
+                }
+            };
+            self.chunk_serialized_number += 1;
+            self.total_bytes_written += bytes_written;
+
+            if let Some(progress) = &self.on_progress {
+                progress(
+                    self.chunk_serialized_number,
+                    self.total_bytes_written,
+                    path.clone(),
+                )
+                .await
+                .map_err(|e| Error::ChunkCreationCallbackError(e.to_string()))?;
+            }
 
             dbg!(self.on_chunk_created_clbk.is_some());
 
@@ -141,7 +286,7 @@ where
                 .as_ref()
                 .map(async move |clbk| {
                     debug!("calling chunk serialization callback");
-                    return clbk(path, stats).await;
+                    return clbk(path, stats, digest).await;
                 })
                 .unwrap()
                 .await
@@ -15,4 +15,23 @@ pub enum Error {
     ChunkCreationCallbackError(String),
     #[error("unsupported write format")]
     Unsupported,
+    /// Returned by [`crate::rw::ChunkCipher::decrypt`] when the buffer doesn't carry
+    /// the expected magic header, i.e. it was never encrypted.
+    #[error("buffer is not an encrypted chunk")]
+    NotEncrypted,
+    /// Returned when the encrypted buffer's version byte doesn't match the one
+    /// this build of [`crate::rw::ChunkCipher`] knows how to decrypt.
+    #[error("unsupported cipher version `{0}`")]
+    UnsupportedCipherVersion(u8),
+    /// Returned when the AEAD authentication tag doesn't match, meaning the
+    /// buffer was corrupted, tampered with, or encrypted with a different key.
+    #[error("chunk decryption failed: authentication tag mismatch")]
+    DecryptionFailed,
+    /// Returned when a re-hashed buffer doesn't match its stored content digest.
+    #[error("chunk integrity check failed: content digest mismatch")]
+    IntegrityMismatch,
+    /// Returned when a chunk's `content_digest_algo` doesn't name a
+    /// [`super::DigestAlgo`] this build knows how to verify against.
+    #[error("unknown digest algorithm `{0}`")]
+    UnknownDigestAlgo(String),
 }
@@ -18,6 +18,15 @@ pub enum Format {
     /// Serialization format for images and dense multi-dimensional arrays.
     /// This format is optimized for storing high-dimensional data efficiently.
     Image,
+
+    /// Arrow IPC stream format (`arrow::ipc::reader::StreamReader`), for
+    /// clients that already produce Arrow-native files instead of Parquet.
+    ArrowIpc,
+    /// Comma-separated values, with the schema either inferred from the
+    /// file or taken from the topic's recorded Arrow schema.
+    Csv,
+    /// Newline-delimited JSON records.
+    Json,
 }
 
 impl traits::AsExtension for Format {
@@ -26,6 +35,9 @@ impl traits::AsExtension for Format {
             Self::Default => params::ext::PARQUET.to_string(),
             Self::Ragged => params::ext::PARQUET.to_string(),
             Self::Image => params::ext::PARQUET.to_string(),
+            Self::ArrowIpc => params::ext::ARROW.to_string(),
+            Self::Csv => params::ext::CSV.to_string(),
+            Self::Json => params::ext::JSON.to_string(),
         }
     }
 }
@@ -36,6 +48,9 @@ impl std::fmt::Display for Format {
             Self::Default => write!(f, "default"),
             Self::Ragged => write!(f, "ragged"),
             Self::Image => write!(f, "image"),
+            Self::ArrowIpc => write!(f, "arrow_ipc"),
+            Self::Csv => write!(f, "csv"),
+            Self::Json => write!(f, "json"),
         }
     }
 }
@@ -48,6 +63,9 @@ impl std::str::FromStr for Format {
             "default" => Ok(Self::Default),
             "ragged" => Ok(Self::Ragged),
             "image" => Ok(Self::Image),
+            "arrow_ipc" => Ok(Self::ArrowIpc),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
             _ => Err(Error::UnkownFormat(value.to_string())),
         }
     }
@@ -76,12 +94,30 @@ mod tests {
         assert!(image.is_ok());
         assert_eq!(image.as_ref().unwrap(), &Format::Image);
         assert_eq!(image.unwrap().as_extension(), params::ext::PARQUET);
+
+        let arrow_ipc = Format::from_str("arrow_ipc");
+        assert!(arrow_ipc.is_ok());
+        assert_eq!(arrow_ipc.as_ref().unwrap(), &Format::ArrowIpc);
+        assert_eq!(arrow_ipc.unwrap().as_extension(), params::ext::ARROW);
+
+        let csv = Format::from_str("csv");
+        assert!(csv.is_ok());
+        assert_eq!(csv.as_ref().unwrap(), &Format::Csv);
+        assert_eq!(csv.unwrap().as_extension(), params::ext::CSV);
+
+        let json = Format::from_str("json");
+        assert!(json.is_ok());
+        assert_eq!(json.as_ref().unwrap(), &Format::Json);
+        assert_eq!(json.unwrap().as_extension(), params::ext::JSON);
     }
 
     #[test]
     fn to_str() {
         assert_eq!("ragged", Format::Ragged.to_string());
         assert_eq!("default", Format::Default.to_string());
+        assert_eq!("arrow_ipc", Format::ArrowIpc.to_string());
+        assert_eq!("csv", Format::Csv.to_string());
+        assert_eq!("json", Format::Json.to_string());
         assert_eq!("image", Format::Image.to_string());
     }
 }
@@ -11,8 +11,20 @@ pub use chunk_writer::*;
 
 mod writer;
 
+pub mod reader;
+pub use reader::Reader;
+
 pub mod chunked_writer;
 pub use chunked_writer::ChunkedWriter;
 
 pub mod chunk_reader;
 pub use chunk_reader::ChunkReader;
+
+pub mod cipher;
+pub use cipher::ChunkCipher;
+
+pub mod keys;
+pub use keys::*;
+
+pub mod digest;
+pub use digest::{ContentDigest, DigestAlgo};
@@ -0,0 +1,214 @@
+//! A Parquet reader that exploits the pruning metadata [`super::Writer`]
+//! already writes for the `timestamp` column -- row-group statistics, a
+//! page index, and a bloom filter (see `Writer::new`'s `Format::Ragged` and
+//! `Format::Image` branches) -- instead of decoding every row group and
+//! page unconditionally the way [`super::ChunkReader`] does.
+
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use parquet::arrow::arrow_reader::{
+    ArrowReaderOptions, ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder, RowSelection,
+    RowSelector,
+};
+use parquet::file::metadata::RowGroupMetaData;
+use parquet::file::page_index::index::Index;
+use parquet::file::statistics::Statistics;
+
+use crate::types::Timestamp;
+
+use super::Error;
+
+/// The column [`super::Writer`] enables statistics/page-index/bloom-filter
+/// pruning for. A blob whose schema has no column by this name (or one that
+/// wasn't written with those enabled, e.g. `Format::Default`) still reads
+/// correctly here -- pruning is simply skipped, not an error.
+const TIMESTAMP_COLUMN: &str = "timestamp";
+
+/// Reads a Parquet blob with optional pushdown pruning against its
+/// `timestamp` column. Iterates the surviving [`RecordBatch`]es the same
+/// way [`super::ChunkReader`] does, so callers don't need a different shape
+/// depending on whether a query was pruned.
+pub struct Reader {
+    reader: ParquetRecordBatchReader,
+    schema: SchemaRef,
+}
+
+impl Reader {
+    /// Reads every row in `buffer`, no pruning applied.
+    pub fn try_new(buffer: bytes::Bytes) -> Result<Self, Error> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(buffer)?;
+        let schema = builder.schema().clone();
+        Ok(Self {
+            reader: builder.build()?,
+            schema,
+        })
+    }
+
+    /// Returns only the rows whose `timestamp` falls in `[lo, hi]`
+    /// (inclusive), pruning whole row groups via their `timestamp` column
+    /// statistics, then pages within the surviving row groups via the page
+    /// index, before any page is decompressed.
+    pub fn scan_time_range(buffer: bytes::Bytes, lo: Timestamp, hi: Timestamp) -> Result<Self, Error> {
+        Self::scan(buffer, lo.into(), hi.into(), false)
+    }
+
+    /// Returns only the rows whose `timestamp` equals `at`. Like
+    /// [`Self::scan_time_range`] with `lo == hi == at`, but additionally
+    /// consults each candidate row group's bloom filter first and skips any
+    /// that report `at` definitely absent, before paying for page reads.
+    pub fn scan_time_point(buffer: bytes::Bytes, at: Timestamp) -> Result<Self, Error> {
+        let at: i64 = at.into();
+        Self::scan(buffer, at, at, true)
+    }
+
+    fn scan(buffer: bytes::Bytes, lo: i64, hi: i64, use_bloom: bool) -> Result<Self, Error> {
+        let options = ArrowReaderOptions::new().with_page_index(true);
+        let mut builder = ParquetRecordBatchReaderBuilder::try_new_with_options(buffer, options)?;
+        let schema = builder.schema().clone();
+
+        let Some(ts_idx) = timestamp_column_index(&builder) else {
+            return Ok(Self {
+                reader: builder.build()?,
+                schema,
+            });
+        };
+
+        let candidates: Vec<usize> = builder
+            .metadata()
+            .row_groups()
+            .iter()
+            .enumerate()
+            .filter(|(_, rg)| row_group_overlaps(rg, ts_idx, lo, hi))
+            .map(|(i, _)| i)
+            .collect();
+
+        let surviving: Vec<usize> = if use_bloom {
+            let mut kept = Vec::with_capacity(candidates.len());
+            for rg in candidates {
+                // A bloom filter can only prove absence, never presence --
+                // fall through to keeping the row group whenever it's
+                // missing, errors out, or reports a possible match.
+                match builder.get_row_group_column_bloom_filter(rg, ts_idx) {
+                    Ok(Some(sbbf)) if !sbbf.check(&lo) => continue,
+                    _ => kept.push(rg),
+                }
+            }
+            kept
+        } else {
+            candidates
+        };
+
+        let selection = page_row_selection(&builder, &surviving, ts_idx, lo, hi);
+
+        let mut builder = builder.with_row_groups(surviving);
+        if let Some(selection) = selection {
+            builder = builder.with_row_selection(selection);
+        }
+
+        Ok(Self {
+            reader: builder.build()?,
+            schema,
+        })
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Iterator for Reader {
+    type Item = Result<RecordBatch, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.next().map(|res| res.map_err(Error::from))
+    }
+}
+
+fn timestamp_column_index(
+    builder: &ParquetRecordBatchReaderBuilder<bytes::Bytes>,
+) -> Option<usize> {
+    builder
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .columns()
+        .iter()
+        .position(|c| c.name() == TIMESTAMP_COLUMN)
+}
+
+/// Whether `rg`'s recorded `[min, max]` statistics on column `ts_idx`
+/// overlap `[lo, hi]`. Row groups with no (or non-`Int64`) statistics
+/// recorded on that column can't be proven out of range, so they're kept.
+fn row_group_overlaps(rg: &RowGroupMetaData, ts_idx: usize, lo: i64, hi: i64) -> bool {
+    let Some(stats) = rg.column(ts_idx).statistics() else {
+        return true;
+    };
+
+    match stats {
+        Statistics::Int64(vs) => match (vs.min_opt(), vs.max_opt()) {
+            (Some(min), Some(max)) => *max >= lo && *min <= hi,
+            _ => true,
+        },
+        _ => true,
+    }
+}
+
+/// Builds a [`RowSelection`] over `row_groups`' pages on column `ts_idx`,
+/// skipping pages whose `[min, max]` don't overlap `[lo, hi]`. Returns
+/// `None` (select everything) if this blob has no page index at all, e.g.
+/// it wasn't written with `EnabledStatistics::Page` on this column.
+fn page_row_selection(
+    builder: &ParquetRecordBatchReaderBuilder<bytes::Bytes>,
+    row_groups: &[usize],
+    ts_idx: usize,
+    lo: i64,
+    hi: i64,
+) -> Option<RowSelection> {
+    let metadata = builder.metadata();
+    let offset_index = metadata.offset_index()?;
+    let column_index = metadata.column_index()?;
+
+    let mut selectors = Vec::new();
+
+    for &rg in row_groups {
+        let num_rows = metadata.row_groups()[rg].num_rows() as usize;
+
+        let (Some(col_offsets), Some(col_index)) = (
+            offset_index.get(rg).and_then(|cols| cols.get(ts_idx)),
+            column_index.get(rg).and_then(|cols| cols.get(ts_idx)),
+        ) else {
+            selectors.push(RowSelector::select(num_rows));
+            continue;
+        };
+
+        let Index::INT64(native) = col_index else {
+            // No (or non-`Int64`) page stats recorded for this column in
+            // this row group -- can't prove any page out of range.
+            selectors.push(RowSelector::select(num_rows));
+            continue;
+        };
+
+        let locations = col_offsets.page_locations();
+        for (i, page) in native.indexes.iter().enumerate() {
+            let first_row = locations[i].first_row_index as usize;
+            let next_row = locations
+                .get(i + 1)
+                .map(|l| l.first_row_index as usize)
+                .unwrap_or(num_rows);
+            let page_len = next_row - first_row;
+
+            let in_range = match (page.min, page.max) {
+                (Some(min), Some(max)) => max >= lo && min <= hi,
+                _ => true,
+            };
+
+            if in_range {
+                selectors.push(RowSelector::select(page_len));
+            } else {
+                selectors.push(RowSelector::skip(page_len));
+            }
+        }
+    }
+
+    Some(RowSelection::from(selectors))
+}
@@ -12,7 +12,20 @@ use super::{Error, Format};
 
 pub enum Writer {
     /// Parquet file format https://parquet.apache.org/docs/file-format/
-    /// (cabba) TODO: evaluate AsyncArrowWriter
+    ///
+    /// Stays on the sync `ArrowWriter<Vec<u8>>` rather than
+    /// `AsyncArrowWriter` uploading row groups straight to the object
+    /// store as they close: `ChunkedWriter::finalize` needs this chunk's
+    /// complete bytes up front to compute its [`super::ContentDigest`]
+    /// (the dedup check decides whether to write *at all* from that
+    /// digest, so it can't be known mid-stream) and, when encryption is
+    /// enabled, to run the whole buffer through [`super::ChunkCipher`] as
+    /// one AEAD payload. Streaming row groups out early would mean
+    /// uploading bytes before it's known they're needed, or before they're
+    /// encrypted. Once finalized, a large chunk's buffer still goes out
+    /// through `store::Store`'s multipart uploader rather than a single
+    /// PUT (see its `write_to_path` impl), so this only bounds the memory
+    /// of one chunk at a time, not a whole topic's.
     Parquet(ArrowWriter<Vec<u8>>),
 }
 
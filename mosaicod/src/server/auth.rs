@@ -0,0 +1,197 @@
+//! SASL PLAIN authentication over the Flight `handshake` RPC, plus the
+//! bearer-token bookkeeping used to authorize every call that follows it.
+//!
+//! Arrow Flight defines `handshake` as a generic, mechanism-agnostic
+//! exchange: the client sends a [`arrow_flight::HandshakeRequest`] payload
+//! and the server replies with a [`arrow_flight::HandshakeResponse`]
+//! payload. This module implements that payload as a single round of
+//! [SASL PLAIN](https://datatracker.ietf.org/doc/html/rfc4616)
+//! (`\0username\0password`) and, on success, returns an opaque bearer token
+//! the client is expected to send back as `authorization: Bearer <token>`
+//! metadata on every subsequent call.
+
+use std::collections::HashSet;
+use std::sync::RwLock;
+
+use tonic::Status;
+
+use crate::utils;
+
+/// The single username/password pair `mosaicod` accepts over `handshake`.
+///
+/// There is no per-user credential store yet; every client authenticates as
+/// the same principal.
+#[derive(Clone)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl Credentials {
+    fn verify(&self, username: &str, password: &str) -> bool {
+        // Plain `==` short-circuits on the first mismatched byte, leaking
+        // how many leading characters of the username/password guessed
+        // correctly through response timing. Compare in constant time
+        // instead, particularly for the password.
+        constant_time_eq(username.as_bytes(), self.username.as_bytes())
+            & constant_time_eq(password.as_bytes(), self.password.as_bytes())
+    }
+}
+
+/// Compares two byte strings in constant time with respect to their
+/// contents, to avoid leaking a timing side-channel on credential checks.
+///
+/// Unequal lengths are rejected up front (and so are distinguishable by
+/// timing), but `mosaicod` only ever compares against a fixed configured
+/// username/password, not attacker-controlled lengths, so that's not a
+/// useful signal here.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+/// Bearer tokens issued by a successful [`handshake`].
+///
+/// Tokens live only in memory and are forgotten on restart, so a client must
+/// re-run `handshake` after the server restarts. A token is not tied to the
+/// connection it was issued on.
+#[derive(Clone, Default)]
+pub struct TokenStore {
+    issued: std::sync::Arc<RwLock<HashSet<String>>>,
+}
+
+impl TokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn issue(&self) -> String {
+        let token = utils::random_string(32);
+        self.issued
+            .write()
+            .expect("token store lock poisoned")
+            .insert(token.clone());
+        token
+    }
+
+    fn is_valid(&self, token: &str) -> bool {
+        self.issued
+            .read()
+            .expect("token store lock poisoned")
+            .contains(token)
+    }
+}
+
+/// Parses a SASL PLAIN payload (`[authzid] \0 authcid \0 passwd`), returning
+/// the `(authcid, passwd)` pair. The optional authorization identity is
+/// ignored, as `mosaicod` has no notion of acting on another user's behalf.
+fn parse_sasl_plain(payload: &[u8]) -> Result<(String, String), Status> {
+    let text = std::str::from_utf8(payload)
+        .map_err(|_| Status::invalid_argument("handshake payload is not valid UTF-8"))?;
+
+    let mut parts = text.split('\0');
+    let _authzid = parts
+        .next()
+        .ok_or_else(|| Status::invalid_argument("malformed SASL PLAIN payload"))?;
+    let authcid = parts
+        .next()
+        .ok_or_else(|| Status::invalid_argument("malformed SASL PLAIN payload"))?;
+    let passwd = parts
+        .next()
+        .ok_or_else(|| Status::invalid_argument("malformed SASL PLAIN payload"))?;
+
+    Ok((authcid.to_string(), passwd.to_string()))
+}
+
+/// Validates a `handshake` request's SASL PLAIN payload against `creds` and,
+/// on success, issues and returns a bearer token from `tokens`.
+pub fn handshake(
+    creds: &Credentials,
+    tokens: &TokenStore,
+    payload: &[u8],
+) -> Result<Vec<u8>, Status> {
+    let (username, password) = parse_sasl_plain(payload)?;
+
+    if !creds.verify(&username, &password) {
+        return Err(Status::unauthenticated("invalid credentials"));
+    }
+
+    Ok(tokens.issue().into_bytes())
+}
+
+/// Checks that `metadata` carries an `authorization: Bearer <token>` entry
+/// naming a token previously issued by [`handshake`].
+pub fn authorize(tokens: &TokenStore, metadata: &tonic::metadata::MetadataMap) -> Result<(), Status> {
+    let header = metadata
+        .get("authorization")
+        .ok_or_else(|| Status::unauthenticated("missing authorization metadata"))?
+        .to_str()
+        .map_err(|_| Status::unauthenticated("malformed authorization metadata"))?;
+
+    let token = header.strip_prefix("Bearer ").unwrap_or(header);
+
+    if !tokens.is_valid(token) {
+        return Err(Status::unauthenticated("invalid or expired token"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn creds() -> Credentials {
+        Credentials {
+            username: "robot".to_string(),
+            password: "hunter2".to_string(),
+        }
+    }
+
+    #[test]
+    fn handshake_issues_a_token_for_valid_credentials() {
+        let tokens = TokenStore::new();
+        let token = handshake(&creds(), &tokens, b"\0robot\0hunter2").expect("should authenticate");
+
+        assert!(tokens.is_valid(std::str::from_utf8(&token).unwrap()));
+    }
+
+    #[test]
+    fn handshake_rejects_wrong_password() {
+        let tokens = TokenStore::new();
+        assert!(handshake(&creds(), &tokens, b"\0robot\0wrong").is_err());
+    }
+
+    #[test]
+    fn handshake_rejects_malformed_payload() {
+        let tokens = TokenStore::new();
+        assert!(handshake(&creds(), &tokens, b"not-sasl-plain").is_err());
+    }
+
+    #[test]
+    fn authorize_rejects_unknown_token() {
+        let tokens = TokenStore::new();
+        let mut metadata = tonic::metadata::MetadataMap::new();
+        metadata.insert("authorization", "Bearer nope".parse().unwrap());
+
+        assert!(authorize(&tokens, &metadata).is_err());
+    }
+
+    #[test]
+    fn authorize_accepts_an_issued_token() {
+        let tokens = TokenStore::new();
+        let token = handshake(&creds(), &tokens, b"\0robot\0hunter2").expect("should authenticate");
+
+        let mut metadata = tonic::metadata::MetadataMap::new();
+        let header = format!("Bearer {}", std::str::from_utf8(&token).unwrap());
+        metadata.insert("authorization", header.parse().unwrap());
+
+        assert!(authorize(&tokens, &metadata).is_ok());
+    }
+}
@@ -3,9 +3,9 @@ use std::sync::Arc;
 use log::{error, info, trace};
 use tokio::sync::Notify;
 
-use crate::{repo, store};
+use crate::{metrics, repo, store};
 
-use super::flight;
+use super::{flight, jobs, tls};
 
 /// Mosaico server.
 /// Handles incoming requests and manages the repository and store.
@@ -14,21 +14,41 @@ pub struct Server {
     pub host: bool,
 
     pub port: u16,
+    /// Port the Prometheus `/metrics` endpoint listens on. `0` disables it.
+    pub metrics_port: u16,
     /// Shutdown notifier used to signal server shutdown
     pub shutdown: flight::ShutdownNotifier,
     /// Store engine
     store: store::StoreRef,
     /// Repository configuration params
     pub repo_config: repo::Config,
+    /// Credentials accepted over the Flight `handshake` RPC. `None` leaves
+    /// the server unauthenticated, matching its historical behavior.
+    pub auth: Option<super::Credentials>,
+    /// Source of the Flight listener's TLS certificate. `None` serves the
+    /// Flight service in plaintext, matching its historical behavior.
+    pub tls: Option<tls::TlsSource>,
 }
 
 impl Server {
-    pub fn new(host: bool, port: u16, store: store::StoreRef, repo_config: repo::Config) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        host: bool,
+        port: u16,
+        metrics_port: u16,
+        store: store::StoreRef,
+        repo_config: repo::Config,
+        auth: Option<super::Credentials>,
+        tls: Option<tls::TlsSource>,
+    ) -> Self {
         Self {
             host,
             port,
+            metrics_port,
             store,
             repo_config,
+            auth,
+            tls,
             shutdown: Arc::new(Notify::new()),
         }
     }
@@ -46,11 +66,8 @@ impl Server {
         F: FnOnce(),
     {
         let host = if self.host { "0.0.0.0" } else { "127.0.0.1" };
-
-        let config = flight::Config {
-            host: host.to_string(),
-            port: self.port,
-        };
+        let port = self.port;
+        let auth = self.auth.clone();
 
         let shutdown = self.shutdown.clone();
 
@@ -71,7 +88,7 @@ impl Server {
             info!("repository initialization");
             let mut tx = repo.transaction().await?;
 
-            repo::layer_bootstrap(&mut tx).await?;
+            repo::migrate(&mut tx).await?;
 
             tx.commit().await?;
 
@@ -79,18 +96,86 @@ impl Server {
         })?;
 
         let store = self.store.clone();
+
+        info!("resuming any delete jobs left incomplete by a previous run");
+        rt.block_on(repo::FacadeSequence::resume_jobs(repo.clone(), store.clone()))
+            .inspect_err(|e| error!("failed to resume incomplete delete jobs: {}", e))?;
+
+        info!(
+            "startup job queue ({} worker(s))",
+            self.repo_config.job_workers
+        );
+        jobs::spawn(
+            &rt,
+            repo.clone(),
+            store.clone(),
+            self.repo_config.job_workers,
+            self.repo_config.job_heartbeat_interval,
+            self.repo_config.job_heartbeat_timeout,
+            self.repo_config.job_max_retries,
+            self.repo_config.job_poll_warn_threshold,
+            shutdown.clone(),
+        );
+
+        let tls_material = match &self.tls {
+            Some(source) => {
+                info!("startup tls ({})", tls_display_name(source));
+                Some(rt.block_on(tls::provision(source, &store))?)
+            }
+            None => None,
+        };
+
+        let (tls_tx, mut tls_rx) = tokio::sync::watch::channel(tls_material);
+        if let Some(source) = self.tls.clone() {
+            tls::spawn_renewal(&rt, source, store.clone(), shutdown.clone(), tls_tx);
+        }
+
+        let metrics_port = self.metrics_port;
+        let metrics_shutdown = shutdown.clone();
+        let handle_metrics = if metrics_port != 0 {
+            info!("startup metrics endpoint on port {}", metrics_port);
+            Some(rt.spawn(async move {
+                if let Err(err) = metrics::serve(host, metrics_port, metrics_shutdown).await {
+                    error!("metrics endpoint error: {}", err);
+                }
+            }))
+        } else {
+            None
+        };
+
         rt.block_on(async {
             // Create a thread in tokio runtime to handle flight requests
             let handle_flight = rt.spawn(async move {
-                trace!("flight service starting");
-                if let Err(err) = flight::start(config, store, repo, Some(shutdown)).await {
-                    error!("flight server error: {}", err);
+                loop {
+                    let config = flight::Config {
+                        host: host.to_string(),
+                        port,
+                        auth: auth.clone(),
+                        tls: tls_rx.borrow().clone(),
+                    };
+
+                    trace!("flight service starting");
+                    tokio::select! {
+                        res = flight::start(config, store.clone(), repo.clone(), Some(shutdown.clone())) => {
+                            if let Err(err) = res {
+                                error!("flight server error: {}", err);
+                            }
+                            break;
+                        }
+                        Ok(()) = tls_rx.changed() => {
+                            info!("tls certificate renewed, restarting flight listener");
+                        }
+                    }
                 }
             });
 
             on_start();
 
-            let _ = tokio::join!(handle_flight);
+            if let Some(handle_metrics) = handle_metrics {
+                let _ = tokio::join!(handle_flight, handle_metrics);
+            } else {
+                let _ = tokio::join!(handle_flight);
+            }
         });
 
         info!("stopped");
@@ -98,3 +183,10 @@ impl Server {
         Ok(())
     }
 }
+
+fn tls_display_name(source: &tls::TlsSource) -> String {
+    match source {
+        tls::TlsSource::Static { .. } => "static certificate".to_string(),
+        tls::TlsSource::Acme { domain, .. } => format!("acme, domain `{}`", domain),
+    }
+}
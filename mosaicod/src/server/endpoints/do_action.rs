@@ -4,9 +4,10 @@ use log::{info, trace, warn};
 
 use crate::{
     marshal::{self, ActionRequest, ActionResponse},
-    query,
-    repo::{self, FacadeError, FacadeLayer, FacadeSequence, FacadeTopic},
-    server::errors::ServerError,
+    metrics, params, query,
+    repo::{self, FacadeError, FacadeJobQueue, FacadeLayer, FacadeRepair, FacadeSequence, FacadeTopic},
+    rw,
+    server::{errors::ServerError, jobs},
     store, types,
     types::{MetadataBlob, Resource},
 };
@@ -17,6 +18,8 @@ pub async fn do_action(
     ts_engine: query::TimeseriesGwRef,
     action: ActionRequest,
 ) -> Result<ActionResponse, ServerError> {
+    let _timer = metrics::start_action_timer(action.name());
+
     let response = match action {
         ActionRequest::SequenceCreate(data) => {
             info!("requested resource {} creation", data.name);
@@ -49,23 +52,25 @@ pub async fn do_action(
         ActionRequest::SequenceDelete(data) => {
             warn!("requested deletion of resource {}", data.name);
 
-            let handle = FacadeSequence::new(data.name, store, repo);
+            let handle = FacadeSequence::new(data.name.clone(), store, repo.clone());
 
             if handle.is_locked().await? {
                 return Err(ServerError::SequenceLocked);
             }
 
-            let loc = handle.locator.clone();
-            handle.delete().await?;
-            warn!("resource {} deleted", loc);
+            // Deleting a sequence can mean deleting thousands of chunks, so
+            // the actual deletion runs on the job queue instead of inline.
+            let mut cx = repo.connection();
+            let job_id = jobs::enqueue_sequence_delete(&mut cx, &data.name).await?;
+            warn!("resource {} queued for deletion (job {})", data.name, job_id);
 
-            ActionResponse::Empty
+            ActionResponse::JobAccepted(job_id.into())
         }
 
         ActionRequest::SequenceAbort(data) => {
             warn!("abort for {}", data.name);
 
-            let handle = FacadeSequence::new(data.name, store, repo);
+            let handle = FacadeSequence::new(data.name.clone(), store, repo.clone());
 
             // Avoid aborting on locked sequences
             if handle.is_locked().await? {
@@ -79,18 +84,19 @@ pub async fn do_action(
                 return Err(ServerError::BadKey);
             }
 
-            // Save handle name (for logging) since the delete will consume the handle
-            let loc = handle.locator.clone();
-            handle.delete().await?;
-            warn!("resource {} deleted", loc.name());
+            // The abort deletes the sequence's data, so defer it to the job
+            // queue just like `SequenceDelete`.
+            let mut cx = repo.connection();
+            let job_id = jobs::enqueue_sequence_abort(&mut cx, &data.name, &data.key).await?;
+            warn!("resource {} queued for abort (job {})", data.name, job_id);
 
-            ActionResponse::Empty
+            ActionResponse::JobAccepted(job_id.into())
         }
 
         ActionRequest::SequenceFinalize(data) => {
             info!("resource {} finalized", data.name);
 
-            let handle = FacadeSequence::new(data.name, store, repo);
+            let handle = FacadeSequence::new(data.name.clone(), store, repo.clone());
 
             // Check that key matches the sequence id
             let r_id = handle.resource_id().await?;
@@ -100,10 +106,13 @@ pub async fn do_action(
                 return Err(ServerError::BadKey);
             }
 
-            handle.lock().await?;
-            trace!("resource {} locked", handle.locator);
+            // Finalization computes stats over every chunk in the
+            // sequence, so it's deferred to the job queue too.
+            let mut cx = repo.connection();
+            let job_id = jobs::enqueue_sequence_finalize(&mut cx, &data.name, &data.key).await?;
+            trace!("resource {} queued for finalize (job {})", data.name, job_id);
 
-            ActionResponse::Empty
+            ActionResponse::JobAccepted(job_id.into())
         }
 
         ActionRequest::SequenceNotifyCreate(data) => {
@@ -161,10 +170,19 @@ pub async fn do_action(
                 marshal::JsonMetadataBlob::try_from_str(data.user_metadata()?.as_str())
                     .map_err(FacadeError::from)?;
 
-            let mdata = types::TopicMetadata::new(
-                types::TopicProperties::new(data.serialization_format, data.ontology_tag),
-                user_mdata,
-            );
+            let mut properties =
+                types::TopicProperties::new(data.serialization_format, data.ontology_tag);
+            if data.encrypted {
+                if params::master_key().is_none() {
+                    return Err(ServerError::MissingMasterKey);
+                }
+                properties = properties.with_encryption(types::EncryptionInfo {
+                    key_id: uuid::Uuid::new_v4().to_string(),
+                    algorithm: rw::ALGORITHM_CHACHA20POLY1305.to_string(),
+                });
+            }
+
+            let mdata = types::TopicMetadata::new(properties, user_mdata);
 
             let received_uuid: uuid::Uuid = data.sequence_key.parse()?;
 
@@ -181,16 +199,19 @@ pub async fn do_action(
         ActionRequest::TopicDelete(data) => {
             warn!("requested deletion of resource {}", data.name);
 
-            let handle = FacadeTopic::new(data.name.clone(), store, repo);
+            let handle = FacadeTopic::new(data.name.clone(), store, repo.clone());
 
             if handle.is_locked().await? {
                 return Err(ServerError::SequenceLocked);
             }
 
-            handle.delete().await?;
-            warn!("resource {} deleted", data.name);
+            // Same rationale as `SequenceDelete`: defer the heavy deletion
+            // to the job queue.
+            let mut cx = repo.connection();
+            let job_id = jobs::enqueue_topic_delete(&mut cx, &data.name).await?;
+            warn!("resource {} queued for deletion (job {})", data.name, job_id);
 
-            ActionResponse::Empty
+            ActionResponse::JobAccepted(job_id.into())
         }
 
         ActionRequest::TopicNotifyCreate(data) => {
@@ -237,6 +258,42 @@ pub async fn do_action(
             ActionResponse::TopicSystemInfo(sysinfo.into())
         }
 
+        ActionRequest::TopicUploadStatus(data) => {
+            info!("[{}] topic upload status", data.name);
+
+            let handle = FacadeTopic::new(data.name, store, repo);
+            let status = handle.upload_status().await?;
+
+            ActionResponse::TopicUploadStatus(status.into())
+        }
+
+        ActionRequest::TopicVerify(data) => {
+            info!("[{}] topic verify", data.name);
+
+            let handle = FacadeTopic::new(data.name, store, repo);
+            let report = handle.verify().await?;
+
+            ActionResponse::TopicVerify(report.into())
+        }
+
+        ActionRequest::TopicCompact(data) => {
+            warn!("requested compaction of resource {}", data.name);
+
+            let handle = FacadeTopic::new(data.name.clone(), store, repo.clone());
+
+            if !handle.is_locked().await? {
+                return Err(FacadeError::TopicUnlocked.into());
+            }
+
+            // Same rationale as `TopicDelete`: defer the heavy rewrite to
+            // the job queue rather than blocking this request.
+            let mut cx = repo.connection();
+            let job_id = jobs::enqueue_topic_compact(&mut cx, &data.name).await?;
+            warn!("resource {} queued for compaction (job {})", data.name, job_id);
+
+            ActionResponse::JobAccepted(job_id.into())
+        }
+
         ActionRequest::LayerCreate(data) => {
             info!("creating layer `{}`", data.name);
 
@@ -292,6 +349,49 @@ pub async fn do_action(
             ActionResponse::LayerList(layers.into())
         }
 
+        ActionRequest::Watch(data) => {
+            trace!("watching for topic changes since token {}", data.since_token);
+
+            // `0` starts a fresh watch from the current high-water token, so
+            // only changes published from here on are reported.
+            let since_token = match data.since_token {
+                0 => repo::watch::current_token(),
+                token => token,
+            };
+
+            let filter = marshal::query_filter_from_serde_value(data.query)?;
+            let (seq_filt, top_filt, _ontology) = filter.into_parts();
+
+            // No sequence/topic filter means "watch every topic", encoded
+            // as an empty name list rather than resolving the full catalog
+            // up front (which would go stale the moment a new topic is
+            // created, the exact case this action exists to report).
+            let names = if seq_filt.is_none() && top_filt.is_none() {
+                Vec::new()
+            } else {
+                let mut cx = repo.connection();
+                repo::topic_from_query_filter(&mut cx, seq_filt, top_filt)
+                    .await?
+                    .into_iter()
+                    .map(|record| record.topic_name)
+                    .collect()
+            };
+
+            let timeout = std::time::Duration::from_millis(data.timeout_ms);
+            let (token, changes) = repo::watch::watch(since_token, &names, timeout).await;
+
+            ActionResponse::Watch((token, changes).into())
+        }
+
+        ActionRequest::JobStatus(data) => {
+            trace!("polling status of job {}", data.job_id);
+
+            let id: uuid::Uuid = data.job_id.parse()?;
+            let state = FacadeJobQueue::find(&repo, id).await?;
+
+            ActionResponse::JobStatus(state.into())
+        }
+
         // (cabba) FIXME: move this code in a QueryFacade in order to avoid using
         // repo low level function directly, do this when the query system is finalized
         ActionRequest::Query(data) => {
@@ -312,39 +412,59 @@ pub async fn do_action(
             let mut topics = repo::topic_from_query_filter(&mut cx, seq_filt, top_filt).await?;
 
             trace!("topics found after initial filtering {:?}", topics);
+            metrics::collectors()
+                .query_candidate_topics
+                .set(topics.len() as i64);
 
             // Here we loop for all fields and ops defined in the query for the ontology catalog,
             // trying to find a chunk that matches. If a chunk is found a query to the chunk
             // data file is performed to find at least a match.
             if let Some(data_catalog_filter) = dc_filt {
                 let mut filtered_topics: HashSet<i32> = HashSet::new();
+                let (mut chunks_opened, mut chunks_discarded, mut records_matched) = (0i64, 0i64, 0i64);
 
                 let chunks = repo::chunks_from_filters(
                     &mut cx, // comment for formatting
-                    data_catalog_filter.clone(),
+                    data_catalog_filter.clone().into(),
                     Some(&topics),
                 )
                 .await?;
 
                 trace!("found {} chunks for provided filter", chunks.len());
+                metrics::collectors()
+                    .query_chunks_enumerated
+                    .set(chunks.len() as i64);
+
+                // Pre-queue every chunk's topic id so the `no_topic_filter`
+                // branch below resolves them all in one batched query
+                // instead of one `topic_find_by_ids` call per chunk.
+                let topic_loader = cx.topic_loader();
+                if no_topic_filter {
+                    for chunk in &chunks {
+                        topic_loader.queue(chunk.topic_id);
+                    }
+                }
 
                 for chunk in chunks {
                     trace!("checking chunk `{}`", chunk.chunk_uuid);
 
                     let topic = if no_topic_filter {
-                        topics.append(
-                            &mut repo::topic_find_by_ids(&mut cx, &[chunk.topic_id]).await?,
-                        );
-                        topics.last()
+                        let topic = topic_loader.load(chunk.topic_id).await?;
+                        if let Some(topic) = &topic {
+                            topics.push(topic.clone());
+                        }
+                        topic
                     } else {
-                        topics.iter().find(|topic| topic.topic_id == chunk.topic_id)
+                        topics
+                            .iter()
+                            .find(|topic| topic.topic_id == chunk.topic_id)
+                            .cloned()
                     };
 
-                    if topic.is_none() {
+                    let Some(topic) = topic else {
                         trace!("can't found a topic associated with the current chunk, skipping.");
                         continue;
-                    }
-                    let topic = topic.unwrap();
+                    };
 
                     trace!(
                         "performing query on data file `{}`",
@@ -358,6 +478,7 @@ pub async fn do_action(
                             false,
                         )
                         .await?;
+                    chunks_opened += 1;
 
                     let qr = qr.filter(data_catalog_filter.clone())?;
 
@@ -366,14 +487,24 @@ pub async fn do_action(
                     if count != 0 {
                         trace!("found {count} records matching filter in chunk");
                         filtered_topics.insert(topic.topic_id);
+                        records_matched += count as i64;
                     } else {
                         trace!(
                             "discarding chunk `{}` for no query match (row count is {count})",
                             chunk.chunk_uuid
-                        )
+                        );
+                        chunks_discarded += 1;
                     }
                 }
 
+                metrics::collectors().query_chunks_opened.set(chunks_opened);
+                metrics::collectors()
+                    .query_chunks_discarded
+                    .set(chunks_discarded);
+                metrics::collectors()
+                    .query_records_matched
+                    .set(records_matched);
+
                 dbg!(&filtered_topics);
                 topics.retain(|e| filtered_topics.contains(&e.topic_id));
             }
@@ -382,6 +513,82 @@ pub async fn do_action(
 
             ActionResponse::Query(group.into())
         }
+
+        ActionRequest::Batch(mode, actions) => {
+            info!("running batch of {} actions in {:?} mode", actions.len(), mode);
+
+            match mode {
+                marshal::requests::BatchMode::BestEffort => {
+                    let mut results = Vec::with_capacity(actions.len());
+                    for action in actions {
+                        let fut =
+                            do_action(store.clone(), repo.clone(), ts_engine.clone(), action);
+                        let outcome = Box::pin(fut).await.map_err(|e| {
+                            let code = e.err_code();
+                            marshal::responses::BatchItemError {
+                                code: code.code.to_string(),
+                                message: e.to_string(),
+                            }
+                        });
+                        results.push(outcome);
+                    }
+
+                    ActionResponse::Batch(results)
+                }
+
+                // Fail-fast, not a transaction: stops and reports the first
+                // error instead of continuing, but does not roll back
+                // sub-actions that already ran (see the `BatchMode::FailFast`
+                // doc comment).
+                //
+                // Real transactional atomicity for batches is still
+                // unimplemented and open -- tracked, not closed, as
+                // `OPEN_BACKLOG_ITEMS.md`'s `chunk5-4` entry. It would need a
+                // single `Tx` threaded through every `Facade*` call a
+                // batch's sub-actions can reach, instead of each call
+                // opening and committing its own
+                // `repo.transaction()`/`repo.connection()` as today -- a
+                // larger refactor across the facade layer, not something
+                // this match arm can provide on its own.
+                marshal::requests::BatchMode::FailFast => {
+                    let mut results = Vec::with_capacity(actions.len());
+                    for (index, action) in actions.into_iter().enumerate() {
+                        let fut =
+                            do_action(store.clone(), repo.clone(), ts_engine.clone(), action);
+                        let outcome =
+                            Box::pin(fut)
+                                .await
+                                .map_err(|e| ServerError::BatchActionFailed {
+                                    index,
+                                    source: Box::new(e),
+                                })?;
+                        results.push(Ok(outcome));
+                    }
+
+                    ActionResponse::Batch(results)
+                }
+            }
+        }
+
+        ActionRequest::Repair(data) => {
+            info!("running repair scan (dry_run={})", data.dry_run);
+
+            let handle = FacadeRepair::new(store, repo);
+            let report = handle.scan(data.dry_run).await?;
+
+            if !report.is_clean() {
+                warn!(
+                    "repair scan found anomalies: {} orphaned data file(s), {} missing data file(s), {} misplaced topic(s), {} empty sequence(s), {} reclaimed chunk ref(s)",
+                    report.orphaned_data_files.len(),
+                    report.missing_data_files.len(),
+                    report.misplaced_topics.len(),
+                    report.empty_sequences.len(),
+                    report.reclaimed_chunk_refs.len(),
+                );
+            }
+
+            ActionResponse::Repair(report.into())
+        }
     };
 
     Ok(response)
@@ -558,4 +765,56 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test]
+    /// `fail_fast` batches stop at the first failing sub-action, but do not
+    /// roll back sub-actions that already ran: this asserts the two
+    /// sequences created before the failing one still exist afterwards.
+    async fn batch_fail_fast_does_not_undo_completed_sub_actions(
+        pool: sqlx::Pool<repo::Database>,
+    ) -> sqlx::Result<()> {
+        let name_1 = "/batch_seq_1".to_string();
+        let name_2 = "/batch_seq_2".to_string();
+
+        let repo = repo::testing::Repository::new(pool);
+        let store = store::testing::Store::new_random_on_tmp().unwrap();
+        let ts_engine = query::TimeseriesGw::try_new((*store).clone()).unwrap();
+
+        let body = serde_json::json!({
+            "mode": "fail_fast",
+            "actions": [
+                {"type": "sequence_create", "body": {"name": name_1, "user_metadata": {}}},
+                {"type": "sequence_create", "body": {"name": name_2, "user_metadata": {}}},
+                // duplicate of the first name: fails and stops the batch
+                {"type": "sequence_create", "body": {"name": name_1, "user_metadata": {}}},
+            ],
+        });
+        let body_raw = serde_json::to_vec(&body).unwrap();
+
+        let action = ActionRequest::try_new("batch", &body_raw)
+            .expect("Unable to create batch action from string");
+
+        let result = do_action(
+            (*store).clone(),
+            repo.clone(),
+            Arc::new(ts_engine),
+            action,
+        )
+        .await;
+
+        match result {
+            Err(ServerError::BatchActionFailed { index, .. }) => assert_eq!(index, 2),
+            Ok(_) => panic!("expected the batch to fail"),
+            Err(e) => panic!("expected BatchActionFailed at index 2, got {e:?}"),
+        }
+
+        // The first two sub-actions already committed their own transaction
+        // and are not undone by the third one failing.
+        let seq_1 = FacadeSequence::new(name_1, (*store).clone(), repo.clone());
+        let seq_2 = FacadeSequence::new(name_2, (*store).clone(), repo.clone());
+        assert!(seq_1.resource_id().await.is_ok());
+        assert!(seq_2.resource_id().await.is_ok());
+
+        Ok(())
+    }
 }
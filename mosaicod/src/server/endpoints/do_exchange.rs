@@ -0,0 +1,194 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+
+use arrow_flight::flight_descriptor::DescriptorType;
+use arrow_flight::{
+    FlightData,
+    encode::FlightDataEncoderBuilder,
+    error::FlightError,
+};
+
+use futures::{Stream, StreamExt, TryStreamExt};
+use log::{info, trace};
+use serde::Deserialize;
+use tonic::Status;
+
+use crate::{
+    marshal, query,
+    repo::{self, FacadeError, FacadeTopic},
+    server::errors::ServerError,
+    store, types,
+    types::Resource,
+};
+
+/// Command carried in the first message of a `do_exchange` call, describing
+/// the standing query a client wants to subscribe to.
+#[derive(Deserialize, Debug)]
+struct DoExchangeSubscribe {
+    query: serde_json::Value,
+}
+
+type NotifyStream = Pin<Box<dyn Stream<Item = Result<types::Notify, FacadeError>> + Send>>;
+
+struct State {
+    notifies: NotifyStream,
+    pending: VecDeque<FlightData>,
+    store: store::StoreRef,
+    repo: repo::Repository,
+    ts_engine: query::TimeseriesGwRef,
+    ontology: Option<query::OntologyFilter>,
+}
+
+/// Implements `do_exchange` as a standing, dataspace-style subscription.
+///
+/// The client opens the exchange with a single message whose
+/// [`arrow_flight::FlightDescriptor`] carries a JSON-encoded [`query::Filter`].
+/// The server resolves the set of topics matching the `sequence`/`topic`
+/// clauses (or every existing topic, if neither is set) and streams back the
+/// contents of each one every time it completes ingestion and matches the
+/// `ontology` clause, for as long as the exchange stays open. No replay of
+/// data ingested before the subscription was opened is performed.
+pub async fn do_exchange(
+    store: store::StoreRef,
+    repo: repo::Repository,
+    ts_engine: query::TimeseriesGwRef,
+    mut request_stream: impl Stream<Item = Result<FlightData, Status>> + Unpin,
+) -> Result<impl Stream<Item = Result<FlightData, FlightError>>, ServerError> {
+    let first = request_stream
+        .try_next()
+        .await
+        .map_err(|e| ServerError::StreamError(e.to_string()))?
+        .ok_or(ServerError::MissingDescriptior)?;
+
+    let cmd = extract_subscribe_command(&first)?;
+    let filter = marshal::query_filter_from_serde_value(cmd.query)?;
+    let (seq_filt, top_filt, ontology) = filter.into_parts();
+
+    let mut cx = repo.connection();
+    let topics = if seq_filt.is_none() && top_filt.is_none() {
+        repo::topic_find_all(&mut cx).await?
+    } else {
+        repo::topic_from_query_filter(&mut cx, seq_filt, top_filt).await?
+    };
+
+    info!(
+        "opening standing subscription over {} topic(s)",
+        topics.len()
+    );
+
+    let mut notify_streams: Vec<NotifyStream> = Vec::with_capacity(topics.len());
+    for record in topics {
+        let handle = FacadeTopic::new(record.topic_name, store.clone(), repo.clone());
+        notify_streams.push(Box::pin(handle.notify_subscribe().await?));
+    }
+
+    let notifies: NotifyStream = Box::pin(futures::stream::select_all(notify_streams));
+
+    let state = State {
+        notifies,
+        pending: VecDeque::new(),
+        store,
+        repo,
+        ts_engine,
+        ontology,
+    };
+
+    Ok(futures::stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(data) = state.pending.pop_front() {
+                return Some((Ok(data), state));
+            }
+
+            match state.notifies.next().await {
+                None => return None,
+                Some(Err(err)) => {
+                    return Some((Err(FlightError::ExternalError(Box::new(err))), state));
+                }
+                Some(Ok(notify)) => {
+                    if !matches!(notify.notify_type, types::NotifyType::Ingest) {
+                        continue;
+                    }
+
+                    trace!("ingestion event received for `{}`", notify.target);
+
+                    match stream_topic_as_flight_data(
+                        &state.store,
+                        &state.repo,
+                        &state.ts_engine,
+                        notify.target.name(),
+                        &state.ontology,
+                    )
+                    .await
+                    {
+                        Ok(batch) => state.pending.extend(batch),
+                        Err(err) => {
+                            return Some((
+                                Err(FlightError::ExternalError(Box::new(err))),
+                                state,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }))
+}
+
+/// Reads the full contents of a topic and encodes it as `FlightData`,
+/// applying `ontology` as a pushed-down predicate when present.
+async fn stream_topic_as_flight_data(
+    store: &store::StoreRef,
+    repo: &repo::Repository,
+    ts_engine: &query::TimeseriesGwRef,
+    topic_name: &str,
+    ontology: &Option<query::OntologyFilter>,
+) -> Result<Vec<FlightData>, ServerError> {
+    let handle = FacadeTopic::new(topic_name.to_string(), store.clone(), repo.clone());
+    let metadata = handle.metadata().await?;
+
+    let query_result = ts_engine
+        .read(
+            &handle.locator.name(),
+            metadata.properties.serialization_format,
+            false,
+        )
+        .await?;
+
+    let query_result = match ontology {
+        Some(ontology) => query_result.filter(ontology.clone())?,
+        None => query_result,
+    };
+
+    let metadata = marshal::JsonTopicMetadata::from(metadata);
+    let flatten_mdata = metadata
+        .to_flat_hashmap()
+        .map_err(repo::FacadeError::from)?;
+    let schema = query_result.schema_with_metadata(flatten_mdata);
+
+    let stream = query_result
+        .stream()
+        .await?
+        .map_err(|e| FlightError::ExternalError(Box::new(e)));
+
+    let encoder = FlightDataEncoderBuilder::new()
+        .with_schema(schema)
+        .build(stream);
+
+    encoder
+        .try_collect::<Vec<_>>()
+        .await
+        .map_err(|e| ServerError::StreamError(e.to_string()))
+}
+
+fn extract_subscribe_command(data: &FlightData) -> Result<DoExchangeSubscribe, ServerError> {
+    let desc = data
+        .flight_descriptor
+        .as_ref()
+        .ok_or(ServerError::MissingDescriptior)?;
+
+    if desc.r#type() == DescriptorType::Path {
+        return Err(ServerError::UnsupportedDescriptor);
+    }
+
+    Ok(serde_json::from_slice::<DoExchangeSubscribe>(&desc.cmd)?)
+}
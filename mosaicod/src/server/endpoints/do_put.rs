@@ -1,15 +1,34 @@
+//! Implementation of the Arrow Flight `do_put` endpoint: the write-side
+//! counterpart to [`super::do_get`].
+//!
+//! A target topic must already be registered (via `do_action`'s
+//! `TopicCreate`, which hands the client back the topic's resource uuid as
+//! its upload key) before it can be written to here -- [`setup_topic_writer`]
+//! looks it up by name and checks the caller's key against that uuid, but
+//! deliberately does not create a missing topic on the fly. Auto-creating
+//! from an unauthenticated `FlightDescriptor` command would let any client
+//! that can reach `do_put` register arbitrary topics under an arbitrary
+//! sequence, bypassing the key check this endpoint otherwise relies on for
+//! authorization.
+
+use std::collections::HashMap;
+
 use arrow::datatypes::SchemaRef;
 use futures::TryStreamExt;
 
 use arrow_flight::decode::{DecodedFlightData, DecodedPayload, FlightDataDecoder};
 use arrow_flight::flight_descriptor::DescriptorType;
 
-use log::{debug, info, trace};
-use serde::Deserialize;
+use log::{debug, error, info, trace};
+use serde::{Deserialize, Serialize};
 
-use crate::{repo, server::errors::ServerError, store, types};
+use crate::{
+    params, repo, rw,
+    server::{errors::ServerError, upload_lock},
+    store, types,
+};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 struct DoPutTopic {
     name: String,
     key: String,
@@ -19,45 +38,105 @@ struct DoPutTopic {
 #[allow(dead_code)]
 #[serde(rename_all = "snake_case")]
 enum DoPutCommand {
+    /// Upload a single topic; the rest of the stream is all batches for it.
     Topic(DoPutTopic),
+    /// Upload several topics over one stream. Carries the manifest of
+    /// `{name, key}` targets the client intends to send; each target's data
+    /// is then framed mid-stream by a `Topic` descriptor attached to the
+    /// `Schema` message that switches the active topic.
+    Batch(Vec<DoPutTopic>),
+}
+
+/// Outcome of uploading one topic, reported back to the client so a single
+/// bad key (or a failure partway through one topic) doesn't hide whether
+/// the other topics in a batch committed.
+#[derive(Serialize, Debug)]
+pub struct DoPutTopicResult {
+    pub name: String,
+    pub key: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl DoPutTopicResult {
+    fn success(name: String, key: String) -> Self {
+        Self {
+            name,
+            key,
+            success: true,
+            error: None,
+        }
+    }
+
+    fn failure(name: String, key: String, error: impl std::fmt::Display) -> Self {
+        Self {
+            name,
+            key,
+            success: false,
+            error: Some(error.to_string()),
+        }
+    }
+
+    fn from_outcome(name: String, key: String, outcome: Result<(), ServerError>) -> Self {
+        match outcome {
+            Ok(()) => Self::success(name, key),
+            Err(err) => Self::failure(name, key, err),
+        }
+    }
+}
+
+/// A topic writer in progress, along with everything needed to finalize it
+/// once its data has all been streamed in.
+struct ActiveTopicWriter<'a> {
+    handle: repo::FacadeTopic,
+    writer: rw::ChunkedWriter<'a, store::Store>,
+    topic_id: i32,
+    ontology_tag: String,
+    key: String,
+    /// Held for as long as this topic is being uploaded to, serializing it
+    /// against any other in-flight upload targeting a sub-resource of it
+    /// (see `upload_lock::acquire`). Dropping `ActiveTopicWriter` releases it.
+    _upload_guard: upload_lock::UploadGuard,
 }
 
 pub async fn do_put(
     store: store::StoreRef,
     repo: repo::Repository,
     decoder: &mut FlightDataDecoder,
-) -> Result<(), ServerError> {
-    let (cmd, schema) = extract_command_and_schema_from_header_message(decoder).await?;
+) -> Result<Vec<DoPutTopicResult>, ServerError> {
+    let (cmd, schema) = extract_command_from_header_message(decoder).await?;
 
     match cmd {
-        DoPutCommand::Topic(cmd) => {
-            return do_put_topic_data(store, repo, decoder, schema, cmd).await;
+        DoPutCommand::Topic(target) => {
+            let schema = schema.ok_or(ServerError::MissingSchema)?;
+            let name = target.name.clone();
+            let key = target.key.clone();
+
+            let outcome = do_put_topic_data(store, repo, decoder, schema, target).await;
+            Ok(vec![DoPutTopicResult::from_outcome(name, key, outcome)])
         }
+        DoPutCommand::Batch(targets) => do_put_batch(store, repo, decoder, targets).await,
     }
 }
 
-async fn extract_command_and_schema_from_header_message(
+async fn extract_command_from_header_message(
     decoder: &mut FlightDataDecoder,
-) -> Result<(DoPutCommand, SchemaRef), ServerError> {
+) -> Result<(DoPutCommand, Option<SchemaRef>), ServerError> {
     if let Some(data) = decoder
         .try_next()
         .await
         .map_err(|e| ServerError::StreamError(e.to_string()))?
     {
         let cmd = extract_command_from_flight_data(&data)?;
-        let schema = extract_schema_from_flight_data(&data)?;
+        let schema = match &data.payload {
+            DecodedPayload::Schema(schema) => Some(schema.clone()),
+            _ => None,
+        };
         return Ok((cmd, schema));
     }
     Err(ServerError::MissingDoPutHeaderMessage)
 }
 
-fn extract_schema_from_flight_data(data: &DecodedFlightData) -> Result<SchemaRef, ServerError> {
-    if let DecodedPayload::Schema(schema) = &data.payload {
-        return Ok(schema.clone());
-    }
-    Err(ServerError::MissingSchema)
-}
-
 /// Extract descriptor tag from flight decoded data
 fn extract_command_from_flight_data(data: &DecodedFlightData) -> Result<DoPutCommand, ServerError> {
     let desc = data
@@ -82,58 +161,14 @@ async fn do_put_topic_data(
     schema: SchemaRef,
     cmd: DoPutTopic,
 ) -> Result<(), ServerError> {
-    let name = cmd.name;
-    let key = &cmd.key;
-
     info!(
         "client trying to upload topic '{}' using key `{}`",
-        name, key
+        cmd.name, cmd.key
     );
 
-    crate::arrow::check_schema(&schema)?;
-
-    let handle = repo::FacadeTopic::new(name, store.clone(), repo.clone());
-
-    // perform the match between received key and topic id
-    let r_id = handle.resource_id().await?;
-    let received_uuid: uuid::Uuid = key.parse()?;
-    if received_uuid != r_id.uuid {
-        return Err(ServerError::BadKey);
-    }
-
-    let mdata = handle.metadata().await?;
-
-    // Setup the callback that will be used to create the repository record for the data catalog
-    // and prepare variables that will be moved in the closure
-    let ontology_tag = mdata.properties.ontology_tag;
-    let serialization_format = mdata.properties.serialization_format;
-    let topic_id = r_id.id;
-
-    let mut writer =
-        handle
-            .writer(serialization_format)
-            .on_chunk_created(move |target_path, cols_stats| {
-                let topic_id = topic_id;
-                let repo_clone = repo.clone();
-                let ontology_tag = ontology_tag.clone();
-
-                async move {
-                    trace!(
-                        "calling chunk creation callback for `{}` {:?}",
-                        target_path.to_string_lossy(),
-                        cols_stats
-                    );
-
-                    Ok(on_chunk_created(
-                        repo_clone,
-                        topic_id,
-                        &ontology_tag,
-                        target_path,
-                        cols_stats,
-                    )
-                    .await?)
-                }
-            });
+    let store_ref = store.as_ref();
+    let mut active =
+        setup_topic_writer(store_ref, store.clone(), repo.clone(), &schema, &cmd).await?;
 
     // Consume all batches
     while let Some(data) = decoder
@@ -148,7 +183,10 @@ async fn do_put_topic_data(
                     batch.columns().len(),
                     batch.get_array_memory_size()
                 );
-                writer.write(&batch).await?;
+                if let Err(err) = active.writer.write(&batch).await {
+                    fail_upload(&active, &repo, &err).await;
+                    return Err(err.into());
+                }
             }
             DecodedPayload::Schema(_) => {
                 return Err(ServerError::DuplicateSchemaInPayload);
@@ -159,14 +197,319 @@ async fn do_put_topic_data(
         }
     }
 
-    // If the finalize fails (e.g. problems during stats computation) the topic will not be locked,
-    // this allows the reindexing (currently not implemented) of
-    // the topic
-    trace!("finializing data write");
-    writer.finalize().await?;
+    finalize_topic(active, repo).await
+}
+
+/// Marks `active`'s upload job `failed` and records a `TopicNotify` of type
+/// `Error` naming the failing chunk and reason, so a standing `do_exchange`
+/// subscriber or a `TopicUploadStatus` poller learns about it instead of
+/// the topic silently stopping mid-upload. Best-effort: a failure here is
+/// logged rather than propagated, since the caller already has a real
+/// error of its own to return.
+async fn fail_upload(active: &ActiveTopicWriter<'_>, repo: &repo::Repository, reason: impl std::fmt::Display) {
+    let mut cx = repo.connection();
+    let chunk = active.writer.chunks_written();
+
+    if let Err(err) = repo::upload_job_fail(&mut cx, active.topic_id).await {
+        error!(
+            "failed to mark upload job for topic {} failed: {}",
+            active.topic_id, err
+        );
+    }
+
+    let msg = format!("upload failed at chunk {}: {}", chunk, reason);
+    if let Err(err) = active.handle.notify(types::NotifyType::Error, msg).await {
+        error!(
+            "failed to record upload-failure notify for topic {}: {}",
+            active.topic_id, err
+        );
+    }
+}
 
-    trace!("resource {} locked", handle.locator);
-    handle.lock().await?;
+/// Uploads several topics interleaved over a single stream.
+///
+/// After the `Batch` header, the stream alternates between a `Topic`
+/// descriptor carried on a `Schema` message (switching the active topic and
+/// opening its writer) and the `RecordBatch` messages that follow it, which
+/// are routed to whichever topic is currently active. A target that fails
+/// to open (bad key, schema mismatch, ...) or fails mid-write is dropped
+/// from the active set and reported as a failure, without aborting the
+/// topics still in flight.
+async fn do_put_batch(
+    store: store::StoreRef,
+    repo: repo::Repository,
+    decoder: &mut FlightDataDecoder,
+    targets: Vec<DoPutTopic>,
+) -> Result<Vec<DoPutTopicResult>, ServerError> {
+    info!("client starting a batch upload of {} topic(s)", targets.len());
+
+    let keys_by_name: HashMap<String, String> =
+        targets.into_iter().map(|t| (t.name, t.key)).collect();
+
+    let store_ref = store.as_ref();
+    let mut active: HashMap<String, ActiveTopicWriter<'_>> = HashMap::new();
+    let mut results = Vec::new();
+    let mut current: Option<String> = None;
+
+    while let Some(data) = decoder
+        .try_next()
+        .await
+        .map_err(|e| ServerError::StreamError(e.to_string()))?
+    {
+        match data.payload {
+            DecodedPayload::Schema(schema) => {
+                let DoPutCommand::Topic(target) = extract_command_from_flight_data(&data)? else {
+                    return Err(ServerError::UnsupportedDescriptor);
+                };
+
+                current = None;
+
+                let Some(expected_key) = keys_by_name.get(&target.name) else {
+                    results.push(DoPutTopicResult::failure(
+                        target.name,
+                        target.key,
+                        "topic not listed in the batch manifest",
+                    ));
+                    continue;
+                };
+
+                if *expected_key != target.key {
+                    results.push(DoPutTopicResult::failure(
+                        target.name.clone(),
+                        target.key,
+                        ServerError::BadKey,
+                    ));
+                    continue;
+                }
+
+                match setup_topic_writer(store_ref, store.clone(), repo.clone(), &schema, &target)
+                    .await
+                {
+                    Ok(writer) => {
+                        current = Some(target.name.clone());
+                        active.insert(target.name, writer);
+                    }
+                    Err(err) => {
+                        results.push(DoPutTopicResult::failure(target.name, target.key, err));
+                    }
+                }
+            }
+            DecodedPayload::RecordBatch(batch) => {
+                // Batches sent before any active topic (e.g. all its
+                // candidate descriptors failed) have nowhere to go.
+                let Some(name) = current.clone() else {
+                    continue;
+                };
+                let Some(writer) = active.get_mut(&name) else {
+                    continue;
+                };
+
+                debug!(
+                    "processing batch for topic `{}` (cols: {})",
+                    name,
+                    batch.columns().len()
+                );
+
+                if let Err(err) = writer.writer.write(&batch).await {
+                    fail_upload(writer, &repo, &err).await;
+                    let key = writer.key.clone();
+                    results.push(DoPutTopicResult::failure(name.clone(), key, err));
+                    active.remove(&name);
+                    current = None;
+                }
+            }
+            DecodedPayload::None => {
+                return Err(ServerError::NoData);
+            }
+        }
+    }
+
+    for (name, writer) in active {
+        let key = writer.key.clone();
+        let outcome = finalize_topic(writer, repo.clone()).await;
+        results.push(DoPutTopicResult::from_outcome(name, key, outcome));
+    }
+
+    Ok(results)
+}
+
+/// Validates `cmd`'s key against the topic's resource id, then opens its
+/// [`rw::ChunkedWriter`] on `store_ref`, wiring the chunk-creation callback
+/// that records the chunk's stats and enqueues a reindex job.
+///
+/// If this topic has an upload checkpoint left over from a prior attempt
+/// (dropped connection, paused job, ...), the writer resumes chunk
+/// numbering from it instead of restarting at `0`. The topic is also
+/// serialized against any other in-flight upload targeting one of its
+/// sub-resources (see `upload_lock::acquire`), since both would otherwise
+/// race on the same on-disk chunk numbers.
+///
+/// `store_ref` is borrowed separately from `store` (an owned clone handed
+/// to the spawned [`repo::FacadeTopic`]) so the returned writer can outlive
+/// a single topic's setup -- needed when several are kept open at once for
+/// a batch upload.
+async fn setup_topic_writer<'a>(
+    store_ref: &'a store::Store,
+    store: store::StoreRef,
+    repo: repo::Repository,
+    schema: &SchemaRef,
+    cmd: &DoPutTopic,
+) -> Result<ActiveTopicWriter<'a>, ServerError> {
+    crate::arrow::check_schema(schema)?;
+
+    let handle = repo::FacadeTopic::new(cmd.name.clone(), store, repo.clone());
+
+    // perform the match between received key and topic id
+    let r_id = handle.resource_id().await?;
+    let received_uuid: uuid::Uuid = cmd.key.parse()?;
+    if received_uuid != r_id.uuid {
+        return Err(ServerError::BadKey);
+    }
+
+    let upload_guard = upload_lock::acquire(handle.locator.clone()).await;
+
+    let mdata = handle.metadata().await?;
+
+    // Setup the callback that will be used to create the repository record for the data catalog
+    // and prepare variables that will be moved in the closure
+    let ontology_tag = mdata.properties.ontology_tag;
+    let serialization_format = mdata.properties.serialization_format;
+    let topic_id = r_id.id;
+
+    let mut cx = repo.connection();
+    let checkpoint = repo::upload_job_find(&mut cx, topic_id).await?;
+    repo::upload_job_start(&mut cx, topic_id).await?;
+
+    // A resumed upload carries on from the checkpointed byte total and the
+    // stored Merkle peaks instead of starting them over.
+    let checkpoint = checkpoint.filter(|c| c.chunks_written > 0);
+    let resumed_bytes = checkpoint.as_ref().map_or(0, |c| c.bytes_written);
+    let merkle_tree = match &checkpoint {
+        Some(_) => repo::topic_merkle_find(&mut cx, topic_id)
+            .await?
+            .and_then(|peaks| types::MerkleTree::from_bytes(&peaks))
+            .unwrap_or_default(),
+        None => types::MerkleTree::new(),
+    };
+
+    let repo_for_chunk = repo.clone();
+    let ontology_tag_for_chunk = ontology_tag.clone();
+    let merkle = std::sync::Arc::new(std::sync::Mutex::new(merkle_tree));
+    let repo_for_progress = repo.clone();
+
+    let mut writer = handle.writer_on(store_ref, serialization_format);
+    if let Some(encryption) = &mdata.properties.encryption {
+        let master_key = params::master_key().ok_or(ServerError::MissingMasterKey)?;
+        let cipher = rw::MasterKey::new(master_key).derive_cipher(&encryption.key_id);
+        writer = writer.with_cipher(cipher);
+    }
+    if let Some(checkpoint) = checkpoint.filter(|c| c.chunks_written > 0) {
+        trace!(
+            "resuming upload for topic `{}` from chunk {}",
+            handle.locator, checkpoint.chunks_written
+        );
+        writer = writer.resume_from(checkpoint.chunks_written);
+    }
+
+    let writer = writer
+        .on_chunk_created(move |target_path, cols_stats, digest| {
+            let repo_clone = repo_for_chunk.clone();
+            let ontology_tag = ontology_tag_for_chunk.clone();
+            let merkle = merkle.clone();
+
+            async move {
+                trace!(
+                    "calling chunk creation callback for `{}` {:?} digest={}",
+                    target_path.to_string_lossy(),
+                    cols_stats,
+                    digest
+                );
+
+                Ok(on_chunk_created(
+                    repo_clone,
+                    topic_id,
+                    &ontology_tag,
+                    target_path,
+                    cols_stats,
+                    digest,
+                    merkle,
+                )
+                .await?)
+            }
+        })
+        .on_progress(move |chunks_written, bytes_written, path| {
+            let repo = repo_for_progress.clone();
+
+            async move {
+                let mut cx = repo.connection();
+                Ok(repo::upload_job_checkpoint(
+                    &mut cx,
+                    topic_id,
+                    chunks_written as i64,
+                    (resumed_bytes + bytes_written) as i64,
+                    &path.to_string_lossy(),
+                )
+                .await?)
+            }
+        });
+
+    Ok(ActiveTopicWriter {
+        handle,
+        writer,
+        topic_id,
+        ontology_tag,
+        key: cmd.key.clone(),
+        _upload_guard: upload_guard,
+    })
+}
+
+/// Finalizes and locks a topic's writer once all its data has been
+/// received, and notifies any standing `do_exchange` subscriptions.
+///
+/// If the finalize fails (e.g. problems during stats computation) the
+/// topic will not be locked; a reindex job is queued so a worker durably
+/// retries bringing it back to a locked, queryable state instead of
+/// leaving it stuck unlocked.
+async fn finalize_topic(
+    mut active: ActiveTopicWriter<'_>,
+    repo: repo::Repository,
+) -> Result<(), ServerError> {
+    trace!("finalizing data write for topic `{}`", active.handle.locator);
+    if let Err(err) = active.writer.finalize().await {
+        fail_upload(&active, &repo, &err).await;
+
+        let mut cx = repo.connection();
+        if let Err(job_err) =
+            crate::server::jobs::enqueue_reindex(&mut cx, active.topic_id, &active.ontology_tag)
+                .await
+        {
+            error!(
+                "failed to queue reindex job for topic {} after finalize error: {}",
+                active.topic_id, job_err
+            );
+        }
+        return Err(err.into());
+    }
+
+    trace!("resource {} locked", active.handle.locator);
+    active.handle.lock().await?;
+
+    let mut cx = repo.connection();
+    if let Err(err) = repo::upload_job_complete(&mut cx, active.topic_id).await {
+        error!(
+            "failed to mark upload job for topic {} completed: {}",
+            active.topic_id, err
+        );
+    }
+
+    // Wake up any standing `do_exchange` subscriptions watching this topic.
+    active
+        .handle
+        .notify(
+            types::NotifyType::Ingest,
+            format!("topic `{}` ingestion complete", active.handle.locator),
+        )
+        .await?;
 
     Ok(())
 }
@@ -177,8 +520,10 @@ async fn on_chunk_created(
     ontology_tag: &str,
     target_path: impl AsRef<std::path::Path>,
     cstats: types::ColumnsStats,
+    digest: rw::ContentDigest,
+    merkle: std::sync::Arc<std::sync::Mutex<types::MerkleTree>>,
 ) -> Result<(), ServerError> {
-    let mut handle = repo::FacadeChunk::create(topic_id, &target_path, &repo).await?;
+    let mut handle = repo::FacadeChunk::create(topic_id, &target_path, digest, &repo).await?;
 
     for (field, stats) in cstats.stats {
         handle.push_stats(ontology_tag, &field, stats).await?;
@@ -186,5 +531,21 @@ async fn on_chunk_created(
 
     handle.finalize().await?;
 
+    // Feed this chunk's digest into the topic's running Merkle tree and
+    // persist the (compact) peak vector, so `TopicSystemInfo::merkle_root`
+    // can be read back without replaying the topic's whole chunk history.
+    let peaks = {
+        let mut merkle = merkle.lock().expect("merkle tree mutex shouldn't be poisoned");
+        merkle.append(digest);
+        merkle.to_bytes()
+    };
+    let mut cx = repo.connection();
+    repo::topic_merkle_upsert(&mut cx, topic_id, &peaks).await?;
+
+    // Durably queue a reindex job for this topic so a finalize() that fails
+    // on a later chunk doesn't lose the ability to retry bringing the topic
+    // back to a locked, queryable state.
+    crate::server::jobs::enqueue_reindex(&mut cx, topic_id, ontology_tag).await?;
+
     Ok(())
 }
@@ -1,35 +1,53 @@
 //! Implementation of the Arrow Flight `list_flights` endpoint.
 //!
-//! Returns a stream of all available sequences when queried at the root level.
+//! Returns a stream of all available sequences when queried at the root level,
+//! or only the sequences matching a JSON-encoded [`query::Filter`] carried in
+//! `Criteria.expression`.
 
 use arrow_flight::{Criteria, FlightDescriptor, FlightEndpoint, FlightInfo, Ticket};
 use futures::stream::BoxStream;
 use log::{info, trace};
 use tonic::Status;
 
-use crate::{repo, server::errors::ServerError, types::Resource};
+use crate::{marshal, query, repo, server::errors::ServerError, types::Resource};
 
 /// Lists all available flights (sequences) in the repository.
 ///
 /// When clients query with an empty or root path ("" or "/"), this function
-/// returns a streamed list of all sequences. Each sequence is represented
-/// as a minimal `FlightInfo` containing only the sequence identifier.
+/// returns a streamed list of all sequences. Otherwise, `Criteria.expression`
+/// is parsed as a JSON-encoded [`query::Filter`] (the same document
+/// `do_exchange` accepts) and pushed down to `FacadeSequence::search`, so
+/// only sequences matching its `sequence` clauses (name, creation,
+/// `user_metadata`, `since`) are streamed back. `topic`/`ontology` clauses
+/// aren't supported here, since a flight is one sequence -- matching on
+/// topic-level fields is left to the topic-returning endpoints
+/// (`do_exchange`, `do_action`).
 pub async fn list_flights(
     repo: repo::Repository,
     criteria: Criteria,
 ) -> Result<BoxStream<'static, Result<FlightInfo, Status>>, ServerError> {
-    // Validate criteria - only root-level queries are supported
     let expression = String::from_utf8_lossy(&criteria.expression);
     let is_root_query = expression.is_empty() || expression == "/";
 
-    if !is_root_query {
-        return Err(ServerError::UnsupportedDescriptor);
-    }
+    let sequences = if is_root_query {
+        info!("listing all sequences");
+        repo::FacadeSequence::all(repo).await?
+    } else {
+        let filter = marshal::query_filter_from_string(&expression)?;
+        if filter.topic.is_some() || filter.ontology.is_some() {
+            return Err(ServerError::UnsupportedDescriptor);
+        }
 
-    info!("listing all sequences");
+        let seq_filter = filter.sequence.unwrap_or(query::SequenceFilter {
+            name: None,
+            creation: None,
+            user_metadata: None,
+            since: None,
+        });
 
-    // Fetch all sequences from repository
-    let sequences = repo::FacadeSequence::all(repo).await?;
+        info!("listing sequences matching filter");
+        repo::FacadeSequence::search(repo, seq_filter).await?
+    };
 
     trace!("found {} sequences", sequences.len());
 
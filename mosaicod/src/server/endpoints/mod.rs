@@ -1,11 +1,15 @@
 mod do_action;
+mod do_exchange;
 mod do_get;
 mod do_put;
 mod get_flight_info;
 mod list_flights;
+mod poll_flight_info;
 
 pub use do_action::do_action;
+pub use do_exchange::do_exchange;
 pub use do_get::do_get;
 pub use do_put::do_put;
 pub use get_flight_info::get_flight_info;
 pub use list_flights::list_flights;
+pub use poll_flight_info::poll_flight_info;
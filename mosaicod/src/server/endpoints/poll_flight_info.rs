@@ -0,0 +1,30 @@
+use arrow_flight::{FlightDescriptor, PollInfo};
+use log::trace;
+
+use crate::{repo, server::errors::ServerError, store};
+
+/// Implements `poll_flight_info`.
+///
+/// Queries in this server execute synchronously within [`get_flight_info`];
+/// they are not run on the background job queue, and nothing tracks a
+/// query's partial progress. A poll
+/// therefore always completes on the first call: `flight_descriptor` comes
+/// back empty (nothing left to retry) and `progress` is `1.0`.
+pub async fn poll_flight_info(
+    store: store::StoreRef,
+    repo: repo::Repository,
+    desc: FlightDescriptor,
+) -> Result<PollInfo, ServerError> {
+    trace!("polling flight info for descriptor {:?}", desc);
+
+    let info = super::get_flight_info(store, repo, desc).await?;
+
+    Ok(PollInfo {
+        info: Some(info),
+        // `None` signals to the client that the query is already complete
+        // and no further polling is needed.
+        flight_descriptor: None,
+        progress: Some(1.0),
+        expiration_time: None,
+    })
+}
@@ -1,3 +1,4 @@
+use http::StatusCode;
 use thiserror::Error;
 
 use crate::{query, rw};
@@ -92,17 +93,177 @@ pub enum ServerError {
 
     #[error("query error :: {0}")]
     QueryError(#[from] query::Error),
+
+    #[error("store error :: {0}")]
+    StoreError(#[from] crate::store::Error),
+
+    #[error("tls error :: {0}")]
+    TlsError(String),
+
+    /// Returned when a topic is configured for encryption (it carries a
+    /// `types::EncryptionInfo`) but this process has no `MOSAICO_MASTER_KEY`
+    /// loaded to derive its per-topic cipher from.
+    #[error("topic is encrypted but no master key is configured on this server")]
+    MissingMasterKey,
+
+    /// A [`crate::marshal::requests::BatchMode::FailFast`] batch stopped
+    /// because the sub-action at `index` failed; earlier sub-actions in the
+    /// batch already ran and are **not** undone (see the `BatchMode::FailFast`
+    /// doc comment on [`crate::marshal::ActionRequest::Batch`] for why this
+    /// is fail-fast reporting, not transactional rollback).
+    #[error("batch action at index {index} failed: {source}")]
+    BatchActionFailed {
+        index: usize,
+        #[source]
+        source: Box<ServerError>,
+    },
 }
 
-impl From<ServerError> for tonic::Status {
-    fn from(value: ServerError) -> Self {
-        use tonic::Status;
-        match value {
-            ServerError::MultiplePathUnsupported => Status::invalid_argument(value.to_string()),
-            ServerError::MissingDescriptior => Status::invalid_argument(value.to_string()),
-            ServerError::BadTicket(_) => Status::invalid_argument(value.to_string()),
+/// Broad category a [`ErrCode`] falls into: whether the client should fix
+/// its request (`Invalid`) or the failure is on our side (`Internal`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrKind {
+    Invalid,
+    Internal,
+}
+
+impl ErrKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Invalid => "invalid",
+            Self::Internal => "internal",
+        }
+    }
+}
+
+/// A stable, machine-readable identity for a [`ServerError`] variant.
+///
+/// `code` is part of the public API contract: once a code ships, it must
+/// keep meaning the same thing across releases, even if the variant's
+/// `Display` message is reworded. Clients should match on `code`, never on
+/// the prose in `message`.
+#[derive(Debug, Clone, Copy)]
+pub struct ErrCode {
+    pub code: &'static str,
+    pub http_status: StatusCode,
+    pub kind: ErrKind,
+}
 
-            _ => Status::internal(value.to_string()),
+impl ErrCode {
+    const fn new(code: &'static str, http_status: StatusCode, kind: ErrKind) -> Self {
+        Self {
+            code,
+            http_status,
+            kind,
         }
     }
+
+    const fn invalid(code: &'static str, http_status: StatusCode) -> Self {
+        Self::new(code, http_status, ErrKind::Invalid)
+    }
+
+    const fn internal(code: &'static str) -> Self {
+        Self::new(code, StatusCode::INTERNAL_SERVER_ERROR, ErrKind::Internal)
+    }
+
+    /// The closest [`tonic::Code`] for this error, derived from
+    /// `http_status` so gRPC clients still get a meaningful status even
+    /// though the stable identity lives in `code`.
+    fn grpc_code(&self) -> tonic::Code {
+        match self.http_status {
+            StatusCode::BAD_REQUEST => tonic::Code::InvalidArgument,
+            StatusCode::NOT_FOUND => tonic::Code::NotFound,
+            StatusCode::CONFLICT => tonic::Code::AlreadyExists,
+            StatusCode::NOT_IMPLEMENTED => tonic::Code::Unimplemented,
+            _ if self.kind == ErrKind::Internal => tonic::Code::Internal,
+            _ => tonic::Code::InvalidArgument,
+        }
+    }
+}
+
+impl ServerError {
+    /// Maps this error to its stable [`ErrCode`], so clients can match on a
+    /// snake-case string instead of parsing `Display` output.
+    pub fn err_code(&self) -> ErrCode {
+        match self {
+            Self::StreamError(_) => ErrCode::internal("stream_error"),
+            Self::MissingDescriptior => {
+                ErrCode::invalid("missing_descriptor", StatusCode::BAD_REQUEST)
+            }
+            Self::MissingOntologyTag => {
+                ErrCode::invalid("missing_ontology_tag", StatusCode::BAD_REQUEST)
+            }
+            Self::MissingSerializationFormat => {
+                ErrCode::invalid("missing_serialization_format", StatusCode::BAD_REQUEST)
+            }
+            Self::UnsupportedDescriptor => {
+                ErrCode::invalid("unsupported_descriptor", StatusCode::BAD_REQUEST)
+            }
+            Self::MultiplePathUnsupported => {
+                ErrCode::invalid("multiple_path_unsupported", StatusCode::BAD_REQUEST)
+            }
+            Self::MissingSchema => ErrCode::invalid("missing_schema", StatusCode::BAD_REQUEST),
+            Self::MissingDoPutHeaderMessage => {
+                ErrCode::invalid("missing_do_put_header_message", StatusCode::BAD_REQUEST)
+            }
+            Self::NotFound => ErrCode::invalid("not_found", StatusCode::NOT_FOUND),
+            Self::DuplicateSchemaInPayload => {
+                ErrCode::invalid("duplicate_schema_in_payload", StatusCode::BAD_REQUEST)
+            }
+            Self::SequenceAlreadyExists(_) => {
+                ErrCode::invalid("sequence_already_exists", StatusCode::CONFLICT)
+            }
+            Self::SequenceLocked => ErrCode::invalid("sequence_locked", StatusCode::CONFLICT),
+            Self::TopicAlreadyExists(_) => {
+                ErrCode::invalid("topic_already_exists", StatusCode::CONFLICT)
+            }
+            Self::NoData => ErrCode::invalid("no_data", StatusCode::BAD_REQUEST),
+            Self::Unimplemented => {
+                ErrCode::new("unimplemented", StatusCode::NOT_IMPLEMENTED, ErrKind::Internal)
+            }
+            Self::BadTicket(_) => ErrCode::invalid("bad_ticket", StatusCode::BAD_REQUEST),
+            Self::BadKey => ErrCode::invalid("bad_key", StatusCode::BAD_REQUEST),
+            Self::IOError(_) => ErrCode::internal("io_error"),
+            Self::SchemaError(_) => ErrCode::invalid("schema_error", StatusCode::BAD_REQUEST),
+            Self::MalformedKey(_) => ErrCode::invalid("malformed_key", StatusCode::BAD_REQUEST),
+            Self::BadCommand(_) => ErrCode::invalid("bad_command", StatusCode::BAD_REQUEST),
+            Self::RwError(_) => ErrCode::internal("rw_error"),
+            Self::ArrowError(_) => ErrCode::internal("arrow_error"),
+            Self::MarshalError(_) => ErrCode::invalid("marshal_error", StatusCode::BAD_REQUEST),
+            Self::ActionError(_) => ErrCode::invalid("action_error", StatusCode::BAD_REQUEST),
+            Self::HandleError(_) => ErrCode::internal("handle_error"),
+            Self::RepositoryError(_) => ErrCode::internal("repository_error"),
+            Self::QueryError(_) => ErrCode::invalid("query_error", StatusCode::BAD_REQUEST),
+            Self::StoreError(_) => ErrCode::internal("store_error"),
+            Self::TlsError(_) => ErrCode::internal("tls_error"),
+            Self::MissingMasterKey => ErrCode::internal("missing_master_key"),
+            Self::BatchActionFailed { source, .. } => source.err_code(),
+        }
+    }
+}
+
+/// Structured body serialized into a [`tonic::Status`]'s message, so
+/// clients can parse `code` instead of matching on `message` prose.
+#[derive(serde::Serialize)]
+struct ErrBody {
+    code: &'static str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    message: String,
+}
+
+impl From<ServerError> for tonic::Status {
+    fn from(value: ServerError) -> Self {
+        let err_code = value.err_code();
+        let body = ErrBody {
+            code: err_code.code,
+            kind: err_code.kind.as_str(),
+            message: value.to_string(),
+        };
+        // `ErrBody` only contains a `&'static str`, a `&'static str` and a
+        // `String`, so serialization can't realistically fail; fall back
+        // to the plain message just in case.
+        let message = serde_json::to_string(&body).unwrap_or_else(|_| value.to_string());
+        tonic::Status::new(err_code.grpc_code(), message)
+    }
 }
@@ -1,14 +1,16 @@
+use crate::server::auth;
 use crate::server::endpoints;
 use crate::server::errors::ServerError;
+use crate::server::tls;
 use crate::{marshal, params, query, repo, store};
 use arrow_flight::decode::FlightDataDecoder;
 use arrow_flight::{
+    flight_service_server::FlightService, flight_service_server::FlightServiceServer,
     Action as FlightAction, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
     HandshakeRequest, HandshakeResponse, PollInfo, PutResult, SchemaResult, Ticket,
-    flight_service_server::FlightService, flight_service_server::FlightServiceServer,
 };
-use futures::TryStreamExt;
 use futures::stream::BoxStream;
+use futures::TryStreamExt;
 use log::{error, trace};
 use std::sync::Arc;
 use tokio::sync::Notify;
@@ -23,6 +25,13 @@ pub type ShutdownNotifier = Arc<Notify>;
 pub struct Config {
     pub host: String,
     pub port: u16,
+    /// Credentials accepted over `handshake`. When `None`, `handshake` is
+    /// unimplemented and every other call is left unauthenticated, matching
+    /// the server's previous behavior.
+    pub auth: Option<auth::Credentials>,
+    /// Certificate/key to terminate TLS with. When `None` the service is
+    /// served in plaintext, matching the server's previous behavior.
+    pub tls: Option<tls::TlsMaterial>,
 }
 
 /// Start mosaico Apache Arrow Flight service
@@ -34,11 +43,18 @@ pub async fn start(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let addr = format!("{}:{}", config.host, config.port).parse()?;
 
-    let service = MosaicoFlightService::try_new(store, repo)?;
+    let service = MosaicoFlightService::try_new(store, repo, config.auth)?;
 
     let svc = FlightServiceServer::new(service);
 
-    let server = Server::builder().add_service(
+    let mut server_builder = Server::builder();
+    if let Some(material) = config.tls {
+        let identity = tonic::transport::Identity::from_pem(material.cert_pem, material.key_pem);
+        server_builder = server_builder
+            .tls_config(tonic::transport::ServerTlsConfig::new().identity(identity))?;
+    }
+
+    let server = server_builder.add_service(
         svc.max_decoding_message_size(params::configurables().max_message_size_in_bytes)
             .max_encoding_message_size(params::configurables().max_message_size_in_bytes),
     );
@@ -61,10 +77,18 @@ struct MosaicoFlightService {
     store: store::StoreRef,
     repo: repo::Repository,
     ts_engine: query::TimeseriesGwRef,
+    /// Credentials accepted over `handshake`. `None` disables authentication
+    /// entirely: `handshake` stays unimplemented and calls aren't checked.
+    auth: Option<auth::Credentials>,
+    tokens: auth::TokenStore,
 }
 
 impl MosaicoFlightService {
-    pub fn try_new(store: store::StoreRef, repo: repo::Repository) -> Result<Self, String> {
+    pub fn try_new(
+        store: store::StoreRef,
+        repo: repo::Repository,
+        auth: Option<auth::Credentials>,
+    ) -> Result<Self, String> {
         let ts_engine =
             Arc::new(query::TimeseriesGw::try_new(store.clone()).map_err(|e| e.to_string())?);
 
@@ -72,8 +96,23 @@ impl MosaicoFlightService {
             store,
             repo,
             ts_engine,
+            auth,
+            tokens: auth::TokenStore::new(),
         })
     }
+
+    /// Checks the `authorization` metadata of an incoming request against a
+    /// token previously issued by `handshake`.
+    ///
+    /// A no-op when the service was started without [`auth::Credentials`],
+    /// i.e. when the operator hasn't opted into authentication.
+    fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        if self.auth.is_none() {
+            return Ok(());
+        }
+
+        auth::authorize(&self.tokens, request.metadata())
+    }
 }
 #[tonic::async_trait]
 impl FlightService for MosaicoFlightService {
@@ -87,17 +126,37 @@ impl FlightService for MosaicoFlightService {
 
     async fn handshake(
         &self,
-        _request: Request<Streaming<HandshakeRequest>>,
+        request: Request<Streaming<HandshakeRequest>>,
     ) -> Result<Response<Self::HandshakeStream>, Status> {
-        Err(Status::unimplemented(
-            "handshake is currently unimplemented",
-        ))
+        let Some(creds) = &self.auth else {
+            return Err(Status::unimplemented(
+                "handshake is currently unimplemented",
+            ));
+        };
+
+        let mut stream = request.into_inner();
+        let req = stream
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("empty handshake request"))?;
+
+        let token = auth::handshake(creds, &self.tokens, &req.payload)?;
+
+        let response = HandshakeResponse {
+            protocol_version: req.protocol_version,
+            payload: token.into(),
+        };
+
+        Ok(Response::new(Box::pin(futures::stream::iter(vec![Ok(
+            response,
+        )]))))
     }
 
     async fn list_flights(
         &self,
         request: Request<Criteria>,
     ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        self.authorize(&request)?;
         let criteria = request.into_inner();
 
         let stream = endpoints::list_flights(self.repo.clone(), criteria)
@@ -111,6 +170,7 @@ impl FlightService for MosaicoFlightService {
         &self,
         request: Request<FlightDescriptor>,
     ) -> Result<Response<FlightInfo>, Status> {
+        self.authorize(&request)?;
         let desc = request.into_inner();
 
         let info = endpoints::get_flight_info(self.store.clone(), self.repo.clone(), desc)
@@ -122,11 +182,16 @@ impl FlightService for MosaicoFlightService {
 
     async fn poll_flight_info(
         &self,
-        _request: Request<FlightDescriptor>,
+        request: Request<FlightDescriptor>,
     ) -> Result<Response<PollInfo>, Status> {
-        Err(Status::unimplemented(
-            "poll_flight_info is currently unimplemented",
-        ))
+        self.authorize(&request)?;
+        let desc = request.into_inner();
+
+        let info = endpoints::poll_flight_info(self.store.clone(), self.repo.clone(), desc)
+            .await
+            .inspect_err(log_server_error)?;
+
+        Ok(Response::new(info))
     }
 
     async fn get_schema(
@@ -142,6 +207,7 @@ impl FlightService for MosaicoFlightService {
         &self,
         request: Request<Ticket>,
     ) -> Result<Response<Self::DoGetStream>, Status> {
+        self.authorize(&request)?;
         let ticket = request.into_inner();
 
         let data_stream = endpoints::do_get(
@@ -165,20 +231,35 @@ impl FlightService for MosaicoFlightService {
         &self,
         request: Request<Streaming<FlightData>>,
     ) -> Result<Response<Self::DoPutStream>, Status> {
+        self.authorize(&request)?;
         let stream = request.into_inner();
         let mut decoder = FlightDataDecoder::new(stream.map_err(Into::into));
 
-        endpoints::do_put(self.store.clone(), self.repo.clone(), &mut decoder)
+        let results = endpoints::do_put(self.store.clone(), self.repo.clone(), &mut decoder)
             .await
             .inspect_err(log_server_error)?;
 
-        Ok(Response::new(Box::pin(futures::stream::empty())))
+        // Report per-topic success/failure so a bad key in a batch upload
+        // doesn't hide whether the other topics committed.
+        let put_results = results
+            .into_iter()
+            .map(|result| {
+                serde_json::to_vec(&result)
+                    .map(|app_metadata| PutResult {
+                        app_metadata: app_metadata.into(),
+                    })
+                    .map_err(|e| Status::internal(format!("failed to encode put result: {}", e)))
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Response::new(Box::pin(futures::stream::iter(put_results))))
     }
 
     async fn do_action(
         &self,
         request: Request<FlightAction>,
     ) -> Result<Response<Self::DoActionStream>, Status> {
+        self.authorize(&request)?;
         let action = request.into_inner();
         let action = marshal::ActionRequest::try_new(action.r#type.as_str(), &action.body)
             .map_err(ServerError::from)
@@ -214,11 +295,26 @@ impl FlightService for MosaicoFlightService {
 
     async fn do_exchange(
         &self,
-        _request: Request<Streaming<FlightData>>,
+        request: Request<Streaming<FlightData>>,
     ) -> Result<Response<Self::DoExchangeStream>, Status> {
-        Err(Status::unimplemented(
-            "do_exchange is currently unimplemented",
-        ))
+        self.authorize(&request)?;
+        let stream = request.into_inner();
+
+        let data_stream = endpoints::do_exchange(
+            self.store.clone(),
+            self.repo.clone(),
+            self.ts_engine.clone(),
+            stream,
+        )
+        .await
+        .inspect_err(log_server_error)?;
+
+        // map data stream error (flight error) to a tonic one
+        let out_stream = data_stream
+            .inspect_err(|e| error!("flight encoding error: {}", e))
+            .map_err(|e| Status::internal(format!("flight encoding error: {}", e)));
+
+        Ok(Response::new(Box::pin(out_stream)))
     }
 }
 
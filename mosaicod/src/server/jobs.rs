@@ -0,0 +1,476 @@
+//! Durable background job-queue workers.
+//!
+//! Jobs enqueued in `job_queue_t` (via [`repo::FacadeJobQueue`]) --
+//! reindexing a topic that failed mid-upload, or a heavy `do_action`
+//! operation such as deleting a sequence with thousands of chunks or
+//! compacting a topic's data files -- are claimed by a small worker pool
+//! instead of being run inline, so a crashed worker's job is reclaimed by
+//! the sweeper and retried rather than lost. Stats recomputation and
+//! orphaned-chunk cleanup have no job kind here (see [`Job`]).
+//!
+//! A job that keeps failing is retried with exponential backoff rather
+//! than hammered immediately, and is left alone once it exhausts
+//! `max_retries`, acting as a simple dead letter (see `job_claim_next`).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, trace, warn};
+use tokio::sync::Notify;
+
+use crate::{
+    repo::{self, FacadeSequence, FacadeTopic},
+    store, types,
+};
+
+/// Postgres-backed queue the reindex worker claims jobs from.
+const QUEUE_REINDEX: &str = "reindex";
+
+/// Postgres-backed queue deferred `do_action` operations are enqueued on.
+const QUEUE_ACTIONS: &str = "actions";
+
+/// Queues polled by every worker, tried in order each time a worker is idle.
+const QUEUES: [&str; 2] = [QUEUE_REINDEX, QUEUE_ACTIONS];
+
+/// A job payload, serialized as-is into `job_queue_t.job`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Job {
+    /// Re-locks a topic left unlocked by a `finalize()` that failed partway
+    /// through (e.g. during stats computation), so it stops looking like a
+    /// still-uploading topic.
+    Reindex {
+        topic_id: i32,
+        #[allow(dead_code)]
+        ontology_tag: String,
+    },
+
+    /// Deletes an unlocked sequence, deferred from `do_action` so a
+    /// sequence with thousands of chunks doesn't block the request.
+    SequenceDelete { name: String },
+
+    /// Deletes an unlocked topic, deferred from `do_action` for the same
+    /// reason as [`Job::SequenceDelete`].
+    TopicDelete { name: String },
+
+    /// Aborts an in-progress sequence upload, deferred from `do_action`.
+    SequenceAbort { name: String, key: String },
+
+    /// Finalizes (locks) a sequence, deferred from `do_action`.
+    SequenceFinalize { name: String, key: String },
+
+    /// Merges a locked topic's small data files into fewer, larger ones,
+    /// deferred from `do_action` for the same reason as [`Job::TopicDelete`].
+    TopicCompact { name: String },
+}
+
+/// Enqueues a [`Job::Reindex`] for `topic_id`.
+///
+/// Can be called from within the same transaction as the work that
+/// triggered it, so the job only becomes visible to workers once that
+/// transaction commits.
+pub async fn enqueue_reindex(
+    exe: &mut impl repo::AsExec,
+    topic_id: i32,
+    ontology_tag: &str,
+) -> Result<(), repo::Error> {
+    let job = Job::Reindex {
+        topic_id,
+        ontology_tag: ontology_tag.to_string(),
+    };
+    repo::job_enqueue(exe, QUEUE_REINDEX, &serde_json::to_value(&job)?).await?;
+    Ok(())
+}
+
+/// Enqueues a [`Job::SequenceDelete`] for `name`, returning the id of the
+/// queued job.
+pub async fn enqueue_sequence_delete(
+    exe: &mut impl repo::AsExec,
+    name: &str,
+) -> Result<uuid::Uuid, repo::Error> {
+    let job = Job::SequenceDelete {
+        name: name.to_string(),
+    };
+    let row = repo::job_enqueue(exe, QUEUE_ACTIONS, &serde_json::to_value(&job)?).await?;
+    Ok(row.job_queue_id)
+}
+
+/// Enqueues a [`Job::TopicDelete`] for `name`, returning the id of the
+/// queued job.
+pub async fn enqueue_topic_delete(
+    exe: &mut impl repo::AsExec,
+    name: &str,
+) -> Result<uuid::Uuid, repo::Error> {
+    let job = Job::TopicDelete {
+        name: name.to_string(),
+    };
+    let row = repo::job_enqueue(exe, QUEUE_ACTIONS, &serde_json::to_value(&job)?).await?;
+    Ok(row.job_queue_id)
+}
+
+/// Enqueues a [`Job::SequenceAbort`] for `name`, returning the id of the
+/// queued job.
+pub async fn enqueue_sequence_abort(
+    exe: &mut impl repo::AsExec,
+    name: &str,
+    key: &str,
+) -> Result<uuid::Uuid, repo::Error> {
+    let job = Job::SequenceAbort {
+        name: name.to_string(),
+        key: key.to_string(),
+    };
+    let row = repo::job_enqueue(exe, QUEUE_ACTIONS, &serde_json::to_value(&job)?).await?;
+    Ok(row.job_queue_id)
+}
+
+/// Enqueues a [`Job::SequenceFinalize`] for `name`, returning the id of the
+/// queued job.
+pub async fn enqueue_sequence_finalize(
+    exe: &mut impl repo::AsExec,
+    name: &str,
+    key: &str,
+) -> Result<uuid::Uuid, repo::Error> {
+    let job = Job::SequenceFinalize {
+        name: name.to_string(),
+        key: key.to_string(),
+    };
+    let row = repo::job_enqueue(exe, QUEUE_ACTIONS, &serde_json::to_value(&job)?).await?;
+    Ok(row.job_queue_id)
+}
+
+/// Enqueues a [`Job::TopicCompact`] for `name`, returning the id of the
+/// queued job.
+pub async fn enqueue_topic_compact(
+    exe: &mut impl repo::AsExec,
+    name: &str,
+) -> Result<uuid::Uuid, repo::Error> {
+    let job = Job::TopicCompact {
+        name: name.to_string(),
+    };
+    let row = repo::job_enqueue(exe, QUEUE_ACTIONS, &serde_json::to_value(&job)?).await?;
+    Ok(row.job_queue_id)
+}
+
+/// Spawns `worker_count` polling workers plus one heartbeat sweeper on `rt`.
+///
+/// All of them stop as soon as `shutdown` is notified, mirroring how the
+/// flight service itself shuts down.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn(
+    rt: &tokio::runtime::Runtime,
+    repo: repo::Repository,
+    store: store::StoreRef,
+    worker_count: usize,
+    heartbeat_interval: Duration,
+    heartbeat_timeout: Duration,
+    max_retries: u32,
+    poll_warn_threshold: Duration,
+    shutdown: Arc<Notify>,
+) {
+    if worker_count == 0 {
+        trace!("job queue disabled (`job_workers` is 0)");
+        return;
+    }
+
+    for worker_id in 0..worker_count {
+        let repo = repo.clone();
+        let store = store.clone();
+        let shutdown = shutdown.clone();
+        rt.spawn(async move {
+            run_worker(
+                worker_id,
+                repo,
+                store,
+                heartbeat_interval,
+                max_retries,
+                poll_warn_threshold,
+                shutdown,
+            )
+            .await
+        });
+    }
+
+    let repo = repo.clone();
+    rt.spawn(async move { run_sweeper(repo, heartbeat_interval, heartbeat_timeout, shutdown).await });
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_worker(
+    worker_id: usize,
+    repo: repo::Repository,
+    store: store::StoreRef,
+    poll_interval: Duration,
+    max_retries: u32,
+    poll_warn_threshold: Duration,
+    shutdown: Arc<Notify>,
+) {
+    trace!("job worker {worker_id} starting on queues `{:?}`", QUEUES);
+    loop {
+        tokio::select! {
+            _ = shutdown.notified() => {
+                trace!("job worker {worker_id} stopping");
+                return;
+            }
+            claimed = claim_from_any_queue(&repo, max_retries) => {
+                let claimed = match claimed {
+                    Ok(Some(claimed)) => claimed,
+                    Ok(None) => {
+                        tokio::time::sleep(poll_interval).await;
+                        continue;
+                    }
+                    Err(err) => {
+                        warn!("job worker {worker_id} failed to claim a job: {}", err);
+                        tokio::time::sleep(poll_interval).await;
+                        continue;
+                    }
+                };
+
+                let id = claimed.id;
+                let result =
+                    run_job_with_poll_warning(&repo, &store, claimed, poll_warn_threshold).await;
+
+                match result {
+                    Ok(()) => {
+                        if let Err(err) = repo::FacadeJobQueue::complete(&repo, id).await {
+                            error!("job worker {worker_id} failed to mark job {} complete: {}", id, err);
+                        }
+                    }
+                    Err(err) => match repo::FacadeJobQueue::fail(&repo, id).await {
+                        Ok(failure) if failure.retries as u32 >= max_retries => {
+                            error!(
+                                "job worker {worker_id} job {} failed permanently after {} attempt(s), giving up: {}",
+                                id, failure.retries, err
+                            );
+                        }
+                        Ok(failure) => {
+                            warn!(
+                                "job worker {worker_id} job {} failed (attempt {}/{}), rescheduling with backoff: {}",
+                                id, failure.retries, max_retries, err
+                            );
+                        }
+                        Err(fail_err) => {
+                            error!(
+                                "job worker {worker_id} failed to record failed attempt for job {}: {}",
+                                id, fail_err
+                            );
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Tries to claim a job off each queue in [`QUEUES`], in order, returning
+/// the first one found.
+async fn claim_from_any_queue(
+    repo: &repo::Repository,
+    max_retries: u32,
+) -> Result<Option<repo::ClaimedJob>, repo::FacadeError> {
+    for queue in QUEUES {
+        if let Some(claimed) = repo::FacadeJobQueue::claim_next(repo, queue, max_retries).await? {
+            return Ok(Some(claimed));
+        }
+    }
+    Ok(None)
+}
+
+/// Runs `claimed`, logging a warning every time a single poll of the
+/// underlying future takes longer than `poll_warn_threshold` without the
+/// job completing, to surface accidental blocking in async code.
+async fn run_job_with_poll_warning(
+    repo: &repo::Repository,
+    store: &store::StoreRef,
+    claimed: repo::ClaimedJob,
+    poll_warn_threshold: Duration,
+) -> Result<(), repo::FacadeError> {
+    let id = claimed.id;
+    let started = tokio::time::Instant::now();
+    let mut fut = Box::pin(run_job(repo, store, claimed));
+
+    loop {
+        match tokio::time::timeout(poll_warn_threshold, &mut fut).await {
+            Ok(result) => return result,
+            Err(_) => warn!(
+                "job {} has been running for {:?} without completing (exceeded the {:?} poll-warning threshold); this may indicate blocking work in async code",
+                id, started.elapsed(), poll_warn_threshold
+            ),
+        }
+    }
+}
+
+async fn run_job(
+    repo: &repo::Repository,
+    store: &store::StoreRef,
+    claimed: repo::ClaimedJob,
+) -> Result<(), repo::FacadeError> {
+    let job: Job = serde_json::from_value(claimed.job)?;
+    match job {
+        Job::Reindex { topic_id, .. } => Ok(reindex_topic(repo, topic_id).await?),
+        Job::SequenceDelete { name } => sequence_delete(repo, store.clone(), name).await,
+        Job::TopicDelete { name } => topic_delete(repo, store.clone(), name).await,
+        Job::SequenceAbort { name, key } => sequence_abort(repo, store.clone(), name, key).await,
+        Job::SequenceFinalize { name, key } => {
+            sequence_finalize(repo, store.clone(), name, key).await
+        }
+        Job::TopicCompact { name } => topic_compact(repo, store.clone(), name).await,
+    }
+}
+
+/// Re-locks `topic_id` if it's still unlocked.
+///
+/// A `finalize()` that fails partway through only ever leaves the lock step
+/// undone: every chunk it already wrote committed its own stats
+/// transactionally as it was created (see `FacadeChunk::finalize`), so
+/// completing the lock is enough to make the topic visible again.
+async fn reindex_topic(repo: &repo::Repository, topic_id: i32) -> Result<(), repo::Error> {
+    let mut tx = repo.transaction().await?;
+
+    let topics = repo::topic_find_by_ids(&mut tx, &[topic_id]).await?;
+    let Some(topic) = topics.into_iter().next() else {
+        // The topic was deleted after the job was enqueued; nothing to redo.
+        tx.commit().await?;
+        return Ok(());
+    };
+
+    if !topic.is_locked() {
+        let loc = types::TopicResourceLocator::from(topic.topic_name.as_str());
+        repo::topic_lock(&mut tx, &loc).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Deletes sequence `name`, re-checking that it's still unlocked since
+/// time may have passed between the `do_action` request and this job
+/// running.
+async fn sequence_delete(
+    repo: &repo::Repository,
+    store: store::StoreRef,
+    name: String,
+) -> Result<(), repo::FacadeError> {
+    let handle = FacadeSequence::new(name, store, repo.clone());
+    if handle.is_locked().await? {
+        return Err(repo::FacadeError::SequenceLocked);
+    }
+
+    let loc = handle.locator.clone();
+    handle.delete().await?;
+    warn!("resource {} deleted", loc);
+    Ok(())
+}
+
+/// Deletes topic `name`, re-checking that it's still unlocked.
+async fn topic_delete(
+    repo: &repo::Repository,
+    store: store::StoreRef,
+    name: String,
+) -> Result<(), repo::FacadeError> {
+    let handle = FacadeTopic::new(name.clone(), store, repo.clone());
+    if handle.is_locked().await? {
+        return Err(repo::FacadeError::SequenceLocked);
+    }
+
+    handle.delete().await?;
+    warn!("resource {} deleted", name);
+    Ok(())
+}
+
+/// Merges topic `name`'s small data files into fewer, larger ones,
+/// re-checking that it's still locked since time may have passed between
+/// the `do_action` request and this job running.
+async fn topic_compact(
+    repo: &repo::Repository,
+    store: store::StoreRef,
+    name: String,
+) -> Result<(), repo::FacadeError> {
+    let handle = FacadeTopic::new(name.clone(), store, repo.clone());
+    if !handle.is_locked().await? {
+        return Err(repo::FacadeError::TopicUnlocked);
+    }
+
+    let report = handle.compact().await?;
+    trace!(
+        "resource {} compacted ({} chunk(s) merged into {})",
+        name, report.chunks_merged, report.chunks_written
+    );
+    Ok(())
+}
+
+/// Aborts sequence `name`, re-checking that it's still unlocked and that
+/// `key` still matches its resource id.
+async fn sequence_abort(
+    repo: &repo::Repository,
+    store: store::StoreRef,
+    name: String,
+    key: String,
+) -> Result<(), repo::FacadeError> {
+    let handle = FacadeSequence::new(name, store, repo.clone());
+    if handle.is_locked().await? {
+        return Err(repo::FacadeError::SequenceLocked);
+    }
+
+    let r_id = handle.resource_id().await?;
+    let received_uuid: uuid::Uuid = key
+        .parse()
+        .map_err(|_| repo::FacadeError::NotFound("malformed key".to_string()))?;
+    if r_id.uuid != received_uuid {
+        return Err(repo::FacadeError::Unauthorized);
+    }
+
+    let loc = handle.locator.clone();
+    handle.delete().await?;
+    warn!("resource {} deleted", loc.name());
+    Ok(())
+}
+
+/// Finalizes (locks) sequence `name`, re-checking that `key` still matches
+/// its resource id.
+async fn sequence_finalize(
+    repo: &repo::Repository,
+    store: store::StoreRef,
+    name: String,
+    key: String,
+) -> Result<(), repo::FacadeError> {
+    let handle = FacadeSequence::new(name, store, repo.clone());
+
+    let r_id = handle.resource_id().await?;
+    let received_uuid: uuid::Uuid = key
+        .parse()
+        .map_err(|_| repo::FacadeError::NotFound("malformed key".to_string()))?;
+    if r_id.uuid != received_uuid {
+        return Err(repo::FacadeError::Unauthorized);
+    }
+
+    handle.lock().await?;
+    trace!("resource {} locked", handle.locator);
+    Ok(())
+}
+
+async fn run_sweeper(
+    repo: repo::Repository,
+    poll_interval: Duration,
+    heartbeat_timeout: Duration,
+    shutdown: Arc<Notify>,
+) {
+    trace!("job sweeper starting (timeout: {:?})", heartbeat_timeout);
+    let timeout =
+        chrono::Duration::from_std(heartbeat_timeout).unwrap_or_else(|_| chrono::Duration::zero());
+
+    loop {
+        tokio::select! {
+            _ = shutdown.notified() => {
+                trace!("job sweeper stopping");
+                return;
+            }
+            _ = tokio::time::sleep(poll_interval) => {
+                match repo::FacadeJobQueue::sweep_stale(&repo, timeout).await {
+                    Ok(0) => {}
+                    Ok(n) => warn!("reclaimed {n} stale job(s) whose worker stopped heartbeating"),
+                    Err(err) => error!("failed to sweep stale jobs: {}", err),
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,14 @@
+mod auth;
 mod core;
 mod errors;
 mod flight;
+mod jobs;
+mod tls;
+mod upload_lock;
 
 mod endpoints;
 
+pub use auth::Credentials;
 pub use core::Server;
 pub use errors::ServerError;
+pub use tls::TlsSource;
@@ -0,0 +1,324 @@
+//! TLS termination for the Flight service.
+//!
+//! A certificate/key pair is obtained either from a static PEM pair on disk
+//! (`TlsSource::Static`) or automatically via ACME (`TlsSource::Acme`), with
+//! ACME-issued material (and the account key used to request it) cached in
+//! the configured [`store::Store`] so a restart doesn't re-request a
+//! certificate. [`spawn_renewal`] keeps an ACME certificate renewed in the
+//! background and pushes the replacement out over a `watch` channel, which
+//! `Server::start_and_wait` uses to restart the Flight listener with the new
+//! material rather than ever falling back to plaintext.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info, trace};
+use tokio::sync::{watch, Notify};
+
+use crate::store;
+
+use super::errors::ServerError;
+
+/// How a Flight listener's TLS certificate is obtained.
+#[derive(Debug, Clone)]
+pub enum TlsSource {
+    /// Load a static PEM certificate/key pair from disk. Never renewed by
+    /// `mosaicod` itself.
+    Static {
+        cert_path: std::path::PathBuf,
+        key_path: std::path::PathBuf,
+    },
+    /// Obtain (and keep renewed) a certificate via ACME, e.g. Let's Encrypt.
+    Acme { domain: String, contact: String },
+}
+
+/// A loaded PEM certificate chain and private key, ready to be handed to
+/// tonic's TLS transport.
+#[derive(Debug, Clone)]
+pub struct TlsMaterial {
+    pub cert_pem: Vec<u8>,
+    pub key_pem: Vec<u8>,
+}
+
+/// Renew an ACME certificate once it's within this long of expiring.
+fn renew_within() -> chrono::Duration {
+    chrono::Duration::days(30)
+}
+
+/// How often the renewal task wakes up to check the cached certificate's
+/// expiry. Cheap relative to the renewal window, so there's no need to track the
+/// exact expiry time across restarts.
+const RENEWAL_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// Obtains the initial TLS material for `source`, provisioning it via ACME
+/// (or loading it from the object store's cache) if needed.
+///
+/// Provisioning failures are returned as an error: there is no plaintext
+/// fallback, so a broken ACME setup keeps the server from starting at all
+/// rather than silently serving without TLS.
+pub async fn provision(
+    source: &TlsSource,
+    store: &store::StoreRef,
+) -> Result<TlsMaterial, ServerError> {
+    match source {
+        TlsSource::Static {
+            cert_path,
+            key_path,
+        } => Ok(TlsMaterial {
+            cert_pem: std::fs::read(cert_path)?,
+            key_pem: std::fs::read(key_path)?,
+        }),
+        TlsSource::Acme { domain, contact } => acme::provision(domain, contact, store).await,
+    }
+}
+
+/// Spawns the background ACME renewal loop for `source`, publishing each
+/// renewed [`TlsMaterial`] on `tx`.
+///
+/// A no-op for [`TlsSource::Static`], which `mosaicod` never renews itself.
+/// Stops as soon as `shutdown` is notified.
+pub fn spawn_renewal(
+    rt: &tokio::runtime::Runtime,
+    source: TlsSource,
+    store: store::StoreRef,
+    shutdown: Arc<Notify>,
+    tx: watch::Sender<Option<TlsMaterial>>,
+) {
+    let TlsSource::Acme { domain, contact } = source else {
+        trace!("tls certificate is static, no renewal task needed");
+        return;
+    };
+
+    rt.spawn(async move {
+        loop {
+            tokio::select! {
+                _ = shutdown.notified() => {
+                    trace!("tls renewal task stopping");
+                    return;
+                }
+                _ = tokio::time::sleep(RENEWAL_CHECK_INTERVAL) => {}
+            }
+
+            match acme::renew_if_expiring_soon(&domain, &contact, &store).await {
+                Ok(Some(material)) => {
+                    info!("renewed ACME certificate for `{}`", domain);
+                    // Only fails if every receiver (the Flight listener loop)
+                    // has been dropped, which only happens during shutdown.
+                    let _ = tx.send(Some(material));
+                }
+                Ok(None) => trace!("ACME certificate for `{}` is not due for renewal", domain),
+                Err(err) => error!("failed to renew ACME certificate for `{}`: {}", domain, err),
+            }
+        }
+    });
+}
+
+mod acme {
+    use instant_acme::{
+        Account, AccountCredentials, AuthorizationStatus, ChallengeType, Identifier, NewAccount,
+        NewOrder, OrderStatus, RetryPolicy,
+    };
+    use log::trace;
+
+    use crate::store;
+
+    use super::{renew_within, ServerError, TlsMaterial};
+
+    const DIRECTORY_URL: &str = "https://acme-v02.api.letsencrypt.org/directory";
+
+    fn cache_path(domain: &str, name: &str) -> String {
+        format!("_mosaico/acme/{}/{}", domain, name)
+    }
+
+    pub(super) async fn provision(
+        domain: &str,
+        contact: &str,
+        store: &store::StoreRef,
+    ) -> Result<TlsMaterial, ServerError> {
+        if let Some(material) = load_cached(domain, store).await? {
+            if !expires_within(&material, renew_within())? {
+                return Ok(material);
+            }
+        }
+
+        request_certificate(domain, contact, store).await
+    }
+
+    /// Renews the cached certificate for `domain` if it's within
+    /// the renewal window of expiry. Returns `Ok(None)` if it isn't due yet.
+    pub(super) async fn renew_if_expiring_soon(
+        domain: &str,
+        contact: &str,
+        store: &store::StoreRef,
+    ) -> Result<Option<TlsMaterial>, ServerError> {
+        if let Some(material) = load_cached(domain, store).await? {
+            if !expires_within(&material, renew_within())? {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(request_certificate(domain, contact, store).await?))
+    }
+
+    async fn load_cached(
+        domain: &str,
+        store: &store::StoreRef,
+    ) -> Result<Option<TlsMaterial>, ServerError> {
+        let cert_pem = match store.read_bytes(cache_path(domain, "cert.pem")).await {
+            Ok(bytes) => bytes,
+            Err(store::Error::BackendError(object_store::Error::NotFound { .. })) => {
+                return Ok(None);
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let key_pem = store.read_bytes(cache_path(domain, "key.pem")).await?;
+
+        Ok(Some(TlsMaterial { cert_pem, key_pem }))
+    }
+
+    async fn save_cached(
+        domain: &str,
+        store: &store::StoreRef,
+        material: &TlsMaterial,
+    ) -> Result<(), ServerError> {
+        store
+            .write_bytes(cache_path(domain, "cert.pem"), material.cert_pem.clone())
+            .await?;
+        store
+            .write_bytes(cache_path(domain, "key.pem"), material.key_pem.clone())
+            .await?;
+        Ok(())
+    }
+
+    fn expires_within(
+        material: &TlsMaterial,
+        window: chrono::Duration,
+    ) -> Result<bool, ServerError> {
+        let (_, pem) = x509_parser::pem::parse_x509_pem(&material.cert_pem).map_err(|e| {
+            ServerError::TlsError(format!("cached certificate is not valid PEM: {}", e))
+        })?;
+        let cert = pem.parse_x509().map_err(|e| {
+            ServerError::TlsError(format!("cached certificate is not valid X.509: {}", e))
+        })?;
+
+        let not_after =
+            chrono::DateTime::parse_from_rfc3339(&cert.validity().not_after.to_rfc3339())
+                .map_err(|e| ServerError::TlsError(e.to_string()))?
+                .with_timezone(&chrono::Utc);
+
+        Ok(not_after - chrono::Utc::now() < window)
+    }
+
+    /// Loads the cached ACME account for `domain`, creating (and caching)
+    /// a new one with the CA if none exists yet.
+    async fn account_for(
+        domain: &str,
+        contact: &str,
+        store: &store::StoreRef,
+    ) -> Result<Account, ServerError> {
+        let key_path = cache_path(domain, "account.json");
+
+        if let Ok(bytes) = store.read_bytes(&key_path).await {
+            let creds: AccountCredentials = serde_json::from_slice(&bytes)?;
+            return Ok(Account::from_credentials(creds).await.map_err(|e| {
+                ServerError::TlsError(format!("failed to restore ACME account: {}", e))
+            })?);
+        }
+
+        let (account, creds) = Account::create(
+            &NewAccount {
+                contact: &[&format!("mailto:{}", contact)],
+                terms_of_service_agreed: true,
+                only_return_existing: false,
+            },
+            DIRECTORY_URL,
+            None,
+        )
+        .await
+        .map_err(|e| ServerError::TlsError(format!("failed to create ACME account: {}", e)))?;
+
+        store
+            .write_bytes(&key_path, serde_json::to_vec(&creds)?)
+            .await?;
+
+        Ok(account)
+    }
+
+    async fn request_certificate(
+        domain: &str,
+        contact: &str,
+        store: &store::StoreRef,
+    ) -> Result<TlsMaterial, ServerError> {
+        trace!("requesting ACME certificate for `{}`", domain);
+
+        let account = account_for(domain, contact, store).await?;
+
+        let identifier = Identifier::Dns(domain.to_string());
+        let mut order = account
+            .new_order(&NewOrder::new(&[identifier]))
+            .await
+            .map_err(|e| ServerError::TlsError(format!("failed to create ACME order: {}", e)))?;
+
+        let mut authorizations = order
+            .authorizations()
+            .await
+            .map_err(|e| ServerError::TlsError(format!("failed to fetch authorizations: {}", e)))?;
+
+        for authz in &mut authorizations {
+            if authz.status != AuthorizationStatus::Pending {
+                continue;
+            }
+
+            // TLS-ALPN-01 is answered on the same port the Flight listener
+            // already binds, so it doesn't need a separate HTTP-01 listener
+            // on port 80. Fall back to HTTP-01 for CAs that don't offer it.
+            let mut challenge = authz
+                .challenge(ChallengeType::TlsAlpn01)
+                .or_else(|| authz.challenge(ChallengeType::Http01))
+                .ok_or_else(|| {
+                    ServerError::TlsError("no supported ACME challenge offered".to_string())
+                })?;
+
+            challenge.set_ready().await.map_err(|e| {
+                ServerError::TlsError(format!("failed to complete ACME challenge: {}", e))
+            })?;
+        }
+
+        order
+            .poll_ready(&RetryPolicy::default())
+            .await
+            .map_err(|e| ServerError::TlsError(format!("ACME order never became ready: {}", e)))?;
+
+        if order.state().status != OrderStatus::Ready {
+            return Err(ServerError::TlsError(
+                "ACME order did not reach `ready`".to_string(),
+            ));
+        }
+
+        let key_pair = rcgen::KeyPair::generate()
+            .map_err(|e| ServerError::TlsError(format!("failed to generate key pair: {}", e)))?;
+        let params = rcgen::CertificateParams::new(vec![domain.to_string()])
+            .map_err(|e| ServerError::TlsError(format!("failed to build CSR params: {}", e)))?;
+        let csr = params
+            .serialize_request(&key_pair)
+            .map_err(|e| ServerError::TlsError(format!("failed to build CSR: {}", e)))?;
+
+        order
+            .finalize(csr.der())
+            .await
+            .map_err(|e| ServerError::TlsError(format!("failed to finalize ACME order: {}", e)))?;
+        let cert_chain_pem = order
+            .poll_certificate(&RetryPolicy::default())
+            .await
+            .map_err(|e| ServerError::TlsError(format!("failed to fetch certificate: {}", e)))?;
+
+        let material = TlsMaterial {
+            cert_pem: cert_chain_pem.into_bytes(),
+            key_pem: key_pair.serialize_pem().into_bytes(),
+        };
+
+        save_cached(domain, store, &material).await?;
+
+        Ok(material)
+    }
+}
@@ -0,0 +1,78 @@
+//! In-process serialization for concurrent uploads that target
+//! sub-resources of one another (see [`types::Resource::is_sub_resource`]),
+//! so two writers can't interleave chunk numbering on the same path -- e.g.
+//! a topic re-uploaded while a previous attempt at it is still in flight.
+//!
+//! This is deliberately process-local rather than persisted: it only needs
+//! to hold for the lifetime of the concurrent streams it's protecting, and
+//! every writer it could conflict with is, by definition, a live request on
+//! this same process.
+
+use std::sync::{Mutex, OnceLock};
+
+use tokio::sync::Notify;
+
+use crate::types::{self, Resource};
+
+struct Registry {
+    active: Mutex<Vec<Box<dyn Resource>>>,
+    notify: Notify,
+}
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Registry {
+        active: Mutex::new(Vec::new()),
+        notify: Notify::new(),
+    })
+}
+
+/// Held for the lifetime of one upload; dropping it frees the resource (and
+/// whatever it conflicted with) for the next waiter.
+pub struct UploadGuard {
+    name: String,
+}
+
+impl Drop for UploadGuard {
+    fn drop(&mut self) {
+        let registry = registry();
+        registry
+            .active
+            .lock()
+            .expect("upload lock registry poisoned")
+            .retain(|r| r.name() != &self.name);
+        registry.notify.notify_waiters();
+    }
+}
+
+/// Waits until no in-flight upload targets a resource that is a
+/// sub-resource of `locator`, in either direction (a topic uploading while
+/// its parent sequence is also mid-upload, or the reverse, would both race
+/// on the same on-disk chunk numbering), then registers `locator` as active
+/// and returns a guard that frees it again on drop.
+pub async fn acquire(locator: impl types::Resource + Clone + 'static) -> UploadGuard {
+    loop {
+        // Registered before the check so a guard dropped between the check
+        // and the wait below still wakes this call.
+        let notified = registry().notify.notified();
+        {
+            let mut active = registry()
+                .active
+                .lock()
+                .expect("upload lock registry poisoned");
+
+            let conflicts = active
+                .iter()
+                .any(|r| locator.is_sub_resource(r.as_ref()) || r.is_sub_resource(&locator));
+
+            if !conflicts {
+                active.push(Box::new(locator.clone()));
+                return UploadGuard {
+                    name: locator.name().clone(),
+                };
+            }
+        }
+
+        notified.await;
+    }
+}
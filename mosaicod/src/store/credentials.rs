@@ -0,0 +1,452 @@
+//! AWS credential resolution for [`super::S3Config`], mirroring the
+//! standard AWS SDK credential chain: explicit static keys, then
+//! environment variables, then the EC2/ECS instance metadata service
+//! (IMDSv2), then a web-identity token exchanged with STS.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+use crate::params::Hidden;
+
+const IMDS_ENDPOINT: &str = "http://169.254.169.254";
+const IMDS_TOKEN_TTL_SECS: &str = "21600";
+const STS_ENDPOINT: &str = "https://sts.amazonaws.com";
+
+/// Credentials are refreshed this long before they actually expire, so a
+/// request in flight never races an expiring token.
+fn expiry_safety_margin() -> chrono::Duration {
+    chrono::Duration::seconds(60)
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("no credential provider in the chain produced credentials")]
+    ChainExhausted,
+    #[error("http error contacting `{0}`: {1}")]
+    Http(String, reqwest::Error),
+    #[error("malformed response from `{0}`: {1}")]
+    MalformedResponse(String, String),
+    #[error("missing required environment variable `{0}`")]
+    MissingEnvVar(String),
+}
+
+/// Resolved AWS credentials, with an optional session token and expiry for
+/// temporary credentials (IMDS/STS). Static/env credentials never expire.
+#[derive(Clone)]
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: Hidden,
+    pub session_token: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &self.secret_access_key)
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| "***"),
+            )
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+impl Credentials {
+    fn is_fresh(&self) -> bool {
+        match self.expires_at {
+            Some(expiry) => Utc::now() + expiry_safety_margin() < expiry,
+            None => true,
+        }
+    }
+}
+
+/// A source of AWS credentials, tried in order by [`ChainCredentialProvider`].
+///
+/// Boxes its future instead of using `async fn` so the trait stays object
+/// safe: [`ChainCredentialProvider`] holds its tiers as `Arc<dyn
+/// CredentialProvider>`, and [`RefreshingCredentials`] holds the whole chain
+/// the same way.
+pub trait CredentialProvider: fmt::Debug + Send + Sync {
+    fn credentials(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Credentials, Error>> + Send + '_>>;
+}
+
+/// Tier 1: the access/secret key pair given directly on [`super::S3Config`].
+#[derive(Debug)]
+pub struct StaticCredentialProvider {
+    access_key_id: String,
+    secret_access_key: Hidden,
+}
+
+impl StaticCredentialProvider {
+    pub fn new(access_key_id: String, secret_access_key: Hidden) -> Self {
+        Self {
+            access_key_id,
+            secret_access_key,
+        }
+    }
+}
+
+impl CredentialProvider for StaticCredentialProvider {
+    fn credentials(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Credentials, Error>> + Send + '_>> {
+        Box::pin(async move {
+            Ok(Credentials {
+                access_key_id: self.access_key_id.clone(),
+                secret_access_key: self.secret_access_key.clone(),
+                session_token: None,
+                expires_at: None,
+            })
+        })
+    }
+}
+
+/// Tier 2: `AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY`/`AWS_SESSION_TOKEN`.
+#[derive(Debug, Default)]
+pub struct EnvCredentialProvider;
+
+impl CredentialProvider for EnvCredentialProvider {
+    fn credentials(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Credentials, Error>> + Send + '_>> {
+        Box::pin(async move {
+            let access_key_id = std::env::var("AWS_ACCESS_KEY_ID")
+                .map_err(|_| Error::MissingEnvVar("AWS_ACCESS_KEY_ID".to_string()))?;
+            let secret_access_key = std::env::var("AWS_SECRET_ACCESS_KEY")
+                .map_err(|_| Error::MissingEnvVar("AWS_SECRET_ACCESS_KEY".to_string()))?;
+            let session_token = std::env::var("AWS_SESSION_TOKEN").ok();
+
+            Ok(Credentials {
+                access_key_id,
+                secret_access_key: Hidden::from(secret_access_key),
+                session_token,
+                expires_at: None,
+            })
+        })
+    }
+}
+
+/// Tier 3: the EC2/ECS instance metadata service (IMDSv2): a `PUT` fetches a
+/// short-lived session token, then that token authorizes a `GET` for the
+/// instance's attached IAM role and its current temporary credentials.
+#[derive(Debug)]
+pub struct ImdsCredentialProvider {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+impl ImdsCredentialProvider {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: IMDS_ENDPOINT.to_string(),
+        }
+    }
+
+    async fn fetch_token(&self) -> Result<String, Error> {
+        let url = format!("{}/latest/api/token", self.endpoint);
+        let res = self
+            .client
+            .put(&url)
+            .header("X-aws-ec2-metadata-token-ttl-seconds", IMDS_TOKEN_TTL_SECS)
+            .send()
+            .await
+            .map_err(|e| Error::Http(url.clone(), e))?
+            .error_for_status()
+            .map_err(|e| Error::Http(url.clone(), e))?;
+
+        res.text().await.map_err(|e| Error::Http(url, e))
+    }
+
+    async fn fetch_role_name(&self, token: &str) -> Result<String, Error> {
+        let url = format!(
+            "{}/latest/meta-data/iam/security-credentials/",
+            self.endpoint
+        );
+        let res = self
+            .client
+            .get(&url)
+            .header("X-aws-ec2-metadata-token", token)
+            .send()
+            .await
+            .map_err(|e| Error::Http(url.clone(), e))?
+            .error_for_status()
+            .map_err(|e| Error::Http(url.clone(), e))?;
+
+        let body = res.text().await.map_err(|e| Error::Http(url.clone(), e))?;
+        body.lines().next().map(str::to_string).ok_or_else(|| {
+            Error::MalformedResponse(url, "no IAM role attached to this instance".to_string())
+        })
+    }
+}
+
+impl Default for ImdsCredentialProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct ImdsSecurityCredentials {
+    #[serde(rename = "AccessKeyId")]
+    access_key_id: String,
+    #[serde(rename = "SecretAccessKey")]
+    secret_access_key: String,
+    #[serde(rename = "Token")]
+    token: String,
+    #[serde(rename = "Expiration")]
+    expiration: DateTime<Utc>,
+}
+
+impl CredentialProvider for ImdsCredentialProvider {
+    fn credentials(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Credentials, Error>> + Send + '_>> {
+        Box::pin(async move {
+            let token = self.fetch_token().await?;
+            let role = self.fetch_role_name(&token).await?;
+
+            let url = format!(
+                "{}/latest/meta-data/iam/security-credentials/{role}",
+                self.endpoint
+            );
+            let res = self
+                .client
+                .get(&url)
+                .header("X-aws-ec2-metadata-token", &token)
+                .send()
+                .await
+                .map_err(|e| Error::Http(url.clone(), e))?
+                .error_for_status()
+                .map_err(|e| Error::Http(url.clone(), e))?;
+
+            let creds: ImdsSecurityCredentials =
+                res.json().await.map_err(|e| Error::Http(url, e))?;
+
+            Ok(Credentials {
+                access_key_id: creds.access_key_id,
+                secret_access_key: Hidden::from(creds.secret_access_key),
+                session_token: Some(creds.token),
+                expires_at: Some(creds.expiration),
+            })
+        })
+    }
+}
+
+/// Tier 4: a Kubernetes/OIDC web-identity token, exchanged for temporary
+/// credentials via STS `AssumeRoleWithWebIdentity`.
+#[derive(Debug)]
+pub struct WebIdentityCredentialProvider {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+impl WebIdentityCredentialProvider {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: STS_ENDPOINT.to_string(),
+        }
+    }
+}
+
+impl Default for WebIdentityCredentialProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialProvider for WebIdentityCredentialProvider {
+    fn credentials(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Credentials, Error>> + Send + '_>> {
+        Box::pin(async move {
+            let token_file = std::env::var("AWS_WEB_IDENTITY_TOKEN_FILE")
+                .map_err(|_| Error::MissingEnvVar("AWS_WEB_IDENTITY_TOKEN_FILE".to_string()))?;
+            let role_arn = std::env::var("AWS_ROLE_ARN")
+                .map_err(|_| Error::MissingEnvVar("AWS_ROLE_ARN".to_string()))?;
+            let session_name =
+                std::env::var("AWS_ROLE_SESSION_NAME").unwrap_or_else(|_| "mosaico".to_string());
+
+            let token = std::fs::read_to_string(&token_file)
+                .map_err(|e| Error::MalformedResponse(token_file, e.to_string()))?;
+
+            let res = self
+                .client
+                .get(&self.endpoint)
+                .query(&[
+                    ("Action", "AssumeRoleWithWebIdentity"),
+                    ("Version", "2011-06-15"),
+                    ("RoleArn", role_arn.as_str()),
+                    ("RoleSessionName", session_name.as_str()),
+                    ("WebIdentityToken", token.trim()),
+                ])
+                .send()
+                .await
+                .map_err(|e| Error::Http(self.endpoint.clone(), e))?
+                .error_for_status()
+                .map_err(|e| Error::Http(self.endpoint.clone(), e))?;
+
+            let body = res
+                .text()
+                .await
+                .map_err(|e| Error::Http(self.endpoint.clone(), e))?;
+
+            parse_assume_role_response(&body)
+        })
+    }
+}
+
+/// Pulls the fields mosaico needs out of STS's `AssumeRoleWithWebIdentity`
+/// XML response by tag name, rather than pulling in a full XML parser for a
+/// single fixed-shape response.
+fn parse_assume_role_response(body: &str) -> Result<Credentials, Error> {
+    let tag_contents = |tag: &str| -> Result<String, Error> {
+        let open = format!("<{tag}>");
+        let close = format!("</{tag}>");
+
+        let start = body.find(&open).ok_or_else(|| {
+            Error::MalformedResponse(
+                "sts:AssumeRoleWithWebIdentity".to_string(),
+                format!("missing `<{tag}>`"),
+            )
+        })? + open.len();
+        let end = body[start..].find(&close).ok_or_else(|| {
+            Error::MalformedResponse(
+                "sts:AssumeRoleWithWebIdentity".to_string(),
+                format!("missing `</{tag}>`"),
+            )
+        })? + start;
+
+        Ok(body[start..end].to_string())
+    };
+
+    let access_key_id = tag_contents("AccessKeyId")?;
+    let secret_access_key = tag_contents("SecretAccessKey")?;
+    let session_token = tag_contents("SessionToken")?;
+    let expiration = tag_contents("Expiration")?;
+
+    let expires_at = DateTime::parse_from_rfc3339(&expiration)
+        .map_err(|e| Error::MalformedResponse("Expiration".to_string(), e.to_string()))?
+        .with_timezone(&Utc);
+
+    Ok(Credentials {
+        access_key_id,
+        secret_access_key: Hidden::from(secret_access_key),
+        session_token: Some(session_token),
+        expires_at: Some(expires_at),
+    })
+}
+
+/// Tries each tier in order, returning the first that successfully produces
+/// credentials: static keys, then environment variables, then IMDS, then
+/// web-identity/STS -- the standard AWS SDK credential chain.
+#[derive(Debug)]
+pub struct ChainCredentialProvider {
+    providers: Vec<Arc<dyn CredentialProvider>>,
+}
+
+impl ChainCredentialProvider {
+    pub fn new(providers: Vec<Arc<dyn CredentialProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// The standard chain, with `static_keys` (if given) tried first.
+    pub fn standard(static_keys: Option<(String, Hidden)>) -> Self {
+        let mut providers: Vec<Arc<dyn CredentialProvider>> = Vec::new();
+
+        if let Some((access_key_id, secret_access_key)) = static_keys {
+            providers.push(Arc::new(StaticCredentialProvider::new(
+                access_key_id,
+                secret_access_key,
+            )));
+        }
+
+        providers.push(Arc::new(EnvCredentialProvider));
+        providers.push(Arc::new(ImdsCredentialProvider::new()));
+        providers.push(Arc::new(WebIdentityCredentialProvider::new()));
+
+        Self::new(providers)
+    }
+}
+
+impl CredentialProvider for ChainCredentialProvider {
+    fn credentials(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Credentials, Error>> + Send + '_>> {
+        Box::pin(async move {
+            for provider in &self.providers {
+                if let Ok(creds) = provider.credentials().await {
+                    return Ok(creds);
+                }
+            }
+
+            Err(Error::ChainExhausted)
+        })
+    }
+}
+
+/// Adapts a mosaico [`CredentialProvider`] to `object_store`'s own
+/// credential trait (what `AmazonS3Builder::with_credentials` consumes),
+/// caching the resolved [`Credentials`] and only calling back into the
+/// chain once they're within the expiry safety margin of expiring. This is
+/// what makes temporary IMDS/STS session tokens refresh transparently:
+/// static/env credentials never expire and are effectively fetched once.
+#[derive(Debug)]
+pub struct RefreshingCredentials {
+    chain: Arc<dyn CredentialProvider>,
+    cached: Mutex<Option<Credentials>>,
+}
+
+impl RefreshingCredentials {
+    pub fn new(chain: Arc<dyn CredentialProvider>) -> Self {
+        Self {
+            chain,
+            cached: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl object_store::CredentialProvider for RefreshingCredentials {
+    type Credential = object_store::aws::AwsCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<Self::Credential>> {
+        let mut guard = self.cached.lock().await;
+
+        if let Some(creds) = guard.as_ref() {
+            if creds.is_fresh() {
+                return Ok(Arc::new(to_aws_credential(creds)));
+            }
+        }
+
+        let fresh = self.chain.credentials().await.map_err(|e| object_store::Error::Generic {
+            store: "aws credential chain",
+            source: Box::new(e),
+        })?;
+
+        let aws_credential = to_aws_credential(&fresh);
+        *guard = Some(fresh);
+
+        Ok(Arc::new(aws_credential))
+    }
+}
+
+fn to_aws_credential(creds: &Credentials) -> object_store::aws::AwsCredential {
+    object_store::aws::AwsCredential {
+        key_id: creds.access_key_id.clone(),
+        secret_key: creds.secret_access_key.clone().take(),
+        token: creds.session_token.clone(),
+    }
+}
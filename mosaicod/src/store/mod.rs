@@ -0,0 +1,700 @@
+//! This module provides the [`Store`], the application's core client for interacting
+//! with S3-compatible object storage services providing
+//! essential CRUD (Create, Read, Update, Delete) methods for byte-level data access.
+
+use futures::{Stream, StreamExt, stream::TryStreamExt};
+use std::sync::Arc;
+
+use datafusion::execution::object_store::{DefaultObjectStoreRegistry, ObjectStoreRegistry};
+use log::trace;
+use object_store::{
+    MultipartUpload, ObjectStore, PutPayload, aws::AmazonS3Builder, azure::MicrosoftAzureBuilder,
+    gcp::GoogleCloudStorageBuilder, local::LocalFileSystem,
+};
+use thiserror::Error;
+use url::Url;
+
+use crate::{params, traits};
+
+mod credentials;
+pub use credentials::*;
+
+mod retry;
+pub use retry::*;
+
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    /// Bucket name.
+    pub bucket: String,
+    /// Endpoint name
+    pub endpoint: String,
+    /// Explicit static keys, the first tier of the credential chain built
+    /// by [`Store::try_from_s3_store`]. Leave unset to fall through to
+    /// `AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY`, then the EC2/ECS
+    /// instance metadata service, then a web-identity/STS exchange.
+    pub access_key: Option<String>,
+    pub secret_key: Option<params::Hidden>,
+    /// AWS region, wired to `AmazonS3Builder::with_region`.
+    pub region: Option<String>,
+    /// Opt-in retry/backoff budget for transient failures (5xx, throttling,
+    /// dropped connections). Leave unset to disable retrying entirely.
+    pub retry: Option<RetryConfig>,
+    /// Opt-in cap on simultaneous in-flight requests to the backend, so a
+    /// bulk `delete_recursive`/`list` over a huge prefix doesn't overwhelm
+    /// the endpoint. Only takes effect alongside `retry`.
+    pub max_concurrent_requests: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AzureConfig {
+    /// Storage account name.
+    pub account: String,
+    /// Container name.
+    pub container: String,
+    /// Explicit account access key. Leave unset to fall through to whatever
+    /// `MicrosoftAzureBuilder::from_env` picks up (`AZURE_STORAGE_*`
+    /// variables, including service-principal and managed-identity auth).
+    pub access_key: Option<params::Hidden>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GcsConfig {
+    /// Bucket name.
+    pub bucket: String,
+    /// Path to a service-account JSON key file. Leave unset to fall through
+    /// to whatever `GoogleCloudStorageBuilder::from_env` picks up
+    /// (`GOOGLE_SERVICE_ACCOUNT`, application-default credentials, ...).
+    pub service_account_path: Option<String>,
+}
+
+/// Per-backend config accepted by [`Store::from_url`], picked based on
+/// `url`'s scheme (`file://`, `s3://`, `az://`, `gs://`).
+#[derive(Debug, Clone)]
+pub enum StoreOptions {
+    Filesystem,
+    S3(S3Config),
+    Azure(AzureConfig),
+    Gcs(GcsConfig),
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("storage backend error: {0}")]
+    BackendError(#[from] object_store::Error),
+    #[error("bad url: {0}")]
+    BadUrl(#[from] url::ParseError),
+    #[error("io error :: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("unsupported store url scheme: {0}")]
+    UnsupportedScheme(String),
+    #[error("store url scheme {scheme} requires {expected} options, got {actual}")]
+    MismatchedOptions {
+        scheme: String,
+        expected: &'static str,
+        actual: &'static str,
+    },
+}
+
+impl Error {
+    /// Whether this error means the object simply doesn't exist, as
+    /// opposed to a transient or backend-level failure.
+    ///
+    /// Callers that need to distinguish "missing" from "broken" (e.g. a
+    /// repair scan checking whether a cataloged data file still exists)
+    /// should match on this instead of reaching into `object_store::Error`
+    /// directly.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::BackendError(object_store::Error::NotFound { .. }))
+    }
+}
+
+/// Subset of [`object_store::ObjectMeta`] exposed by [`Store::head`]: just
+/// enough for a caller to size a read and detect the object changing
+/// underneath a cached value.
+#[derive(Debug, Clone)]
+pub struct ObjectInfo {
+    pub size: usize,
+    /// Backend-assigned version tag. `None` on backends that don't support
+    /// one (e.g. the local filesystem driver).
+    pub e_tag: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum StoreTarget {
+    Filesystem(String),
+    S3Compatible(String),
+    Azure(String),
+    Gcs(String),
+}
+
+impl StoreTarget {
+    /// Short label identifying the backend, for metric label values (e.g.
+    /// [`crate::metrics::record_bytes_scanned`]). Deliberately not the
+    /// bucket/container/root path itself, which would blow up label
+    /// cardinality.
+    pub fn backend_label(&self) -> &'static str {
+        match self {
+            StoreTarget::Filesystem(_) => "file",
+            StoreTarget::S3Compatible(_) => "s3",
+            StoreTarget::Azure(_) => "az",
+            StoreTarget::Gcs(_) => "gs",
+        }
+    }
+}
+
+/// Default size of a single multipart upload part, used by
+/// [`Store::write_stream`] unless overridden via
+/// [`Store::with_multipart_chunk_size`].
+const DEFAULT_MULTIPART_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+/// S3 requires every part but the last of a multipart upload to be at
+/// least this size.
+const MIN_MULTIPART_CHUNK_SIZE: usize = 5 * 1024 * 1024;
+
+/// Implements the object storage client for the application.
+///
+/// It provides methods to read, write, list, and delete byte-level data
+/// from S3-compatible object storage services or local filesystem.
+#[derive(Debug, Clone)]
+pub struct Store {
+    pub url_schema: Url,
+    target: StoreTarget,
+    driver: Arc<dyn ObjectStore>,
+    registry: Arc<dyn ObjectStoreRegistry>,
+    multipart_chunk_size: usize,
+}
+
+pub type StoreRef = Arc<Store>;
+
+impl Store {
+    pub fn try_from_filesystem(root: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        // Create the directory structure if not existing
+        std::fs::create_dir_all(&root)?;
+
+        let target = root.as_ref().to_string_lossy().to_string();
+
+        let storage = Arc::new(LocalFileSystem::new_with_prefix(root)?);
+
+        let bucket_url = Url::parse("file://")?;
+
+        // Create object store registry (for datafusion support)
+        let registry = Arc::new(DefaultObjectStoreRegistry::default());
+        registry.register_store(&bucket_url, storage.clone());
+
+        Ok(Self {
+            url_schema: bucket_url,
+            target: StoreTarget::Filesystem(target),
+            driver: storage.clone(),
+            registry,
+            multipart_chunk_size: DEFAULT_MULTIPART_CHUNK_SIZE,
+        })
+    }
+
+    pub fn try_from_s3_store(config: S3Config) -> Result<Self, Error> {
+        trace!(
+            "creating object driver for a s3 compatible store, endpoint: {}",
+            config.endpoint
+        );
+
+        let bucket_url = Url::parse(&format!("s3://{}", config.bucket))?;
+
+        // Setup connection with object storage service. Credentials are
+        // resolved through the standard AWS chain (static keys, then env
+        // vars, then IMDS, then web-identity/STS) and refreshed
+        // transparently as temporary session tokens approach expiry.
+        let static_keys = match (config.access_key, config.secret_key) {
+            (Some(access_key), Some(secret_key)) => Some((access_key, secret_key)),
+            _ => None,
+        };
+        let credentials = Arc::new(RefreshingCredentials::new(Arc::new(
+            ChainCredentialProvider::standard(static_keys),
+        )));
+
+        let mut builder = AmazonS3Builder::new()
+            .with_endpoint(&config.endpoint)
+            .with_bucket_name(&config.bucket)
+            .with_credentials(credentials)
+            .with_allow_http(true);
+
+        if let Some(region) = &config.region {
+            builder = builder.with_region(region);
+        }
+
+        let storage: Arc<dyn ObjectStore> = Arc::new(builder.build()?);
+
+        // Retrying/throttling is opt-in: only wrap the driver when the
+        // caller actually configured a retry budget.
+        let storage: Arc<dyn ObjectStore> = match config.retry {
+            Some(retry) => Arc::new(RetryingObjectStore::new(
+                storage,
+                retry,
+                config.max_concurrent_requests,
+            )),
+            None => storage,
+        };
+
+        // Create object store registry (for datafusion support)
+        let registry = Arc::new(DefaultObjectStoreRegistry::default());
+        registry.register_store(&bucket_url, storage.clone());
+
+        Ok(Self {
+            url_schema: bucket_url,
+            target: StoreTarget::S3Compatible(config.bucket),
+            driver: storage.clone(),
+            registry: registry.clone(),
+            multipart_chunk_size: DEFAULT_MULTIPART_CHUNK_SIZE,
+        })
+    }
+
+    pub fn try_from_azure(config: AzureConfig) -> Result<Self, Error> {
+        trace!(
+            "creating object driver for azure blob storage, account: {}, container: {}",
+            config.account, config.container
+        );
+
+        let bucket_url = Url::parse(&format!("az://{}", config.container))?;
+
+        let mut builder = MicrosoftAzureBuilder::from_env()
+            .with_account(&config.account)
+            .with_container_name(&config.container);
+
+        if let Some(access_key) = config.access_key {
+            builder = builder.with_access_key(access_key.take());
+        }
+
+        let storage: Arc<dyn ObjectStore> = Arc::new(builder.build()?);
+
+        // Create object store registry (for datafusion support)
+        let registry = Arc::new(DefaultObjectStoreRegistry::default());
+        registry.register_store(&bucket_url, storage.clone());
+
+        Ok(Self {
+            url_schema: bucket_url,
+            target: StoreTarget::Azure(config.container),
+            driver: storage.clone(),
+            registry,
+            multipart_chunk_size: DEFAULT_MULTIPART_CHUNK_SIZE,
+        })
+    }
+
+    pub fn try_from_gcs(config: GcsConfig) -> Result<Self, Error> {
+        trace!(
+            "creating object driver for google cloud storage, bucket: {}",
+            config.bucket
+        );
+
+        let bucket_url = Url::parse(&format!("gs://{}", config.bucket))?;
+
+        let mut builder = GoogleCloudStorageBuilder::from_env().with_bucket_name(&config.bucket);
+
+        if let Some(path) = &config.service_account_path {
+            builder = builder.with_service_account_path(path);
+        }
+
+        let storage: Arc<dyn ObjectStore> = Arc::new(builder.build()?);
+
+        // Create object store registry (for datafusion support)
+        let registry = Arc::new(DefaultObjectStoreRegistry::default());
+        registry.register_store(&bucket_url, storage.clone());
+
+        Ok(Self {
+            url_schema: bucket_url,
+            target: StoreTarget::Gcs(config.bucket),
+            driver: storage.clone(),
+            registry,
+            multipart_chunk_size: DEFAULT_MULTIPART_CHUNK_SIZE,
+        })
+    }
+
+    /// Single entry point dispatching on `url`'s scheme (`file`, `s3`, `az`,
+    /// `gs`) to the matching `try_from_*` constructor, so the same binary
+    /// can target any backend via runtime configuration rather than a
+    /// compile-time choice.
+    ///
+    /// `options` must match the scheme (e.g. `url` starting with `s3://`
+    /// requires `StoreOptions::S3`); a mismatch is an error rather than a
+    /// silent fallback.
+    pub fn from_url(url: &Url, options: StoreOptions) -> Result<Self, Error> {
+        match (url.scheme(), options) {
+            ("file", StoreOptions::Filesystem) => Self::try_from_filesystem(url.path()),
+            ("s3", StoreOptions::S3(config)) => Self::try_from_s3_store(config),
+            ("az", StoreOptions::Azure(config)) => Self::try_from_azure(config),
+            ("gs", StoreOptions::Gcs(config)) => Self::try_from_gcs(config),
+            (scheme, options) => {
+                let actual = match options {
+                    StoreOptions::Filesystem => "Filesystem",
+                    StoreOptions::S3(_) => "S3",
+                    StoreOptions::Azure(_) => "Azure",
+                    StoreOptions::Gcs(_) => "Gcs",
+                };
+                match scheme {
+                    "file" | "s3" | "az" | "gs" => Err(Error::MismatchedOptions {
+                        scheme: scheme.to_string(),
+                        expected: match scheme {
+                            "file" => "Filesystem",
+                            "s3" => "S3",
+                            "az" => "Azure",
+                            _ => "Gcs",
+                        },
+                        actual,
+                    }),
+                    other => Err(Error::UnsupportedScheme(other.to_string())),
+                }
+            }
+        }
+    }
+
+    /// Overrides the multipart part size used by [`Store::write_stream`].
+    /// Clamped to the 5 MiB minimum S3 requires for all but the last part.
+    pub fn with_multipart_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.multipart_chunk_size = chunk_size.max(MIN_MULTIPART_CHUNK_SIZE);
+        self
+    }
+
+    pub fn registry(&self) -> Arc<dyn ObjectStoreRegistry> {
+        self.registry.clone()
+    }
+
+    pub fn target(&self) -> &StoreTarget {
+        &self.target
+    }
+
+    pub async fn read_bytes(&self, path: impl AsRef<std::path::Path>) -> Result<Vec<u8>, Error> {
+        trace!("reading bytes from {}", path.as_ref().display());
+        Ok(self
+            .driver
+            .get(&object_store::path::Path::from(
+                path.as_ref().to_string_lossy().to_string(),
+            ))
+            .await?
+            .bytes()
+            .await?
+            .into())
+    }
+
+    /// Reads only `range` (byte offsets) out of the object at `path`,
+    /// instead of materializing the whole thing like [`Store::read_bytes`]
+    /// does -- for seeking into large Parquet/recording objects, e.g. to
+    /// serve an HTTP byte-range request.
+    pub async fn read_range(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        range: std::ops::Range<usize>,
+    ) -> Result<Vec<u8>, Error> {
+        trace!(
+            "reading byte range {:?} from {}",
+            range,
+            path.as_ref().display()
+        );
+        Ok(self
+            .driver
+            .get_range(
+                &object_store::path::Path::from(path.as_ref().to_string_lossy().to_string()),
+                range,
+            )
+            .await?
+            .into())
+    }
+
+    /// Streams the object at `path` chunk-by-chunk instead of materializing
+    /// it whole like [`Store::read_bytes`] does -- for serving partial
+    /// reads over HTTP and for incremental DataFusion scans over large
+    /// objects.
+    pub async fn read_stream(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<impl Stream<Item = Result<bytes::Bytes, Error>>, Error> {
+        trace!("streaming bytes from {}", path.as_ref().display());
+
+        let result = self
+            .driver
+            .get(&object_store::path::Path::from(
+                path.as_ref().to_string_lossy().to_string(),
+            ))
+            .await?;
+
+        Ok(result.into_stream().map_err(Error::from))
+    }
+
+    pub async fn write_bytes(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        bytes: impl Into<bytes::Bytes>,
+    ) -> Result<(), Error> {
+        trace!("writing bytes to {}", path.as_ref().display());
+
+        self.driver
+            .put(
+                &object_store::path::Path::from(path.as_ref().to_string_lossy().to_string()),
+                PutPayload::from_bytes(bytes.into()),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Streams `bytes` to `path` through a multipart upload, for payloads
+    /// too large to buffer whole in memory (unlike [`Store::write_bytes`]).
+    ///
+    /// Incoming bytes are accumulated into a buffer and flushed as a part
+    /// whenever it reaches [`Store::with_multipart_chunk_size`] (default
+    /// [`DEFAULT_MULTIPART_CHUNK_SIZE`]), with the tail flushed as the final
+    /// part on completion. If `bytes` or a part upload fails partway
+    /// through, the multipart upload is aborted so no orphaned parts are
+    /// left on the backend.
+    pub async fn write_stream(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        bytes: impl futures::Stream<Item = Result<bytes::Bytes, Error>> + Unpin,
+    ) -> Result<(), Error> {
+        trace!("streaming bytes to {}", path.as_ref().display());
+
+        let location =
+            object_store::path::Path::from(path.as_ref().to_string_lossy().to_string());
+        let mut upload = self.driver.put_multipart(&location).await?;
+
+        match write_stream_parts(upload.as_mut(), bytes, self.multipart_chunk_size).await {
+            Ok(()) => {
+                upload.complete().await?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = upload.abort().await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns a list of elements located at the given `path`.
+    ///
+    /// If an extension is provided, the results will be filtered to include only
+    /// the elements whose extension matches exactly.es extacly
+    pub async fn list(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        extension: Option<&str>,
+    ) -> Result<Vec<String>, Error> {
+        let mut list_stream = self.driver.list(Some(&object_store::path::Path::from(
+            path.as_ref().to_string_lossy().to_string(),
+        )));
+
+        let mut locations = Vec::new();
+        while let Some(elem) = list_stream.try_next().await? {
+            let location = &elem.location;
+            // If some extension is provided:
+            // - check if current element has an extension, if has no extension
+            //   should the excluded
+            // - if has an extension but is different from the one provided shoukd
+            //   be excluded
+            if let Some(ext) = extension {
+                if let Some(path_ext) = location.extension() {
+                    if path_ext != ext {
+                        continue;
+                    }
+                } else {
+                    continue;
+                }
+            }
+            locations.push(location.to_string());
+        }
+
+        Ok(locations)
+    }
+
+    pub async fn size(&self, path: impl AsRef<std::path::Path>) -> Result<usize, Error> {
+        Ok(self.head(path).await?.size)
+    }
+
+    /// Returns the subset of an object's [`object_store::ObjectMeta`] that
+    /// callers outside this module need: its size and its ETag.
+    ///
+    /// `ETag` is what lets a caller (e.g. [`crate::query::ParquetFooterCache`])
+    /// detect that an object changed underneath a cached value without
+    /// re-fetching the object itself.
+    pub async fn head(&self, path: impl AsRef<std::path::Path>) -> Result<ObjectInfo, Error> {
+        let head = self
+            .driver
+            .head(&object_store::path::Path::from(
+                path.as_ref().to_string_lossy().to_string(),
+            ))
+            .await?;
+
+        Ok(ObjectInfo {
+            size: head.size as usize,
+            e_tag: head.e_tag,
+        })
+    }
+
+    pub async fn delete(&self, path: impl AsRef<std::path::Path>) -> Result<(), Error> {
+        Ok(self
+            .driver
+            .delete(&object_store::path::Path::from(
+                path.as_ref().to_string_lossy().to_string(),
+            ))
+            .await?)
+    }
+
+    /// Deletes recursively all objects under a given path
+    pub async fn delete_recursive(&self, path: impl AsRef<std::path::Path>) -> Result<(), Error> {
+        let mut list_stream = self.driver.list(Some(&object_store::path::Path::from(
+            path.as_ref().to_string_lossy().to_string(),
+        )));
+
+        while let Some(e) = list_stream.try_next().await? {
+            self.driver.delete(&e.location).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Drains `stream` into `upload`, flushing a part every time the buffer
+/// reaches `chunk_size` and flushing the tail at the end. Split out of
+/// [`Store::write_stream`] so that function can abort the multipart upload
+/// on any error returned here, rather than leaving it dangling.
+async fn write_stream_parts(
+    upload: &mut (dyn MultipartUpload + '_),
+    mut stream: impl Stream<Item = Result<bytes::Bytes, Error>> + Unpin,
+    chunk_size: usize,
+) -> Result<(), Error> {
+    let mut buffer = bytes::BytesMut::new();
+
+    while let Some(chunk) = stream.next().await {
+        buffer.extend_from_slice(&chunk?);
+
+        while buffer.len() >= chunk_size {
+            let part = buffer.split_to(chunk_size);
+            upload.put_part(PutPayload::from_bytes(part.freeze())).await?;
+        }
+    }
+
+    if !buffer.is_empty() {
+        upload.put_part(PutPayload::from_bytes(buffer.freeze())).await?;
+    }
+
+    Ok(())
+}
+
+impl traits::AsyncWriteToPath for Store {
+    /// Routes through [`Store::write_stream`]'s multipart upload once `buf`
+    /// reaches [`Store::multipart_chunk_size`], rather than always doing a
+    /// single-shot [`Store::write_bytes`] PUT regardless of size -- this is
+    /// the method [`crate::rw::ChunkedWriter`] calls once per finalized
+    /// chunk, so a topic with large per-chunk buffers now benefits from the
+    /// same multipart path large uploads elsewhere in this module already
+    /// use.
+    #[allow(clippy::manual_async_fn)]
+    fn write_to_path(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        buf: impl Into<bytes::Bytes>,
+    ) -> impl Future<Output = std::io::Result<()>> {
+        async move {
+            let buf = buf.into();
+
+            let result = if buf.len() >= self.multipart_chunk_size {
+                self.write_stream(&path, futures::stream::iter(std::iter::once(Ok(buf))))
+                    .await
+            } else {
+                self.write_bytes(&path, buf).await
+            };
+
+            result.map_err(|e| {
+                std::io::Error::other(format!(
+                    "unable to write data to store on path {}: {}",
+                    path.as_ref().display(),
+                    e
+                ))
+            })
+        }
+    }
+}
+
+/// Provides a temporary store wrapper for testing.
+///
+/// This module contains a [`Store`] struct which wraps a `super::StoreRef` and manages
+/// a temporary directory on the filesystem. When the [`Store`] struct is dropped,
+/// it automatically deletes the directory it was created with, cleaning up all resources.
+/// This is useful for integration tests that need a real store instance.
+#[cfg(test)]
+pub mod testing {
+    use super::*;
+    use std::ops::Deref;
+
+    pub struct Store {
+        inner: super::StoreRef,
+        root: std::path::PathBuf,
+    }
+
+    impl Store {
+        /// Creates a new temporary [`Store`] at the specified root path.
+        ///
+        /// The path **must not** exist, as it will be created by this function
+        /// and recursively deleted when the returned [`Store`] is dropped.
+        pub fn new(root: impl AsRef<std::path::Path>) -> Result<Self, Box<dyn std::error::Error>> {
+            if root.as_ref().exists() {
+                Err(format!(
+                    "directory {:?} already exist, can't be used as temporary store since at the end will be deleted",
+                    root.as_ref()
+                ))?;
+            }
+
+            Ok(Self {
+                root: root.as_ref().to_path_buf(),
+                inner: Arc::new(super::Store::try_from_filesystem(root)?),
+            })
+        }
+
+        /// Creates a new temporary [`Store`] in a randomly named directory inside `/tmp`.
+        ///
+        /// The store's directory will be automatically deleted when the [`Store`] is dropped.
+        /// The directory name is based on the current timestamp.
+        pub fn new_random_on_tmp() -> Result<Self, Box<dyn std::error::Error>> {
+            let random_location = format!("/tmp/{}", crate::utils::random::random_string(10));
+            Self::new(random_location)
+        }
+    }
+
+    impl Drop for Store {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.root).unwrap();
+        }
+    }
+
+    impl Deref for Store {
+        type Target = super::StoreRef;
+
+        fn deref(&self) -> &Self::Target {
+            &self.inner
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{traits::AsyncWriteToPath, types};
+
+    use super::*;
+
+    /// Checks that filesystem store works, writing and reading data to `/tmp`` directory
+    ///
+    /// To avoid to delete system files the test directories are created in `/tmp` and are not removed automatically
+    #[tokio::test]
+    async fn test_filesystem_store() {
+        let bucket_name = types::DateTime::now().fmt_to_ms();
+        let path = format!("/tmp/{}", bucket_name);
+        let store = Store::try_from_filesystem(path).unwrap();
+
+        let sample = r#"
+            Some example text
+        "#;
+        let buffer = sample.as_bytes();
+        let target = "write_text";
+
+        store.write_to_path(&target, buffer).await.unwrap();
+
+        let read_buffer = store.read_bytes(&target).await.unwrap();
+
+        assert_eq!(buffer, read_buffer);
+    }
+}
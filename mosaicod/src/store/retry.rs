@@ -0,0 +1,345 @@
+//! An opt-in [`ObjectStore`] decorator that retries idempotent operations
+//! (`get`, `head`, `list`, `put`, `delete`) with exponential backoff and
+//! jitter, and optionally caps how many requests are in flight at once.
+//!
+//! Wraps the `driver` rather than hooking into each [`super::Store`]
+//! method, so every existing CRUD method keeps working unchanged -- the
+//! retrying/throttling is transparent to callers.
+
+use std::fmt;
+use std::future::Future;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts,
+    PutOptions, PutPayload, PutResult, path::Path,
+};
+use tokio::sync::Semaphore;
+
+/// Backoff/retry budget for [`RetryingObjectStore`].
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub max_elapsed: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(15),
+            max_elapsed: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Wraps an [`ObjectStore`], retrying `get`/`head`/`list`/`put`/`delete` on
+/// retryable errors, and optionally bounding how many requests (of any
+/// kind) are in flight at once so a bulk `delete_recursive`/`list` over a
+/// huge prefix doesn't overwhelm the endpoint.
+pub struct RetryingObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    retry: RetryConfig,
+    limiter: Option<Arc<Semaphore>>,
+}
+
+impl RetryingObjectStore {
+    pub fn new(
+        inner: Arc<dyn ObjectStore>,
+        retry: RetryConfig,
+        max_concurrent_requests: Option<usize>,
+    ) -> Self {
+        Self {
+            inner,
+            retry,
+            limiter: max_concurrent_requests.map(|n| Arc::new(Semaphore::new(n.max(1)))),
+        }
+    }
+
+    /// Acquires a permit for the duration of one request, if a concurrency
+    /// limit is configured.
+    async fn acquire(&self) -> Option<tokio::sync::SemaphorePermit<'_>> {
+        match &self.limiter {
+            Some(sem) => sem.acquire().await.ok(),
+            None => None,
+        }
+    }
+
+    /// Runs `op` (an idempotent request), retrying on a retryable error with
+    /// exponential backoff and jitter, up to `self.retry.max_retries`
+    /// attempts or `self.retry.max_elapsed` elapsed, whichever comes first.
+    async fn with_retry<T, F, Fut>(&self, op: F) -> object_store::Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = object_store::Result<T>>,
+    {
+        let started = std::time::Instant::now();
+        let mut backoff = self.retry.initial_backoff;
+        let mut attempt = 0;
+
+        loop {
+            let _permit = self.acquire().await;
+            let result = op().await;
+            drop(_permit);
+
+            let err = match result {
+                Ok(v) => return Ok(v),
+                Err(e) => e,
+            };
+
+            attempt += 1;
+            if attempt > self.retry.max_retries
+                || started.elapsed() >= self.retry.max_elapsed
+                || !is_retryable(&err)
+            {
+                return Err(err);
+            }
+
+            let jitter = jitter_factor();
+            let wait = backoff.mul_f64(jitter).min(self.retry.max_backoff);
+            tokio::time::sleep(wait).await;
+            backoff = (backoff * 2).min(self.retry.max_backoff);
+        }
+    }
+}
+
+/// Deterministic-enough jitter without pulling in a `rand` dependency: mixes
+/// the current time's sub-millisecond component into a factor in `[0.5, 1.0)`.
+fn jitter_factor() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    0.5 + 0.5 * (nanos % 1_000_000) as f64 / 1_000_000.0
+}
+
+/// Whether `err` looks like a transient failure worth retrying (throttling,
+/// a 5xx, or a dropped connection) rather than a real, permanent failure.
+///
+/// `object_store::Error` doesn't expose a structured status code that's
+/// consistent across backends, so this matches on the common substrings
+/// backends surface for these cases.
+fn is_retryable(err: &object_store::Error) -> bool {
+    if matches!(
+        err,
+        object_store::Error::NotFound { .. }
+            | object_store::Error::AlreadyExists { .. }
+            | object_store::Error::NotSupported { .. }
+            | object_store::Error::Precondition { .. }
+    ) {
+        return false;
+    }
+
+    let message = err.to_string().to_ascii_lowercase();
+    ["429", "500", "502", "503", "504", "throttl", "timed out", "timeout", "connection reset", "connection closed", "broken pipe"]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
+
+impl fmt::Debug for RetryingObjectStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryingObjectStore")
+            .field("inner", &self.inner)
+            .field("retry", &self.retry)
+            .finish()
+    }
+}
+
+impl fmt::Display for RetryingObjectStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RetryingObjectStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for RetryingObjectStore {
+    async fn put(&self, location: &Path, payload: PutPayload) -> object_store::Result<PutResult> {
+        self.with_retry(|| self.inner.put(location, payload.clone()))
+            .await
+    }
+
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> object_store::Result<PutResult> {
+        self.with_retry(|| self.inner.put_opts(location, payload.clone(), opts.clone()))
+            .await
+    }
+
+    async fn put_multipart(
+        &self,
+        location: &Path,
+    ) -> object_store::Result<Box<dyn MultipartUpload>> {
+        // Not retried: a multipart upload is a stateful, multi-request
+        // sequence, not a single idempotent call.
+        let _permit = self.acquire().await;
+        self.inner.put_multipart(location).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> object_store::Result<Box<dyn MultipartUpload>> {
+        let _permit = self.acquire().await;
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get(&self, location: &Path) -> object_store::Result<GetResult> {
+        self.with_retry(|| self.inner.get(location)).await
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> object_store::Result<GetResult> {
+        self.with_retry(|| self.inner.get_opts(location, options.clone()))
+            .await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> object_store::Result<Bytes> {
+        self.with_retry(|| self.inner.get_range(location, range.clone()))
+            .await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> object_store::Result<Vec<Bytes>> {
+        self.with_retry(|| self.inner.get_ranges(location, ranges))
+            .await
+    }
+
+    async fn head(&self, location: &Path) -> object_store::Result<ObjectMeta> {
+        self.with_retry(|| self.inner.head(location)).await
+    }
+
+    async fn delete(&self, location: &Path) -> object_store::Result<()> {
+        self.with_retry(|| self.inner.delete(location)).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        // `list` hands back a lazy stream rather than a single future, so
+        // there's no single request to retry around it here -- the
+        // underlying backend's own retry (if any) governs each page fetch.
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> object_store::Result<ListResult> {
+        self.with_retry(|| self.inner.list_with_delimiter(prefix))
+            .await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        let _permit = self.acquire().await;
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        let _permit = self.acquire().await;
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        let _permit = self.acquire().await;
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        let _permit = self.acquire().await;
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_factor_stays_within_bounds() {
+        for _ in 0..100 {
+            let factor = jitter_factor();
+            assert!((0.5..1.0).contains(&factor), "{factor} out of [0.5, 1.0)");
+        }
+    }
+
+    #[test]
+    fn not_found_is_not_retryable() {
+        assert!(!is_retryable(&object_store::Error::NotFound {
+            path: "x".to_string(),
+            source: "missing".into(),
+        }));
+    }
+
+    #[test]
+    fn already_exists_is_not_retryable() {
+        assert!(!is_retryable(&object_store::Error::AlreadyExists {
+            path: "x".to_string(),
+            source: "conflict".into(),
+        }));
+    }
+
+    #[test]
+    fn not_supported_is_not_retryable() {
+        assert!(!is_retryable(&object_store::Error::NotSupported {
+            source: "unsupported".into(),
+        }));
+    }
+
+    #[test]
+    fn precondition_is_not_retryable() {
+        assert!(!is_retryable(&object_store::Error::Precondition {
+            path: "x".to_string(),
+            source: "etag mismatch".into(),
+        }));
+    }
+
+    #[test]
+    fn throttling_status_codes_are_retryable() {
+        for code in ["429", "503"] {
+            let err = object_store::Error::Generic {
+                store: "test",
+                source: format!("server returned {code}").into(),
+            };
+            assert!(is_retryable(&err), "{code} should be retryable");
+        }
+    }
+
+    #[test]
+    fn connection_reset_is_retryable() {
+        let err = object_store::Error::Generic {
+            store: "test",
+            source: "connection reset by peer".into(),
+        };
+        assert!(is_retryable(&err));
+    }
+
+    #[test]
+    fn unrecognized_message_is_not_retryable() {
+        let err = object_store::Error::Generic {
+            store: "test",
+            source: "permission denied".into(),
+        };
+        assert!(!is_retryable(&err));
+    }
+}
@@ -0,0 +1,148 @@
+//! A split-block bloom filter (SBBF), modeled after the bloom filter format
+//! used internally by Parquet.
+//!
+//! The filter is organized into fixed-size 256-bit blocks (8 x 32-bit words).
+//! Membership of a value is tested by hashing it once with `xxh3_64`, using
+//! the high bits of the hash to select a block and the low bits to derive
+//! eight per-word masks (one bit set per word) via fixed odd multipliers.
+
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Number of 32-bit words per block.
+const WORDS_PER_BLOCK: usize = 8;
+/// Size in bytes of a single block (8 x 4 bytes = 32 bytes / 256 bits).
+const BLOCK_SIZE_BYTES: usize = WORDS_PER_BLOCK * 4;
+
+/// Fixed odd multipliers used to derive, for each of the 8 words in a block,
+/// which single bit (0..32) should be set/tested. These are the same
+/// constants used by Parquet's reference SBBF implementation.
+const SALT: [u32; WORDS_PER_BLOCK] = [
+    0x47b6137b, 0x44974d91, 0x8824ad5b, 0xa2b7289d, 0x705495c7, 0x2df1424b, 0x9efc4947, 0x5c6bfb31,
+];
+
+/// A split-block bloom filter supporting probabilistic set-membership checks
+/// over byte slices (used here for text/binary column values).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomFilter {
+    /// Flat array of blocks, each `WORDS_PER_BLOCK` 32-bit words long.
+    blocks: Vec<[u32; WORDS_PER_BLOCK]>,
+}
+
+impl BloomFilter {
+    /// Builds a filter sized from an expected number of distinct values (`ndv`)
+    /// and a target false-positive probability (`fpp`).
+    pub fn with_capacity(ndv: usize, fpp: f64) -> Self {
+        let num_blocks = optimal_num_blocks(ndv.max(1), fpp);
+        Self {
+            blocks: vec![[0u32; WORDS_PER_BLOCK]; num_blocks],
+        }
+    }
+
+    /// Inserts a value into the filter.
+    pub fn insert(&mut self, value: impl AsRef<[u8]>) {
+        let hash = xxh3_64(value.as_ref());
+        let (block_idx, mask) = self.locate(hash);
+        let block = &mut self.blocks[block_idx];
+        for i in 0..WORDS_PER_BLOCK {
+            block[i] |= mask[i];
+        }
+    }
+
+    /// Returns `true` if the value **might** be present in the filter.
+    /// A `false` result is a guarantee of absence; `true` may be a false positive.
+    pub fn maybe_contains(&self, value: impl AsRef<[u8]>) -> bool {
+        if self.blocks.is_empty() {
+            return true;
+        }
+
+        let hash = xxh3_64(value.as_ref());
+        let (block_idx, mask) = self.locate(hash);
+        let block = &self.blocks[block_idx];
+        for i in 0..WORDS_PER_BLOCK {
+            if block[i] & mask[i] != mask[i] {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Computes the target block index and the per-word test masks for a hash.
+    fn locate(&self, hash: u64) -> (usize, [u32; WORDS_PER_BLOCK]) {
+        // High 32 bits select the block (via a multiply-shift to stay within range).
+        let block_idx = (((hash >> 32) * self.blocks.len() as u64) >> 32) as usize;
+        // Low 32 bits derive the 8 per-word bit positions.
+        let lo = hash as u32;
+        let mut mask = [0u32; WORDS_PER_BLOCK];
+        for (i, salt) in SALT.iter().enumerate() {
+            let bit = (lo.wrapping_mul(*salt)) >> 27;
+            mask[i] = 1u32 << bit;
+        }
+        (block_idx, mask)
+    }
+
+    /// Serializes the filter into its raw little-endian byte representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.blocks.len() * BLOCK_SIZE_BYTES);
+        for block in &self.blocks {
+            for word in block {
+                buf.extend_from_slice(&word.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    /// Reconstructs a filter from bytes previously produced by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() % BLOCK_SIZE_BYTES != 0 {
+            return None;
+        }
+
+        let blocks = bytes
+            .chunks_exact(BLOCK_SIZE_BYTES)
+            .map(|block_bytes| {
+                let mut block = [0u32; WORDS_PER_BLOCK];
+                for (word, word_bytes) in block.iter_mut().zip(block_bytes.chunks_exact(4)) {
+                    *word = u32::from_le_bytes(word_bytes.try_into().unwrap());
+                }
+                block
+            })
+            .collect();
+
+        Some(Self { blocks })
+    }
+}
+
+/// Picks the number of 256-bit blocks needed to reach `fpp` for `ndv` distinct
+/// values, following the same sizing heuristic used by Parquet's SBBF.
+fn optimal_num_blocks(ndv: usize, fpp: f64) -> usize {
+    let num_bits = -8.0 * ndv as f64 * fpp.ln() / (2f64.ln().powi(2));
+    let num_blocks = (num_bits / (BLOCK_SIZE_BYTES * 8) as f64).ceil() as usize;
+    num_blocks.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bloom_filter_roundtrip() {
+        let mut filter = BloomFilter::with_capacity(100, 0.01);
+
+        let values = ["alpha", "bravo", "charlie", "delta"];
+        for v in &values {
+            filter.insert(v);
+        }
+
+        for v in &values {
+            assert!(filter.maybe_contains(v));
+        }
+        assert!(!filter.maybe_contains("not-in-the-set"));
+
+        let bytes = filter.to_bytes();
+        let restored = BloomFilter::from_bytes(&bytes).expect("valid bloom filter bytes");
+        assert_eq!(filter, restored);
+        for v in &values {
+            assert!(restored.maybe_contains(v));
+        }
+    }
+}
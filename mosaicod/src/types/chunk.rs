@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 const TEXT_MIN_PLACEHOLDER: &str = "";
 const TEXT_MAX_PLACEHOLDER: &str = "";
@@ -6,6 +6,17 @@ const TEXT_MAX_PLACEHOLDER: &str = "";
 const NUMERIC_MIN_PLACEHOLDER: f64 = f64::MAX;
 const NUMERIC_MAX_PLACEHOLDER: f64 = f64::MIN;
 
+/// Expected number of distinct values used to size a [`BloomFilter`] for a
+/// single chunk's column. Chunks are bounded in size, so this is a generous
+/// upper bound rather than a precise estimate.
+const BLOOM_EXPECTED_NDV: usize = 4096;
+/// Target false-positive probability for per-chunk bloom filters.
+const BLOOM_FPP: f64 = 0.01;
+
+/// Maximum number of distinct values tracked per chunk before a text column
+/// is considered too high-cardinality for exact dictionary pruning.
+const DICTIONARY_CARDINALITY_THRESHOLD: usize = 1024;
+
 #[derive(Debug)]
 pub struct ColumnsStats {
     pub stats: HashMap<String, Stats>,
@@ -17,6 +28,37 @@ impl ColumnsStats {
             stats: HashMap::new(),
         }
     }
+
+    /// Re-sizes each column's bloom filter to the chunk's actual distinct
+    /// count where that count is known exactly, instead of the generous
+    /// [`BLOOM_EXPECTED_NDV`] upper bound used while streaming.
+    ///
+    /// Must be called once all rows have been [`Stats::eval`]uated for this
+    /// chunk, since it consumes the exact dictionary built up during
+    /// streaming to rebuild a tighter filter.
+    pub fn finalize_blooms(&mut self) {
+        for stats in self.stats.values_mut() {
+            if let Stats::Text(stats) = stats {
+                stats.finalize_bloom();
+            }
+        }
+    }
+}
+
+/// Same accumulator as [`ColumnsStats`], keyed by a column's stable field id
+/// (see `arrow::assign_field_ids`) instead of its dotted name, so renaming a
+/// field or reordering struct children doesn't orphan its accumulated stats.
+#[derive(Debug)]
+pub struct ColumnsStatsById {
+    pub stats: HashMap<i64, Stats>,
+}
+
+impl ColumnsStatsById {
+    pub fn empty() -> Self {
+        Self {
+            stats: HashMap::new(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -42,6 +84,16 @@ pub struct NumericStats {
 
     pub has_null: bool,
     pub has_nan: bool,
+    /// Whether at least one non-null value was seen. Combined with
+    /// `has_null`, this lets a query prune a chunk where every value is
+    /// null (`has_null && !has_non_null`) without a dedicated `all_null`
+    /// column.
+    pub has_non_null: bool,
+
+    /// Per-chunk bloom filter over the column's values, used by the query IR
+    /// to prune chunks on equality predicates without scanning the data
+    /// file -- min/max zone maps rarely prune high-cardinality columns.
+    pub bloom: super::BloomFilter,
 }
 
 impl Default for NumericStats {
@@ -58,6 +110,8 @@ impl NumericStats {
 
             has_null: false,
             has_nan: false,
+            has_non_null: false,
+            bloom: super::BloomFilter::with_capacity(BLOOM_EXPECTED_NDV, BLOOM_FPP),
         }
     }
 
@@ -66,6 +120,8 @@ impl NumericStats {
     pub fn eval(&mut self, val: &Option<f64>) {
         if let Some(val) = val {
             let val = *val;
+            self.has_non_null = true;
+            self.bloom.insert(val.to_le_bytes());
             if val.is_nan() {
                 self.has_nan = true;
             } else {
@@ -88,6 +144,56 @@ pub struct TextStats {
     pub max: String,
 
     pub has_null: bool,
+    /// Whether at least one non-null value was seen. Combined with
+    /// `has_null`, this lets a query prune a chunk where every value is
+    /// null (`has_null && !has_non_null`) without a dedicated `all_null`
+    /// column.
+    pub has_non_null: bool,
+
+    /// Per-chunk bloom filter over the column's values, used by the query IR
+    /// to prune chunks on equality predicates without scanning the data file.
+    pub bloom: super::BloomFilter,
+
+    /// Exact set of distinct values seen so far, kept while the column looks
+    /// low-cardinality (e.g. ontology tags, enum-like labels). Once the
+    /// cardinality exceeds [`DICTIONARY_CARDINALITY_THRESHOLD`] this is
+    /// dropped in favor of the bloom/min-max path.
+    pub dictionary: Dictionary,
+}
+
+/// Captures the distinct-value set for a column, while it remains cheap to do so.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dictionary {
+    /// The exact set of distinct values observed so far.
+    Exact(HashSet<String>),
+    /// Cardinality exceeded [`DICTIONARY_CARDINALITY_THRESHOLD`]; no exact set is kept.
+    HighCardinality,
+}
+
+impl Dictionary {
+    fn eval(&mut self, val: &str) {
+        match self {
+            Dictionary::Exact(set) => {
+                if !set.contains(val) && set.len() >= DICTIONARY_CARDINALITY_THRESHOLD {
+                    *self = Dictionary::HighCardinality;
+                    return;
+                }
+                set.insert(val.to_owned());
+            }
+            Dictionary::HighCardinality => {}
+        }
+    }
+
+    pub fn is_high_cardinality(&self) -> bool {
+        matches!(self, Dictionary::HighCardinality)
+    }
+
+    pub fn values(&self) -> Option<&HashSet<String>> {
+        match self {
+            Dictionary::Exact(set) => Some(set),
+            Dictionary::HighCardinality => None,
+        }
+    }
 }
 
 impl Default for TextStats {
@@ -103,6 +209,9 @@ impl TextStats {
             max: TEXT_MAX_PLACEHOLDER.to_owned(),
 
             has_null: false,
+            has_non_null: false,
+            bloom: super::BloomFilter::with_capacity(BLOOM_EXPECTED_NDV, BLOOM_FPP),
+            dictionary: Dictionary::Exact(HashSet::new()),
         }
     }
 
@@ -111,14 +220,69 @@ impl TextStats {
     pub fn eval(&mut self, val: &Option<&str>) {
         if let Some(val) = val {
             let val = *val;
+            self.has_non_null = true;
             if self.min == TEXT_MIN_PLACEHOLDER || *self.min > *val {
                 self.min = val.to_owned();
             }
             if self.max == TEXT_MAX_PLACEHOLDER || *self.max < *val {
                 self.max = val.to_owned();
             }
+            self.bloom.insert(val);
+            self.dictionary.eval(val);
         } else {
             self.has_null = true
         }
     }
+
+    /// Rebuilds [`Self::bloom`] sized to the column's actual distinct count
+    /// rather than [`BLOOM_EXPECTED_NDV`], when [`Self::dictionary`] stayed
+    /// [`Dictionary::Exact`] (i.e. cardinality never crossed
+    /// [`DICTIONARY_CARDINALITY_THRESHOLD`]) and so the exact value set is
+    /// still available to re-insert. A column that crossed the threshold
+    /// keeps the upper-bound-sized filter built while streaming, since its
+    /// exact distinct count isn't tracked once the dictionary is dropped.
+    fn finalize_bloom(&mut self) {
+        if let Dictionary::Exact(values) = &self.dictionary {
+            let mut bloom = super::BloomFilter::with_capacity(values.len().max(1), BLOOM_FPP);
+            for value in values {
+                bloom.insert(value.as_str());
+            }
+            self.bloom = bloom;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finalize_bloom_keeps_exact_values_reachable_after_resize() {
+        let mut stats = TextStats::new();
+        for val in ["a", "b", "c", "b"] {
+            stats.eval(&Some(val));
+        }
+
+        stats.finalize_bloom();
+
+        assert!(matches!(stats.dictionary, Dictionary::Exact(_)));
+        for val in ["a", "b", "c"] {
+            assert!(stats.bloom.maybe_contains(val));
+        }
+        assert!(!stats.bloom.maybe_contains("definitely-not-inserted"));
+    }
+
+    #[test]
+    fn finalize_bloom_is_a_noop_once_high_cardinality() {
+        let mut stats = TextStats::new();
+        for i in 0..(DICTIONARY_CARDINALITY_THRESHOLD + 1) {
+            stats.eval(&Some(&i.to_string()));
+        }
+        let before = stats.bloom.clone();
+
+        stats.finalize_bloom();
+
+        assert!(matches!(stats.dictionary, Dictionary::HighCardinality));
+        assert_eq!(stats.bloom, before);
+    }
 }
@@ -0,0 +1,31 @@
+/// Summary of an integrity scan over a single topic's chunks, produced by
+/// `repo::FacadeTopic::verify`.
+///
+/// Unlike `repo::FacadeRepair::scan` (which only checks that a chunk's data
+/// file still exists), this recomputes each chunk's digest from the bytes
+/// actually on disk, so it also catches silent corruption that left the
+/// file in place but altered its content.
+#[derive(Debug, Default)]
+pub struct ChunkVerifyReport {
+    /// Chunks whose data file no longer exists in the store.
+    pub missing_chunks: Vec<uuid::Uuid>,
+    /// Chunks whose re-hashed data file doesn't match the stored digest.
+    pub digest_mismatches: Vec<uuid::Uuid>,
+    /// Chunks whose stored `content_digest_algo` isn't a scheme this build
+    /// knows how to recompute, so they were skipped rather than flagged.
+    pub unsupported_digest_algo: Vec<uuid::Uuid>,
+    /// Present when the topic has a recorded upload-job byte count (see
+    /// `types::TopicUploadStatus`) that doesn't match the sum of its
+    /// chunks' actual sizes found in the store: `(expected, actual)`.
+    pub size_drift: Option<(usize, usize)>,
+}
+
+impl ChunkVerifyReport {
+    /// Whether the scan found no anomalies of any category.
+    pub fn is_clean(&self) -> bool {
+        self.missing_chunks.is_empty()
+            && self.digest_mismatches.is_empty()
+            && self.unsupported_digest_algo.is_empty()
+            && self.size_drift.is_none()
+    }
+}
@@ -0,0 +1,16 @@
+/// Summary of a [`crate::repo::FacadeTopic::compact`] run.
+#[derive(Debug, Default)]
+pub struct CompactionReport {
+    /// Number of small chunks read and merged away.
+    pub chunks_merged: usize,
+    /// Number of larger chunks they were rewritten into.
+    pub chunks_written: usize,
+}
+
+impl CompactionReport {
+    /// Whether the topic already had few enough chunks that nothing needed
+    /// rewriting.
+    pub fn is_noop(&self) -> bool {
+        self.chunks_merged == 0
+    }
+}
@@ -0,0 +1,51 @@
+//! Lifecycle state and progress of a resumable sequence-deletion job (see
+//! `repo::sequence_delete_job_*` and [`crate::repo::FacadeSequence::delete`]).
+
+/// Lifecycle state of a sequence's delete job, stored as a raw string in
+/// `sequence_delete_job_t`, following the same convention as
+/// [`super::UploadJobState`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteJobState {
+    /// Topics are still being torn down one at a time.
+    Running,
+    /// Every topic is torn down; only the final sequence-record delete and
+    /// recursive store purge are left, which happen in the same commit that
+    /// deletes this job's row.
+    Completed,
+}
+
+impl std::fmt::Display for DeleteJobState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Running => write!(f, "running"),
+            Self::Completed => write!(f, "completed"),
+        }
+    }
+}
+
+impl std::str::FromStr for DeleteJobState {
+    type Err = std::io::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "running" => Ok(Self::Running),
+            "completed" => Ok(Self::Completed),
+            _ => Err(std::io::Error::other(format!(
+                "unknown delete job state `{}`",
+                value
+            ))),
+        }
+    }
+}
+
+/// Progress of a sequence's delete job, as reported to a polling client or
+/// consulted by [`crate::repo::FacadeSequence::resume_jobs`].
+#[derive(Debug)]
+pub struct SequenceDeleteProgress {
+    pub state: DeleteJobState,
+    /// Topics enumerated when the job started.
+    pub topics_total: usize,
+    /// Topics already torn down (`delete_unsafe` committed and the job's
+    /// checkpoint updated).
+    pub topics_done: usize,
+}
@@ -0,0 +1,348 @@
+//! Append-only Merkle tree over a topic's chunk digests.
+//!
+//! Chunks are only ever appended to a topic, so the tree is kept as a
+//! vector of "peaks" -- roots of maximal perfect subtrees -- instead of a
+//! full binary tree. Appending a leaf is then O(log n) and never needs to
+//! re-read prior chunks: push the leaf as a new height-0 peak, then while
+//! the last two peaks share a height, pop and hash them into their parent.
+//! The overall root folds the remaining peaks right-to-left.
+//!
+//! Leaves reuse the existing per-chunk [`ContentDigest`] rather than a new
+//! hash primitive, so internal nodes are `blake3(left || right)` too.
+
+use crate::rw::ContentDigest;
+
+/// One peak: the root hash of a maximal perfect subtree, and that
+/// subtree's height (`0` for a bare leaf).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Peak {
+    height: u32,
+    hash: [u8; 32],
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(left);
+    buf[32..].copy_from_slice(right);
+    *blake3::hash(&buf).as_bytes()
+}
+
+/// Folds `peaks` right-to-left into a single hash, the same way
+/// [`MerkleTree::root`] does.
+fn fold_peaks(peaks: &[Peak]) -> [u8; 32] {
+    let mut iter = peaks.iter().rev();
+    let mut acc = iter.next().expect("fold_peaks called with no peaks").hash;
+    for peak in iter {
+        acc = hash_node(&peak.hash, &acc);
+    }
+    acc
+}
+
+/// An append-only Merkle tree keyed by chunk order, represented as its
+/// vector of peaks so a new chunk can be folded in without re-reading
+/// earlier ones.
+#[derive(Debug, Clone, Default)]
+pub struct MerkleTree {
+    peaks: Vec<Peak>,
+    leaf_count: u64,
+}
+
+impl MerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn leaf_count(&self) -> u64 {
+        self.leaf_count
+    }
+
+    /// Appends one more chunk's digest as the next leaf, folding completed
+    /// peaks as needed.
+    pub fn append(&mut self, leaf: ContentDigest) {
+        let mut peak = Peak {
+            height: 0,
+            hash: *leaf.as_bytes(),
+        };
+        self.leaf_count += 1;
+
+        while let Some(top) = self.peaks.last() {
+            if top.height != peak.height {
+                break;
+            }
+            let top = self.peaks.pop().expect("just checked via .last()");
+            peak = Peak {
+                height: top.height + 1,
+                hash: hash_node(&top.hash, &peak.hash),
+            };
+        }
+
+        self.peaks.push(peak);
+    }
+
+    /// Folds the current peaks into the overall root. `None` for an empty
+    /// tree.
+    pub fn root(&self) -> Option<[u8; 32]> {
+        if self.peaks.is_empty() {
+            return None;
+        }
+        Some(fold_peaks(&self.peaks))
+    }
+
+    /// Serializes the peak vector and leaf count for persistence between
+    /// uploads. Layout: `leaf_count: u64 LE`, then for each peak
+    /// `height: u32 LE` followed by its 32-byte hash.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(8 + self.peaks.len() * 36);
+        out.extend_from_slice(&self.leaf_count.to_le_bytes());
+        for peak in &self.peaks {
+            out.extend_from_slice(&peak.height.to_le_bytes());
+            out.extend_from_slice(&peak.hash);
+        }
+        out
+    }
+
+    /// Reverses [`MerkleTree::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 8 {
+            return None;
+        }
+        let leaf_count = u64::from_le_bytes(bytes[0..8].try_into().ok()?);
+
+        let mut peaks = Vec::new();
+        let mut offset = 8;
+        while offset < bytes.len() {
+            if bytes.len() < offset + 36 {
+                return None;
+            }
+            let height = u32::from_le_bytes(bytes[offset..offset + 4].try_into().ok()?);
+            let hash: [u8; 32] = bytes[offset + 4..offset + 36].try_into().ok()?;
+            peaks.push(Peak { height, hash });
+            offset += 36;
+        }
+
+        Some(Self { peaks, leaf_count })
+    }
+}
+
+/// Which side of a hash combination a proof's sibling sits on.
+#[derive(Debug, Clone, Copy)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// A membership proof that some leaf digest belongs to a [`MerkleTree`],
+/// checked against that tree's root by [`MerkleProof::verify`].
+#[derive(Debug, Clone)]
+pub struct MerkleProof {
+    /// Ordered sibling hashes from the leaf up to its peak, then the
+    /// peak-folding siblings from that peak up to the root.
+    siblings: Vec<([u8; 32], Side)>,
+}
+
+impl MerkleProof {
+    /// Recomputes the root implied by `leaf` and this proof, and compares
+    /// it against `root`.
+    pub fn verify(&self, leaf: ContentDigest, root: [u8; 32]) -> bool {
+        let mut acc = *leaf.as_bytes();
+        for (sibling, side) in &self.siblings {
+            acc = match side {
+                Side::Left => hash_node(sibling, &acc),
+                Side::Right => hash_node(&acc, sibling),
+            };
+        }
+        acc == root
+    }
+}
+
+/// Folds a perfect-power-of-two slice of leaves bottom-up, returning the
+/// sibling path for `local_index` alongside the segment's root.
+fn segment_proof(segment: &[[u8; 32]], local_index: usize) -> (Vec<([u8; 32], Side)>, [u8; 32]) {
+    let mut level = segment.to_vec();
+    let mut idx = local_index;
+    let mut siblings = Vec::new();
+
+    while level.len() > 1 {
+        let sibling_idx = idx ^ 1;
+        let side = if idx % 2 == 0 { Side::Right } else { Side::Left };
+        siblings.push((level[sibling_idx], side));
+
+        let mut next = Vec::with_capacity(level.len() / 2);
+        for pair in level.chunks_exact(2) {
+            next.push(hash_node(&pair[0], &pair[1]));
+        }
+        level = next;
+        idx /= 2;
+    }
+
+    (siblings, level[0])
+}
+
+/// Folds a perfect-power-of-two slice of leaves bottom-up into its root,
+/// without tracking a proof path.
+fn fold_leaves(segment: &[[u8; 32]]) -> [u8; 32] {
+    let mut level = segment.to_vec();
+    while level.len() > 1 {
+        let mut next = Vec::with_capacity(level.len() / 2);
+        for pair in level.chunks_exact(2) {
+            next.push(hash_node(&pair[0], &pair[1]));
+        }
+        level = next;
+    }
+    level[0]
+}
+
+/// The `(height, segment_start)` of each peak that [`MerkleTree::append`]
+/// would produce after folding `leaves.len()` leaves in order, without
+/// actually hashing anything -- used to find which maximal perfect subtree
+/// a given leaf index falls into.
+fn peak_segments(leaf_count: usize) -> Vec<(u32, usize)> {
+    let mut peaks: Vec<(u32, usize)> = Vec::new();
+
+    for i in 0..leaf_count {
+        let mut height = 0u32;
+        let mut start = i;
+
+        while let Some(&(h, s)) = peaks.last() {
+            if h != height {
+                break;
+            }
+            peaks.pop();
+            start = s;
+            height += 1;
+        }
+
+        peaks.push((height, start));
+    }
+
+    peaks
+}
+
+/// Builds a [`MerkleProof`] that `leaves[index]` belongs to the tree formed
+/// by appending `leaves` in order, along with that tree's root.
+///
+/// Proof generation is a rare, offline audit operation, so this rebuilds
+/// the tree from the topic's full leaf history (already persisted as each
+/// chunk's [`ContentDigest`]) instead of reading the compact persisted peak
+/// list, which only retains peak roots and not the internal nodes a proof
+/// needs for a leaf buried inside an already-folded subtree.
+pub fn merkle_proof(leaves: &[ContentDigest], index: usize) -> Option<(MerkleProof, [u8; 32])> {
+    if index >= leaves.len() {
+        return None;
+    }
+
+    let leaf_bytes: Vec<[u8; 32]> = leaves.iter().map(|d| *d.as_bytes()).collect();
+    let segments = peak_segments(leaves.len());
+
+    let seg_idx = segments
+        .iter()
+        .position(|&(h, s)| index >= s && index < s + (1usize << h))?;
+    let (height, start) = segments[seg_idx];
+    let segment = &leaf_bytes[start..start + (1usize << height)];
+    let local_index = index - start;
+
+    let (mut siblings, _) = segment_proof(segment, local_index);
+
+    let peak_hashes: Vec<Peak> = segments
+        .iter()
+        .map(|&(h, s)| Peak {
+            height: h,
+            hash: fold_leaves(&leaf_bytes[s..s + (1usize << h)]),
+        })
+        .collect();
+
+    if seg_idx + 1 < peak_hashes.len() {
+        let right_fold = fold_peaks(&peak_hashes[seg_idx + 1..]);
+        siblings.push((right_fold, Side::Right));
+    }
+    for i in (0..seg_idx).rev() {
+        siblings.push((peak_hashes[i].hash, Side::Left));
+    }
+
+    let root = fold_peaks(&peak_hashes);
+
+    Some((MerkleProof { siblings }, root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(byte: u8) -> ContentDigest {
+        ContentDigest::of(&[byte; 8])
+    }
+
+    #[test]
+    fn incremental_root_matches_brute_force_fold() {
+        let mut tree = MerkleTree::new();
+        let mut leaves = Vec::new();
+
+        for i in 0..13u8 {
+            let d = digest(i);
+            leaves.push(d);
+            tree.append(d);
+        }
+
+        let leaf_bytes: Vec<[u8; 32]> = leaves.iter().map(|d| *d.as_bytes()).collect();
+        let segments = peak_segments(leaves.len());
+        let expected_peaks: Vec<Peak> = segments
+            .iter()
+            .map(|&(h, s)| Peak {
+                height: h,
+                hash: fold_leaves(&leaf_bytes[s..s + (1usize << h)]),
+            })
+            .collect();
+
+        assert_eq!(tree.root(), Some(fold_peaks(&expected_peaks)));
+    }
+
+    #[test]
+    fn peak_roundtrips_through_bytes() {
+        let mut tree = MerkleTree::new();
+        for i in 0..7u8 {
+            tree.append(digest(i));
+        }
+
+        let bytes = tree.to_bytes();
+        let restored = MerkleTree::from_bytes(&bytes).expect("should decode");
+
+        assert_eq!(restored.leaf_count(), tree.leaf_count());
+        assert_eq!(restored.root(), tree.root());
+    }
+
+    #[test]
+    fn proof_verifies_every_leaf_for_non_power_of_two_counts() {
+        for count in [1usize, 2, 3, 5, 8, 13, 17] {
+            let mut tree = MerkleTree::new();
+            let mut leaves = Vec::new();
+            for i in 0..count {
+                let d = digest(i as u8);
+                leaves.push(d);
+                tree.append(d);
+            }
+            let root = tree.root().expect("non-empty tree has a root");
+
+            for i in 0..count {
+                let (proof, proof_root) =
+                    merkle_proof(&leaves, i).expect("index is in range");
+                assert_eq!(proof_root, root);
+                assert!(proof.verify(leaves[i], root));
+            }
+        }
+    }
+
+    #[test]
+    fn proof_rejects_wrong_leaf() {
+        let mut tree = MerkleTree::new();
+        let mut leaves = Vec::new();
+        for i in 0..5u8 {
+            let d = digest(i);
+            leaves.push(d);
+            tree.append(d);
+        }
+        let root = tree.root().unwrap();
+
+        let (proof, _) = merkle_proof(&leaves, 2).unwrap();
+        assert!(!proof.verify(digest(99), root));
+    }
+}
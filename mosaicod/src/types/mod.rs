@@ -17,3 +17,24 @@ pub use layer::*;
 
 mod chunk;
 pub use chunk::*;
+
+mod bloom;
+pub use bloom::*;
+
+mod repair;
+pub use repair::*;
+
+mod compaction;
+pub use compaction::*;
+
+mod merkle;
+pub use merkle::*;
+
+mod upload_job;
+pub use upload_job::*;
+
+mod delete_job;
+pub use delete_job::*;
+
+mod chunk_verify;
+pub use chunk_verify::*;
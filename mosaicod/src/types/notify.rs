@@ -1,11 +1,15 @@
 pub enum NotifyType {
     Error,
+    /// A topic completed ingestion (i.e. it was locked after a successful
+    /// `do_put`). Used to wake up standing subscriptions such as `do_exchange`.
+    Ingest,
 }
 
 impl std::fmt::Display for NotifyType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Error => write!(f, "error"),
+            Self::Ingest => write!(f, "ingest"),
         }
     }
 }
@@ -16,6 +20,7 @@ impl std::str::FromStr for NotifyType {
     fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value {
             "error" => Ok(Self::Error),
+            "ingest" => Ok(Self::Ingest),
             _ => Err(std::io::Error::other(format!(
                 "unkwnown notify type `{}`",
                 value
@@ -0,0 +1,38 @@
+/// Summary of an online consistency scan of the chunk catalog against the
+/// object store, produced by `repo::FacadeRepair::scan`.
+///
+/// Each field is one anomaly category; an empty `Vec` means no anomalies of
+/// that kind were found. When the scan ran with `dry_run = false`, the
+/// offending entries in [`Self::orphaned_data_files`] and
+/// [`Self::missing_data_files`] have already been deleted (the store object
+/// and the catalog row, respectively) by the time this is returned --
+/// [`Self::misplaced_topics`] and [`Self::empty_sequences`] are report-only,
+/// since resolving them requires a judgment call the scan can't make safely
+/// on its own.
+#[derive(Debug, Default)]
+pub struct RepairReport {
+    /// Data files found in the store with no corresponding `chunk_t` row.
+    pub orphaned_data_files: Vec<String>,
+    /// Catalog rows whose `data_file` no longer exists in the store.
+    pub missing_data_files: Vec<String>,
+    /// Topic names that are not a sub-resource of any known sequence.
+    pub misplaced_topics: Vec<String>,
+    /// Sequences with no topics underneath them.
+    pub empty_sequences: Vec<String>,
+    /// Content-addressed chunk data files reclaimed because every `chunk_t`
+    /// row that used to reference their digest is gone -- the dedup
+    /// refcount (`chunk_ref_t`) recomputed to zero. See
+    /// `repo::FacadeRepair::scan_chunk_refs`.
+    pub reclaimed_chunk_refs: Vec<String>,
+}
+
+impl RepairReport {
+    /// Whether the scan found no anomalies of any category.
+    pub fn is_clean(&self) -> bool {
+        self.orphaned_data_files.is_empty()
+            && self.missing_data_files.is_empty()
+            && self.misplaced_topics.is_empty()
+            && self.empty_sequences.is_empty()
+            && self.reclaimed_chunk_refs.is_empty()
+    }
+}
@@ -63,10 +63,22 @@ impl<M> TopicMetadata<M> {
     }
 }
 
+/// Records which derived key and algorithm encrypt a topic's chunks at
+/// rest, never the key itself -- a reader re-derives the actual cipher key
+/// from the process's master key (see [`rw::MasterKey::derive_cipher`])
+/// using `key_id`.
+#[derive(Debug, Clone)]
+pub struct EncryptionInfo {
+    pub key_id: String,
+    pub algorithm: String,
+}
+
 #[derive(Debug)]
 pub struct TopicProperties {
     pub serialization_format: rw::Format,
     pub ontology_tag: String,
+    /// `Some` if chunks written to this topic are encrypted at rest.
+    pub encryption: Option<EncryptionInfo>,
 }
 
 impl TopicProperties {
@@ -74,8 +86,15 @@ impl TopicProperties {
         Self {
             serialization_format,
             ontology_tag,
+            encryption: None,
         }
     }
+
+    /// Marks this topic as encrypted with the key derived from `key_id`.
+    pub fn with_encryption(mut self, encryption: EncryptionInfo) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
 }
 
 pub struct TopicSystemInfo {
@@ -89,6 +108,9 @@ pub struct TopicSystemInfo {
     pub total_size_bytes: usize,
     /// Datetime of the topic creation
     pub created_datetime: super::DateTime,
+    /// Root of the topic's [`super::MerkleTree`] over its chunk digests, in
+    /// hex. `None` if the topic has no chunks yet.
+    pub merkle_root: Option<String>,
 }
 
 #[derive(Clone)]
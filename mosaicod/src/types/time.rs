@@ -62,6 +62,11 @@ impl DateTime {
     pub fn fmt_to_ms(&self) -> String {
         self.0.format("%Y%m%d%H%M%S%3f").to_string()
     }
+
+    /// Milliseconds since the Unix epoch, the inverse of `Timestamp::into::<DateTime>()`.
+    pub fn unix_millis(&self) -> i64 {
+        self.0.timestamp_millis()
+    }
 }
 
 impl std::fmt::Display for DateTime {
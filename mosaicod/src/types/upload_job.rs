@@ -0,0 +1,64 @@
+//! Lifecycle state and progress of a resumable topic upload job (see
+//! `repo::upload_job_*` and [`crate::rw::ChunkedWriter::resume_from`]).
+
+/// Lifecycle state of a topic's upload job, stored as a raw string in
+/// `upload_job_t`, following the same convention as [`super::NotifyType`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadJobState {
+    /// A checkpoint exists but no chunk has been written yet.
+    Queued,
+    /// Actively receiving and finalizing chunks.
+    Running,
+    /// The upload stopped gracefully before the topic was locked; it stays
+    /// unlocked and its checkpoint stays in place for a future resume.
+    Paused,
+    /// The topic was finalized and locked.
+    Completed,
+    /// The upload hit an unrecoverable error; a `TopicNotify` of type
+    /// `Error` was recorded with the failing chunk and reason.
+    Failed,
+}
+
+impl std::fmt::Display for UploadJobState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Queued => write!(f, "queued"),
+            Self::Running => write!(f, "running"),
+            Self::Paused => write!(f, "paused"),
+            Self::Completed => write!(f, "completed"),
+            Self::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl std::str::FromStr for UploadJobState {
+    type Err = std::io::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "queued" => Ok(Self::Queued),
+            "running" => Ok(Self::Running),
+            "paused" => Ok(Self::Paused),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            _ => Err(std::io::Error::other(format!(
+                "unknown upload job state `{}`",
+                value
+            ))),
+        }
+    }
+}
+
+/// Checkpoint and progress for a topic's upload job, as reported to a
+/// polling client.
+#[derive(Debug)]
+pub struct TopicUploadStatus {
+    pub state: UploadJobState,
+    /// Number of chunks successfully finalized and durably persisted so
+    /// far. A resumed upload continues numbering from here instead of `0`.
+    pub chunks_written: usize,
+    /// Cumulative bytes written across those chunks.
+    pub bytes_written: usize,
+    /// Path of the most recently written chunk, if any.
+    pub current_file: Option<String>,
+}
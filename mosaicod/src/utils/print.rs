@@ -5,6 +5,7 @@ use colored::Colorize;
 pub fn print_startup_info(
     host: bool,
     port: u16,
+    tls: bool,
     store: &str,
     version: &str,
     startup_time: &Instant,
@@ -19,6 +20,8 @@ pub fn print_startup_info(
         "⎪".purple()
     );
 
+    let scheme = if tls { "grpc+tls" } else { "grpc" };
+
     let addrs = if_addrs::get_if_addrs().unwrap();
 
     if !host {
@@ -26,7 +29,10 @@ pub fn print_startup_info(
         for iface in addrs {
             match iface.ip() {
                 IpAddr::V4(ipv4) if ipv4.is_loopback() => {
-                    format_addr(true, format!("{}:{}", ipv4, port).cyan().to_string());
+                    format_addr(
+                        true,
+                        format!("{}://{}:{}", scheme, ipv4, port).cyan().to_string(),
+                    );
                 }
                 _ => {}
             }
@@ -38,13 +44,18 @@ pub fn print_startup_info(
             if let IpAddr::V4(ipv4) = iface.ip() {
                 format_addr(
                     ipv4.is_loopback(),
-                    format!("{}:{}", iface.ip(), port).cyan().to_string(),
+                    format!("{}://{}:{}", scheme, iface.ip(), port)
+                        .cyan()
+                        .to_string(),
                 );
             }
         }
     }
     println!(" {}", "⎪".purple());
     println!(" {} {:10} {}", "⎬".purple(), "Store", store);
+    if tls {
+        println!(" {} {:10} {}", "⎬".purple(), "TLS", "enabled".green());
+    }
     println!();
     println!("{}", "Press Ctrl+C to stop.".dimmed());
     println!();